@@ -1,14 +1,21 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use std::path::{Path, PathBuf};
 
-use chordcraft_core::chord::{Chord, VoicingType};
+use chordcraft_core::chord::{Chord, Inversion, NamingProfile, VoicingType};
+use chordcraft_core::fingering::Fingering;
+use chordcraft_core::formula::{ChordFormula, register_quality};
 use chordcraft_core::generator::{
-	GeneratorOptions, PlayingContext, ScoredFingering, format_fingering_diagram,
-	generate_fingerings,
+	Avoid, BarreStrength, GeneratorOptions, HandSize, PlayerProfile, PlayingContext,
+	ScoredFingering, SlashBass, StringPin, StringSet, format_fingering_diagram_spelled,
+	generate_fingerings_parallel, generate_fingerings_with_capo,
 };
-use chordcraft_core::instrument::{ConfigurableInstrument, Guitar, Ukulele};
-use chordcraft_core::note::Note;
+use chordcraft_core::instrument::{CapoedInstrument, ConfigurableInstrument, Guitar, Ukulele};
+use chordcraft_core::note::{Note, PitchClass};
+use chordcraft_core::scale::Scale;
+use chordcraft_core::spelling::Accidentals;
+use chordcraft_core::tuning::Tuning;
 
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 enum InstrumentChoice {
@@ -17,6 +24,14 @@ enum InstrumentChoice {
 	Guitar,
 	/// Standard ukulele (GCEA tuning)
 	Ukulele,
+	/// Low-G ukulele (GCEA tuning, linear)
+	UkuleleLowG,
+	/// Concert ukulele (GCEA tuning, 18 frets)
+	ConcertUkulele,
+	/// Tenor ukulele (GCEA tuning, 19 frets)
+	TenorUkulele,
+	/// Tenor ukulele strung low G
+	TenorUkuleleLowG,
 	/// 4-string bass guitar (EADG tuning)
 	Bass,
 	/// 5-string bass guitar (BEADG tuning)
@@ -29,6 +44,16 @@ enum InstrumentChoice {
 	BariUke,
 	/// 7-string guitar (BEADGBE tuning)
 	Guitar7,
+	/// 8-string guitar (F#BEADGBE tuning)
+	#[value(alias = "guitar-8")]
+	Guitar8,
+	/// Baritone guitar in B standard (BEADF#B tuning)
+	BaritoneGuitar,
+	/// Baritone guitar in A standard (ADGCEA tuning)
+	BaritoneGuitarA,
+	/// 12-string guitar (EADGBE in doubled courses)
+	#[value(alias = "guitar-12")]
+	Guitar12,
 	/// Drop D guitar (DADGBE tuning)
 	DropD,
 	/// Open G guitar (DGDGBD tuning)
@@ -49,32 +74,12 @@ impl InstrumentWrapper {
 		match choice {
 			InstrumentChoice::Guitar => InstrumentWrapper::Guitar(Guitar::default()),
 			InstrumentChoice::Ukulele => InstrumentWrapper::Ukulele(Ukulele::default()),
-			InstrumentChoice::Bass => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::bass())
-			}
-			InstrumentChoice::Bass5 => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::bass_5_string())
-			}
-			InstrumentChoice::Mandolin => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::mandolin())
-			}
-			InstrumentChoice::Banjo => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::banjo())
-			}
-			InstrumentChoice::BariUke => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::baritone_ukulele())
-			}
-			InstrumentChoice::Guitar7 => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::guitar_7_string())
-			}
-			InstrumentChoice::DropD => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::guitar_drop_d())
-			}
-			InstrumentChoice::OpenG => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::guitar_open_g())
-			}
-			InstrumentChoice::Dadgad => {
-				InstrumentWrapper::Configurable(ConfigurableInstrument::guitar_dadgad())
+			other => {
+				let value = other.to_possible_value().expect("no instrument is skipped");
+				InstrumentWrapper::Configurable(
+					ConfigurableInstrument::by_name(value.get_name())
+						.expect("every instrument choice is a preset"),
+				)
 			}
 		}
 	}
@@ -86,6 +91,15 @@ impl InstrumentWrapper {
 			InstrumentWrapper::Configurable(c) => c.name(),
 		}
 	}
+
+	/// The instrument as a configurable one, for saving to a file
+	fn to_configurable(&self) -> ConfigurableInstrument {
+		match self {
+			InstrumentWrapper::Guitar(_) => ConfigurableInstrument::guitar(),
+			InstrumentWrapper::Ukulele(_) => ConfigurableInstrument::ukulele(),
+			InstrumentWrapper::Configurable(c) => c.clone(),
+		}
+	}
 }
 
 // Helper macro to execute operations on any instrument type
@@ -109,23 +123,107 @@ fn parse_voicing_type(voicing: Option<&String>) -> Option<VoicingType> {
 	})
 }
 
+fn parse_naming_profile(style: Option<&String>) -> NamingProfile {
+	style
+		.map(|s| match s.to_lowercase().as_str() {
+			"pop" => NamingProfile::Pop,
+			"jazz" => NamingProfile::Jazz,
+			_ => NamingProfile::Standard,
+		})
+		.unwrap_or_default()
+}
+
+fn parse_hand_size(hand: Option<&String>) -> HandSize {
+	hand.map(|h| match h.to_lowercase().as_str() {
+		"small" | "s" => HandSize::Small,
+		"large" | "l" => HandSize::Large,
+		_ => HandSize::Medium,
+	})
+	.unwrap_or_default()
+}
+
+/// Player profile from a hand size (or "child") plus stretch and barre adjustments
+fn parse_player(
+	hand: Option<&String>,
+	stretch: Option<i8>,
+	barres: Option<&String>,
+) -> PlayerProfile {
+	let mut player = match hand {
+		Some(h) if h.eq_ignore_ascii_case("child") => PlayerProfile::child(),
+		_ => PlayerProfile::from(parse_hand_size(hand)),
+	};
+	if let Some(stretch) = stretch {
+		player.stretch_tolerance = stretch;
+	}
+	if let Some(barres) = barres {
+		player.barre_strength = match barres.to_lowercase().as_str() {
+			"weak" => BarreStrength::Weak,
+			"strong" => BarreStrength::Strong,
+			_ => BarreStrength::Normal,
+		};
+	}
+	player
+}
+
+/// Whether a player level asks for the beginner preset
+fn is_beginner(level: Option<&String>) -> bool {
+	level.is_some_and(|l| l.eq_ignore_ascii_case("beginner"))
+}
+
+/// Parse a chord tone to omit: "root" (or 1), 3 or 5
+fn parse_omit(degree: &str) -> Result<u8> {
+	match degree.trim().to_lowercase().as_str() {
+		"root" | "1" => Ok(1),
+		"3" | "third" => Ok(3),
+		"5" | "fifth" => Ok(5),
+		_ => anyhow::bail!("Can't omit '{degree}' (expected root, 3 or 5)"),
+	}
+}
+
+/// Parse the grips and shapes given with --avoid
+fn parse_avoid(avoid: &[String]) -> Result<Vec<Avoid>> {
+	avoid
+		.iter()
+		.map(|a| {
+			Avoid::parse(a).with_context(|| {
+				format!("Invalid fingering or shape to avoid: '{a}' (e.g. 133211 or \"E shape\")")
+			})
+		})
+		.collect()
+}
+
+/// Parse "STRING=STATE", with strings counted from the lowest starting at 1
+fn parse_pin(pin: &str) -> Result<StringPin> {
+	let invalid = || format!("Invalid pin: '{pin}' (expected STRING=STATE, e.g. 6=3)");
+	let (string, state) = pin.split_once('=').with_context(invalid)?;
+	let string: usize = string
+		.trim()
+		.parse()
+		.ok()
+		.filter(|&s| s > 0)
+		.with_context(invalid)?;
+	let states = Fingering::parse(state).with_context(invalid)?;
+	match states.strings() {
+		[state] => Ok(StringPin::new(string - 1, *state)),
+		_ => Err(anyhow::anyhow!(invalid())),
+	}
+}
+
 fn parse_playing_context(context: Option<&String>) -> PlayingContext {
 	context
 		.map(|c| match c.to_lowercase().as_str() {
 			"band" => PlayingContext::Band,
+			"fingerstyle" => PlayingContext::Fingerstyle,
+			"strumming" | "strum" => PlayingContext::Strumming,
+			"recording" | "studio" => PlayingContext::Recording,
 			_ => PlayingContext::Solo,
 		})
 		.unwrap_or(PlayingContext::Solo)
 }
 
-/// Parse a custom tuning string like "E2,A2,D3,G3,B3,E4" into notes
+/// Parse a custom tuning string like "E2,A2,D3,G3,B3,E4" or "DADGAD" into notes
 fn parse_tuning(tuning_str: &str) -> Result<Vec<Note>> {
-	tuning_str
-		.split(',')
-		.map(|s| {
-			Note::parse(s.trim()).map_err(|e| anyhow::anyhow!("Invalid note '{}': {}", s.trim(), e))
-		})
-		.collect()
+	Ok(Tuning::parse(tuning_str)?)
 }
 
 /// Create a custom instrument from a tuning string
@@ -168,6 +266,21 @@ fn create_custom_instrument(tuning_str: &str) -> Result<ConfigurableInstrument>
 struct Cli {
 	#[command(subcommand)]
 	command: Commands,
+
+	/// Define a chord symbol, e.g. "mu=1 2 3 5" (repeatable; degrees in parentheses are optional)
+	#[arg(long, global = true, value_name = "SYMBOL=FORMULA")]
+	define: Vec<String>,
+}
+
+/// Register a "SYMBOL=FORMULA" chord definition from the command line
+fn define_quality(definition: &str) -> Result<()> {
+	let (symbol, formula) = definition
+		.split_once('=')
+		.context("Chord definitions look like \"mu=1 2 3 5\"")?;
+	let formula =
+		ChordFormula::parse(formula).with_context(|| format!("Invalid formula for '{symbol}'"))?;
+	register_quality(symbol, formula).with_context(|| format!("Can't define '{symbol}'"))?;
+	Ok(())
 }
 
 #[derive(Subcommand)]
@@ -185,48 +298,146 @@ enum Commands {
 		#[arg(short, long)]
 		position: Option<u8>,
 
+		/// Lowest fret to press (open strings are still allowed)
+		#[arg(long, default_value = "0")]
+		min_fret: u8,
+
+		/// Highest fret to press
+		#[arg(long, default_value = "12")]
+		max_fret: u8,
+
 		/// Voicing type: core, full, or jazzy
 		#[arg(short, long)]
 		voicing: Option<String>,
 
-		/// Playing context: solo or band (default: solo)
+		/// Playing context: solo, band, fingerstyle, strumming, or recording (default: solo)
 		#[arg(short = 'x', long)]
 		context: Option<String>,
 
+		/// Hand size: small, medium, large, or child (default: medium)
+		#[arg(long)]
+		hand: Option<String>,
+
+		/// Frets of stretch on top of the hand size (negative: less)
+		#[arg(long, allow_hyphen_values = true)]
+		stretch: Option<i8>,
+
+		/// Barre strength: weak, normal, or strong (default: normal)
+		#[arg(long)]
+		barres: Option<String>,
+
+		/// Gentle fingerings for sore or injured hands: no barres, at most three fingers
+		#[arg(long)]
+		accessible: bool,
+
+		/// Player level: beginner (open shapes in the first three frets, no barres), intermediate or advanced
+		#[arg(long)]
+		level: Option<String>,
+
+		/// Let the thumb wrap over the neck to fret the lowest string
+		#[arg(long)]
+		thumb: bool,
+
+		/// Also use natural harmonics (over frets 12, 7, 5 and 4) as chord tones
+		#[arg(long)]
+		harmonics: bool,
+
+		/// Slide-guitar voicings: one straight bar plus open bass strings
+		#[arg(long)]
+		slide: bool,
+
+		/// Show each note's degree in this scale (e.g., "A minor", "D dorian")
+		#[arg(long)]
+		scale: Option<String>,
+
+		/// Inversion to play: 0 (root position), 1 (3rd in bass), 2 (5th), 3 (7th)
+		#[arg(long, value_parser = clap::value_parser!(u8).range(0..=3))]
+		inversion: Option<u8>,
+
+		/// For slash chords and inversions, also show voicings with another bass note (ranked lower)
+		#[arg(long)]
+		loose_bass: bool,
+
+		/// Pin a string, counted from the lowest (1), to a tab state: "6=3", "3=0", "1=x" (repeatable)
+		#[arg(long = "pin", value_name = "STRING=STATE")]
+		pins: Vec<String>,
+
+		/// Chord tones to leave out: root, 3 or 5 (e.g., "--omit root,5")
+		#[arg(long, value_delimiter = ',')]
+		omit: Vec<String>,
+
+		/// Melody note the highest played string must sound (e.g., "E"), even outside the chord
+		#[arg(long, value_name = "NOTE")]
+		top: Option<String>,
+
+		/// Strings to play: "top4", "middle4", "bottom3", or a list counted from the lowest ("2,3,4")
+		#[arg(long, value_name = "SET")]
+		strings: Option<String>,
+
+		/// Movable shapes only: no open strings, so every result works in any key
+		#[arg(long)]
+		movable: bool,
+
+		/// Spread results over different shapes instead of variants of one
+		#[arg(long)]
+		diverse: bool,
+
+		/// Best fingering per region of the neck (open, frets 2-4, 5-7, ...)
+		#[arg(long)]
+		by_position: bool,
+
+		/// Never suggest this grip or shape: a tab ("133211") or a shape ("E shape", "E shape at 1"); repeatable
+		#[arg(long, value_name = "TAB|SHAPE")]
+		avoid: Vec<String>,
+
 		/// Capo position (fret number)
 		#[arg(short, long)]
 		capo: Option<u8>,
 
-		/// Instrument type (guitar, ukulele, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, drop-d, open-g, dadgad)
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
 		#[arg(short, long, default_value = "guitar")]
 		instrument: InstrumentChoice,
 
-		/// Custom tuning (e.g., "D2,A2,D3,G3,B3,E4" for Drop D). Overrides --instrument.
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
 		#[arg(short, long)]
 		tuning: Option<String>,
 	},
 
 	/// Identify chord from fingering notation
 	Name {
-		/// Tab notation (e.g., "x32010", "022100")
+		/// Tab notation (e.g., "x32010", "022100"), or notes with --notes
 		fingering: String,
 
+		/// Name a list of notes, lowest first (e.g., "E G C"), or MIDI note numbers
+		/// in any order (e.g., "52 60 67"), instead of a fingering
+		#[arg(long)]
+		notes: bool,
+
+		/// Chord naming style: standard, pop (C2), or jazz (C∆9, rootless voicings)
+		#[arg(short, long)]
+		style: Option<String>,
+
+		/// Spell names with flats (Ab, Bb) instead of sharps, e.g. when playing in a flat key
+		#[arg(long)]
+		flats: bool,
+
 		/// Capo position (fret number)
 		#[arg(short, long)]
 		capo: Option<u8>,
 
-		/// Instrument type (guitar, ukulele, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, drop-d, open-g, dadgad)
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
 		#[arg(short, long, default_value = "guitar")]
 		instrument: InstrumentChoice,
 
-		/// Custom tuning (e.g., "D2,A2,D3,G3,B3,E4" for Drop D). Overrides --instrument.
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
 		#[arg(short, long)]
 		tuning: Option<String>,
 	},
 
 	/// Find optimal fingerings for a chord progression
 	Progression {
-		/// Chord names separated by spaces (e.g., "C Am F G")
+		/// Chord names separated by spaces (e.g., "C Am F G"), bar notation
+		/// (e.g., "|: C . . . | Am | F G :|"), or roman numerals with --key
 		chords: String,
 
 		/// Number of alternative progressions to show
@@ -237,27 +448,301 @@ enum Commands {
 		#[arg(short = 'd', long, default_value = "3")]
 		max_distance: u8,
 
+		/// Chords each alternative must finger differently from the ones above it
+		#[arg(long, default_value = "2")]
+		min_difference: usize,
+
+		/// Weight of each chord's own voicing against smooth changes, 0 (smoothest) to 1 (best voicings)
+		#[arg(long, default_value = "0")]
+		alpha: f32,
+
 		/// Prefer fingerings near this fret position
 		#[arg(short, long)]
 		position: Option<u8>,
 
+		/// Lowest fret any chord may press (open strings are still allowed)
+		#[arg(long, default_value = "0")]
+		min_fret: u8,
+
+		/// Highest fret any chord may press (e.g., 5 keeps the whole song below fret 5)
+		#[arg(long, default_value = "12")]
+		max_fret: u8,
+
 		/// Voicing type: core, full, or jazzy
 		#[arg(short, long)]
 		voicing: Option<String>,
 
-		/// Playing context: solo or band (default: solo)
+		/// Playing context: solo, band, fingerstyle, strumming, or recording (default: solo)
 		#[arg(short = 'x', long)]
 		context: Option<String>,
 
+		/// Hand size: small, medium, large, or child (default: medium)
+		#[arg(long)]
+		hand: Option<String>,
+
+		/// Gentle fingerings for sore or injured hands: no barres, at most three fingers
+		#[arg(long)]
+		accessible: bool,
+
+		/// Player level: beginner (open shapes in the first three frets, no barres), intermediate or advanced
+		#[arg(long)]
+		level: Option<String>,
+
+		/// Also use natural harmonics (over frets 12, 7, 5 and 4) as chord tones
+		#[arg(long)]
+		harmonics: bool,
+
+		/// Never use this grip or shape: a tab ("133211") or a shape ("E shape", "E shape at 1"); repeatable
+		#[arg(long, value_name = "TAB|SHAPE")]
+		avoid: Vec<String>,
+
 		/// Capo position (fret number)
 		#[arg(short, long)]
 		capo: Option<u8>,
 
-		/// Instrument type (guitar, ukulele, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, drop-d, open-g, dadgad)
+		/// Song tempo; favors quick changes and warns about ones too slow to make in time
+		#[arg(long)]
+		bpm: Option<f32>,
+
+		/// Stop searching after this many milliseconds and show the best found so far
+		#[arg(long)]
+		time_budget: Option<u64>,
+
+		/// Keep only this many partial progressions per chord: faster on long songs, may miss the best
+		#[arg(long)]
+		beam_width: Option<usize>,
+
+		/// Also favor smooth voice leading: held common tones and a stepwise top voice
+		#[arg(long)]
+		voice_leading: bool,
+
+		/// Favor one kind of grip throughout: all open, all barres, or all compact
+		#[arg(long)]
+		consistent_voicing: bool,
+
+		/// Suggest passing chords for each change (chromatic approach, diminished, sus)
+		#[arg(long)]
+		passing: bool,
+
+		/// Label chords with roman numerals and harmonic functions in the detected key
+		#[arg(long)]
+		numerals: bool,
+
+		/// Key for roman numerals, in the output and in place of chords (e.g., "G", "Em", "Bb major")
+		#[arg(long)]
+		key: Option<String>,
+
+		/// Output format: text, or sheet (chord names over bars of tab, for printing)
+		#[arg(short, long, default_value = "text")]
+		format: String,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+	},
+
+	/// List the easy chords in a tuning (open strings, one finger, or one-finger barres)
+	Explore {
+		/// Highest fret to consider
+		#[arg(long, default_value = "12")]
+		max_fret: u8,
+
+		/// Leave out one-finger barres
+		#[arg(long)]
+		no_barres: bool,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "D2,G2,D3,G3,B3,D4" for Open G), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+	},
+
+	/// Find the keys a progression is easiest to play in, with or without a capo
+	Keys {
+		/// Chord names separated by spaces (e.g., "Ab Fm Db Eb")
+		chords: String,
+
+		/// Highest capo position to try (0 = no capo)
+		#[arg(long, default_value = "5")]
+		max_capo: u8,
+
+		/// Number of keys to show
+		#[arg(short, long, default_value = "3")]
+		limit: usize,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+	},
+
+	/// Print a measure-aligned chord chart
+	Chart {
+		/// Chord names separated by spaces (e.g., "C Am F G")
+		chords: String,
+
+		/// Beats each chord lasts (default: one bar)
+		#[arg(short, long)]
+		beats: Option<f32>,
+
+		/// Beats per bar
+		#[arg(long, default_value = "4")]
+		time: u8,
+
+		/// Bars per line
+		#[arg(long, default_value = "4")]
+		width: usize,
+
+		/// Chart title
+		#[arg(long)]
+		title: Option<String>,
+	},
+
+	/// Convert a fingering to another tuning or instrument
+	Convert {
+		/// Fingering in the source tuning (e.g., "x32010")
+		fingering: String,
+
+		/// Source instrument type
+		#[arg(long, default_value = "guitar")]
+		from: InstrumentChoice,
+
+		/// Custom source tuning. Overrides --from.
+		#[arg(long)]
+		from_tuning: Option<String>,
+
+		/// Target instrument type
+		#[arg(long)]
+		to: InstrumentChoice,
+
+		/// Custom target tuning. Overrides --to.
+		#[arg(long)]
+		to_tuning: Option<String>,
+	},
+
+	/// Import chords from a MIDI, MusicXML or ChordPro file
+	Import {
+		/// Path to a MIDI (.mid), uncompressed MusicXML (.musicxml, .xml) or ChordPro (.cho) file
+		file: PathBuf,
+
+		/// Chord granularity for MIDI files: bar or beat
+		#[arg(long, default_value = "bar")]
+		by: String,
+
+		/// Also find optimal fingerings for the imported chords
+		#[arg(short, long)]
+		fingerings: bool,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+	},
+
+	/// Arrange a progression for several instruments playing together
+	Ensemble {
+		/// Chord names separated by spaces (e.g., "C Am F G")
+		chords: String,
+
+		/// Instruments of the ensemble, repeated or comma-separated (e.g., -i guitar,ukulele,bass)
+		#[arg(short, long = "instrument", value_delimiter = ',', required = true)]
+		instruments: Vec<InstrumentChoice>,
+
+		/// Maximum fret distance between consecutive chords within a part
+		#[arg(short = 'd', long, default_value = "4")]
+		max_distance: u8,
+	},
+
+	/// Export chords or instrument definitions for use in other apps
+	Export {
+		#[command(subcommand)]
+		what: ExportCommand,
+	},
+
+	/// Analyze a ChordPro song: key, difficulty, hardest changes, capo and tuning suggestions
+	Song {
+		/// Path to a ChordPro (.cho) file
+		file: PathBuf,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+
+		/// Re-render whenever the file changes (Ctrl-C to stop)
+		#[arg(short, long)]
+		watch: bool,
+	},
+}
+
+#[derive(Subcommand)]
+enum ExportCommand {
+	/// Instrument definition (TOML or JSON) to edit and load back with --tuning
+	Instrument {
+		/// Output format: toml or json
+		#[arg(short, long, default_value = "toml")]
+		format: String,
+
+		/// Write the instrument to this file instead of printing it
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
+		#[arg(short, long, default_value = "guitar")]
+		instrument: InstrumentChoice,
+
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
+		#[arg(short, long)]
+		tuning: Option<String>,
+	},
+
+	/// Flashcard deck of chord diagrams (CSV or JSON) for Anki-style apps
+	Flashcards {
+		/// Chord names separated by spaces (e.g., "C Am F G")
+		#[arg(required_unless_present_any = ["shapes", "song"])]
+		chords: Option<String>,
+
+		/// Cards for the instrument's standard shapes instead of a chord list
+		#[arg(long, conflicts_with_all = ["chords", "song"])]
+		shapes: bool,
+
+		/// Cards for the voicings of a ChordPro song instead of a chord list
+		#[arg(long, conflicts_with = "chords")]
+		song: Option<PathBuf>,
+
+		/// Cards per chord, for the best voicings of each (chord lists only)
+		#[arg(long, default_value = "1")]
+		voicings: usize,
+
+		/// Output format: csv or json
+		#[arg(short, long, default_value = "csv")]
+		format: String,
+
+		/// Write the deck to this file instead of printing it
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+
+		/// Instrument type (guitar, ukulele, ukulele-low-g, concert-ukulele, tenor-ukulele, tenor-ukulele-low-g, bass, bass-5, mandolin, banjo, bari-uke, guitar-7, guitar-8, baritone-guitar, baritone-guitar-a, guitar-12, drop-d, open-g, dadgad)
 		#[arg(short, long, default_value = "guitar")]
 		instrument: InstrumentChoice,
 
-		/// Custom tuning (e.g., "D2,A2,D3,G3,B3,E4" for Drop D). Overrides --instrument.
+		/// Custom tuning (e.g., "DADGAD", or "D2,A2,D3,G3,B3,E4" for Drop D), or an instrument .toml/.json file. Overrides --instrument.
 		#[arg(short, long)]
 		tuning: Option<String>,
 	},
@@ -265,14 +750,38 @@ enum Commands {
 
 fn main() -> Result<()> {
 	let cli = Cli::parse();
+	for definition in &cli.define {
+		define_quality(definition)?;
+	}
 
 	match cli.command {
 		Commands::Find {
 			chord,
 			limit,
 			position,
+			min_fret,
+			max_fret,
 			voicing,
 			context,
+			hand,
+			stretch,
+			barres,
+			accessible,
+			level,
+			thumb,
+			harmonics,
+			slide,
+			scale,
+			inversion,
+			loose_bass,
+			pins,
+			omit,
+			top,
+			strings,
+			movable,
+			diverse,
+			by_position,
+			avoid,
 			capo,
 			instrument,
 			tuning,
@@ -285,27 +794,78 @@ fn main() -> Result<()> {
 				CliOptions {
 					limit,
 					position,
+					min_fret,
+					max_fret,
 					voicing,
 					context,
+					hand,
+					stretch,
+					barres,
+					accessible,
+					level,
+					thumb,
+					harmonics,
+					slide,
+					scale,
+					inversion,
+					loose_bass,
+					pins,
+					omit,
+					top,
+					strings,
+					movable,
+					diverse,
+					by_position,
+					avoid,
 				},
 			)?;
 		}
 		Commands::Name {
 			fingering,
+			notes,
+			style,
+			flats,
 			capo,
 			instrument,
 			tuning,
 		} => {
-			name_chord(&fingering, capo, instrument, tuning)?;
+			let accidentals = if flats {
+				Accidentals::Flats
+			} else {
+				Accidentals::Sharps
+			};
+			if notes {
+				name_notes(&fingering, style, accidentals)?;
+			} else {
+				name_chord(&fingering, style, accidentals, capo, instrument, tuning)?;
+			}
 		}
 		Commands::Progression {
 			chords,
 			limit,
 			max_distance,
+			min_difference,
+			alpha,
 			position,
+			min_fret,
+			max_fret,
 			voicing,
 			context,
+			hand,
+			accessible,
+			level,
+			harmonics,
+			avoid,
 			capo,
+			bpm,
+			time_budget,
+			beam_width,
+			voice_leading,
+			consistent_voicing,
+			passing,
+			numerals,
+			key,
+			format,
 			instrument,
 			tuning,
 		} => {
@@ -314,6 +874,11 @@ fn main() -> Result<()> {
 				FindProgressionInstrumentOptions {
 					voicing,
 					context,
+					hand,
+					accessible,
+					level,
+					harmonics,
+					avoid,
 					capo,
 					instrument,
 					tuning,
@@ -321,10 +886,177 @@ fn main() -> Result<()> {
 				FindProgressionOptions {
 					limit,
 					max_distance,
+					min_difference,
+					alpha,
 					position,
+					min_fret,
+					max_fret,
+					bpm,
+					time_budget,
+					beam_width,
+					voice_leading,
+					consistent_voicing,
+					passing,
+					numerals,
+					key,
+					format,
+				},
+			)?;
+		}
+		Commands::Explore {
+			max_fret,
+			no_barres,
+			instrument,
+			tuning,
+		} => {
+			explore(max_fret, no_barres, instrument, tuning)?;
+		}
+		Commands::Keys {
+			chords,
+			max_capo,
+			limit,
+			instrument,
+			tuning,
+		} => {
+			easiest_keys(&chords, max_capo, limit, instrument, tuning)?;
+		}
+		Commands::Chart {
+			chords,
+			beats,
+			time,
+			width,
+			title,
+		} => {
+			print_chart(&chords, beats, time, width, title)?;
+		}
+		Commands::Convert {
+			fingering,
+			from,
+			from_tuning,
+			to,
+			to_tuning,
+		} => {
+			convert(&fingering, from, from_tuning, to, to_tuning)?;
+		}
+		Commands::Import {
+			file,
+			by,
+			fingerings,
+			instrument,
+			tuning,
+		} => {
+			import_song(&file, &by, fingerings, instrument, tuning)?;
+		}
+		Commands::Ensemble {
+			chords,
+			instruments,
+			max_distance,
+		} => {
+			arrange_for_ensemble(&chords, &instruments, max_distance)?;
+		}
+		Commands::Export {
+			what:
+				ExportCommand::Flashcards {
+					chords,
+					shapes,
+					song,
+					voicings,
+					format,
+					output,
+					instrument,
+					tuning,
 				},
+		} => {
+			let source = match (chords, song) {
+				(_, Some(path)) => FlashcardSource::Song(path),
+				(Some(chords), None) if !shapes => FlashcardSource::Chords(chords),
+				_ => FlashcardSource::Shapes,
+			};
+			export_flashcards(
+				source,
+				voicings,
+				&format,
+				output.as_deref(),
+				instrument,
+				tuning,
 			)?;
 		}
+		Commands::Export {
+			what:
+				ExportCommand::Instrument {
+					format,
+					output,
+					instrument,
+					tuning,
+				},
+		} => {
+			export_instrument(&format, output.as_deref(), instrument, tuning)?;
+		}
+		Commands::Song {
+			file,
+			instrument,
+			tuning,
+			watch,
+		} => {
+			if watch {
+				watch_song(&file, instrument, tuning)?;
+			} else {
+				analyze_song(&file, instrument, tuning)?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// The instrument file a --tuning value names, if it's a .toml or .json path
+fn instrument_file(tuning: &str) -> Option<&Path> {
+	let path = Path::new(tuning);
+	let extension = path.extension()?.to_str()?.to_lowercase();
+	matches!(extension.as_str(), "toml" | "json").then_some(path)
+}
+
+/// Load a custom instrument from a .toml or .json file
+fn load_instrument(path: &Path) -> Result<ConfigurableInstrument> {
+	let text = std::fs::read_to_string(path)
+		.with_context(|| format!("Could not read {}", path.display()))?;
+	let is_json = path
+		.extension()
+		.is_some_and(|e| e.eq_ignore_ascii_case("json"));
+	let instrument = if is_json {
+		serde_json::from_str(&text).map_err(anyhow::Error::from)
+	} else {
+		toml::from_str(&text).map_err(anyhow::Error::from)
+	};
+	instrument.with_context(|| format!("Could not load an instrument from {}", path.display()))
+}
+
+/// Save an instrument to a file (or print it) as TOML or JSON
+fn export_instrument(
+	format: &str,
+	output: Option<&Path>,
+	instrument_choice: InstrumentChoice,
+	tuning: Option<String>,
+) -> Result<()> {
+	let instrument = get_instrument(instrument_choice, tuning)?.to_configurable();
+	let text = match format.to_lowercase().as_str() {
+		"toml" => toml::to_string(&instrument)?,
+		"json" => serde_json::to_string_pretty(&instrument)? + "\n",
+		other => anyhow::bail!("Unknown format '{other}' (expected toml or json)"),
+	};
+
+	match output {
+		Some(path) => {
+			std::fs::write(path, text)
+				.with_context(|| format!("Could not write {}", path.display()))?;
+			eprintln!(
+				"{} {} to {}",
+				"Exported".green().bold(),
+				instrument.name(),
+				path.display()
+			);
+		}
+		None => print!("{text}"),
 	}
 
 	Ok(())
@@ -336,7 +1068,10 @@ fn get_instrument(
 	tuning: Option<String>,
 ) -> Result<InstrumentWrapper> {
 	if let Some(tuning_str) = tuning {
-		let custom = create_custom_instrument(&tuning_str)?;
+		let custom = match instrument_file(&tuning_str) {
+			Some(path) => load_instrument(path)?,
+			None => create_custom_instrument(&tuning_str)?,
+		};
 		Ok(InstrumentWrapper::Configurable(custom))
 	} else {
 		Ok(InstrumentWrapper::from_choice(instrument_choice))
@@ -347,8 +1082,29 @@ fn get_instrument(
 pub struct CliOptions {
 	pub limit: usize,
 	pub position: Option<u8>,
+	pub min_fret: u8,
+	pub max_fret: u8,
 	pub voicing: Option<String>,
 	pub context: Option<String>,
+	pub hand: Option<String>,
+	pub stretch: Option<i8>,
+	pub barres: Option<String>,
+	pub accessible: bool,
+	pub level: Option<String>,
+	pub thumb: bool,
+	pub harmonics: bool,
+	pub slide: bool,
+	pub scale: Option<String>,
+	pub inversion: Option<u8>,
+	pub loose_bass: bool,
+	pub pins: Vec<String>,
+	pub omit: Vec<String>,
+	pub top: Option<String>,
+	pub strings: Option<String>,
+	pub movable: bool,
+	pub diverse: bool,
+	pub by_position: bool,
+	pub avoid: Vec<String>,
 }
 
 fn find_fingerings(
@@ -361,37 +1117,111 @@ fn find_fingerings(
 	let CliOptions {
 		limit,
 		position,
+		min_fret,
+		max_fret,
 		voicing,
 		context,
+		hand,
+		stretch,
+		barres,
+		accessible,
+		level,
+		thumb,
+		harmonics,
+		slide,
+		scale,
+		inversion,
+		loose_bass,
+		pins,
+		omit,
+		top,
+		strings,
+		movable,
+		diverse,
+		by_position,
+		avoid,
 	} = cli_options;
-	let original_chord =
+	let mut original_chord =
 		Chord::parse(chord_str).with_context(|| format!("Invalid chord name: '{chord_str}'"))?;
-
-	let (search_chord, shape_chord) = if let Some(capo_fret) = capo {
-		let shape = original_chord.transpose(-(capo_fret as i32));
-		(shape.clone(), Some(shape))
-	} else {
-		(original_chord.clone(), None)
-	};
+	if let Some(inversion) = inversion {
+		let inversion = match inversion {
+			0 => Inversion::Root,
+			1 => Inversion::First,
+			2 => Inversion::Second,
+			_ => Inversion::Third,
+		};
+		original_chord = original_chord.with_inversion(inversion)?;
+	}
+	let scale = scale
+		.map(|s| Scale::parse(&s).with_context(|| format!("Invalid scale: '{s}'")))
+		.transpose()?;
 
 	let voicing_type = parse_voicing_type(voicing.as_ref());
 	let playing_context = parse_playing_context(context.as_ref());
+	let top_note = top
+		.map(|n| PitchClass::parse(&n).with_context(|| format!("Invalid note: '{n}'")))
+		.transpose()?;
+	let string_set = strings
+		.map(|s| StringSet::parse(&s).with_context(|| format!("Invalid string set: '{s}'")))
+		.transpose()?;
 
 	let options = GeneratorOptions {
 		limit,
 		preferred_position: position,
 		voicing_type,
 		playing_context,
+		min_fret,
+		max_fret,
+		accessible,
+		beginner: is_beginner(level.as_ref()),
+		include_harmonics: harmonics,
+		thumb_fretting: thumb,
+		slash_bass: if loose_bass {
+			SlashBass::Prefer
+		} else {
+			SlashBass::Require
+		},
+		pins: pins.iter().map(|p| parse_pin(p)).collect::<Result<_>>()?,
+		string_set,
+		top_note,
+		omit: omit.iter().map(|d| parse_omit(d)).collect::<Result<_>>()?,
+		movable_only: movable,
+		diverse,
+		by_position,
+		avoid: parse_avoid(&avoid)?,
 		..Default::default()
-	};
+	}
+	.with_player(parse_player(hand.as_ref(), stretch, barres.as_ref()));
 
 	let instrument = get_instrument(instrument_choice, tuning)?;
 	let instrument_name = instrument.name();
 
-	let fingerings: Vec<ScoredFingering> =
-		with_instrument!(&instrument, instr => generate_fingerings(&search_chord, instr, &options));
-
-	if fingerings.is_empty() {
+	let (fingerings, shape_chord): (Vec<ScoredFingering>, Option<Chord>) = if slide {
+		use chordcraft_core::slide::{SlideOptions, generate_slide_voicings};
+		let slide_options = SlideOptions {
+			limit,
+			max_fret: options.max_fret,
+			preferred_position: position,
+			..Default::default()
+		};
+		let shape = original_chord.transpose(-(capo.unwrap_or(0) as i32));
+		let voicings = with_instrument!(&instrument, instr => generate_slide_voicings(&shape, instr, &slide_options));
+		(
+			voicings.iter().map(|v| v.to_scored()).collect(),
+			capo.map(|_| shape),
+		)
+	} else if let Some(capo) = capo {
+		let found = with_instrument!(&instrument, instr => {
+			generate_fingerings_with_capo(&original_chord, instr, capo, &options)
+		})
+		.context("Invalid capo position")?;
+		(found.fingerings, Some(found.shape_chord))
+	} else {
+		let fingerings = with_instrument!(&instrument, instr => generate_fingerings_parallel(&original_chord, instr, &options));
+		(fingerings, None)
+	};
+
+	if fingerings.is_empty() {
 		println!(
 			"{}",
 			format!("No fingerings found for chord: {original_chord}").yellow()
@@ -418,16 +1248,42 @@ fn find_fingerings(
 			fingerings.len()
 		);
 	}
+	if let Some(scale) = &scale {
+		println!(
+			"{} {} ({})\n",
+			"Scale:".dimmed(),
+			scale.to_string().cyan(),
+			scale.note_names().join(" ")
+		);
+	}
 
 	for (i, scored) in fingerings.iter().take(limit).enumerate() {
+		if by_position {
+			let region = with_instrument!(&instrument, instr => scored.region(instr));
+			println!("{}", region.to_string().yellow().bold());
+		}
 		println!(
 			"{}. {}",
 			(i + 1).to_string().cyan().bold(),
 			scored.fingering
 		);
-		let diagram =
-			with_instrument!(&instrument, instr => format_fingering_diagram(scored, instr));
+		let diagram = with_instrument!(&instrument, instr => {
+			format_fingering_diagram_spelled(scored, instr, original_chord.accidentals())
+		});
 		println!("{diagram}");
+		if let Some(scale) = &scale {
+			let notes = with_instrument!(&instrument, instr => scored.fingering.notes(instr));
+			let degrees: Vec<String> = notes
+				.iter()
+				.map(|note| {
+					let degree = scale
+						.degree_of(note.pitch)
+						.map_or_else(|| "-".to_string(), |d| d.to_string());
+					format!("{}({degree})", scale.accidentals().spell(note.pitch))
+				})
+				.collect();
+			println!("Scale degrees: {}", degrees.join(" "));
+		}
 		println!();
 	}
 
@@ -438,24 +1294,54 @@ struct FindProgressionInstrumentOptions {
 	instrument: InstrumentChoice,
 	voicing: Option<String>,
 	context: Option<String>,
+	hand: Option<String>,
+	accessible: bool,
+	level: Option<String>,
+	harmonics: bool,
+	avoid: Vec<String>,
 	capo: Option<u8>,
 	tuning: Option<String>,
 }
 struct FindProgressionOptions {
 	limit: usize,
 	max_distance: u8,
+	min_difference: usize,
+	alpha: f32,
 	position: Option<u8>,
+	min_fret: u8,
+	max_fret: u8,
+	bpm: Option<f32>,
+	time_budget: Option<u64>,
+	beam_width: Option<usize>,
+	voice_leading: bool,
+	consistent_voicing: bool,
+	passing: bool,
+	numerals: bool,
+	key: Option<String>,
+	format: String,
 }
 fn find_progression(
 	chords_str: &str,
 	instrument_opts: FindProgressionInstrumentOptions,
 	progression_opts: FindProgressionOptions,
 ) -> Result<()> {
-	use chordcraft_core::progression::{ProgressionOptions, generate_progression};
+	use chordcraft_core::chart::Chart;
+	use chordcraft_core::harmony::resolve_numerals;
+	use chordcraft_core::key::Key;
+	use chordcraft_core::progress::SearchBudget;
+	use chordcraft_core::progression::{
+		Numerals, ProgressionOptions, generate_progression, generate_progression_within,
+		parse_chord_names,
+	};
 	let FindProgressionInstrumentOptions {
 		instrument: instrument_choice,
 		voicing,
 		context,
+		hand,
+		accessible,
+		level,
+		harmonics,
+		avoid,
 		capo,
 		tuning,
 	} = instrument_opts;
@@ -463,15 +1349,71 @@ fn find_progression(
 	let FindProgressionOptions {
 		limit,
 		max_distance,
+		min_difference,
+		alpha,
 		position,
+		min_fret,
+		max_fret,
+		bpm,
+		time_budget,
+		beam_width,
+		voice_leading,
+		consistent_voicing,
+		passing,
+		numerals,
+		key,
+		format,
 	} = progression_opts;
 
-	let chord_names: Vec<&str> = chords_str.split_whitespace().collect();
+	let sheet = match format.to_lowercase().as_str() {
+		"text" => false,
+		"sheet" => true,
+		other => anyhow::bail!("Unknown format '{other}' (expected text or sheet)"),
+	};
+
+	let key = key
+		.map(|key| Key::parse(&key).with_context(|| format!("Invalid key: '{key}'")))
+		.transpose()?;
+
+	// Bar notation ("| C . . . | Am | F G |") keeps the song's measures and repeats
+	let chart = if chords_str.contains('|') {
+		Some(
+			Chart::parse(chords_str, 4)
+				.with_context(|| format!("Invalid chord chart: '{chords_str}'"))?,
+		)
+	} else {
+		None
+	};
+	let song = chart.as_ref().map(|c| c.to_song());
+	let chart_names = song.as_ref().map(|s| s.chord_names());
+	// How long each chord is held tells the search how much time each change has
+	let durations: Vec<f32> = song
+		.iter()
+		.flat_map(|s| s.chords())
+		.map(|c| c.beats)
+		.collect();
+	let tokens: Vec<&str> = match &chart_names {
+		Some(names) => names.iter().map(String::as_str).collect(),
+		None => chords_str.split_whitespace().collect(),
+	};
+
+	// With a key, roman numerals ("I vi IV V7/V") stand for the chords they name
+	let resolved_names: Vec<String> = tokens
+		.into_iter()
+		.map(|token| match key {
+			Some(key) if Chord::parse(token).is_err() => resolve_numerals(&[token], &key)
+				.map(|chords| chords[0].to_string())
+				.map_err(|_| anyhow::anyhow!("Invalid chord or numeral: '{token}'")),
+			_ => Ok(token.to_string()),
+		})
+		.collect::<Result<_>>()?;
+	let chord_names: Vec<&str> = resolved_names.iter().map(String::as_str).collect();
 
 	if chord_names.is_empty() {
 		println!("{}", "No chords provided".yellow());
 		return Ok(());
 	}
+	parse_chord_names(&chord_names)?;
 
 	let transposed_chords: Vec<String> = if let Some(capo_fret) = capo {
 		chord_names
@@ -497,48 +1439,144 @@ fn find_progression(
 
 	let gen_options = GeneratorOptions {
 		preferred_position: position,
+		min_fret,
+		max_fret,
 		voicing_type,
 		playing_context,
+		accessible,
+		beginner: is_beginner(level.as_ref()),
+		include_harmonics: harmonics,
+		avoid: parse_avoid(&avoid)?,
 		..Default::default()
+	}
+	.with_player(parse_player(hand.as_ref(), None, None));
+
+	// With a capo the search runs on the shapes, so the key moves down with them
+	let numerals = match key {
+		Some(key) => {
+			let shift = -(capo.unwrap_or(0) as i32);
+			Numerals::InKey(Key::new(key.tonic.add_semitones(shift), key.mode))
+		}
+		None if numerals => Numerals::DetectedKey,
+		None => Numerals::Off,
 	};
 
 	let options = ProgressionOptions {
 		limit,
 		max_fret_distance: max_distance,
+		min_difference,
+		alpha,
 		generator_options: gen_options,
+		numerals,
+		beam_width,
+		bpm,
+		durations,
+		voice_leading,
+		consistent_voicing,
 		..Default::default()
 	};
 
 	let instrument = get_instrument(instrument_choice, tuning)?;
 	let instrument_name = instrument.name().to_string();
 
-	let progressions = with_instrument!(&instrument, instr => {
-		generate_progression(&search_chords, instr, &options)
+	let (progressions, complete) = with_instrument!(&instrument, instr => {
+		match time_budget {
+			Some(ms) => {
+				let partial = generate_progression_within(
+					&search_chords,
+					instr,
+					&options,
+					&SearchBudget::time_ms(ms),
+				);
+				(partial.results, partial.complete)
+			}
+			None => (generate_progression(&search_chords, instr, &options), true),
+		}
 	});
 
 	if progressions.is_empty() {
 		println!("{}", "No valid progressions found".yellow());
+		if !complete {
+			println!(
+				"{}",
+				"Search ran out of time; try a larger --time-budget".dimmed()
+			);
+		}
+		return Ok(());
+	}
+
+	if sheet {
+		display_sheets(&progressions, capo, &instrument);
 		return Ok(());
 	}
 
+	if let Some(chart) = &chart {
+		println!("\n{chart}");
+	}
+	// A few suggestions per change keep the listing readable
+	let passing_options = passing.then(|| ProgressionOptions {
+		limit: 3,
+		..options.clone()
+	});
 	display_progressions(
 		&progressions,
 		&chord_names,
 		capo,
+		bpm,
+		passing_options.as_ref(),
 		&instrument_name,
 		&instrument,
 	);
+	if !complete {
+		println!(
+			"{}",
+			"Search stopped at the time budget; these are the best found so far".dimmed()
+		);
+	}
 
 	Ok(())
 }
 
+/// Print each alternative as a plain-text chord sheet
+fn display_sheets(
+	progressions: &[chordcraft_core::progression::ProgressionSequence],
+	capo: Option<u8>,
+	instrument: &InstrumentWrapper,
+) {
+	use chordcraft_core::sheet::{DEFAULT_CHORDS_PER_LINE, render_sheet};
+
+	for (alt_idx, progression) in progressions.iter().enumerate() {
+		if alt_idx > 0 {
+			println!("\n");
+		}
+		if progressions.len() > 1 {
+			println!("Alternative #{}", alt_idx + 1);
+		}
+		if let Some(capo_fret) = capo {
+			println!("Capo {capo_fret} (shapes shown)");
+		}
+		if progressions.len() > 1 || capo.is_some() {
+			println!();
+		}
+		let sheet = with_instrument!(instrument, instr => {
+			render_sheet(progression, instr, DEFAULT_CHORDS_PER_LINE)
+		});
+		println!("{sheet}");
+	}
+}
+
 fn display_progressions(
 	progressions: &[chordcraft_core::progression::ProgressionSequence],
 	chord_names: &[&str],
 	capo: Option<u8>,
+	bpm: Option<f32>,
+	passing: Option<&chordcraft_core::progression::ProgressionOptions>,
 	instrument_name: &str,
 	instrument: &InstrumentWrapper,
 ) {
+	use chordcraft_core::key::Key;
+	use chordcraft_core::passing::suggest_passing_chords;
+
 	let chord_display = chord_names.join(" → ");
 	if let Some(capo_fret) = capo {
 		println!(
@@ -569,6 +1607,27 @@ fn display_progressions(
 			"Avg Transition".bold(),
 			progression.avg_transition_score
 		);
+		if let Some(analysis) = &progression.numerals {
+			let key = analysis.key;
+			let key = Key::new(key.tonic.add_semitones(capo.unwrap_or(0) as i32), key.mode);
+			println!(
+				"{}: {} | {}",
+				"Key".bold(),
+				key,
+				analysis.labels().join(" → ").cyan()
+			);
+			let functions: Vec<String> =
+				analysis.functions().iter().map(|f| f.to_string()).collect();
+			println!("{}: {}", "Functions".bold(), functions.join(" → ").cyan());
+			let cadences: Vec<String> = analysis
+				.cadences()
+				.iter()
+				.map(|(i, cadence)| format!("{cadence} into {}", chord_names[*i]))
+				.collect();
+			if !cadences.is_empty() {
+				println!("{}: {}", "Cadences".bold(), cadences.join(", ").cyan());
+			}
+		}
 		println!("{}", "━".repeat(60).dimmed());
 		println!();
 
@@ -579,15 +1638,24 @@ fn display_progressions(
 				&progression.chords[i]
 			};
 
+			let numeral = progression
+				.numerals
+				.as_ref()
+				.and_then(|n| n.numerals.get(i))
+				.map(|n| format!(" ({n})"))
+				.unwrap_or_default();
 			println!(
-				"[{}] {} - Fret {}",
+				"[{}] {}{} - Fret {}",
 				(i + 1).to_string().cyan().bold(),
 				chord_name.green().bold(),
+				numeral.cyan(),
 				fingering.position
 			);
 
-			let diagram =
-				with_instrument!(instrument, instr => format_fingering_diagram(fingering, instr));
+			let accidentals = Accidentals::of_name(chord_name).unwrap_or_default();
+			let diagram = with_instrument!(instrument, instr => {
+				format_fingering_diagram_spelled(fingering, instr, accidentals)
+			});
 			for line in diagram.lines() {
 				println!("  {line}");
 			}
@@ -602,14 +1670,43 @@ fn display_progressions(
 					trans.score.to_string().cyan()
 				);
 				println!(
-					"    {}: {} fingers | {}: {} | {}: {} frets",
+					"    {}: {} fingers | {}: {} | {}: {} frets | {}: ~{} ms",
 					"Movements".dimmed(),
 					trans.finger_movements,
 					"Anchors".dimmed(),
 					trans.common_anchors,
 					"Distance".dimmed(),
-					trans.position_distance
+					trans.position_distance,
+					"Change".dimmed(),
+					trans.estimated_ms
 				);
+				if let Some(voice_leading) = trans.voice_leading {
+					println!("    {}: {voice_leading:+}", "Voice Leading".dimmed());
+				}
+				if let (Some(bpm), Some(budget)) = (bpm, trans.budget_ms)
+					&& trans.is_risky()
+				{
+					println!(
+						"    {} {}",
+						"⚠".yellow(),
+						format!("Hard to make at {bpm} BPM ({budget} ms to change)").yellow()
+					);
+				}
+				if let Some(options) = passing {
+					let suggestions = with_instrument!(instrument, instr => {
+						suggest_passing_chords(trans, instr, options)
+					});
+					let suggestions: Vec<String> = suggestions
+						.iter()
+						.map(|p| {
+							let chord = p.chord.transpose(capo.unwrap_or(0) as i32);
+							format!("{chord} {} ({})", p.fingering.fingering, p.kind)
+						})
+						.collect();
+					if !suggestions.is_empty() {
+						println!("    {}: {}", "Passing".dimmed(), suggestions.join(", "));
+					}
+				}
 				println!();
 			}
 		}
@@ -618,14 +1715,93 @@ fn display_progressions(
 	}
 }
 
+fn name_notes(notes_str: &str, style: Option<String>, accidentals: Accidentals) -> Result<()> {
+	use chordcraft_core::analyzer::{AnalyzerOptions, analyze_midi_notes_with, analyze_notes_with};
+
+	let options = AnalyzerOptions {
+		naming: parse_naming_profile(style.as_ref()),
+		accidentals,
+	};
+	let tokens: Vec<&str> = notes_str
+		.split([' ', ','])
+		.filter(|n| !n.is_empty())
+		.collect();
+	// MIDI note numbers carry their octave, so they can come in any order
+	let midi: Option<Vec<u8>> = tokens.iter().map(|n| n.parse().ok()).collect();
+	let (notes, matches) = if let Some(mut midi) = midi {
+		if let Some(n) = midi.iter().find(|&&n| n > 127) {
+			anyhow::bail!("Invalid MIDI note: {n} (0-127)");
+		}
+		midi.sort_unstable();
+		let notes: Vec<PitchClass> = midi.iter().map(|&n| Note::from_midi(n).pitch).collect();
+		(notes, analyze_midi_notes_with(&midi, &options))
+	} else {
+		let notes = tokens
+			.iter()
+			.map(|n| PitchClass::parse(n).with_context(|| format!("Invalid note: '{n}'")))
+			.collect::<Result<Vec<_>>>()?;
+		let matches = analyze_notes_with(&notes, &options);
+		(notes, matches)
+	};
+
+	println!(
+		"\n{} {}\n",
+		"Analyzing notes:".bold(),
+		notes
+			.iter()
+			.map(|p| accidentals.spell(*p))
+			.collect::<Vec<_>>()
+			.join(", ")
+			.green()
+			.bold()
+	);
+
+	let Some(top) = matches.first() else {
+		println!("{}", "Could not identify chord (not enough notes)".yellow());
+		return Ok(());
+	};
+
+	println!(
+		"{} {}\n",
+		"Best match:".bold().green(),
+		top.name.green().bold()
+	);
+	println!("  Confidence: {:.0}%", top.confidence * 100.0);
+	println!("  Score: {}", top.score);
+
+	if matches.len() > 1 {
+		println!("\n{}", "Alternative interpretations:".bold());
+		for (i, m) in matches.iter().skip(1).take(4).enumerate() {
+			println!(
+				"  {}. {} (confidence: {:.0}%, score: {})",
+				i + 1,
+				m.name.cyan(),
+				m.confidence * 100.0,
+				m.score
+			);
+		}
+	}
+
+	Ok(())
+}
+
 fn name_chord(
 	fingering_str: &str,
+	style: Option<String>,
+	accidentals: Accidentals,
 	capo: Option<u8>,
 	instrument_choice: InstrumentChoice,
 	tuning: Option<String>,
 ) -> Result<()> {
-	use chordcraft_core::analyzer::analyze_fingering;
-	use chordcraft_core::fingering::Fingering;
+	use chordcraft_core::analyzer::{
+		AnalyzerOptions, CapoMatch, Identification, analyze_fingering_with_capo,
+		identify_fingering, near_misses, string_roles,
+	};
+
+	let options = AnalyzerOptions {
+		naming: parse_naming_profile(style.as_ref()),
+		accidentals,
+	};
 
 	let fingering = Fingering::parse(fingering_str)
 		.with_context(|| format!("Invalid fingering notation: '{fingering_str}'"))?;
@@ -633,12 +1809,20 @@ fn name_chord(
 	let instrument = get_instrument(instrument_choice, tuning)?;
 	let instrument_name = instrument.name();
 
-	let (pitches, matches) = with_instrument!(&instrument, instr => {
-		let p = fingering.unique_pitch_classes(instr);
-		let m = analyze_fingering(&fingering, instr);
-		(p, m)
+	let (pitches, issues) = with_instrument!(&instrument, instr => {
+		(fingering.unique_pitch_classes(instr), fingering.validate_for(instr))
 	});
 
+	if let Some(issue) = issues.iter().find(|i| i.is_structural()) {
+		anyhow::bail!("'{fingering_str}' doesn't fit a {instrument_name}: {issue}");
+	}
+	// Frets count from the capo, which leaves less neck above it
+	let capo_fret = capo.unwrap_or(0);
+	let matches = with_instrument!(&instrument, instr => {
+		analyze_fingering_with_capo(&fingering, instr, capo_fret, &options)
+	})
+	.with_context(|| format!("'{fingering_str}' doesn't fit above a capo at fret {capo_fret}"))?;
+
 	if let Some(capo_fret) = capo {
 		println!(
 			"\n{} {} {} [{instrument_name}]\n",
@@ -658,37 +1842,55 @@ fn name_chord(
 		"Notes played: {}\n",
 		pitches
 			.iter()
-			.map(|p| p.to_string())
+			.map(|p| accidentals.spell(*p))
 			.collect::<Vec<_>>()
 			.join(", ")
 	);
 
+	if !issues.is_empty() {
+		println!("{}", "Hard to play:".yellow().bold());
+		for issue in &issues {
+			println!("  - {issue}");
+		}
+		println!();
+	}
+
+	// Likely typos: standard shapes a fret away, named as they sound
+	let near = with_instrument!(&instrument, instr => {
+		near_misses(&fingering, &CapoedInstrument::new(instr, capo_fret)?, &options)
+	});
+
 	if matches.is_empty() {
-		println!("{}", "Could not identify chord (not enough notes)".yellow());
+		// Named as it sounds, like the chords
+		let identification = with_instrument!(&instrument, instr => {
+			identify_fingering(&fingering, &CapoedInstrument::new(instr, capo_fret)?, &options)
+		});
+		if let Identification::Dyad { bass, interval } = identification {
+			println!(
+				"{} {} up from {}",
+				"Interval:".bold().green(),
+				interval.full_name().green().bold(),
+				accidentals.spell(bass)
+			);
+			println!(
+				"  {}",
+				"Not enough notes for a chord; a 5th would make a power chord (A5)".dimmed()
+			);
+		} else {
+			println!("{}", "Could not identify chord (not enough notes)".yellow());
+		}
+		print_near_misses(&near);
 		return Ok(());
 	}
 
-	let transposed_matches: Vec<_> = if let Some(capo_fret) = capo {
-		matches
-			.iter()
-			.map(|m| {
-				let mut transposed = m.clone();
-				transposed.chord = m.chord.transpose(capo_fret as i32);
-				transposed
-			})
-			.collect()
-	} else {
-		matches.clone()
-	};
-
-	let top = &transposed_matches[0];
-	let shape_chord = &matches[0].chord;
+	let top = &matches[0].sounding;
+	let shape_chord = &matches[0].shape.name;
 
 	if capo.is_some() {
 		println!(
 			"{} {} {} {}\n",
 			"Best match:".bold().green(),
-			top.chord.to_string().green().bold(),
+			top.name.green().bold(),
 			"(".dimmed(),
 			format!("{shape_chord} shape)").dimmed()
 		);
@@ -696,11 +1898,15 @@ fn name_chord(
 		println!(
 			"{} {}\n",
 			"Best match:".bold().green(),
-			top.chord.to_string().green().bold()
+			top.name.green().bold()
 		);
 	}
 
-	println!("  Confidence: {:.0}%", top.completeness * 100.0);
+	if top.rootless {
+		println!("  {}", "Rootless voicing (root implied)".dimmed());
+	}
+
+	println!("  Confidence: {:.0}%", top.confidence * 100.0);
 	println!(
 		"  Root in bass: {}",
 		if top.root_in_bass {
@@ -710,37 +1916,700 @@ fn name_chord(
 		}
 	);
 	println!("  Score: {}", top.score);
-
-	if transposed_matches.len() > 1 {
+	if let Some(shape) = &top.shape {
+		println!("  Shape: {shape}");
+	}
+	// Degrees don't change with a capo, so the shape's notes are labelled
+	let roles = with_instrument!(&instrument, instr => string_roles(&fingering, instr, &matches[0].shape.chord));
+	let tones: Vec<String> = roles
+		.iter()
+		.map(|role| format!("{}({})", accidentals.spell(role.note.pitch), role.label()))
+		.collect();
+	println!("  Tones (low to high): {}", tones.join(" "));
+
+	if matches.len() > 1 {
 		println!("\n{}", "Alternative interpretations:".bold());
-		for (i, (m, shape)) in transposed_matches
-			.iter()
-			.zip(matches.iter())
-			.skip(1)
-			.take(4)
-			.enumerate()
-		{
+		for (i, CapoMatch { shape, sounding: m }) in matches.iter().skip(1).take(4).enumerate() {
 			if capo.is_some() {
-				let shape_name = &shape.chord;
+				let shape_name = &shape.name;
 				println!(
 					"  {}. {} {} (confidence: {:.0}%, score: {})",
 					i + 1,
-					m.chord.to_string().cyan(),
+					m.name.cyan(),
 					format!("({shape_name} shape)").dimmed(),
-					m.completeness * 100.0,
+					m.confidence * 100.0,
 					m.score
 				);
 			} else {
 				println!(
 					"  {}. {} (confidence: {:.0}%, score: {})",
 					i + 1,
-					m.chord.to_string().cyan(),
-					m.completeness * 100.0,
+					m.name.cyan(),
+					m.confidence * 100.0,
 					m.score
 				);
 			}
 		}
 	}
+	print_near_misses(&near);
+
+	Ok(())
+}
+
+fn print_near_misses(near: &[chordcraft_core::analyzer::NearMiss]) {
+	if near.is_empty() {
+		return;
+	}
+	println!("\n{}", "Did you mean:".bold());
+	for miss in near.iter().take(3) {
+		println!(
+			"  {} ({}, string {} moved)",
+			miss.fingering.to_string().cyan(),
+			miss.chord.name,
+			miss.string + 1
+		);
+	}
+}
+
+fn explore(
+	max_fret: u8,
+	no_barres: bool,
+	instrument_choice: InstrumentChoice,
+	tuning: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::tuning::{EasyGrip, ExplorerOptions, explore_tuning};
+
+	let instrument = get_instrument(instrument_choice, tuning)?;
+	let options = ExplorerOptions {
+		max_fret,
+		include_barres: !no_barres,
+	};
+
+	let chords = with_instrument!(&instrument, instr => explore_tuning(instr, &options));
+
+	println!("\n{} [{}]\n", "Easy chords".bold(), instrument.name());
+
+	if chords.is_empty() {
+		println!("{}", "No complete chords found".yellow());
+		return Ok(());
+	}
+
+	for explored in &chords {
+		let grip = match explored.grip {
+			EasyGrip::Open => "open strings".to_string(),
+			EasyGrip::OneFinger => format!("one finger, fret {}", explored.fret),
+			EasyGrip::Barre => format!("barre, fret {}", explored.fret),
+		};
+		println!(
+			"  {:8} {:14} {}",
+			explored.chord.to_string().green().bold(),
+			explored.fingering.to_string(),
+			grip.dimmed()
+		);
+	}
+	println!();
+
+	Ok(())
+}
+
+fn easiest_keys(
+	chords_str: &str,
+	max_capo: u8,
+	limit: usize,
+	instrument_choice: InstrumentChoice,
+	tuning: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::transposition::{self, KeySuggestionOptions};
+
+	let chord_names: Vec<&str> = chords_str.split_whitespace().collect();
+	for name in &chord_names {
+		Chord::parse(name).with_context(|| format!("Invalid chord name: '{name}'"))?;
+	}
+
+	let instrument = get_instrument(instrument_choice, tuning)?;
+	let options = KeySuggestionOptions {
+		max_capo,
+		limit,
+		..Default::default()
+	};
+	let keys = with_instrument!(&instrument, instr => {
+		transposition::easiest_keys(&chord_names, instr, &options)
+	});
+
+	println!(
+		"\n{} {} [{}]\n",
+		"Easiest keys for".bold(),
+		chord_names.join(" ").green().bold(),
+		instrument.name()
+	);
+
+	if keys.is_empty() {
+		println!("{}", "No playable keys found".yellow());
+		return Ok(());
+	}
+
+	for (i, key) in keys.iter().enumerate() {
+		let name = key
+			.key
+			.map(|k| k.to_string())
+			.unwrap_or_else(|| "?".to_string());
+		let capo = if key.capo > 0 {
+			format!(" (capo {}, play {})", key.capo, key.shapes.join(" "))
+		} else {
+			String::new()
+		};
+		println!(
+			"{}. {} {:+} semitones: {}{}",
+			i + 1,
+			name.cyan().bold(),
+			key.semitones,
+			key.chords.join(" ").green(),
+			capo.yellow()
+		);
+		let tabs: Vec<String> = key
+			.progression
+			.fingerings
+			.iter()
+			.map(|f| f.fingering.to_string())
+			.collect();
+		println!(
+			"   {}: {} | {}: {}",
+			"Ease".dimmed(),
+			key.score(),
+			"Fingerings".dimmed(),
+			tabs.join(" ")
+		);
+	}
+	println!();
+
+	Ok(())
+}
+
+fn print_chart(
+	chords_str: &str,
+	beats: Option<f32>,
+	time: u8,
+	width: usize,
+	title: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::chart::Chart;
+	use chordcraft_core::song::{Song, SongSection};
+
+	let beats = beats.unwrap_or(time as f32);
+	let mut section = SongSection::new(None);
+	for name in chords_str.split_whitespace() {
+		let chord = Chord::parse(name).with_context(|| format!("Invalid chord name: '{name}'"))?;
+		section.push(chord, beats);
+	}
+
+	let mut song = Song::new(title);
+	song.beats_per_bar = time.max(1);
+	song.sections.push(section);
+
+	println!("\n{}\n", Chart::from_song(&song).render(width));
+
+	Ok(())
+}
+
+fn convert(
+	fingering_str: &str,
+	from_choice: InstrumentChoice,
+	from_tuning: Option<String>,
+	to_choice: InstrumentChoice,
+	to_tuning: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::conversion::convert_fingering;
+
+	let fingering = Fingering::parse(fingering_str)
+		.with_context(|| format!("Invalid fingering: '{fingering_str}'"))?;
+	let from = get_instrument(from_choice, from_tuning)?;
+	let to = get_instrument(to_choice, to_tuning)?;
+
+	with_instrument!(&from, from_instr => fingering.check_for(from_instr))
+		.with_context(|| format!("'{fingering_str}' doesn't fit a {}", from.name()))?;
+	let converted = with_instrument!(&from, from_instr => {
+		with_instrument!(&to, to_instr => convert_fingering(&fingering, from_instr, to_instr))
+	});
+
+	println!(
+		"\n{} {} [{}] → [{}]\n",
+		"Converting".bold(),
+		fingering_str.cyan(),
+		from.name(),
+		to.name()
+	);
+
+	let Some(converted) = converted else {
+		println!("{}", "No playable fingering found".yellow());
+		return Ok(());
+	};
+
+	println!("  {}", converted.fingering.to_string().green().bold());
+	if converted.is_exact() {
+		println!("  {}", "All notes preserved".dimmed());
+	} else {
+		let lost: Vec<String> = converted.lost_notes.iter().map(|n| n.to_string()).collect();
+		println!("  {} {}", "Octave changes:".yellow(), lost.join(", "));
+	}
+	if !converted.lost_pitch_classes.is_empty() {
+		let lost: Vec<String> = converted
+			.lost_pitch_classes
+			.iter()
+			.map(|p| p.to_string())
+			.collect();
+		println!("  {} {}", "Missing notes:".red(), lost.join(", "));
+	}
+	if !converted.bass_preserved {
+		println!("  {}", "Bass note changed".yellow());
+	}
+	println!();
+
+	Ok(())
+}
+
+fn import_song(
+	path: &Path,
+	by: &str,
+	fingerings: bool,
+	instrument: InstrumentChoice,
+	tuning: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::chart::Chart;
+	use chordcraft_core::chordpro::import_chordpro;
+	use chordcraft_core::midi::{MidiImportOptions, MidiSegmentation, import_midi};
+	use chordcraft_core::musicxml::import_musicxml;
+
+	let extension = path
+		.extension()
+		.map(|e| e.to_string_lossy().to_lowercase())
+		.unwrap_or_default();
+
+	let song = match extension.as_str() {
+		"mid" | "midi" => {
+			let segmentation = match by.to_lowercase().as_str() {
+				"beat" => MidiSegmentation::Beat,
+				"bar" => MidiSegmentation::Bar,
+				other => anyhow::bail!("Unknown granularity '{other}' (expected bar or beat)"),
+			};
+
+			let bytes = std::fs::read(path)
+				.with_context(|| format!("Could not read {}", path.display()))?;
+			import_midi(
+				&bytes,
+				&MidiImportOptions {
+					segmentation,
+					..Default::default()
+				},
+			)
+		}
+		"musicxml" | "xml" => {
+			let xml = std::fs::read_to_string(path)
+				.with_context(|| format!("Could not read {}", path.display()))?;
+			import_musicxml(&xml)
+		}
+		"cho" | "chopro" | "chordpro" | "crd" => {
+			let text = std::fs::read_to_string(path)
+				.with_context(|| format!("Could not read {}", path.display()))?;
+			import_chordpro(&text)
+		}
+		_ => anyhow::bail!(
+			"Unsupported file type '{}' (expected .mid, .musicxml, .xml or .cho)",
+			path.display()
+		),
+	}
+	.with_context(|| format!("Could not import {}", path.display()))?;
+
+	let title = song
+		.title
+		.clone()
+		.unwrap_or_else(|| path.display().to_string());
+	println!("\n{} {}\n", "Imported:".bold(), title.green().bold());
+
+	if song.sections.is_empty() {
+		println!("{}", "No chords found".yellow());
+		return Ok(());
+	}
+
+	let mut chart = Chart::from_song(&song);
+	// The title is already printed above
+	chart.title = None;
+	println!("{chart}\n");
+
+	if fingerings {
+		find_progression(
+			&song.chord_names().join(" "),
+			FindProgressionInstrumentOptions {
+				instrument,
+				voicing: None,
+				context: None,
+				hand: None,
+				accessible: false,
+				level: None,
+				harmonics: false,
+				avoid: vec![],
+				capo: None,
+				tuning,
+			},
+			FindProgressionOptions {
+				limit: 1,
+				max_distance: 3,
+				min_difference: 2,
+				alpha: 0.0,
+				position: None,
+				min_fret: 0,
+				max_fret: 12,
+				bpm: None,
+				time_budget: None,
+				beam_width: None,
+				voice_leading: false,
+				consistent_voicing: false,
+				passing: false,
+				numerals: false,
+				key: None,
+				format: "text".to_string(),
+			},
+		)?;
+	}
+
+	Ok(())
+}
+
+/// How often a watched file is checked for changes
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+/// A change is rendered once the file has been quiet this long (editors often write in bursts)
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// Re-render the song report whenever the file changes, until interrupted
+fn watch_song(path: &Path, instrument: InstrumentChoice, tuning: Option<String>) -> Result<()> {
+	let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+	if modified(path).is_none() {
+		anyhow::bail!("Could not read {}", path.display());
+	}
+
+	let mut rendered = None;
+	loop {
+		let current = modified(path);
+		if current.is_some() && current != rendered {
+			// Wait for the writes to settle before re-reading
+			std::thread::sleep(WATCH_DEBOUNCE);
+			if modified(path) != current {
+				continue;
+			}
+
+			// Clear the screen and move the cursor home
+			print!("\x1b[2J\x1b[H");
+			if let Err(err) = analyze_song(path, instrument, tuning.clone()) {
+				println!("{} {err:#}", "Error:".red().bold());
+			}
+			println!(
+				"{}",
+				format!("Watching {} (Ctrl-C to stop)", path.display()).dimmed()
+			);
+			rendered = current;
+		}
+		std::thread::sleep(WATCH_POLL_INTERVAL);
+	}
+}
+
+fn arrange_for_ensemble(
+	chords_str: &str,
+	instruments: &[InstrumentChoice],
+	max_distance: u8,
+) -> Result<()> {
+	use chordcraft_core::arrangement::{EnsembleOptions, EnsemblePart, arrange_ensemble};
+	use chordcraft_core::instrument::Instrument;
+
+	let chord_names: Vec<&str> = chords_str.split_whitespace().collect();
+	if chord_names.is_empty() {
+		anyhow::bail!("No chords provided");
+	}
+
+	let wrappers: Vec<InstrumentWrapper> = instruments
+		.iter()
+		.map(|&choice| InstrumentWrapper::from_choice(choice))
+		.collect();
+	let parts: Vec<EnsemblePart> = wrappers
+		.iter()
+		.enumerate()
+		.map(|(i, wrapper)| {
+			// Tell apart several players of the same instrument
+			let same = wrappers[..i]
+				.iter()
+				.filter(|w| w.name() == wrapper.name())
+				.count();
+			let name = if same == 0 {
+				wrapper.name().to_string()
+			} else {
+				format!("{} {}", wrapper.name(), same + 1)
+			};
+			EnsemblePart::new(
+				name,
+				with_instrument!(wrapper, instr => instr as &dyn Instrument),
+			)
+		})
+		.collect();
+
+	let options = EnsembleOptions {
+		max_fret_distance: max_distance,
+		..Default::default()
+	};
+
+	println!(
+		"\n{} {}\n",
+		"Ensemble arrangement for:".bold(),
+		chord_names.join(" → ").green().bold()
+	);
+
+	let Some(ensemble) = arrange_ensemble(&chord_names, &parts, &options) else {
+		println!(
+			"{}",
+			"No arrangement found. Every chord must be playable on every instrument; try increasing --max-distance."
+				.yellow()
+		);
+		return Ok(());
+	};
+
+	let name_width = ensemble
+		.part_names
+		.iter()
+		.map(|n| n.len())
+		.max()
+		.unwrap_or(0);
+	let column_width = ensemble
+		.parts
+		.iter()
+		.flatten()
+		.map(|f| f.fingering.to_string().len())
+		.chain(ensemble.chords.iter().map(|c| c.len()))
+		.max()
+		.unwrap_or(0);
+
+	print!("{:name_width$}  ", "");
+	for chord in &ensemble.chords {
+		print!("{:column_width$}  ", chord.bold());
+	}
+	println!();
+	for (name, part) in ensemble.part_names.iter().zip(&ensemble.parts) {
+		print!("{:name_width$}  ", name.cyan());
+		for scored in part {
+			print!("{:column_width$}  ", scored.fingering.to_string());
+		}
+		println!();
+	}
+
+	println!(
+		"\nScore: {}, shared register: {} semitones",
+		ensemble.total_score, ensemble.register_overlap
+	);
+	for (chord, coverage) in ensemble.chords.iter().zip(&ensemble.coverage) {
+		if *coverage < 1.0 {
+			println!(
+				"  {} {chord}: only {:.0}% of the chord tones are played",
+				"Note:".yellow(),
+				coverage * 100.0
+			);
+		}
+	}
+
+	Ok(())
+}
+
+enum FlashcardSource {
+	Chords(String),
+	Shapes,
+	Song(PathBuf),
+}
+
+fn export_flashcards(
+	source: FlashcardSource,
+	voicings: usize,
+	format: &str,
+	output: Option<&Path>,
+	instrument_choice: InstrumentChoice,
+	tuning: Option<String>,
+) -> Result<()> {
+	use chordcraft_core::chordpro::import_chordpro;
+	use chordcraft_core::flashcards::{
+		FlashcardOptions, flashcards_for_chords, flashcards_for_shapes, flashcards_for_song,
+		flashcards_to_csv, flashcards_to_json,
+	};
+	use chordcraft_core::shapes;
+
+	let render = match format.to_lowercase().as_str() {
+		"csv" => flashcards_to_csv,
+		"json" => flashcards_to_json,
+		other => anyhow::bail!("Unknown format '{other}' (expected csv or json)"),
+	};
+
+	let instrument = get_instrument(instrument_choice, tuning)?;
+	let options = FlashcardOptions {
+		voicings_per_chord: voicings,
+		..Default::default()
+	};
+
+	let cards = match source {
+		FlashcardSource::Chords(chords) => {
+			let names: Vec<&str> = chords.split_whitespace().collect();
+			with_instrument!(&instrument, instr => flashcards_for_chords(&names, instr, &options))
+				.context("Could not build the deck")?
+		}
+		FlashcardSource::Shapes => {
+			let library = match instrument_choice {
+				InstrumentChoice::Ukulele
+				| InstrumentChoice::UkuleleLowG
+				| InstrumentChoice::ConcertUkulele
+				| InstrumentChoice::TenorUkulele
+				| InstrumentChoice::TenorUkuleleLowG => shapes::ukulele::ALL_SHAPES,
+				InstrumentChoice::Mandolin => shapes::mandolin::ALL_SHAPES,
+				InstrumentChoice::Banjo => shapes::banjo::ALL_SHAPES,
+				_ => shapes::guitar::ALL_SHAPES,
+			};
+			with_instrument!(&instrument, instr => flashcards_for_shapes(library, instr))
+		}
+		FlashcardSource::Song(path) => {
+			let text = std::fs::read_to_string(&path)
+				.with_context(|| format!("Could not read {}", path.display()))?;
+			let song = import_chordpro(&text)
+				.with_context(|| format!("Could not import {}", path.display()))?;
+			with_instrument!(&instrument, instr => flashcards_for_song(&song, instr, &options))
+		}
+	};
+
+	if cards.is_empty() {
+		anyhow::bail!("No cards to export for {}", instrument.name());
+	}
+
+	let deck = render(&cards);
+	match output {
+		Some(path) => {
+			std::fs::write(path, deck)
+				.with_context(|| format!("Could not write {}", path.display()))?;
+			eprintln!(
+				"{} {} cards to {}",
+				"Exported".green().bold(),
+				cards.len(),
+				path.display()
+			);
+		}
+		None => print!("{deck}"),
+	}
+	Ok(())
+}
+
+fn analyze_song(path: &Path, instrument: InstrumentChoice, tuning: Option<String>) -> Result<()> {
+	use chordcraft_core::report::{SongReportOptions, chordpro_report};
+
+	let text = std::fs::read_to_string(path)
+		.with_context(|| format!("Could not read {}", path.display()))?;
+	let instrument = get_instrument(instrument, tuning)?;
+	let report = with_instrument!(&instrument, instr => {
+		chordpro_report(&text, instr, &SongReportOptions::default())
+	})
+	.with_context(|| format!("Could not import {}", path.display()))?;
+
+	let title = report
+		.title
+		.clone()
+		.unwrap_or_else(|| path.display().to_string());
+	println!(
+		"\n{} {} [{}]\n",
+		"Song:".bold(),
+		title.green().bold(),
+		instrument.name()
+	);
+
+	if report.sections.is_empty() {
+		println!("{}", "No chords found".yellow());
+		return Ok(());
+	}
+
+	if let Some(key) = report.key {
+		println!(
+			"{} {} ({:.0}% of chord tones in key)",
+			"Key:".bold(),
+			key.key.to_string().cyan(),
+			key.fit * 100.0
+		);
+	}
+	for modulation in &report.modulations {
+		let section = modulation
+			.label
+			.clone()
+			.unwrap_or_else(|| format!("section {}", modulation.section + 1));
+		println!(
+			"  {} {} → {} at {section}",
+			"Modulates:".dimmed(),
+			modulation.from,
+			modulation.to.to_string().cyan()
+		);
+	}
+	match report.difficulty {
+		Some(difficulty) => println!("{} {difficulty}/100", "Difficulty:".bold()),
+		None => println!("{} {}", "Difficulty:".bold(), "unplayable".red()),
+	}
+
+	println!("\n{}", "Sections:".bold());
+	for (i, section) in report.sections.iter().enumerate() {
+		let label = section
+			.label
+			.clone()
+			.unwrap_or_else(|| format!("Section {}", i + 1));
+		let difficulty = section
+			.difficulty
+			.map(|d| format!("{d}/100"))
+			.unwrap_or_else(|| "unplayable".to_string());
+		println!("  {} {}", format!("[{label}]").cyan(), difficulty.dimmed());
+		if let Some(progression) = &section.progression {
+			let cells: Vec<String> = progression
+				.chords
+				.iter()
+				.zip(&progression.fingerings)
+				.map(|(chord, f)| format!("{chord} {}", f.fingering))
+				.collect();
+			println!("    {}", cells.join("  "));
+		}
+	}
+
+	if !report.hardest_transitions.is_empty() {
+		println!("\n{}", "Hardest changes:".bold());
+		for hard in &report.hardest_transitions {
+			let t = &hard.transition;
+			println!(
+				"  {} → {}  {} → {}  (~{}ms, {} fingers move)",
+				t.from_chord.green(),
+				t.to_chord.green(),
+				t.from_fingering.fingering,
+				t.to_fingering.fingering,
+				t.estimated_ms,
+				t.finger_movements
+			);
+		}
+	}
+
+	if !report.capo_suggestions.is_empty() || !report.tuning_suggestions.is_empty() {
+		let baseline = report.shape_difficulty.unwrap_or(100);
+		println!(
+			"\n{} {}",
+			"Easier ways to play it:".bold(),
+			format!("(shapes now {baseline}/100)").dimmed()
+		);
+		for capo in &report.capo_suggestions {
+			println!(
+				"  Capo {}: {}  {}",
+				capo.capo.to_string().yellow(),
+				capo.shapes.join(" "),
+				format!("{}/100", capo.difficulty).dimmed()
+			);
+		}
+		for tuning in &report.tuning_suggestions {
+			println!(
+				"  {}  {}",
+				tuning.instrument.name().yellow(),
+				format!("{}/100", tuning.difficulty).dimmed()
+			);
+		}
+	}
+	println!();
 
 	Ok(())
 }
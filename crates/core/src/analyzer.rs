@@ -3,44 +3,545 @@
 //! This module contains the algorithm for identifying chords from
 //! fingering patterns (reverse lookup).
 
-use crate::chord::{Chord, ChordQuality};
-use crate::fingering::Fingering;
-use crate::instrument::Instrument;
-use crate::interval::Interval;
-use crate::note::PitchClass;
-use strum::IntoEnumIterator;
+use crate::chord::{Chord, ChordQuality, NamingProfile};
+use crate::error::{ChordCraftError, Result};
+use crate::fingering::{Fingering, StringState};
+use crate::instrument::{CapoedInstrument, Instrument};
+use crate::interval::{Interval, OCTAVE, UNISON};
+use crate::note::{Note, PitchClass};
+use crate::shapes::ShapeMatch;
+use crate::spelling::Accidentals;
+
+/// Extra score for a rootless voicing in the jazz profile: E-G-Bb-D over E reads
+/// as a rootless C9 rather than Em7b5, but E-G-B-D over E stays Em7 (the fifth
+/// earns the rooted reading its optional-tone points)
+const JAZZ_ROOTLESS_BONUS: u32 = 16;
+
+/// Penalty for a bass from outside the chord (C/B), which then doesn't also
+/// count as an extra note; a little more than an extra note, so Cmaj7/B
+/// reads better than C/B
+const FOREIGN_BASS_PENALTY: u32 = 15;
+
+/// Confidence kept with a chord tone other than the root in the bass (C/E)
+const INVERSION_CONFIDENCE: f32 = 0.9;
+/// Confidence kept with a bass from outside the chord (C/B)
+const FOREIGN_BASS_CONFIDENCE: f32 = 0.75;
+/// Confidence kept when the root is only implied
+const ROOTLESS_CONFIDENCE: f32 = 0.8;
+/// Share of the missing confidence won back by the reading a standard shape
+/// spells (the G of the E shape barred at fret 3)
+const SHAPE_CONFIDENCE_GAIN: f32 = 0.5;
+
+/// Qualities commonly played rootless (the bass player covers the root)
+const ROOTLESS_QUALITIES: [ChordQuality; 8] = [
+	ChordQuality::Dominant9,
+	ChordQuality::Major9,
+	ChordQuality::Minor9,
+	ChordQuality::Dominant13,
+	ChordQuality::Major13,
+	ChordQuality::Minor13,
+	ChordQuality::Dominant7b9,
+	ChordQuality::Dominant7sharp9,
+];
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChordMatch {
 	pub chord: Chord,
+	/// Chord symbol in the analyzer's naming profile
+	pub name: String,
 	pub score: u32,
 	pub root_in_bass: bool,
+	/// Share of the chord's required tones that are sounding (0.0 - 1.0)
 	pub completeness: f32,
+	/// How sure the reading is, for showing to users (0.0 - 1.0)
+	///
+	/// The product of three shares: the chord's required tones that sound
+	/// ([`ChordMatch::completeness`]), the sounding notes the chord explains
+	/// (each non-chord tone lowers it), and how usual the bass is (1.0 for the
+	/// root or an unknown bass, less for an inversion, a bass from outside the
+	/// chord, or an implied root). An idiomatic voicing then wins back half of
+	/// what it lost: when a fingering matches a standard shape, the reading the
+	/// shape spells (G/D for the G shape on a ukulele) is taken to be what the
+	/// player meant. So 1.0 means every note is accounted for in a
+	/// root-position voicing, and two readings of the same notes can be
+	/// compared. Unlike `score`, it doesn't favour more specific chords.
+	pub confidence: f32,
+	/// The root isn't sounding; the chord is named after the implied root
+	pub rootless: bool,
+	/// Standard shape the fingering matches, e.g. the E shape barred at fret 3
+	/// (only when analyzing a fingering)
+	pub shape: Option<ShapeMatch>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzerOptions {
+	/// Naming conventions; the jazz profile also reports rootless voicings
+	pub naming: NamingProfile,
+	/// How to spell the chord names (e.g., flats when playing in Eb)
+	pub accidentals: Accidentals,
 }
 
 pub fn analyze_fingering<I: Instrument>(fingering: &Fingering, instrument: &I) -> Vec<ChordMatch> {
+	analyze_fingering_with(fingering, instrument, &AnalyzerOptions::default())
+}
+
+pub fn analyze_fingering_with<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+	options: &AnalyzerOptions,
+) -> Vec<ChordMatch> {
+	let pitches = fingering.unique_pitch_classes(instrument);
+	let bass_note = fingering.bass_note(instrument).map(|n| n.pitch);
+
+	let shape = ShapeMatch::find(fingering, instrument);
+	let shape_chord = shape.as_ref().and_then(|s| s.chord(instrument));
+	let mut matches = analyze_pitches_with(&pitches, bass_note, options);
+	for m in &mut matches {
+		m.shape = shape.clone();
+		if shape_chord
+			.as_ref()
+			.is_some_and(|c| c.root == m.chord.root && c.quality == m.chord.quality)
+		{
+			m.confidence += (1.0 - m.confidence) * SHAPE_CONFIDENCE_GAIN;
+		}
+	}
+	matches
+}
+
+/// One reading of a shape played with a capo on
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapoMatch {
+	/// What the shape makes as if the capo were the nut (G for 320003)
+	pub shape: ChordMatch,
+	/// The same reading at the pitch that sounds (A with a capo at 2)
+	pub sounding: ChordMatch,
+}
+
+/// Analyze a fingering played with a capo at the given fret
+///
+/// Frets count from the capo, so the tab is the shape. Fails if the instrument
+/// can't take a capo that high or the shape runs past the neck left above it.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::analyzer::{AnalyzerOptions, analyze_fingering_with_capo};
+/// use chordcraft_core::fingering::Fingering;
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let g_shape = Fingering::parse("320003").unwrap();
+/// let matches = analyze_fingering_with_capo(&g_shape, &Guitar::default(), 2, &AnalyzerOptions::default()).unwrap();
+/// assert_eq!(matches[0].shape.name, "G");
+/// assert_eq!(matches[0].sounding.name, "A");
+/// ```
+pub fn analyze_fingering_with_capo<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+	capo: u8,
+	options: &AnalyzerOptions,
+) -> Result<Vec<CapoMatch>> {
+	fingering.check_for(&CapoedInstrument::new(instrument, capo)?)?;
+
+	Ok(analyze_fingering_with(fingering, instrument, options)
+		.into_iter()
+		.map(|shape| {
+			let chord = shape.chord.transpose(capo as i32);
+			let sounding = ChordMatch {
+				name: chord.name(options.naming),
+				chord,
+				..shape.clone()
+			};
+			CapoMatch { shape, sounding }
+		})
+		.collect())
+}
+
+/// What a fingering's notes add up to
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Identification {
+	/// Chords the notes spell, best match first (577xxx is A5)
+	Chords(Vec<ChordMatch>),
+	/// Two notes that make an interval rather than a chord, measured up from
+	/// the bass: C and E is a major 3rd, A and a higher A an octave
+	Dyad {
+		bass: PitchClass,
+		interval: Interval,
+	},
+	/// Nothing sounding, or a single note
+	InsufficientNotes,
+}
+
+/// Identify a fingering, saying why when it isn't a chord
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::analyzer::{AnalyzerOptions, Identification, identify_fingering};
+/// use chordcraft_core::fingering::Fingering;
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let options = AnalyzerOptions::default();
+/// let third = identify_fingering(&Fingering::parse("x32xxx").unwrap(), &Guitar::default(), &options);
+/// assert!(matches!(third, Identification::Dyad { interval, .. } if interval.full_name() == "Major 3rd"));
+/// ```
+pub fn identify_fingering<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+	options: &AnalyzerOptions,
+) -> Identification {
+	let matches = analyze_fingering_with(fingering, instrument, options);
+	if !matches.is_empty() {
+		return Identification::Chords(matches);
+	}
+
+	let mut notes = fingering.notes(instrument);
+	notes.sort_by_key(|n| n.to_midi());
+	notes.dedup();
 	let pitches = fingering.unique_pitch_classes(instrument);
+	match (fingering.bass_note(instrument), pitches.as_slice()) {
+		(Some(bass), [_, _]) => {
+			let upper = pitches.iter().find(|&&p| p != bass.pitch).copied();
+			let semitones = upper.map_or(0, |p| bass.pitch.semitone_distance_to(&p));
+			Identification::Dyad {
+				bass: bass.pitch,
+				interval: Interval::from_semitones(semitones),
+			}
+		}
+		(Some(bass), [_]) if notes.len() > 1 => Identification::Dyad {
+			bass: bass.pitch,
+			interval: OCTAVE,
+		},
+		_ => Identification::InsufficientNotes,
+	}
+}
+
+/// A one-fret correction that turns a fingering into a standard shape
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NearMiss {
+	/// The corrected fingering (x32010 for x32011)
+	pub fingering: Fingering,
+	/// String that moved, lowest string first
+	pub string: usize,
+	/// Best reading of the corrected fingering
+	pub chord: ChordMatch,
+}
 
-	if pitches.is_empty() {
+/// Guess at typos: standard shapes one fret away on a single string
+///
+/// Each played string is moved a fret up and down, and the changes that land
+/// on a standard shape (see [`crate::shapes`]) are kept, best scoring first. A
+/// fingering that already is a standard shape gets none.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::analyzer::{AnalyzerOptions, near_misses};
+/// use chordcraft_core::fingering::Fingering;
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let typo = Fingering::parse("x32011").unwrap();
+/// let fixes = near_misses(&typo, &Guitar::default(), &AnalyzerOptions::default());
+/// assert_eq!(fixes[0].fingering.to_string(), "x32010");
+/// assert_eq!(fixes[0].chord.name, "C");
+/// ```
+pub fn near_misses<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+	options: &AnalyzerOptions,
+) -> Vec<NearMiss> {
+	if ShapeMatch::find(fingering, instrument).is_some() {
 		return vec![];
 	}
 
-	let bass_note = fingering.bass_note(instrument).map(|n| n.pitch);
+	let max_fret = instrument.fret_range().1;
+	let mut found: Vec<NearMiss> = Vec::new();
+	for (string, state) in fingering.strings().iter().enumerate() {
+		let Some(fret) = state.fret() else { continue };
+		let moves = [
+			fret.checked_sub(1),
+			fret.checked_add(1).filter(|&f| f <= max_fret),
+		];
+		for new_fret in moves.into_iter().flatten() {
+			let mut strings = fingering.strings().to_vec();
+			strings[string] = StringState::Fretted(new_fret);
+			let candidate = Fingering::new(strings);
+			if ShapeMatch::find(&candidate, instrument).is_none() {
+				continue;
+			}
+			if let Some(chord) = analyze_fingering_with(&candidate, instrument, options)
+				.into_iter()
+				.next()
+			{
+				found.push(NearMiss {
+					fingering: candidate,
+					string,
+					chord,
+				});
+			}
+		}
+	}
+	found.sort_by_key(|m| std::cmp::Reverse(m.chord.score));
+	found
+}
+
+/// What one played string sounds in a chord
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StringRole {
+	/// String index, lowest string first
+	pub string: usize,
+	pub note: Note,
+	/// Degree above the root, as in the chord's formula (1, b3, 5, b7, 9)
+	pub degree: Interval,
+	/// False for a note the chord doesn't have, like the F# of C/F#
+	pub in_chord: bool,
+}
+
+impl StringRole {
+	/// Label for a chart: "R" for the root, otherwise the degree ("b3", "9")
+	pub fn label(&self) -> String {
+		if self.degree == UNISON {
+			"R".to_string()
+		} else {
+			self.degree.to_degree()
+		}
+	}
+}
+
+/// Note and chord-tone role of every played string, lowest string first
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::analyzer::string_roles;
+/// use chordcraft_core::chord::Chord;
+/// use chordcraft_core::fingering::Fingering;
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let am7 = Fingering::parse("x02010").unwrap();
+/// let chord = Chord::parse("Am7").unwrap();
+/// let roles = string_roles(&am7, &Guitar::default(), &chord);
+/// let labels: Vec<String> = roles.iter().map(|r| r.label()).collect();
+/// assert_eq!(labels, ["R", "5", "b7", "b3", "5"]);
+/// ```
+pub fn string_roles<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+	chord: &Chord,
+) -> Vec<StringRole> {
+	let (required, optional) = chord.intervals();
+	fingering
+		.strings()
+		.iter()
+		.zip(instrument.tuning())
+		.enumerate()
+		.filter_map(|(string, (state, open))| {
+			let note = state.note_above_nut(*open, instrument.string_nut_fret(string))?;
+			let above_root = Interval::from_semitones(chord.root.semitone_distance_to(&note.pitch));
+			let formula_degree = required
+				.iter()
+				.chain(&optional)
+				.find(|d| same_pitch_class(d, &above_root));
+			Some(StringRole {
+				string,
+				note,
+				degree: formula_degree.copied().unwrap_or(above_root),
+				in_chord: formula_degree.is_some(),
+			})
+		})
+		.collect()
+}
+
+/// Identify chords from a list of notes, without a fingering or instrument
+///
+/// Notes are listed lowest first, so the first one is taken as the bass.
+/// Repeated notes are ignored.
+pub fn analyze_notes(notes: &[PitchClass]) -> Vec<ChordMatch> {
+	analyze_notes_with(notes, &AnalyzerOptions::default())
+}
+
+pub fn analyze_notes_with(notes: &[PitchClass], options: &AnalyzerOptions) -> Vec<ChordMatch> {
+	let mut pitches: Vec<PitchClass> = Vec::new();
+	for &note in notes {
+		if !pitches.contains(&note) {
+			pitches.push(note);
+		}
+	}
+	analyze_pitches_with(&pitches, notes.first().copied(), options)
+}
+
+/// Identify chords from MIDI note numbers, as played on a keyboard or read from a MIDI file
+///
+/// Notes can come in any order: the lowest one is the bass, so the inversion
+/// follows the voicing. Doublings in other octaves are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::analyzer::analyze_midi_notes;
+///
+/// // E3, C4, G4: a C major triad over E
+/// let matches = analyze_midi_notes(&[60, 52, 67]);
+/// assert_eq!(matches[0].name, "C/E");
+/// ```
+pub fn analyze_midi_notes(notes: &[u8]) -> Vec<ChordMatch> {
+	analyze_midi_notes_with(notes, &AnalyzerOptions::default())
+}
 
+pub fn analyze_midi_notes_with(notes: &[u8], options: &AnalyzerOptions) -> Vec<ChordMatch> {
+	let mut notes = notes.to_vec();
+	notes.sort_unstable();
+	let pitches: Vec<PitchClass> = notes.iter().map(|&n| Note::from_midi(n).pitch).collect();
+	analyze_notes_with(&pitches, options)
+}
+
+/// Options for chroma analysis
+#[derive(Debug, Clone)]
+pub struct ChromaOptions {
+	/// Bins weaker than this fraction of the strongest bin are ignored (0.0 - 1.0)
+	pub threshold: f32,
+	/// Maximum number of pitch classes considered, strongest first
+	pub max_notes: usize,
+	/// Bass pitch class, if known (e.g., from a separate low-frequency chroma)
+	pub bass: Option<PitchClass>,
+	/// Maximum number of matches to return
+	pub limit: usize,
+}
+
+impl Default for ChromaOptions {
+	fn default() -> Self {
+		ChromaOptions {
+			threshold: 0.3,
+			max_notes: 5,
+			bass: None,
+			limit: 5,
+		}
+	}
+}
+
+/// Identify chords from a 12-bin chroma vector
+///
+/// Bin 0 is C, bin 1 is C#, and so on up to B (the layout used by librosa,
+/// Essentia and most other audio analysis libraries). The vector does not need
+/// to be normalized. Bins above the threshold become the chord's pitch
+/// classes, which are then matched exactly like the notes of a fingering.
+pub fn analyze_chroma(chroma: &[f32; 12], options: &ChromaOptions) -> Vec<ChordMatch> {
+	let peak = chroma
+		.iter()
+		.copied()
+		.filter(|v| v.is_finite())
+		.fold(0.0f32, f32::max);
+	if peak <= 0.0 {
+		return vec![];
+	}
+
+	let mut bins: Vec<(usize, f32)> = chroma
+		.iter()
+		.enumerate()
+		.filter(|(_, v)| v.is_finite() && **v / peak >= options.threshold)
+		.map(|(i, v)| (i, *v))
+		.collect();
+	bins.sort_by(|a, b| b.1.total_cmp(&a.1));
+	bins.truncate(options.max_notes);
+
+	let pitches: Vec<PitchClass> = bins
+		.iter()
+		.map(|(i, _)| PitchClass::from_semitone(*i as u8))
+		.collect();
+
+	let mut matches = analyze_pitches(&pitches, options.bass);
+	matches.truncate(options.limit);
+	matches
+}
+
+/// Match a set of unique pitch classes (plus the sounding bass, if known) against all chord qualities
+pub(crate) fn analyze_pitches(
+	pitches: &[PitchClass],
+	bass_note: Option<PitchClass>,
+) -> Vec<ChordMatch> {
+	analyze_pitches_with(pitches, bass_note, &AnalyzerOptions::default())
+}
+
+pub(crate) fn analyze_pitches_with(
+	pitches: &[PitchClass],
+	bass_note: Option<PitchClass>,
+	options: &AnalyzerOptions,
+) -> Vec<ChordMatch> {
+	if pitches.len() < 2 {
+		return vec![];
+	}
+
+	// Two notes only spell a chord a fifth apart (A and E make A5); any other
+	// pair would be a guess (C and B as Cmaj7), see [`identify_fingering`]
+	let dyad = pitches.len() == 2;
 	let mut matches = Vec::new();
 
-	for root in &pitches {
-		let intervals = calculate_intervals_from_root(*root, &pitches);
+	for root in pitches {
+		let intervals = calculate_intervals_from_root(*root, pitches);
 
-		for quality in ChordQuality::iter() {
+		for quality in ChordQuality::all().filter(|q| !dyad || *q == ChordQuality::Power) {
 			if let Some(chord_match) = try_match_chord(*root, quality, &intervals, bass_note) {
 				matches.push(chord_match);
 			}
 		}
 	}
 
-	matches.sort_by(|a, b| b.score.cmp(&a.score));
-	deduplicate_matches(matches)
+	if options.naming == NamingProfile::Jazz {
+		matches.extend(rootless_matches(pitches));
+	}
+
+	matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+	let mut matches = deduplicate_matches(matches);
+	for m in &mut matches {
+		m.chord.spelling = Some(options.accidentals);
+		m.name = m.chord.name(options.naming);
+	}
+	matches
+}
+
+/// Extended chords whose every tone except the root is sounding, and nothing else
+///
+/// Scored as if the root were present, plus [`JAZZ_ROOTLESS_BONUS`].
+fn rootless_matches(pitches: &[PitchClass]) -> Vec<ChordMatch> {
+	if pitches.len() < 4 {
+		return vec![];
+	}
+
+	(0..12)
+		.map(PitchClass::from_semitone)
+		.filter(|root| !pitches.contains(root))
+		.flat_map(|root| {
+			let intervals = calculate_intervals_from_root(root, pitches);
+			ROOTLESS_QUALITIES.into_iter().filter_map(move |quality| {
+				let (required, optional) = quality.intervals();
+				let in_chord = |i: &Interval| {
+					required
+						.iter()
+						.chain(&optional)
+						.any(|c| same_pitch_class(c, i))
+				};
+				let has_all = required
+					.iter()
+					.filter(|r| !same_pitch_class(r, &UNISON))
+					.all(|r| intervals.iter().any(|i| same_pitch_class(i, r)));
+				if !has_all || !intervals.iter().all(in_chord) {
+					return None;
+				}
+
+				let mut with_root = intervals.clone();
+				with_root.push(UNISON);
+				let mut m = try_match_chord(root, quality, &with_root, None)?;
+				m.score += JAZZ_ROOTLESS_BONUS;
+				m.confidence *= ROOTLESS_CONFIDENCE;
+				m.rootless = true;
+				Some(m)
+			})
+		})
+		.collect()
 }
 
 fn calculate_intervals_from_root(root: PitchClass, pitches: &[PitchClass]) -> Vec<Interval> {
@@ -53,6 +554,11 @@ fn calculate_intervals_from_root(root: PitchClass, pitches: &[PitchClass]) -> Ve
 		.collect()
 }
 
+/// Intervals measured between pitch classes carry no octave, so a 9th is a 2nd
+fn same_pitch_class(a: &Interval, b: &Interval) -> bool {
+	a.to_semitones() % 12 == b.to_semitones() % 12
+}
+
 fn try_match_chord(
 	root: PitchClass,
 	quality: ChordQuality,
@@ -63,7 +569,7 @@ fn try_match_chord(
 
 	let required_present: Vec<_> = required
 		.iter()
-		.filter(|req| intervals.iter().any(|i| i.enharmonic_eq(req)))
+		.filter(|req| intervals.iter().any(|i| same_pitch_class(i, req)))
 		.collect();
 
 	if required_present.len() < 2 {
@@ -71,8 +577,24 @@ fn try_match_chord(
 	}
 
 	let completeness = required_present.len() as f32 / required.len() as f32;
-	let chord = Chord::new(root, quality);
 	let root_in_bass = bass_note == Some(root);
+	let all_chord_intervals: Vec<_> = required.iter().chain(optional.iter()).collect();
+	let in_chord = |interval: &Interval| {
+		all_chord_intervals
+			.iter()
+			.any(|ci| same_pitch_class(ci, interval))
+	};
+
+	// Any other bass makes a slash chord: an inversion (C/E) if it is a chord
+	// tone, otherwise a bass from outside the chord (C/B)
+	let slash_bass = bass_note.filter(|&bass| bass != root);
+	let foreign_bass = slash_bass
+		.map(|bass| Interval::from_semitones(root.semitone_distance_to(&bass)))
+		.filter(|interval| !in_chord(interval));
+	let chord = Chord {
+		bass: slash_bass,
+		..Chord::new(root, quality)
+	};
 
 	let mut score = 0u32;
 	score += (completeness * 100.0) as u32;
@@ -83,20 +605,19 @@ fn try_match_chord(
 
 	let optional_count = optional
 		.iter()
-		.filter(|opt| intervals.iter().any(|i| i.enharmonic_eq(opt)))
+		.filter(|opt| intervals.iter().any(|i| same_pitch_class(i, opt)))
 		.count();
 	score += (optional_count * 5) as u32;
 
-	let all_chord_intervals: Vec<_> = required.iter().chain(optional.iter()).collect();
 	let extra_count = intervals
 		.iter()
-		.filter(|interval| {
-			!all_chord_intervals
-				.iter()
-				.any(|ci| ci.enharmonic_eq(interval))
-		})
+		.filter(|interval| !in_chord(interval))
+		.filter(|interval| foreign_bass.is_none_or(|bass| !same_pitch_class(&bass, interval)))
 		.count();
 	score = score.saturating_sub((extra_count * 10) as u32);
+	if foreign_bass.is_some() {
+		score = score.saturating_sub(FOREIGN_BASS_PENALTY);
+	}
 
 	// Prefer more specific chords (G7 over G when 7th is present)
 	score += (required.len() * 3) as u32;
@@ -108,11 +629,24 @@ fn try_match_chord(
 		}
 	}
 
+	// The foreign bass is named in the symbol, so it's explained but costs
+	// through the bass share instead
+	let explained = 1.0 - extra_count as f32 / intervals.len() as f32;
+	let bass_share = match slash_bass {
+		None => 1.0,
+		Some(_) if foreign_bass.is_some() => FOREIGN_BASS_CONFIDENCE,
+		Some(_) => INVERSION_CONFIDENCE,
+	};
+
 	Some(ChordMatch {
+		name: chord.to_string(),
 		chord,
 		score,
 		root_in_bass,
 		completeness,
+		confidence: completeness * explained * bass_share,
+		rootless: false,
+		shape: None,
 	})
 }
 
@@ -132,10 +666,270 @@ fn deduplicate_matches(mut matches: Vec<ChordMatch>) -> Vec<ChordMatch> {
 	unique
 }
 
+/// A chord an incomplete tab can still become
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartialMatch {
+	pub chord: Chord,
+	pub name: String,
+	/// Core chord tones not sounding yet
+	pub missing_notes: Vec<PitchClass>,
+	/// One way to finish the tab: missing tones on unknown strings, the rest muted
+	pub completion: Fingering,
+}
+
+/// Parse a tab that may be unfinished: `?` marks an unknown string, and
+/// strings beyond the last symbol are unknown too (`x32` on a guitar is `x32???`)
+///
+/// Returns one entry per string, None where unknown.
+pub fn parse_partial_tab<I: Instrument>(
+	input: &str,
+	instrument: &I,
+) -> Result<Vec<Option<StringState>>> {
+	let mut states = Vec::new();
+	for (i, segment) in input.trim().split('?').enumerate() {
+		if i > 0 {
+			states.push(None);
+		}
+		if segment.chars().any(|c| !matches!(c, ' ' | '-')) {
+			let known = Fingering::parse(segment)?;
+			states.extend(known.strings().iter().copied().map(Some));
+		}
+	}
+
+	let string_count = instrument.tuning().len();
+	if states.len() > string_count {
+		return Err(ChordCraftError::InvalidFingering(format!(
+			"{} strings given for a {string_count}-string instrument",
+			states.len()
+		)));
+	}
+	states.resize(string_count, None);
+	Ok(states)
+}
+
+/// Chords still reachable from a partially typed tab, most likely first
+///
+/// A chord qualifies when every sounding note is one of its tones and its
+/// missing core tones fit on distinct unknown strings within the hand's
+/// stretch of the fretted notes. Matches that are already complete come
+/// first, then those missing fewer notes, with the root sounding (ideally in
+/// the bass), then simpler qualities. Returns nothing until a note sounds.
+pub fn analyze_partial_tab<I: Instrument>(
+	input: &str,
+	instrument: &I,
+	limit: usize,
+) -> Result<Vec<PartialMatch>> {
+	let states = parse_partial_tab(input, instrument)?;
+	let tuning = instrument.tuning();
+
+	let sounding: Vec<(usize, PitchClass)> = states
+		.iter()
+		.enumerate()
+		.filter_map(|(i, s)| {
+			let note = s
+				.as_ref()?
+				.note_above_nut(tuning[i], instrument.string_nut_fret(i))?;
+			Some((i, note.pitch))
+		})
+		.collect();
+	let Some(&(bass_string, bass)) = sounding.first() else {
+		return Ok(vec![]);
+	};
+	// An unknown string below the lowest sounding one could still change the bass
+	let bass_settled = states[..bass_string].iter().all(|s| s.is_some());
+
+	let fretted: Vec<u8> = states
+		.iter()
+		.flatten()
+		.filter_map(|s| s.fret())
+		.filter(|&f| f > 0)
+		.collect();
+	let (max_fret, max_stretch) = (instrument.fret_range().1, instrument.max_stretch());
+	let within_reach =
+		|fret: u8| fret == 0 || fretted.iter().all(|&f| f.abs_diff(fret) <= max_stretch);
+	let center = if fretted.is_empty() {
+		0
+	} else {
+		fretted.iter().map(|&f| f as i32).sum::<i32>() / fretted.len() as i32
+	};
+
+	let mut pitches: Vec<PitchClass> = Vec::new();
+	for &(_, pitch) in &sounding {
+		if !pitches.contains(&pitch) {
+			pitches.push(pitch);
+		}
+	}
+	let unknown: Vec<usize> = (0..states.len()).filter(|&i| states[i].is_none()).collect();
+
+	let mut matches: Vec<(PartialMatch, (usize, bool, bool, usize))> = Vec::new();
+	for root in (0..12).map(PitchClass::from_semitone) {
+		for (rank, quality) in ChordQuality::all().enumerate() {
+			let chord = Chord::new(root, quality);
+			let notes = chord.notes();
+			if !pitches.iter().all(|p| notes.contains(p)) {
+				continue;
+			}
+			let missing: Vec<PitchClass> = chord
+				.core_notes()
+				.into_iter()
+				.filter(|n| !pitches.contains(n))
+				.collect();
+			if missing.len() > unknown.len() {
+				continue;
+			}
+
+			// Closest reachable fret for each missing note on each unknown string
+			let options: Vec<Vec<(usize, u8)>> = missing
+				.iter()
+				.map(|note| {
+					unknown
+						.iter()
+						.filter_map(|&string| {
+							let nut = instrument.string_nut_fret(string);
+							(0..=max_fret)
+								.filter(|&f| {
+									(f == 0 || f > nut)
+										&& StringState::Fretted(f)
+											.note_above_nut(tuning[string], nut)
+											.is_some_and(|n| n.pitch == *note)
+										&& within_reach(f)
+								})
+								.min_by_key(|&f| (f as i32 - center).abs())
+								.map(|f| (string, f))
+						})
+						.collect()
+				})
+				.collect();
+			let mut assignment = Vec::new();
+			if !assign_strings(&options, &mut assignment) {
+				continue;
+			}
+
+			let mut completion: Vec<StringState> = states
+				.iter()
+				.map(|s| s.unwrap_or(StringState::Muted))
+				.collect();
+			for &(string, fret) in &assignment {
+				completion[string] = StringState::Fretted(fret);
+			}
+			let completion = Fingering::new(completion);
+			if completion.fret_span() > max_stretch {
+				continue;
+			}
+
+			let key = (
+				missing.len(),
+				!pitches.contains(&root),
+				!(bass_settled && bass == root),
+				rank,
+			);
+			matches.push((
+				PartialMatch {
+					name: chord.to_string(),
+					chord,
+					missing_notes: missing,
+					completion,
+				},
+				key,
+			));
+		}
+	}
+
+	matches.sort_by_key(|(_, key)| *key);
+	Ok(matches.into_iter().map(|(m, _)| m).take(limit).collect())
+}
+
+/// Give each missing note its own unknown string; false if impossible
+fn assign_strings(options: &[Vec<(usize, u8)>], assignment: &mut Vec<(usize, u8)>) -> bool {
+	let Some((first, rest)) = options.split_first() else {
+		return true;
+	};
+	for &(string, fret) in first {
+		if assignment.iter().any(|&(s, _)| s == string) {
+			continue;
+		}
+		assignment.push((string, fret));
+		if assign_strings(rest, assignment) {
+			return true;
+		}
+		assignment.pop();
+	}
+	false
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::instrument::Guitar;
+	use crate::chord::Inversion;
+	use crate::instrument::{Guitar, Ukulele};
+
+	#[test]
+	fn test_analyze_chroma_c_major() {
+		// Strong C, E, G with some low-level spectral leakage
+		let mut chroma = [0.05; 12];
+		chroma[0] = 1.0;
+		chroma[4] = 0.8;
+		chroma[7] = 0.9;
+
+		let matches = analyze_chroma(&chroma, &ChromaOptions::default());
+		assert_eq!(matches[0].chord.root, PitchClass::C);
+		assert_eq!(matches[0].chord.quality, ChordQuality::Major);
+		assert!(!matches[0].root_in_bass);
+	}
+
+	#[test]
+	fn test_analyze_chroma_threshold_and_bass() {
+		// A minor with a faint G that only shows up with a low threshold
+		let mut chroma = [0.0; 12];
+		chroma[9] = 1.0;
+		chroma[0] = 0.7;
+		chroma[4] = 0.9;
+		chroma[7] = 0.2;
+
+		let options = ChromaOptions {
+			bass: Some(PitchClass::A),
+			..Default::default()
+		};
+		let matches = analyze_chroma(&chroma, &options);
+		assert_eq!(matches[0].chord.to_string(), "Am");
+		assert!(matches[0].root_in_bass);
+
+		let options = ChromaOptions {
+			threshold: 0.1,
+			bass: Some(PitchClass::A),
+			..Default::default()
+		};
+		let matches = analyze_chroma(&chroma, &options);
+		assert_eq!(matches[0].chord.to_string(), "Am7");
+	}
+
+	#[test]
+	fn test_analyze_chroma_silence() {
+		assert!(analyze_chroma(&[0.0; 12], &ChromaOptions::default()).is_empty());
+		assert!(analyze_chroma(&[f32::NAN; 12], &ChromaOptions::default()).is_empty());
+	}
+
+	#[test]
+	fn test_analyze_notes() {
+		use PitchClass::*;
+
+		let matches = analyze_notes(&[C, E, G, C, E]);
+		assert_eq!(matches[0].chord.to_string(), "C");
+		assert!(matches[0].root_in_bass);
+
+		let matches = analyze_notes(&[E, G, C]);
+		assert_eq!(matches[0].chord.root, C);
+		assert!(!matches[0].root_in_bass);
+
+		let options = AnalyzerOptions {
+			accidentals: Accidentals::Flats,
+			..Default::default()
+		};
+		let matches = analyze_notes_with(&[ASharp, D, F, GSharp], &options);
+		assert_eq!(matches[0].name, "Bb7");
+		assert!(analyze_notes(&[]).is_empty());
+	}
 
 	#[test]
 	fn test_analyze_c_major() {
@@ -153,6 +947,352 @@ mod tests {
 		assert!(first.root_in_bass);
 	}
 
+	#[test]
+	fn test_slash_chords() {
+		let guitar = Guitar::default();
+		let c_over_e = analyze_fingering(&Fingering::parse("032010").unwrap(), &guitar);
+		assert_eq!(c_over_e[0].name, "C/E");
+		assert_eq!(c_over_e[0].chord.inversion(), Some(Inversion::First));
+		assert!(!c_over_e[0].root_in_bass);
+
+		let c = analyze_fingering(&Fingering::parse("x32010").unwrap(), &guitar);
+		assert_eq!(c[0].name, "C");
+		assert_eq!(c[0].chord.bass, None);
+		assert!(c[0].score > c_over_e[0].score);
+
+		// A bass from outside the chord
+		let notes = [
+			PitchClass::FSharp,
+			PitchClass::C,
+			PitchClass::E,
+			PitchClass::G,
+		];
+		let matches = analyze_notes(&notes);
+		assert_eq!(matches[0].name, "C/F#");
+		assert_eq!(matches[0].chord.inversion(), None);
+		assert!(matches[0].score < c_over_e[0].score);
+
+		// A chord tone of a bigger chord names that chord instead
+		let notes = [PitchClass::B, PitchClass::C, PitchClass::E, PitchClass::G];
+		assert_eq!(analyze_notes(&notes)[0].name, "Cmaj7/B");
+	}
+
+	#[test]
+	fn test_confidence() {
+		let guitar = Guitar::default();
+		let confidence =
+			|tab: &str| analyze_fingering(&Fingering::parse(tab).unwrap(), &guitar)[0].confidence;
+
+		assert_eq!(confidence("x32010"), 1.0);
+		assert_eq!(confidence("032010"), INVERSION_CONFIDENCE);
+		assert_eq!(confidence("577xxx"), 1.0);
+
+		// C with a stray C# explains three of its four notes
+		let stray = analyze_notes(&[
+			PitchClass::C,
+			PitchClass::E,
+			PitchClass::G,
+			PitchClass::CSharp,
+		]);
+		let c = stray.iter().find(|m| m.name == "C").unwrap();
+		assert_eq!(c.completeness, 1.0);
+		assert_eq!(c.confidence, 0.75);
+
+		// Missing tones and a foreign bass both cost, and every reading stays in range
+		let c_over_f_sharp = analyze_notes(&[
+			PitchClass::FSharp,
+			PitchClass::C,
+			PitchClass::E,
+			PitchClass::G,
+		]);
+		assert_eq!(c_over_f_sharp[0].confidence, FOREIGN_BASS_CONFIDENCE);
+		for m in analyze_fingering(&Fingering::parse("x3x010").unwrap(), &guitar) {
+			assert!(m.confidence <= m.completeness);
+			assert!((0.0..=1.0).contains(&m.confidence));
+		}
+
+		let jazz = AnalyzerOptions {
+			naming: NamingProfile::Jazz,
+			..Default::default()
+		};
+		let rootless = analyze_notes_with(
+			&[
+				PitchClass::E,
+				PitchClass::G,
+				PitchClass::ASharp,
+				PitchClass::D,
+			],
+			&jazz,
+		);
+		assert!(rootless[0].rootless);
+		assert_eq!(rootless[0].confidence, ROOTLESS_CONFIDENCE);
+	}
+
+	#[test]
+	fn test_confidence_rewards_standard_shapes() {
+		// The same G/D, as the ukulele's G shape and as an odd voicing
+		let ukulele = Ukulele::default();
+		let reading = |tab: &str| {
+			analyze_fingering(&Fingering::parse(tab).unwrap(), &ukulele)
+				.into_iter()
+				.find(|m| m.name == "G/D")
+				.unwrap()
+		};
+		let (shape, odd) = (reading("0232"), reading("4235"));
+		assert_eq!(shape.shape.as_ref().unwrap().name, "G");
+		assert!(odd.shape.is_none());
+		assert_eq!(odd.confidence, INVERSION_CONFIDENCE);
+		assert!(shape.confidence > odd.confidence);
+		assert_eq!(
+			shape.confidence,
+			INVERSION_CONFIDENCE + (1.0 - INVERSION_CONFIDENCE) * SHAPE_CONFIDENCE_GAIN
+		);
+
+		// Only the reading the shape spells gains, in the key it sounds in
+		let capoed = Ukulele::default().with_capo(2).unwrap();
+		let a = analyze_fingering(&Fingering::parse("0232").unwrap(), &capoed);
+		let a_over_e = a.iter().find(|m| m.name == "A/E").unwrap();
+		assert!(a_over_e.confidence > INVERSION_CONFIDENCE);
+		assert!(
+			a.iter()
+				.filter(|m| m.name != "A/E")
+				.all(|m| m.confidence <= INVERSION_CONFIDENCE)
+		);
+	}
+
+	#[test]
+	fn test_string_roles() {
+		let guitar = Guitar::default();
+		let c9 = Chord::parse("C9").unwrap();
+		let roles = string_roles(&Fingering::parse("x3233x").unwrap(), &guitar, &c9);
+		let labels: Vec<String> = roles.iter().map(|r| r.label()).collect();
+		assert_eq!(labels, ["R", "3", "b7", "9"]);
+		assert_eq!(roles[0].string, 1);
+		assert_eq!(roles[3].note.to_string(), "D4");
+		assert!(roles.iter().all(|r| r.in_chord));
+
+		// Notes outside the chord keep their distance from the root
+		let c_over_f_sharp = Chord::parse("C/F#").unwrap();
+		let roles = string_roles(
+			&Fingering::parse("2x2010").unwrap(),
+			&guitar,
+			&c_over_f_sharp,
+		);
+		assert_eq!(roles[0].label(), "#4");
+		assert!(!roles[0].in_chord);
+		assert!(roles[1..].iter().all(|r| r.in_chord));
+	}
+
+	#[test]
+	fn test_near_misses() {
+		let guitar = Guitar::default();
+		let options = AnalyzerOptions::default();
+		let fixes = |tab: &str| near_misses(&Fingering::parse(tab).unwrap(), &guitar, &options);
+
+		// Down to G7 or up to G
+		let g = fixes("320002");
+		let up = g
+			.iter()
+			.find(|m| m.fingering.to_string() == "320003")
+			.unwrap();
+		assert_eq!(up.string, 5);
+		assert_eq!(up.chord.name, "G");
+		assert!(g.iter().any(|m| m.chord.name == "G7"));
+
+		// A fret too high on the D string of an E
+		assert!(
+			fixes("023100")
+				.iter()
+				.any(|m| m.fingering.to_string() == "022100")
+		);
+
+		// Standard shapes are left alone, and so are voicings far from any
+		assert!(fixes("x32010").is_empty());
+		assert!(fixes("8x998x").is_empty());
+	}
+
+	#[test]
+	fn test_analyze_midi_notes() {
+		// G2, B3, D4, F4: G7 with the root in the bass
+		let g7 = analyze_midi_notes(&[43, 59, 62, 65]);
+		assert_eq!(g7[0].name, "G7");
+		assert!(g7[0].root_in_bass);
+
+		// The lowest note decides the inversion, whatever the order
+		let c_over_g = analyze_midi_notes(&[72, 64, 55, 60]);
+		assert_eq!(c_over_g[0].name, "C/G");
+		assert_eq!(c_over_g[0].chord.inversion(), Some(Inversion::Second));
+
+		assert!(analyze_midi_notes(&[60, 72]).is_empty());
+		assert!(analyze_midi_notes(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_shape_in_matches() {
+		let guitar = Guitar::default();
+		let g = analyze_fingering(&Fingering::parse("355433").unwrap(), &guitar);
+		assert_eq!(g[0].name, "G");
+		let shape = g[0].shape.as_ref().unwrap();
+		assert_eq!((shape.name.as_str(), shape.fret), ("E", 3));
+
+		let odd = analyze_fingering(&Fingering::parse("8x998x").unwrap(), &guitar);
+		assert!(odd.iter().all(|m| m.shape.is_none()));
+
+		// Retuned strings get no standard shape names, nor near misses to them
+		let drop_d = crate::instrument::ConfigurableInstrument::by_name("drop-d").unwrap();
+		let low_g = analyze_fingering(&Fingering::parse("555433").unwrap(), &drop_d);
+		assert_eq!(low_g[0].name, "G");
+		assert!(low_g[0].shape.is_none());
+		let options = AnalyzerOptions::default();
+		assert!(near_misses(&Fingering::parse("x32011").unwrap(), &drop_d, &options).is_empty());
+		assert!(
+			analyze_notes(&[PitchClass::C, PitchClass::E, PitchClass::G])[0]
+				.shape
+				.is_none()
+		);
+	}
+
+	#[test]
+	fn test_dyads() {
+		let guitar = Guitar::default();
+		let options = AnalyzerOptions::default();
+		let identify =
+			|tab: &str| identify_fingering(&Fingering::parse(tab).unwrap(), &guitar, &options);
+
+		match identify("577xxx") {
+			Identification::Chords(matches) => assert_eq!(matches[0].name, "A5"),
+			other => panic!("expected A5, got {other:?}"),
+		}
+		// A fourth is a power chord over its fifth
+		match identify("x33xxx") {
+			Identification::Chords(matches) => assert_eq!(matches[0].name, "F5/C"),
+			other => panic!("expected F5/C, got {other:?}"),
+		}
+		// Other pairs are intervals, not guesses like Cmaj7
+		assert!(analyze_fingering(&Fingering::parse("x39xxx").unwrap(), &guitar).is_empty());
+		assert!(matches!(
+			identify("x39xxx"),
+			Identification::Dyad { bass: PitchClass::C, interval } if interval.full_name() == "Major 7th"
+		));
+		assert!(matches!(
+			identify("x0x2xx"),
+			Identification::Dyad {
+				bass: PitchClass::A,
+				interval: OCTAVE
+			}
+		));
+
+		assert!(matches!(
+			identify("x0xxxx"),
+			Identification::InsufficientNotes
+		));
+		assert!(matches!(
+			identify("xxxxxx"),
+			Identification::InsufficientNotes
+		));
+		// The same note twice is still one note
+		assert!(matches!(
+			identify("xxx40x"),
+			Identification::InsufficientNotes
+		));
+	}
+
+	#[test]
+	fn test_analyze_with_capo() {
+		let guitar = Guitar::default();
+		let flats = AnalyzerOptions {
+			accidentals: Accidentals::Flats,
+			..Default::default()
+		};
+		let em7 = Fingering::parse("022030").unwrap();
+		let matches = analyze_fingering_with_capo(&em7, &guitar, 6, &flats).unwrap();
+		assert_eq!(matches[0].shape.name, "Em7");
+		assert_eq!(matches[0].sounding.name, "Bbm7");
+		assert_eq!(matches[0].sounding.score, matches[0].shape.score);
+
+		// No capo leaves the shape as it sounds
+		let matches = analyze_fingering_with_capo(&em7, &guitar, 0, &flats).unwrap();
+		assert_eq!(matches[0].sounding.name, matches[0].shape.name);
+
+		// A shape past the neck above the capo, or a capo past the neck
+		let high = Fingering::parse("xx(20)(20)(20)x").unwrap();
+		assert!(analyze_fingering_with_capo(&high, &guitar, 5, &flats).is_err());
+		assert!(analyze_fingering_with_capo(&em7, &guitar, 30, &flats).is_err());
+	}
+
+	#[test]
+	fn test_naming_profiles() {
+		let guitar = Guitar::default();
+		let cadd9 = Fingering::parse("x32030").unwrap();
+
+		let pop = AnalyzerOptions {
+			naming: NamingProfile::Pop,
+			..Default::default()
+		};
+		assert_eq!(analyze_fingering(&cadd9, &guitar)[0].name, "Cadd9");
+		assert_eq!(analyze_fingering_with(&cadd9, &guitar, &pop)[0].name, "C2");
+
+		let jazz = AnalyzerOptions {
+			naming: NamingProfile::Jazz,
+			..Default::default()
+		};
+		let cmaj9 = Fingering::parse("x3243x").unwrap();
+		assert_eq!(
+			analyze_fingering_with(&cmaj9, &guitar, &jazz)[0].name,
+			"C∆9"
+		);
+	}
+
+	#[test]
+	fn test_flat_spelling() {
+		let guitar = Guitar::default();
+		let abm7 = Fingering::parse("464444").unwrap();
+		assert_eq!(analyze_fingering(&abm7, &guitar)[0].name, "G#m7");
+
+		let flats = AnalyzerOptions {
+			accidentals: Accidentals::Flats,
+			..Default::default()
+		};
+		let matches = analyze_fingering_with(&abm7, &guitar, &flats);
+		assert_eq!(matches[0].name, "Abm7");
+		assert_eq!(matches[0].chord.to_string(), "Abm7");
+	}
+
+	#[test]
+	fn test_jazz_rootless_voicings() {
+		use PitchClass::*;
+		let jazz = AnalyzerOptions {
+			naming: NamingProfile::Jazz,
+			..Default::default()
+		};
+
+		// E-G-Bb-D over E: Em7b5 by default, a rootless C9 to a jazz player
+		let pitches = [E, G, ASharp, D];
+		let standard = analyze_pitches(&pitches, Some(E));
+		assert_eq!(standard[0].name, "Em7b5");
+		assert!(standard.iter().all(|m| !m.rootless));
+
+		let matches = analyze_pitches_with(&pitches, Some(E), &jazz);
+		assert_eq!(matches[0].name, "C9");
+		assert!(matches[0].rootless);
+		assert!(!matches[0].root_in_bass);
+
+		// A plain minor seventh stays rooted
+		let matches = analyze_pitches_with(&[E, G, B, D], Some(E), &jazz);
+		assert_eq!(matches[0].name, "E-7");
+		assert!(!matches[0].rootless);
+	}
+
+	#[test]
+	fn test_analyze_with_harmonics() {
+		let guitar = Guitar::default();
+		let fingering = Fingering::parse("0<7>2<12>00").unwrap();
+
+		let matches = analyze_fingering(&fingering, &guitar);
+		assert_eq!(matches[0].chord.to_string(), "Em");
+		assert!(matches[0].root_in_bass);
+	}
+
 	#[test]
 	fn test_analyze_am() {
 		let guitar = Guitar::default();
@@ -293,4 +1433,35 @@ mod tests {
 				.collect::<Vec<_>>()
 		);
 	}
+
+	#[test]
+	fn test_partial_tab_suggests_completions() {
+		let guitar = Guitar::default();
+
+		// "x32" reads as x32???: C and E sound, so C major is one G away
+		let matches = analyze_partial_tab("x32", &guitar, 10).unwrap();
+		let c = matches
+			.iter()
+			.find(|m| m.chord == Chord::new(PitchClass::C, ChordQuality::Major))
+			.expect("C should still be possible");
+		assert_eq!(c.missing_notes, vec![PitchClass::G]);
+		assert_eq!(
+			&c.completion.strings()[..3],
+			Fingering::parse("x32").unwrap().strings()
+		);
+		let completed = analyze_fingering(&c.completion, &guitar);
+		assert_eq!(completed[0].chord, c.chord);
+
+		// A finished chord ranks first with nothing missing
+		let matches = analyze_partial_tab("x3201?", &guitar, 3).unwrap();
+		assert_eq!(matches[0].name, "C");
+		assert!(matches[0].missing_notes.is_empty());
+
+		assert!(
+			analyze_partial_tab("xx????", &guitar, 5)
+				.unwrap()
+				.is_empty()
+		);
+		assert!(analyze_partial_tab("x3200011", &guitar, 5).is_err());
+	}
 }
@@ -0,0 +1,672 @@
+//! Multi-part arrangements
+//!
+//! This module coordinates fingerings for several players playing the same
+//! chord chart, e.g. a guitar duo where one part stays low and open while the
+//! other plays compact voicings higher up (often capoed), or a whole ensemble
+//! (guitar, ukulele and bass) whose parts keep to their own registers and
+//! share out the chord tones.
+
+use crate::chord::Chord;
+use crate::generator::{GeneratorOptions, PlayingContext, ScoredFingering, generate_fingerings};
+use crate::instrument::Instrument;
+use crate::note::{Note, PitchClass};
+use crate::progression::transition_metrics;
+
+/// Penalty per semitone of shared pitch range between the two parts
+const OVERLAP_PENALTY: i32 = 6;
+
+/// Bonus when the high part sits entirely above the low part
+const SEPARATION_BONUS: i32 = 40;
+
+/// Bonus per chord tone sounded by at least one ensemble part
+const COVERAGE_BONUS: i32 = 25;
+
+#[derive(Debug, Clone)]
+pub struct DuoOptions {
+	/// Number of alternative arrangements to return
+	pub limit: usize,
+	/// Candidate fingerings generated per chord for each part
+	pub candidates_per_chord: usize,
+	/// Maximum position jump allowed between consecutive chords within a part
+	pub max_fret_distance: u8,
+	/// Generator options for the low part
+	pub low_options: GeneratorOptions,
+	/// Generator options for the high part
+	pub high_options: GeneratorOptions,
+}
+
+impl Default for DuoOptions {
+	fn default() -> Self {
+		DuoOptions {
+			limit: 3,
+			candidates_per_chord: 8,
+			max_fret_distance: 4,
+			low_options: GeneratorOptions::default(),
+			// Band context already favors compact voicings off the bass strings
+			high_options: GeneratorOptions {
+				playing_context: PlayingContext::Band,
+				..Default::default()
+			},
+		}
+	}
+}
+
+/// Two coordinated fingering sequences for the same chord list
+#[derive(Debug, Clone)]
+pub struct DuoArrangement {
+	pub chords: Vec<String>,
+	pub low: Vec<ScoredFingering>,
+	pub high: Vec<ScoredFingering>,
+	pub total_score: i32,
+	/// Total semitones of pitch range shared by both parts, summed over all chords
+	pub register_overlap: u32,
+}
+
+/// Arrange a chord list for two players
+///
+/// Both parts get their own voicing lattice; the optimizer picks one fingering
+/// per chord for each part, maximizing each part's fingering and transition
+/// scores while penalizing overlap between the parts' pitch ranges. For a
+/// capoed high part, pass a [`crate::instrument::CapoedInstrument`] as `high`.
+pub fn arrange_duo<L: Instrument, H: Instrument>(
+	chord_names: &[&str],
+	low: &L,
+	high: &H,
+	options: &DuoOptions,
+) -> Vec<DuoArrangement> {
+	let chords: Vec<(&str, Chord)> = chord_names
+		.iter()
+		.filter_map(|name| Chord::parse(name).ok().map(|c| (*name, c)))
+		.collect();
+
+	if chords.is_empty() {
+		return vec![];
+	}
+
+	let mut low_opts = options.low_options.clone();
+	low_opts.limit = options.candidates_per_chord;
+	let mut high_opts = options.high_options.clone();
+	high_opts.limit = options.candidates_per_chord;
+
+	let mut low_lattice = Vec::new();
+	let mut high_lattice = Vec::new();
+	for (_, chord) in &chords {
+		let l = generate_fingerings(chord, low, &low_opts);
+		let h = generate_fingerings(chord, high, &high_opts);
+		if l.is_empty() || h.is_empty() {
+			return vec![];
+		}
+		low_lattice.push(l);
+		high_lattice.push(h);
+	}
+
+	// Viterbi over (low, high) pairs: best path ending in each pair state
+	let pair_score = |step: usize, a: usize, b: usize| -> (i32, u32) {
+		let l = &low_lattice[step][a];
+		let h = &high_lattice[step][b];
+		let overlap = register_overlap(l, low, h, high);
+		let mut score = l.score as i32 + h.score as i32 - overlap as i32 * OVERLAP_PENALTY;
+		if overlap == 0 {
+			score += SEPARATION_BONUS;
+		}
+		(score, overlap)
+	};
+
+	struct PathState {
+		score: i32,
+		overlap: u32,
+		path: Vec<(usize, usize)>,
+	}
+
+	let mut states: Vec<PathState> = Vec::new();
+	for a in 0..low_lattice[0].len() {
+		for b in 0..high_lattice[0].len() {
+			let (score, overlap) = pair_score(0, a, b);
+			states.push(PathState {
+				score,
+				overlap,
+				path: vec![(a, b)],
+			});
+		}
+	}
+
+	for step in 1..chords.len() {
+		let mut next_states = Vec::new();
+		for a in 0..low_lattice[step].len() {
+			for b in 0..high_lattice[step].len() {
+				let to_low = &low_lattice[step][a];
+				let to_high = &high_lattice[step][b];
+
+				let best = states
+					.iter()
+					.filter_map(|state| {
+						let (pa, pb) = *state.path.last().unwrap();
+						let from_low = &low_lattice[step - 1][pa];
+						let from_high = &high_lattice[step - 1][pb];
+
+						let low_move = transition_metrics(
+							&from_low.fingering,
+							from_low.position,
+							&to_low.fingering,
+							to_low.position,
+							low,
+							options.low_options.playing_context,
+						);
+						let high_move = transition_metrics(
+							&from_high.fingering,
+							from_high.position,
+							&to_high.fingering,
+							to_high.position,
+							high,
+							options.high_options.playing_context,
+						);
+
+						if low_move.distance > options.max_fret_distance
+							|| high_move.distance > options.max_fret_distance
+						{
+							return None;
+						}
+
+						Some((state, state.score + low_move.score + high_move.score))
+					})
+					.max_by_key(|(_, score)| *score);
+
+				if let Some((prev, score)) = best {
+					let (pair, overlap) = pair_score(step, a, b);
+					let mut path = prev.path.clone();
+					path.push((a, b));
+					next_states.push(PathState {
+						score: score + pair,
+						overlap: prev.overlap + overlap,
+						path,
+					});
+				}
+			}
+		}
+
+		if next_states.is_empty() {
+			return vec![];
+		}
+		states = next_states;
+	}
+
+	states.sort_by_key(|s| std::cmp::Reverse(s.score));
+	states.truncate(options.limit);
+
+	let names: Vec<String> = chords.iter().map(|(name, _)| name.to_string()).collect();
+	states
+		.into_iter()
+		.map(|state| DuoArrangement {
+			chords: names.clone(),
+			low: state
+				.path
+				.iter()
+				.enumerate()
+				.map(|(i, &(a, _))| low_lattice[i][a].clone())
+				.collect(),
+			high: state
+				.path
+				.iter()
+				.enumerate()
+				.map(|(i, &(_, b))| high_lattice[i][b].clone())
+				.collect(),
+			total_score: state.score,
+			register_overlap: state.overlap,
+		})
+		.collect()
+}
+
+/// One player of an ensemble
+#[derive(Clone)]
+pub struct EnsemblePart<'a> {
+	pub name: String,
+	pub instrument: &'a dyn Instrument,
+	pub options: GeneratorOptions,
+}
+
+impl<'a> EnsemblePart<'a> {
+	pub fn new(name: impl Into<String>, instrument: &'a dyn Instrument) -> Self {
+		EnsemblePart {
+			name: name.into(),
+			instrument,
+			options: GeneratorOptions::default(),
+		}
+	}
+
+	pub fn with_options(mut self, options: GeneratorOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct EnsembleOptions {
+	/// Candidate fingerings generated per chord for each part
+	pub candidates_per_chord: usize,
+	/// Maximum position jump allowed between consecutive chords within a part
+	pub max_fret_distance: u8,
+	/// Rounds of re-optimizing each part against the others
+	pub refinement_passes: usize,
+}
+
+impl Default for EnsembleOptions {
+	fn default() -> Self {
+		EnsembleOptions {
+			candidates_per_chord: 8,
+			max_fret_distance: 4,
+			refinement_passes: 3,
+		}
+	}
+}
+
+/// Coordinated fingering sequences for every part of an ensemble
+#[derive(Debug, Clone)]
+pub struct EnsembleArrangement {
+	pub chords: Vec<String>,
+	/// Part names, in the order the parts were given
+	pub part_names: Vec<String>,
+	/// Fingering sequence of each part
+	pub parts: Vec<Vec<ScoredFingering>>,
+	pub total_score: i32,
+	/// Total semitones of pitch range shared by any two parts, summed over all chords
+	pub register_overlap: u32,
+	/// Share of each chord's tones sounded by at least one part (0.0 - 1.0)
+	pub coverage: Vec<f32>,
+}
+
+/// Arrange a chord list for any number of players
+///
+/// Like [`arrange_duo`], but the joint lattice of several parts is too large
+/// to search exhaustively. Instead each part is optimized in turn with the
+/// others held fixed, rewarding register separation from the other parts and
+/// chord tones they don't already cover, until no part changes. Returns None
+/// if some chord has no playable fingering on some part.
+pub fn arrange_ensemble(
+	chord_names: &[&str],
+	parts: &[EnsemblePart],
+	options: &EnsembleOptions,
+) -> Option<EnsembleArrangement> {
+	let chords: Vec<(&str, Chord)> = chord_names
+		.iter()
+		.filter_map(|name| Chord::parse(name).ok().map(|c| (*name, c)))
+		.collect();
+
+	if chords.is_empty() || parts.is_empty() {
+		return None;
+	}
+
+	// lattices[part][step] holds the candidates of that part for that chord
+	let mut lattices: Vec<Vec<Vec<ScoredFingering>>> = Vec::new();
+	for part in parts {
+		let opts = GeneratorOptions {
+			limit: options.candidates_per_chord,
+			..part.options.clone()
+		};
+		let mut lattice = Vec::new();
+		for (_, chord) in &chords {
+			let candidates = generate_fingerings(chord, &part.instrument, &opts);
+			if candidates.is_empty() {
+				return None;
+			}
+			lattice.push(candidates);
+		}
+		lattices.push(lattice);
+	}
+
+	let pitch_classes: Vec<Vec<Vec<Vec<PitchClass>>>> = lattices
+		.iter()
+		.zip(parts)
+		.map(|(lattice, part)| {
+			lattice
+				.iter()
+				.map(|step| {
+					step.iter()
+						.map(|f| f.fingering.unique_pitch_classes(&part.instrument))
+						.collect()
+				})
+				.collect()
+		})
+		.collect();
+
+	let mut paths: Vec<Option<Vec<usize>>> = vec![None; parts.len()];
+	for _ in 0..=options.refinement_passes {
+		let mut changed = false;
+		for p in 0..parts.len() {
+			// Score of each candidate against the parts already placed
+			let unary: Vec<Vec<i32>> = (0..chords.len())
+				.map(|step| {
+					let others: Vec<(usize, usize)> = paths
+						.iter()
+						.enumerate()
+						.filter(|(q, _)| *q != p)
+						.filter_map(|(q, path)| Some((q, path.as_ref()?[step])))
+						.collect();
+					let covered: Vec<PitchClass> = others
+						.iter()
+						.flat_map(|&(q, c)| pitch_classes[q][step][c].iter().copied())
+						.collect();
+
+					(0..lattices[p][step].len())
+						.map(|c| {
+							let candidate = &lattices[p][step][c];
+							let pair_terms: i32 = others
+								.iter()
+								.map(|&(q, qc)| {
+									pair_term(register_overlap(
+										candidate,
+										&parts[p].instrument,
+										&lattices[q][step][qc],
+										&parts[q].instrument,
+									))
+								})
+								.sum();
+							let new_tones = pitch_classes[p][step][c]
+								.iter()
+								.filter(|pc| !covered.contains(pc))
+								.count() as i32;
+							candidate.score as i32 + pair_terms + new_tones * COVERAGE_BONUS
+						})
+						.collect()
+				})
+				.collect();
+
+			let path = best_path(
+				&lattices[p],
+				&unary,
+				&parts[p].instrument,
+				parts[p].options.playing_context,
+				options.max_fret_distance,
+			)?;
+			if paths[p].as_ref() != Some(&path) {
+				paths[p] = Some(path);
+				changed = true;
+			}
+		}
+		if !changed {
+			break;
+		}
+	}
+	let paths: Vec<Vec<usize>> = paths.into_iter().collect::<Option<_>>()?;
+
+	let chosen: Vec<Vec<ScoredFingering>> = paths
+		.iter()
+		.enumerate()
+		.map(|(p, path)| {
+			path.iter()
+				.enumerate()
+				.map(|(step, &c)| lattices[p][step][c].clone())
+				.collect()
+		})
+		.collect();
+
+	let mut total_score = 0;
+	for (part, sequence) in parts.iter().zip(&chosen) {
+		total_score += sequence.iter().map(|f| f.score as i32).sum::<i32>();
+		total_score += sequence
+			.windows(2)
+			.map(|w| {
+				transition_metrics(
+					&w[0].fingering,
+					w[0].position,
+					&w[1].fingering,
+					w[1].position,
+					&part.instrument,
+					part.options.playing_context,
+				)
+				.score
+			})
+			.sum::<i32>();
+	}
+
+	let mut register = 0;
+	let mut coverage = Vec::new();
+	for (step, (_, chord)) in chords.iter().enumerate() {
+		for p in 0..parts.len() {
+			for q in p + 1..parts.len() {
+				let overlap = register_overlap(
+					&chosen[p][step],
+					&parts[p].instrument,
+					&chosen[q][step],
+					&parts[q].instrument,
+				);
+				register += overlap;
+				total_score += pair_term(overlap);
+			}
+		}
+
+		let tones = chord.notes();
+		let sounded = tones
+			.iter()
+			.filter(|t| {
+				(0..parts.len()).any(|p| pitch_classes[p][step][paths[p][step]].contains(t))
+			})
+			.count();
+		total_score += sounded as i32 * COVERAGE_BONUS;
+		coverage.push(sounded as f32 / tones.len() as f32);
+	}
+
+	Some(EnsembleArrangement {
+		chords: chords.iter().map(|(name, _)| name.to_string()).collect(),
+		part_names: parts.iter().map(|p| p.name.clone()).collect(),
+		parts: chosen,
+		total_score,
+		register_overlap: register,
+		coverage,
+	})
+}
+
+/// Score between two parts sounding together, from their shared range
+fn pair_term(overlap: u32) -> i32 {
+	if overlap == 0 {
+		SEPARATION_BONUS
+	} else {
+		-(overlap as i32) * OVERLAP_PENALTY
+	}
+}
+
+/// Best candidate per step for one part (Viterbi), given each candidate's own score
+fn best_path<I: Instrument>(
+	lattice: &[Vec<ScoredFingering>],
+	unary: &[Vec<i32>],
+	instrument: &I,
+	playing_context: PlayingContext,
+	max_fret_distance: u8,
+) -> Option<Vec<usize>> {
+	let mut scores: Vec<Option<i32>> = unary[0].iter().copied().map(Some).collect();
+	let mut back: Vec<Vec<usize>> = Vec::new();
+
+	for step in 1..lattice.len() {
+		let mut next = Vec::new();
+		let mut pointers = Vec::new();
+		for (c, to) in lattice[step].iter().enumerate() {
+			let best = lattice[step - 1]
+				.iter()
+				.enumerate()
+				.filter_map(|(prev, from)| {
+					let metrics = transition_metrics(
+						&from.fingering,
+						from.position,
+						&to.fingering,
+						to.position,
+						instrument,
+						playing_context,
+					);
+					(metrics.distance <= max_fret_distance)
+						.then_some((prev, scores[prev]? + metrics.score))
+				})
+				.max_by_key(|(_, score)| *score);
+			next.push(best.map(|(_, score)| score + unary[step][c]));
+			pointers.push(best.map_or(0, |(prev, _)| prev));
+		}
+		scores = next;
+		back.push(pointers);
+	}
+
+	let (mut c, _) = scores
+		.iter()
+		.enumerate()
+		.filter_map(|(c, s)| Some((c, (*s)?)))
+		.max_by_key(|(_, score)| *score)?;
+	let mut path = vec![c];
+	for pointers in back.iter().rev() {
+		c = pointers[c];
+		path.push(c);
+	}
+	path.reverse();
+	Some(path)
+}
+
+/// Semitones of pitch range shared by two sounding fingerings
+pub(crate) fn register_overlap<A: Instrument, B: Instrument>(
+	a: &ScoredFingering,
+	a_instrument: &A,
+	b: &ScoredFingering,
+	b_instrument: &B,
+) -> u32 {
+	let range = |notes: Vec<Note>| {
+		let midi = notes.iter().map(|n| n.to_midi());
+		(midi.clone().min().unwrap_or(0), midi.max().unwrap_or(0))
+	};
+
+	let (a_min, a_max) = range(a.fingering.sounding_notes(a_instrument));
+	let (b_min, b_max) = range(b.fingering.sounding_notes(b_instrument));
+
+	(a_max.min(b_max) as i32 - a_min.max(b_min) as i32).max(0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+
+	#[test]
+	fn test_arrange_duo_basic() {
+		let guitar = Guitar::default();
+		let capoed = guitar.with_capo(5).unwrap();
+		let chords = vec!["C", "Am", "F", "G"];
+
+		let arrangements = arrange_duo(&chords, &guitar, &capoed, &DuoOptions::default());
+		assert!(!arrangements.is_empty());
+
+		let best = &arrangements[0];
+		assert_eq!(best.low.len(), 4);
+		assert_eq!(best.high.len(), 4);
+		for (i, (l, h)) in best.low.iter().zip(&best.high).enumerate() {
+			println!(
+				"{}: low {} | high {} (capo 5)",
+				best.chords[i], l.fingering, h.fingering
+			);
+		}
+	}
+
+	#[test]
+	fn test_arrange_duo_parts_sound_the_chord() {
+		let guitar = Guitar::default();
+		let capoed = guitar.with_capo(7).unwrap();
+		let chords = vec!["G", "D", "Em", "C"];
+
+		let arrangements = arrange_duo(&chords, &guitar, &capoed, &DuoOptions::default());
+		let best = &arrangements[0];
+
+		for (i, name) in chords.iter().enumerate() {
+			let chord = Chord::parse(name).unwrap();
+			let notes = chord.notes();
+			for pc in best.low[i].fingering.unique_pitch_classes(&guitar) {
+				assert!(notes.contains(&pc));
+			}
+			for pc in best.high[i].fingering.unique_pitch_classes(&capoed) {
+				assert!(notes.contains(&pc));
+			}
+		}
+	}
+
+	#[test]
+	fn test_arrange_duo_minimizes_overlap() {
+		let guitar = Guitar::default();
+		let capoed = guitar.with_capo(7).unwrap();
+		let chords = vec!["C", "G"];
+
+		let arrangements = arrange_duo(&chords, &guitar, &capoed, &DuoOptions::default());
+		assert!(!arrangements.is_empty());
+
+		// Parts stay mostly apart: well under an octave of shared range per chord
+		let best_overlap = arrangements[0].register_overlap;
+		println!("Best overlap: {best_overlap}");
+		assert!(best_overlap <= 12);
+	}
+
+	#[test]
+	fn test_register_overlap() {
+		let guitar = Guitar::default();
+		let scored = |tab: &str| ScoredFingering {
+			fingering: crate::fingering::Fingering::parse(tab).unwrap(),
+			score: 0,
+			voicing_type: crate::chord::VoicingType::Full,
+			has_root_in_bass: true,
+			position: 0,
+			fingers: None,
+			difficulty: Default::default(),
+			omitted: Vec::new(),
+		};
+
+		// Low open C (C3-C4) vs C triad on the top strings (C4-G4)
+		let low = scored("x3201x");
+		let high = scored("xxx553");
+		assert_eq!(register_overlap(&low, &guitar, &high, &guitar), 0);
+
+		// Identical fingerings overlap over their whole range
+		let full = scored("x32010");
+		assert_eq!(register_overlap(&full, &guitar, &full, &guitar), 16);
+	}
+
+	#[test]
+	fn test_arrange_ensemble_guitar_ukulele_bass() {
+		let guitar = Guitar::default();
+		let ukulele = crate::instrument::Ukulele::default();
+		let bass = crate::instrument::ConfigurableInstrument::bass();
+		let parts = [
+			EnsemblePart::new("Guitar", &guitar),
+			EnsemblePart::new("Ukulele", &ukulele),
+			EnsemblePart::new("Bass", &bass),
+		];
+		let chords = vec!["C", "Am", "F", "G"];
+
+		let ensemble = arrange_ensemble(&chords, &parts, &EnsembleOptions::default()).unwrap();
+		assert_eq!(ensemble.part_names, vec!["Guitar", "Ukulele", "Bass"]);
+		assert!(ensemble.parts.iter().all(|p| p.len() == 4));
+		assert!(ensemble.coverage.iter().all(|&c| c == 1.0));
+
+		// Coordinating beats letting every part pick its own favourite
+		let favourites: u32 = chords
+			.iter()
+			.map(|name| {
+				let chord = Chord::parse(name).unwrap();
+				let g = &generate_fingerings(&chord, &guitar, &GeneratorOptions::default())[0];
+				let u = &generate_fingerings(&chord, &ukulele, &GeneratorOptions::default())[0];
+				let b = &generate_fingerings(&chord, &bass, &GeneratorOptions::default())[0];
+				register_overlap(g, &guitar, u, &ukulele)
+					+ register_overlap(g, &guitar, b, &bass)
+					+ register_overlap(u, &ukulele, b, &bass)
+			})
+			.sum();
+		println!(
+			"Overlap: {} (favourites: {favourites})",
+			ensemble.register_overlap
+		);
+		assert!(ensemble.register_overlap < favourites);
+	}
+
+	#[test]
+	fn test_arrange_ensemble_empty() {
+		let guitar = Guitar::default();
+		let parts = [EnsemblePart::new("Guitar", &guitar)];
+		assert!(arrange_ensemble(&[], &parts, &EnsembleOptions::default()).is_none());
+		assert!(arrange_ensemble(&["C"], &[], &EnsembleOptions::default()).is_none());
+	}
+
+	#[test]
+	fn test_arrange_duo_empty() {
+		let guitar = Guitar::default();
+		assert!(arrange_duo(&[], &guitar, &guitar, &DuoOptions::default()).is_empty());
+	}
+}
@@ -0,0 +1,325 @@
+//! Memoized fingering generation
+//!
+//! Progressions and interactive UIs ask for the same chord over and over. A
+//! [`FingeringCache`] keeps the results of [`generate_fingerings`] per chord,
+//! instrument and options, dropping the least recently used entry once it
+//! holds `capacity` of them.
+//!
+//! Entries are keyed on the chord, the options and every [`Instrument`]
+//! property the search reads (tuning, fret ranges, hand limits, position and
+//! barre thresholds, bass strings and courses). An entry goes stale when
+//! something outside the key changes the results: registering a new formula
+//! for a custom quality changes its chords' notes, so call
+//! [`FingeringCache::invalidate`] or [`FingeringCache::clear`] afterwards.
+//! The same goes for an [`Instrument`] implementation whose search-relevant
+//! behavior isn't visible through those properties.
+
+use std::collections::HashMap;
+
+use crate::chord::Chord;
+use crate::error::Result;
+use crate::generator::{CapoFingerings, GeneratorOptions, ScoredFingering, generate_fingerings};
+use crate::instrument::{CapoedInstrument, Instrument};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+	/// Chord name spelled with sharps, so Abm7 and G#m7 share an entry
+	chord: String,
+	instrument: String,
+	options: String,
+}
+
+impl CacheKey {
+	fn new<I: Instrument>(chord: &Chord, instrument: &I, options: &GeneratorOptions) -> Self {
+		let tuning: Vec<String> = instrument.tuning().iter().map(|n| n.to_string()).collect();
+		let string_ranges: Vec<(u8, (u8, u8))> = (0..instrument.string_count())
+			.map(|string| {
+				(
+					instrument.string_nut_fret(string),
+					instrument.string_fret_range(string),
+				)
+			})
+			.collect();
+		CacheKey {
+			chord: chord_key(chord),
+			instrument: format!(
+				"{} {:?} {:?} {} {} {} {} {} {} {:?} {:?}",
+				tuning.join(" "),
+				instrument.fret_range(),
+				string_ranges,
+				instrument.max_stretch(),
+				instrument.max_fingers(),
+				instrument.min_played_strings(),
+				instrument.open_position_threshold(),
+				instrument.main_barre_threshold(),
+				instrument.bass_string_index(),
+				instrument.bass_string_indices(),
+				instrument.course_pairs()
+			),
+			options: format!("{options:?}"),
+		}
+	}
+}
+
+fn chord_key(chord: &Chord) -> String {
+	Chord {
+		spelling: None,
+		..chord.clone()
+	}
+	.to_string()
+}
+
+/// Cache of generated fingerings, least recently used out first
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::cache::FingeringCache;
+/// use chordcraft_core::chord::Chord;
+/// use chordcraft_core::generator::GeneratorOptions;
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let mut cache = FingeringCache::new(32);
+/// let chord = Chord::parse("Am7").unwrap();
+/// let guitar = Guitar::default();
+/// let options = GeneratorOptions::default();
+///
+/// let first = cache.generate(&chord, &guitar, &options);
+/// let again = cache.generate(&chord, &guitar, &options);
+/// assert_eq!(first.len(), again.len());
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FingeringCache {
+	capacity: usize,
+	/// Results with the tick they were last used at
+	entries: HashMap<CacheKey, (u64, Vec<ScoredFingering>)>,
+	tick: u64,
+	hits: usize,
+	misses: usize,
+}
+
+impl FingeringCache {
+	/// Cache holding up to `capacity` searches; 0 caches nothing
+	pub fn new(capacity: usize) -> Self {
+		FingeringCache {
+			capacity,
+			..Default::default()
+		}
+	}
+
+	/// Fingerings for the chord, generated only if they aren't cached yet
+	pub fn generate<I: Instrument>(
+		&mut self,
+		chord: &Chord,
+		instrument: &I,
+		options: &GeneratorOptions,
+	) -> Vec<ScoredFingering> {
+		let key = CacheKey::new(chord, instrument, options);
+		self.tick += 1;
+		if let Some((used, fingerings)) = self.entries.get_mut(&key) {
+			*used = self.tick;
+			self.hits += 1;
+			return fingerings.clone();
+		}
+
+		self.misses += 1;
+		let fingerings = generate_fingerings(chord, instrument, options);
+		if self.capacity > 0 {
+			self.evict_to(self.capacity - 1);
+			self.entries.insert(key, (self.tick, fingerings.clone()));
+		}
+		fingerings
+	}
+
+	/// Fingerings with a capo on, like
+	/// [`generate_fingerings_with_capo`](crate::generator::generate_fingerings_with_capo)
+	pub fn generate_with_capo<I: Instrument>(
+		&mut self,
+		chord: &Chord,
+		instrument: &I,
+		capo: u8,
+		options: &GeneratorOptions,
+	) -> Result<CapoFingerings> {
+		let capoed = CapoedInstrument::new(instrument, capo)?;
+		let fingerings = self.generate(chord, &capoed, options);
+		Ok(CapoFingerings::new(chord, capo, fingerings))
+	}
+
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Change the capacity, dropping the least recently used entries that no longer fit
+	pub fn set_capacity(&mut self, capacity: usize) {
+		self.capacity = capacity;
+		self.evict_to(capacity);
+	}
+
+	/// Number of cached searches
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Lookups answered from the cache
+	pub fn hits(&self) -> usize {
+		self.hits
+	}
+
+	/// Lookups that had to generate
+	pub fn misses(&self) -> usize {
+		self.misses
+	}
+
+	/// Drop every entry for a chord (on any instrument, with any options),
+	/// returning how many were dropped
+	pub fn invalidate(&mut self, chord: &Chord) -> usize {
+		let name = chord_key(chord);
+		let before = self.entries.len();
+		self.entries.retain(|key, _| key.chord != name);
+		before - self.entries.len()
+	}
+
+	/// Drop every entry; hit and miss counts are kept
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+
+	fn evict_to(&mut self, size: usize) {
+		while self.entries.len() > size {
+			let oldest = self
+				.entries
+				.iter()
+				.min_by_key(|(_, (used, _))| *used)
+				.map(|(key, _)| key.clone());
+			match oldest {
+				Some(key) => self.entries.remove(&key),
+				None => break,
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::{ConfigurableInstrument, Guitar, Ukulele};
+
+	#[test]
+	fn test_cache_hits_and_keys() {
+		let mut cache = FingeringCache::new(8);
+		let guitar = Guitar::default();
+		let options = GeneratorOptions::default();
+		let ab = Chord::parse("Abm7").unwrap();
+
+		let fresh = cache.generate(&ab, &guitar, &options);
+		let cached = cache.generate(&Chord::parse("G#m7").unwrap(), &guitar, &options);
+		let tabs = |results: &[ScoredFingering]| -> Vec<String> {
+			results.iter().map(|f| f.fingering.to_string()).collect()
+		};
+		assert_eq!(tabs(&fresh), tabs(&cached));
+		assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+		// Another instrument or other options are separate searches
+		cache.generate(&ab, &Ukulele::default(), &options);
+		let band = GeneratorOptions {
+			playing_context: crate::generator::PlayingContext::Band,
+			..Default::default()
+		};
+		cache.generate(&ab, &guitar, &band);
+		assert_eq!(cache.misses(), 3);
+		assert_eq!(cache.len(), 3);
+
+		assert_eq!(cache.invalidate(&ab), 3);
+		assert!(cache.is_empty());
+	}
+
+	#[test]
+	fn test_cache_keys_on_string_ranges() {
+		let mut cache = FingeringCache::new(8);
+		let options = GeneratorOptions::default();
+		let a = Chord::parse("A").unwrap();
+		let banjo = ConfigurableInstrument::banjo();
+		// Same tuning and neck, but the fifth string runs the full length
+		let long_banjo = ConfigurableInstrument::builder()
+			.tuning(banjo.tuning().to_vec())
+			.fret_range(0, 22)
+			.max_stretch(4)
+			.open_position_threshold(5)
+			.min_played_strings(2)
+			.bass_string_index(1)
+			.build()
+			.unwrap();
+
+		let tabs = |fingerings: Vec<ScoredFingering>| -> Vec<String> {
+			fingerings.iter().map(|f| f.fingering.to_string()).collect()
+		};
+		let short = tabs(cache.generate(&a, &banjo, &options));
+		let long = tabs(cache.generate(&a, &long_banjo, &options));
+		assert_eq!(cache.misses(), 2);
+		assert_eq!(long, tabs(generate_fingerings(&a, &long_banjo, &options)));
+		assert_ne!(short, long);
+	}
+
+	#[test]
+	fn test_cache_keys_on_thresholds_and_bass_string() {
+		let mut cache = FingeringCache::new(8);
+		let options = GeneratorOptions::default();
+		let f = Chord::parse("F").unwrap();
+		let build = |open: u8, barre: usize, bass: Option<usize>| {
+			let mut builder = ConfigurableInstrument::builder()
+				.tuning(Guitar::default().tuning().to_vec())
+				.fret_range(0, 15)
+				.max_stretch(4)
+				.open_position_threshold(open)
+				.main_barre_threshold(barre);
+			if let Some(bass) = bass {
+				builder = builder.bass_string_index(bass);
+			}
+			builder.build().unwrap()
+		};
+
+		// Each property on its own is a separate search
+		let instruments = [
+			build(4, 3, None),
+			build(0, 3, None),
+			build(4, 6, None),
+			build(4, 3, Some(3)),
+		];
+		for instrument in &instruments {
+			cache.generate(&f, instrument, &options);
+		}
+		assert_eq!(cache.misses(), instruments.len());
+		assert_eq!(cache.len(), instruments.len());
+		assert_eq!(cache.hits(), 0);
+	}
+
+	#[test]
+	fn test_cache_evicts_least_recently_used() {
+		let mut cache = FingeringCache::new(2);
+		let guitar = Guitar::default();
+		let options = GeneratorOptions::default();
+		let [c, g, d] = ["C", "G", "D"].map(|name| Chord::parse(name).unwrap());
+
+		cache.generate(&c, &guitar, &options);
+		cache.generate(&g, &guitar, &options);
+		cache.generate(&c, &guitar, &options);
+		// G is the least recently used, so D pushes it out
+		cache.generate(&d, &guitar, &options);
+		assert_eq!(cache.len(), 2);
+		cache.generate(&c, &guitar, &options);
+		assert_eq!(cache.hits(), 2);
+		cache.generate(&g, &guitar, &options);
+		assert_eq!(cache.misses(), 4);
+
+		cache.set_capacity(1);
+		assert_eq!(cache.len(), 1);
+		cache.set_capacity(0);
+		cache.generate(&c, &guitar, &options);
+		assert!(cache.is_empty());
+	}
+}
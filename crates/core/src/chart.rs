@@ -0,0 +1,469 @@
+//! Measure-aware chord charts
+//!
+//! A [`Chart`] lays a song's harmony out on a beat grid: sections of measures,
+//! each beat either starting a chord or continuing the previous one. It
+//! renders as monospace text with chord symbols aligned over beat slashes:
+//!
+//! ```text
+//! [Verse]
+//!   C             Am            F     G
+//! | /  /  /  /  | /  /  /  /  | /  /  /  /  |
+//! ```
+
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::error::{ChordCraftError, Result};
+use crate::song::{Song, SongSection};
+
+/// Measures per rendered line when using `Display`
+pub const DEFAULT_MEASURES_PER_LINE: usize = 4;
+
+/// One bar: a slot per beat, `Some` where a chord starts
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measure {
+	pub beats: Vec<Option<Chord>>,
+}
+
+impl Measure {
+	/// An empty measure (every beat continues the previous chord)
+	pub fn new(beats_per_bar: u8) -> Self {
+		Measure {
+			beats: vec![None; beats_per_bar as usize],
+		}
+	}
+
+	/// Chords starting in this measure with their beat index
+	pub fn changes(&self) -> impl Iterator<Item = (usize, &Chord)> {
+		self.beats
+			.iter()
+			.enumerate()
+			.filter_map(|(i, c)| c.as_ref().map(|c| (i, c)))
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartSection {
+	pub label: Option<String>,
+	pub measures: Vec<Measure>,
+	/// Times the section is played (1 = no repeat)
+	pub repeat: u8,
+}
+
+impl ChartSection {
+	pub fn new(label: Option<String>) -> Self {
+		ChartSection {
+			label,
+			measures: Vec::new(),
+			repeat: 1,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chart {
+	pub title: Option<String>,
+	pub beats_per_bar: u8,
+	pub sections: Vec<ChartSection>,
+}
+
+impl Chart {
+	pub fn new(title: Option<String>, beats_per_bar: u8) -> Self {
+		Chart {
+			title,
+			beats_per_bar: beats_per_bar.max(1),
+			sections: Vec::new(),
+		}
+	}
+
+	/// Lay a song out on the beat grid
+	///
+	/// Every section starts on a new bar. Chord starts are rounded to the
+	/// nearest beat; a chord that rounds onto an already-taken beat replaces it.
+	pub fn from_song(song: &Song) -> Self {
+		let mut chart = Chart::new(song.title.clone(), song.beats_per_bar);
+		let beats_per_bar = chart.beats_per_bar as usize;
+
+		for section in &song.sections {
+			let mut chart_section = ChartSection::new(section.label.clone());
+			let mut position = 0.0f32;
+
+			for song_chord in &section.chords {
+				let beat = position.round() as usize;
+				let (bar, beat) = (beat / beats_per_bar, beat % beats_per_bar);
+				while chart_section.measures.len() <= bar {
+					chart_section
+						.measures
+						.push(Measure::new(chart.beats_per_bar));
+				}
+				chart_section.measures[bar].beats[beat] = Some(song_chord.chord.clone());
+				position += song_chord.beats;
+			}
+
+			// Fill out the bars the last chord rings through
+			let total_bars = (position.round() as usize).div_ceil(beats_per_bar);
+			while chart_section.measures.len() < total_bars {
+				chart_section
+					.measures
+					.push(Measure::new(chart.beats_per_bar));
+			}
+
+			chart.sections.push(chart_section);
+		}
+
+		chart
+	}
+
+	/// Parse bar notation such as `| C . . . | Am | F G |`
+	///
+	/// Bars are separated by `|`. Within a bar, `.` (or `/`) holds the chord
+	/// for another beat; without dots the chords split the bar evenly, so
+	/// `| F G |` in 4/4 gives each two beats. `%` repeats the previous bar,
+	/// `|: ... :|` repeats its bars (twice, or `:| x3` times) and `[Chorus]`
+	/// starts a labelled section.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::chart::Chart;
+	///
+	/// let chart = Chart::parse("|: C . . . | Am | F G :| x2", 4).unwrap();
+	/// assert_eq!(chart.measure_count(), 6);
+	/// assert_eq!(chart.to_song().chord_names(), ["C", "Am", "F", "G", "C", "Am", "F", "G"]);
+	/// ```
+	pub fn parse(text: &str, beats_per_bar: u8) -> Result<Self> {
+		let mut chart = Chart::new(None, beats_per_bar);
+		let mut section = ChartSection::new(None);
+		let mut bar: Vec<String> = Vec::new();
+		let mut tokens = tokenize_bars(text).into_iter().peekable();
+
+		while let Some(token) = tokens.next() {
+			match token.as_str() {
+				"|" => chart.close_bar(&mut section, &mut bar)?,
+				"|:" => {
+					chart.close_bar(&mut section, &mut bar)?;
+					chart.close_section(&mut section, None);
+				}
+				":|" => {
+					chart.close_bar(&mut section, &mut bar)?;
+					let times = tokens
+						.next_if(|t| t.starts_with(['x', 'X']) && t[1..].parse::<u8>().is_ok())
+						.map_or(2, |t| t[1..].parse().unwrap_or(2));
+					section.repeat = times.max(1);
+					chart.close_section(&mut section, None);
+				}
+				label if label.starts_with('[') && label.ends_with(']') => {
+					chart.close_bar(&mut section, &mut bar)?;
+					let name = label[1..label.len() - 1].trim().to_string();
+					chart.close_section(&mut section, Some(name));
+				}
+				_ => bar.push(token),
+			}
+		}
+		chart.close_bar(&mut section, &mut bar)?;
+		chart.close_section(&mut section, None);
+
+		if chart.sections.is_empty() {
+			return Err(ChordCraftError::InvalidChart(format!(
+				"no bars in '{text}'"
+			)));
+		}
+		Ok(chart)
+	}
+
+	/// Turn the tokens of one bar into a measure of `section`
+	fn close_bar(&self, section: &mut ChartSection, bar: &mut Vec<String>) -> Result<()> {
+		if bar.is_empty() {
+			return Ok(());
+		}
+		let tokens = std::mem::take(bar);
+		let beats = self.beats_per_bar as usize;
+
+		if tokens == ["%"] {
+			let previous = section.measures.last().cloned().ok_or_else(|| {
+				ChordCraftError::InvalidChart("'%' needs a bar before it to repeat".to_string())
+			})?;
+			section.measures.push(previous);
+			return Ok(());
+		}
+
+		let mut measure = Measure::new(self.beats_per_bar);
+		let holds = tokens.iter().any(|t| t == "." || t == "/");
+		if tokens.len() > beats || (holds && tokens.len() != beats) {
+			return Err(ChordCraftError::InvalidChart(format!(
+				"bar '{}' doesn't fill {beats} beats",
+				tokens.join(" ")
+			)));
+		}
+		for (i, token) in tokens.iter().enumerate() {
+			if token == "." || token == "/" {
+				continue;
+			}
+			let chord = Chord::parse(token)?;
+			// Without dots, chords split the bar evenly
+			let beat = if holds { i } else { i * beats / tokens.len() };
+			measure.beats[beat] = Some(chord);
+		}
+		section.measures.push(measure);
+		Ok(())
+	}
+
+	/// Finish `section` if it has bars and start a new one
+	fn close_section(&mut self, section: &mut ChartSection, label: Option<String>) {
+		let finished = std::mem::replace(section, ChartSection::new(label));
+		if !finished.measures.is_empty() {
+			self.sections.push(finished);
+		} else if finished.label.is_some() && section.label.is_none() {
+			section.label = finished.label;
+		}
+	}
+
+	/// Convert back to a song, writing out repeats
+	pub fn to_song(&self) -> Song {
+		let mut song = Song::new(self.title.clone());
+		song.beats_per_bar = self.beats_per_bar;
+
+		for section in &self.sections {
+			let mut song_section = SongSection::new(section.label.clone());
+			for _ in 0..section.repeat.max(1) {
+				let mut current: Option<(&Chord, f32)> = None;
+				for slot in section.measures.iter().flat_map(|m| &m.beats) {
+					match (slot, current.as_mut()) {
+						(Some(chord), _) => {
+							if let Some((prev, beats)) = current {
+								song_section.push(prev.clone(), beats);
+							}
+							current = Some((chord, 1.0));
+						}
+						(None, Some((_, beats))) => *beats += 1.0,
+						// Silence before the first chord
+						(None, None) => {}
+					}
+				}
+				if let Some((chord, beats)) = current {
+					song_section.push(chord.clone(), beats);
+				}
+			}
+			song.sections.push(song_section);
+		}
+
+		song
+	}
+
+	pub fn measure_count(&self) -> usize {
+		self.sections
+			.iter()
+			.map(|s| s.measures.len() * s.repeat.max(1) as usize)
+			.sum()
+	}
+
+	/// Render as monospace text, `measures_per_line` bars per line
+	///
+	/// Each beat gets a slash, with the chord symbol starting there written
+	/// directly above it. Repeated sections are bracketed with `|:` and `:|`
+	/// and followed by the repeat count.
+	pub fn render(&self, measures_per_line: usize) -> String {
+		let symbol_width = self
+			.sections
+			.iter()
+			.flat_map(|s| &s.measures)
+			.flat_map(|m| m.changes())
+			.map(|(_, c)| c.to_string().chars().count())
+			.max()
+			.unwrap_or(0);
+		let cell = (symbol_width + 1).max(3);
+
+		let mut lines = Vec::new();
+		if let Some(title) = &self.title {
+			lines.push(title.clone());
+			lines.push(String::new());
+		}
+
+		for (s, section) in self.sections.iter().enumerate() {
+			if s > 0 {
+				lines.push(String::new());
+			}
+			if let Some(label) = &section.label {
+				lines.push(format!("[{label}]"));
+			}
+
+			let repeated = section.repeat > 1;
+			let rows: Vec<&[Measure]> = section.measures.chunks(measures_per_line.max(1)).collect();
+			for (r, row) in rows.iter().enumerate() {
+				let mut chords = String::new();
+				let mut bars = String::new();
+
+				for (m, measure) in row.iter().enumerate() {
+					let opens_repeat = repeated && r == 0 && m == 0;
+					chords.push_str("  ");
+					bars.push_str(if opens_repeat { "|:" } else { "| " });
+
+					for slot in &measure.beats {
+						let symbol = slot.as_ref().map(|c| c.to_string()).unwrap_or_default();
+						chords.push_str(&format!("{symbol:cell$}"));
+						bars.push_str(&format!("{:cell$}", "/"));
+					}
+				}
+
+				if repeated && r == rows.len() - 1 {
+					bars.push_str(&format!(":| x{}", section.repeat));
+				} else {
+					bars.push('|');
+				}
+				lines.push(chords.trim_end().to_string());
+				lines.push(bars);
+			}
+		}
+
+		lines.join("\n")
+	}
+}
+
+/// Split bar notation into bar lines (`|`, `|:`, `:|`) and words
+fn tokenize_bars(text: &str) -> Vec<String> {
+	let mut tokens = Vec::new();
+	let mut word = String::new();
+	let mut chars = text.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		let bar_line = match c {
+			'|' if chars.next_if_eq(&':').is_some() => Some("|:"),
+			'|' => Some("|"),
+			':' if chars.next_if_eq(&'|').is_some() => Some(":|"),
+			_ => None,
+		};
+		if bar_line.is_some() || c.is_whitespace() {
+			if !word.is_empty() {
+				tokens.push(std::mem::take(&mut word));
+			}
+		} else {
+			word.push(c);
+		}
+		tokens.extend(bar_line.map(String::from));
+	}
+	if !word.is_empty() {
+		tokens.push(word);
+	}
+	tokens
+}
+
+impl fmt::Display for Chart {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.render(DEFAULT_MEASURES_PER_LINE))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn chord(name: &str) -> Chord {
+		Chord::parse(name).unwrap()
+	}
+
+	fn sample_song() -> Song {
+		let mut verse = SongSection::new(Some("Verse".to_string()));
+		verse.push(chord("C"), 4.0);
+		verse.push(chord("Am"), 4.0);
+		verse.push(chord("F"), 2.0);
+		verse.push(chord("G"), 2.0);
+		let mut song = Song::new(Some("Test".to_string()));
+		song.sections.push(verse);
+		song
+	}
+
+	#[test]
+	fn test_from_song_places_chords_on_beats() {
+		let chart = Chart::from_song(&sample_song());
+		let verse = &chart.sections[0];
+
+		assert_eq!(verse.measures.len(), 3);
+		assert_eq!(verse.measures[0].beats[0], Some(chord("C")));
+		assert_eq!(verse.measures[0].beats[1], None);
+		let changes: Vec<_> = verse.measures[2].changes().collect();
+		assert_eq!(changes, vec![(0, &chord("F")), (2, &chord("G"))]);
+
+		assert_eq!(chart.to_song(), sample_song());
+	}
+
+	#[test]
+	fn test_render_aligns_chords_over_beats() {
+		let chart = Chart::from_song(&sample_song());
+		let text = chart.to_string();
+		println!("{text}");
+
+		let lines: Vec<&str> = text.lines().collect();
+		assert_eq!(lines[0], "Test");
+		assert_eq!(lines[2], "[Verse]");
+
+		// Every chord symbol sits directly above a beat slash
+		let (chords, bars) = (lines[3], lines[4]);
+		for (i, ch) in chords.char_indices() {
+			if ch.is_alphabetic() && (i == 0 || chords.as_bytes()[i - 1] == b' ') {
+				assert_eq!(bars.as_bytes()[i], b'/', "{chords}\n{bars}");
+			}
+		}
+		assert_eq!(bars.matches('/').count(), 12);
+		assert!(bars.ends_with('|'));
+	}
+
+	#[test]
+	fn test_repeats() {
+		let mut chart = Chart::from_song(&sample_song());
+		chart.sections[0].repeat = 2;
+
+		let text = chart.render(2);
+		assert!(text.contains("|:"));
+		assert!(text.trim_end().ends_with(":| x2"));
+
+		assert_eq!(chart.measure_count(), 6);
+		let song = chart.to_song();
+		assert_eq!(song.total_beats(), 24.0);
+		assert_eq!(
+			song.chord_names(),
+			vec!["C", "Am", "F", "G", "C", "Am", "F", "G"]
+		);
+	}
+
+	#[test]
+	fn test_parse_bar_notation() {
+		let chart = Chart::parse("| C . . G | F G | % |", 4).unwrap();
+		let bars = &chart.sections[0].measures;
+		assert_eq!(bars.len(), 3);
+		assert_eq!(
+			bars[0].changes().collect::<Vec<_>>(),
+			vec![(0, &chord("C")), (3, &chord("G"))]
+		);
+		assert_eq!(
+			bars[1].changes().collect::<Vec<_>>(),
+			vec![(0, &chord("F")), (2, &chord("G"))]
+		);
+		assert_eq!(bars[2], bars[1]);
+
+		let chart = Chart::parse("[Verse] | C | G | [Chorus] |: F G :| x3", 4).unwrap();
+		assert_eq!(chart.sections.len(), 2);
+		assert_eq!(chart.sections[0].label.as_deref(), Some("Verse"));
+		assert_eq!(chart.sections[1].label.as_deref(), Some("Chorus"));
+		assert_eq!(chart.sections[1].repeat, 3);
+		assert_eq!(chart.measure_count(), 5);
+		assert_eq!(
+			chart.to_song().chord_names(),
+			["C", "G", "F", "G", "F", "G", "F", "G"]
+		);
+
+		// Round-trips through the rendered chart's chords
+		let song = sample_song();
+		let rendered = Chart::parse("| C | Am | F G |", 4).unwrap().to_song();
+		assert_eq!(rendered.chord_names(), song.chord_names());
+		assert_eq!(rendered.total_beats(), song.total_beats());
+
+		assert!(Chart::parse("| C D E F G |", 4).is_err());
+		assert!(Chart::parse("| C . . |", 4).is_err());
+		assert!(Chart::parse("| % | C |", 4).is_err());
+		assert!(Chart::parse("| H |", 4).is_err());
+		assert!(Chart::parse("| |", 4).is_err());
+	}
+}
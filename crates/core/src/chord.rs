@@ -7,8 +7,10 @@
 //! - Voicing classification (core, full, jazzy)
 
 use crate::error::{ChordCraftError, Result};
+use crate::formula;
 use crate::interval::*;
 use crate::note::PitchClass;
+use crate::spelling::Accidentals;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
@@ -23,6 +25,9 @@ pub enum ChordQuality {
 	Sus2,
 	Sus4,
 
+	// Power chord
+	Power, // X5
+
 	// 7th chords
 	Dominant7,       // X7
 	Major7,          // Xmaj7
@@ -32,20 +37,23 @@ pub enum ChordQuality {
 	HalfDiminished7, // Xm7b5
 
 	// Extended chords
-	Dominant9,  // X9
-	Major9,     // Xmaj9
-	Minor9,     // Xm9
-	Dominant11, // X11
-	Minor11,    // Xm11
-	Dominant13, // X13
-	Major13,    // Xmaj13
-	Minor13,    // Xm13
+	Dominant9,     // X9
+	Major9,        // Xmaj9
+	Minor9,        // Xm9
+	Dominant9sus4, // X9sus4
+	Dominant11,    // X11
+	Minor11,       // Xm11
+	Dominant13,    // X13
+	Major13,       // Xmaj13
+	Minor13,       // Xm13
+	Major7sharp11, // Xmaj7#11
 
 	// Altered dominants
 	Dominant7b9,     // X7b9
 	Dominant7sharp9, // X7#9
 	Dominant7b5,     // X7b5
 	Dominant7sharp5, // X7#5 (aug7)
+	Dominant7alt,    // X7alt
 
 	// Add chords
 	Add9,      // Xadd9
@@ -53,8 +61,13 @@ pub enum ChordQuality {
 	Add11,     // Xadd11
 
 	// 6th chords
-	Major6, // X6
-	Minor6, // Xm6
+	Major6,  // X6
+	Minor6,  // Xm6
+	SixNine, // X6/9
+
+	// Registered at runtime (see [`crate::formula`])
+	#[strum(disabled)]
+	Custom(u16),
 }
 
 impl ChordQuality {
@@ -87,6 +100,9 @@ impl ChordQuality {
 			Sus2 => (vec![UNISON, MAJOR_SECOND, PERFECT_FIFTH], vec![]),
 			Sus4 => (vec![UNISON, PERFECT_FOURTH, PERFECT_FIFTH], vec![]),
 
+			// Power chord: no 3rd at all
+			Power => (vec![UNISON, PERFECT_FIFTH], vec![]),
+
 			// 7th chords (5th is optional - the 7th defines the chord's color)
 			Dominant7 => (
 				vec![UNISON, MAJOR_THIRD, MINOR_SEVENTH],
@@ -136,6 +152,10 @@ impl ChordQuality {
 				vec![UNISON, MINOR_THIRD, MINOR_SEVENTH, MAJOR_NINTH],
 				vec![PERFECT_FIFTH],
 			),
+			Dominant9sus4 => (
+				vec![UNISON, PERFECT_FOURTH, MINOR_SEVENTH, MAJOR_NINTH],
+				vec![PERFECT_FIFTH],
+			),
 
 			// Extended chords (11ths)
 			Dominant11 => (
@@ -190,6 +210,15 @@ impl ChordQuality {
 				],
 				vec![PERFECT_FIFTH, PERFECT_ELEVENTH],
 			),
+			Major7sharp11 => (
+				vec![
+					UNISON,
+					MAJOR_THIRD,
+					MAJOR_SEVENTH,
+					Interval::new(IntervalQuality::Augmented, 11),
+				],
+				vec![PERFECT_FIFTH],
+			),
 
 			// Altered dominants
 			Dominant7b9 => (
@@ -230,6 +259,20 @@ impl ChordQuality {
 				],
 				vec![],
 			),
+			// Altered scale: the #9 plus any of b9, b5 and #5 (b13)
+			Dominant7alt => (
+				vec![
+					UNISON,
+					MAJOR_THIRD,
+					MINOR_SEVENTH,
+					Interval::new(IntervalQuality::Augmented, 9),
+				],
+				vec![
+					MINOR_NINTH,
+					Interval::new(IntervalQuality::Diminished, 5),
+					Interval::new(IntervalQuality::Augmented, 5),
+				],
+			),
 
 			// Add chords
 			Add9 => (
@@ -254,9 +297,21 @@ impl ChordQuality {
 				vec![UNISON, MINOR_THIRD, PERFECT_FIFTH, MAJOR_SIXTH],
 				vec![],
 			),
+			SixNine => (
+				vec![UNISON, MAJOR_THIRD, MAJOR_SIXTH, MAJOR_NINTH],
+				vec![PERFECT_FIFTH],
+			),
+
+			Custom(id) => formula::formula(*id)
+				.map_or_else(|| (vec![UNISON], vec![]), |f| (f.required, f.optional)),
 		}
 	}
 
+	/// Built-in qualities followed by the registered custom ones
+	pub fn all() -> impl Iterator<Item = ChordQuality> {
+		<ChordQuality as strum::IntoEnumIterator>::iter().chain(formula::custom_qualities())
+	}
+
 	/// The 7th defines the chord's color, making the 5th redundant in extended chords.
 	pub fn can_omit_fifth(&self) -> bool {
 		use ChordQuality::*;
@@ -273,6 +328,10 @@ impl ChordQuality {
 				| Dominant7sharp9
 				| Dominant7b5
 				| Dominant7sharp5
+				| Dominant9sus4
+				| Major7sharp11
+				| Dominant7alt
+				| SixNine
 		)
 	}
 
@@ -285,6 +344,7 @@ impl ChordQuality {
 			Augmented => "aug",
 			Sus2 => "sus2",
 			Sus4 => "sus4",
+			Power => "5",
 			Dominant7 => "7",
 			Major7 => "maj7",
 			Minor7 => "m7",
@@ -294,26 +354,121 @@ impl ChordQuality {
 			Dominant9 => "9",
 			Major9 => "maj9",
 			Minor9 => "m9",
+			Dominant9sus4 => "9sus4",
 			Dominant11 => "11",
 			Minor11 => "m11",
 			Dominant13 => "13",
 			Major13 => "maj13",
 			Minor13 => "m13",
+			Major7sharp11 => "maj7#11",
 			Dominant7b9 => "7b9",
 			Dominant7sharp9 => "7#9",
 			Dominant7b5 => "7b5",
 			Dominant7sharp5 => "7#5",
+			Dominant7alt => "7alt",
 			Add9 => "add9",
 			MinorAdd9 => "madd9",
 			Add11 => "add11",
 			Major6 => "6",
 			Minor6 => "m6",
+			SixNine => "6/9",
+			Custom(id) => formula::symbol(*id),
+		}
+	}
+
+	/// Quality symbol in the given naming profile
+	pub fn symbol(&self, profile: NamingProfile) -> &'static str {
+		use ChordQuality::*;
+		match profile {
+			NamingProfile::Standard => self.display_name(),
+			NamingProfile::Pop => match self {
+				Add9 => "2",
+				MinorAdd9 => "m2",
+				Sus4 => "sus",
+				Augmented => "+",
+				_ => self.display_name(),
+			},
+			NamingProfile::Jazz => match self {
+				Minor => "-",
+				Minor7 => "-7",
+				Minor9 => "-9",
+				Minor11 => "-11",
+				Minor13 => "-13",
+				Minor6 => "-6",
+				MinorMajor7 => "-∆7",
+				MinorAdd9 => "-(add9)",
+				Major7 => "∆7",
+				Major9 => "∆9",
+				Major13 => "∆13",
+				Major7sharp11 => "∆7#11",
+				HalfDiminished7 => "ø7",
+				Diminished7 => "°7",
+				Diminished => "°",
+				Augmented => "+",
+				_ => self.display_name(),
+			},
 		}
 	}
 }
 
+/// Chord-symbol conventions used when naming chords
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamingProfile {
+	/// Lead-sheet defaults: Cadd9, Cmaj7, Cm7b5
+	#[default]
+	Standard,
+	/// Pop/rock songbooks: C2, Csus, C+
+	Pop,
+	/// Real Book style: C∆9, C-7, Cø7, C°7
+	Jazz,
+}
+
+/// How to write a chord name: the naming profile plus typographic choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChordFormat {
+	pub profile: NamingProfile,
+	/// ♯ and ♭ instead of # and b (C♯m7♭5)
+	pub unicode_accidentals: bool,
+	/// Extension numbers and alteration brackets raised (C⁷⁽♭⁹⁾, Cmaj⁹)
+	pub superscript: bool,
+}
+
+impl ChordFormat {
+	/// Real Book style with Unicode accidentals and raised extensions (C∆⁹, B♭-⁷)
+	pub fn lead_sheet() -> Self {
+		ChordFormat {
+			profile: NamingProfile::Jazz,
+			unicode_accidentals: true,
+			superscript: true,
+		}
+	}
+
+	/// Apply the typographic choices to a note name or quality symbol
+	fn apply(&self, text: &str) -> String {
+		let mut out = String::with_capacity(text.len());
+		let mut raised = false;
+		let mut chars = text.chars().peekable();
+		while let Some(c) = chars.next() {
+			raised |= self.superscript && c.is_ascii_digit();
+			let flat = c == 'b' && chars.peek().is_none_or(|n| n.is_ascii_digit() || *n == 'b');
+			out.push(match c {
+				'#' if self.unicode_accidentals => '♯',
+				'b' if flat && self.unicode_accidentals => '♭',
+				'0'..='9' if raised => SUPERSCRIPT_DIGITS[c as usize - '0' as usize],
+				'(' if raised => '⁽',
+				')' if raised => '⁾',
+				c => c,
+			});
+		}
+		out
+	}
+}
+
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
 /// Voicing type classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VoicingType {
 	/// Core notes only (root, 3rd, 7th for 7th chords)
 	Core,
@@ -325,19 +480,134 @@ pub enum VoicingType {
 	Incomplete,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A change to a chord's tones written in parentheses, like the b9 and #11 of C7(b9,#11)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alteration {
+	/// Adds a tone, replacing any of the quality's tones on the same degree
+	/// (b5 replaces the 5th, b9 the 9th)
+	Add(Interval),
+	/// Leaves out a degree (no3, no5)
+	Omit(u8),
+	/// Replaces the third with a 2nd or 4th (the sus4 of C7sus4)
+	Sus(u8),
+}
+
+impl Alteration {
+	/// Parse one alteration: "b9", "#11", "b13", "add11", "9", "maj7", "no3", "omit5", "sus4"
+	pub fn parse(s: &str) -> Result<Self> {
+		let invalid = || ChordCraftError::InvalidChordName(format!("Unknown alteration: {s}"));
+		let s = s.trim();
+
+		match s {
+			"maj7" | "M7" | "Δ7" | "∆7" => return Ok(Alteration::Add(MAJOR_SEVENTH)),
+			"sus4" | "sus" => return Ok(Alteration::Sus(4)),
+			"sus2" => return Ok(Alteration::Sus(2)),
+			_ => {}
+		}
+
+		if let Some(degree) = s.strip_prefix("no").or_else(|| s.strip_prefix("omit")) {
+			return match degree.parse() {
+				Ok(degree @ (1 | 3 | 5)) => Ok(Alteration::Omit(degree)),
+				_ => Err(invalid()),
+			};
+		}
+
+		let s = s.strip_prefix("add").unwrap_or(s);
+		let interval = Interval::from_degree(s).map_err(|_| invalid())?;
+		let single = s.chars().filter(|c| !c.is_ascii_digit()).count() <= 1;
+		if !single || !matches!(interval.distance, 2 | 4 | 5 | 6 | 9 | 11 | 13) {
+			return Err(invalid());
+		}
+		Ok(Alteration::Add(interval))
+	}
+
+	/// Degree the alteration replaces or leaves out
+	fn degree(&self) -> u8 {
+		match self {
+			Alteration::Add(interval) => interval.distance,
+			Alteration::Omit(degree) => *degree,
+			Alteration::Sus(_) => 3,
+		}
+	}
+
+	/// Apply to a quality's (required, optional) intervals; added tones are required
+	fn apply(&self, required: &mut Vec<Interval>, optional: &mut Vec<Interval>) {
+		let degree = self.degree();
+		required.retain(|i| i.distance != degree);
+		optional.retain(|i| i.distance != degree);
+		match self {
+			Alteration::Add(interval) => required.push(*interval),
+			Alteration::Sus(2) => required.push(MAJOR_SECOND),
+			Alteration::Sus(_) => required.push(PERFECT_FOURTH),
+			Alteration::Omit(_) => {}
+		}
+	}
+}
+
+impl fmt::Display for Alteration {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Alteration::Add(interval) => match interval.quality {
+				IntervalQuality::Minor | IntervalQuality::Diminished => {
+					write!(f, "b{}", interval.distance)
+				}
+				IntervalQuality::Augmented => write!(f, "#{}", interval.distance),
+				IntervalQuality::Major if interval.distance == 7 => write!(f, "maj7"),
+				IntervalQuality::Major | IntervalQuality::Perfect => {
+					write!(f, "add{}", interval.distance)
+				}
+			},
+			Alteration::Omit(degree) => write!(f, "no{degree}"),
+			Alteration::Sus(degree) => write!(f, "sus{degree}"),
+		}
+	}
+}
+
+/// Which chord tone sounds in the bass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Inversion {
+	/// Root in the bass
+	Root,
+	/// 3rd (or the suspended tone) in the bass
+	First,
+	/// 5th in the bass
+	Second,
+	/// 7th (or 6th) in the bass
+	Third,
+}
+
+#[derive(Debug, Clone)]
 pub struct Chord {
 	pub root: PitchClass,
 	pub quality: ChordQuality,
+	/// Parenthesized alterations on top of the quality, in written order
+	pub alterations: Vec<Alteration>,
 	pub bass: Option<PitchClass>, // For slash chords (e.g., C/G)
+	/// How to spell the root and bass; None prints sharps. Ignored by equality,
+	/// so Abm7 == G#m7.
+	pub spelling: Option<Accidentals>,
+}
+
+impl PartialEq for Chord {
+	fn eq(&self, other: &Self) -> bool {
+		self.root == other.root
+			&& self.quality == other.quality
+			&& self.alterations == other.alterations
+			&& self.bass == other.bass
+	}
 }
 
+impl Eq for Chord {}
+
 impl Chord {
 	pub fn new(root: PitchClass, quality: ChordQuality) -> Self {
 		Chord {
 			root,
 			quality,
+			alterations: Vec::new(),
 			bass: None,
+			spelling: None,
 		}
 	}
 
@@ -345,7 +615,196 @@ impl Chord {
 		Chord {
 			root,
 			quality,
+			alterations: Vec::new(),
 			bass: Some(bass),
+			spelling: None,
+		}
+	}
+
+	/// Chord of a root and the intervals above it, named after the quality with those tones
+	///
+	/// Tones are compared by pitch class, so a 2nd matches a 9th, and leaving
+	/// out a quality's optional tones still matches it (a root, major 3rd and
+	/// minor 7th make a dominant 7th). Tones no quality fits are registered as
+	/// a custom quality named after their degrees in braces, like C{1,b2,5}.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::chord::Chord;
+	/// use chordcraft_core::interval::{MAJOR_THIRD, MINOR_SECOND, MINOR_SEVENTH, PERFECT_FIFTH};
+	/// use chordcraft_core::note::PitchClass;
+	///
+	/// let c7 = Chord::from_intervals(PitchClass::C, &[MAJOR_THIRD, MINOR_SEVENTH]).unwrap();
+	/// assert_eq!(c7.to_string(), "C7");
+	///
+	/// let custom = Chord::from_intervals(PitchClass::C, &[MINOR_SECOND, PERFECT_FIFTH]).unwrap();
+	/// assert_eq!(custom.to_string(), "C{1,b2,5}");
+	/// ```
+	pub fn from_intervals(root: PitchClass, intervals: &[Interval]) -> Result<Self> {
+		let pitch_classes = |intervals: &[Interval]| {
+			let mut classes: Vec<u8> = intervals.iter().map(|i| i.to_semitones() % 12).collect();
+			classes.push(0);
+			classes.sort_unstable();
+			classes.dedup();
+			classes
+		};
+		let wanted = pitch_classes(intervals);
+		let fits = |quality: &ChordQuality, exact: bool| {
+			let (required, optional) = quality.intervals();
+			let all = pitch_classes(&[required.clone(), optional].concat());
+			if exact {
+				return all == wanted;
+			}
+			let required = pitch_classes(&required);
+			required.iter().all(|c| wanted.contains(c)) && wanted.iter().all(|c| all.contains(c))
+		};
+
+		let quality = ChordQuality::all()
+			.find(|q| fits(q, true))
+			.or_else(|| ChordQuality::all().find(|q| fits(q, false)));
+		let quality = match quality {
+			Some(quality) => quality,
+			None => {
+				let mut tones = intervals.to_vec();
+				tones.sort_by_key(|i| (i.distance, i.to_semitones()));
+				tones.dedup();
+				let formula = formula::ChordFormula::new(tones, vec![])?;
+				let degrees: Vec<String> =
+					formula.required.iter().map(Interval::to_degree).collect();
+				formula::register_quality(&format!("{{{}}}", degrees.join(",")), formula)?
+			}
+		};
+		Ok(Chord::new(root, quality))
+	}
+
+	/// The same chord spelled with the given accidentals (e.g., for a key's signature)
+	pub fn with_spelling(mut self, accidentals: Accidentals) -> Self {
+		self.spelling = Some(accidentals);
+		self
+	}
+
+	/// Accidentals the chord is spelled with
+	pub fn accidentals(&self) -> Accidentals {
+		self.spelling.unwrap_or_default()
+	}
+
+	/// The same chord with another alteration (e.g., b9 on a dominant 7th)
+	pub fn with_alteration(mut self, alteration: Alteration) -> Self {
+		self.alterations.push(alteration);
+		self
+	}
+
+	/// The chord with the given chord tone in the bass
+	///
+	/// Returns an error if the chord has no such tone (a triad has no third
+	/// inversion, a power chord no second).
+	pub fn with_inversion(mut self, inversion: Inversion) -> Result<Self> {
+		let bass = match inversion {
+			Inversion::Root => Some(self.root),
+			Inversion::First => self.stacked_tones().first().copied(),
+			Inversion::Second => self.stacked_tones().get(1).copied(),
+			Inversion::Third => self.stacked_tones().get(2).copied(),
+		};
+		let bass = bass.ok_or_else(|| {
+			ChordCraftError::InvalidChordName(format!("{self} has no {inversion:?} inversion"))
+		})?;
+		self.bass = Some(bass);
+		Ok(self)
+	}
+
+	/// Inversion the bass note makes, or None without a bass or with a non-chord tone
+	pub fn inversion(&self) -> Option<Inversion> {
+		let bass = self.bass?;
+		if bass == self.root {
+			return Some(Inversion::Root);
+		}
+		let inversions = [Inversion::First, Inversion::Second, Inversion::Third];
+		self.stacked_tones()
+			.iter()
+			.position(|&tone| tone == bass)
+			.and_then(|i| inversions.get(i).copied())
+	}
+
+	/// Chord tones within the octave above the root, stacked in thirds (3rd, 5th, 7th)
+	fn stacked_tones(&self) -> Vec<PitchClass> {
+		let (required, optional) = self.intervals();
+		let mut tones: Vec<Interval> = required
+			.into_iter()
+			.chain(optional)
+			.filter(|i| (2..=7).contains(&i.distance))
+			.collect();
+		tones.sort_by_key(|i| i.distance);
+		tones
+			.iter()
+			.map(|i| self.root.add_semitones(i.to_semitones() as i32))
+			.collect()
+	}
+
+	/// Bass note to print after the slash; a root-position bass is implied
+	fn slash_bass(&self) -> Option<PitchClass> {
+		self.bass.filter(|&bass| bass != self.root)
+	}
+
+	/// (required, optional) intervals of the quality with the alterations applied
+	pub fn intervals(&self) -> (Vec<Interval>, Vec<Interval>) {
+		let (mut required, mut optional) = self.quality.intervals();
+		for (i, alteration) in self.alterations.iter().enumerate() {
+			// b9 and #9 are both played; only the first replaces the quality's 9th
+			let stacked = self.alterations[..i].iter().any(|earlier| {
+				matches!(earlier, Alteration::Add(_)) && earlier.degree() == alteration.degree()
+			});
+			match alteration {
+				Alteration::Add(interval) if stacked => required.push(*interval),
+				_ => alteration.apply(&mut required, &mut optional),
+			}
+		}
+		(required, optional)
+	}
+
+	/// Alterations as written after the quality, e.g. "sus4(b9,#11)"; empty without any
+	fn alterations_suffix(&self) -> String {
+		let (sus, rest): (Vec<&Alteration>, Vec<_>) = self
+			.alterations
+			.iter()
+			.partition(|a| matches!(a, Alteration::Sus(_)));
+		let sus: String = sus.iter().map(|a| a.to_string()).collect();
+		if rest.is_empty() {
+			return sus;
+		}
+		let list: Vec<String> = rest.iter().map(|a| a.to_string()).collect();
+		format!("{sus}({})", list.join(","))
+	}
+
+	/// Chord symbol in the given naming profile (`Display` uses the standard profile)
+	pub fn name(&self, profile: NamingProfile) -> String {
+		self.format(&ChordFormat {
+			profile,
+			..Default::default()
+		})
+	}
+
+	/// Chord name written in the given format
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::chord::{Chord, ChordFormat};
+	///
+	/// let chord = Chord::parse("Bbm7b5").unwrap();
+	/// assert_eq!(chord.format(&ChordFormat::lead_sheet()), "B♭ø⁷");
+	/// ```
+	pub fn format(&self, format: &ChordFormat) -> String {
+		let accidentals = self.accidentals();
+		let root = format.apply(accidentals.spell(self.root));
+		let symbol = format.apply(&format!(
+			"{}{}",
+			self.quality.symbol(format.profile),
+			self.alterations_suffix()
+		));
+		match self.slash_bass() {
+			Some(bass) => format!("{root}{symbol}/{}", format.apply(accidentals.spell(bass))),
+			None => format!("{root}{symbol}"),
 		}
 	}
 
@@ -367,12 +826,14 @@ impl Chord {
 		Chord {
 			root: self.root.add_semitones(semitones),
 			quality: self.quality,
+			alterations: self.alterations.clone(),
 			bass: self.bass.map(|b| b.add_semitones(semitones)),
+			spelling: self.spelling,
 		}
 	}
 
 	pub fn notes(&self) -> Vec<PitchClass> {
-		let (required, optional) = self.quality.intervals();
+		let (required, optional) = self.intervals();
 		let all_intervals: Vec<_> = required.into_iter().chain(optional).collect();
 
 		all_intervals
@@ -382,7 +843,7 @@ impl Chord {
 	}
 
 	pub fn required_notes(&self) -> Vec<PitchClass> {
-		let (required, _) = self.quality.intervals();
+		let (required, _) = self.intervals();
 		required
 			.iter()
 			.map(|interval| self.root.add_semitones(interval.to_semitones() as i32))
@@ -391,7 +852,7 @@ impl Chord {
 
 	/// For triads: root, 3rd, 5th. For 7th chords: root, 3rd, 7th (5th omittable).
 	pub fn core_notes(&self) -> Vec<PitchClass> {
-		let (required, _) = self.quality.intervals();
+		let (required, _) = self.intervals();
 
 		let skip_fifth = self.quality.can_omit_fifth();
 
@@ -414,13 +875,21 @@ impl Chord {
 			return Err(ChordCraftError::InvalidChordName(s.to_string()));
 		}
 
-		if let Some(slash_pos) = s.find('/') {
+		// The slash in 6/9 is part of the quality, and one inside parentheses
+		// separates alterations; neither is a bass note
+		let slash = s.match_indices('/').map(|(i, _)| i).find(|&i| {
+			let in_parens = s[..i].matches('(').count() > s[..i].matches(')').count();
+			let six_nine = s[..i].ends_with('6') && s[i + 1..].starts_with('9');
+			!in_parens && !six_nine
+		});
+		if let Some(slash_pos) = slash {
 			let chord_part = &s[..slash_pos];
 			let bass_part = &s[slash_pos + 1..];
 
 			let mut chord = Self::parse(chord_part)?;
 			let bass = PitchClass::parse(bass_part)?;
 			chord.bass = Some(bass);
+			chord.spelling = chord.spelling.or(Accidentals::of_name(bass_part));
 			return Ok(chord);
 		}
 
@@ -432,9 +901,116 @@ impl Chord {
 
 		let root = PitchClass::parse(&s[..root_end])?;
 		let quality_str = &s[root_end..];
-		let quality = Self::parse_quality(quality_str)?;
+		let (quality, alterations) = match Self::parse_quality(quality_str) {
+			Ok(quality) => (quality, Vec::new()),
+			Err(_) => Self::parse_altered_quality(quality_str)?,
+		};
 
-		Ok(Chord::new(root, quality))
+		Ok(Chord {
+			alterations,
+			spelling: Accidentals::of_name(&s[..root_end]),
+			..Chord::new(root, quality)
+		})
+	}
+
+	/// A quality followed by a parenthesized alteration list: "7(b9,#11)",
+	/// "m7(add11)", "dim(maj7)", "7sus(b9)"
+	///
+	/// The list may be left out after stacked or sus alterations ("7b9#11", "7sus4").
+	/// The same alteration can't be written twice, nor repeat a tone the quality
+	/// already has (the b9 of 7b9), nor can a left out degree be altered.
+	fn parse_altered_quality(s: &str) -> Result<(ChordQuality, Vec<Alteration>)> {
+		let (base, list) = match s.strip_suffix(')').and_then(|s| s.rsplit_once('(')) {
+			Some((base, list)) => (base, Some(list)),
+			None => (s, None),
+		};
+		let (quality, mut alterations) = Self::parse_base_quality(base)?;
+
+		if let Some(list) = list {
+			let listed = list
+				.split([',', '/', ' '])
+				.filter(|token| !token.is_empty())
+				.map(Alteration::parse)
+				.collect::<Result<Vec<_>>>()?;
+			if listed.is_empty() {
+				return Err(ChordCraftError::InvalidChordName(format!(
+					"Empty alteration list: {s}"
+				)));
+			}
+			alterations.extend(listed);
+		}
+
+		let (required, optional) = quality.intervals();
+		for (i, alteration) in alterations.iter().enumerate() {
+			if let Alteration::Add(interval) = alteration
+				&& required
+					.iter()
+					.chain(&optional)
+					.any(|tone| tone == interval)
+			{
+				return Err(ChordCraftError::InvalidChordName(format!(
+					"Alteration {alteration} repeats a tone of the quality: {s}"
+				)));
+			}
+			let clash = alterations[..i].iter().find(|earlier| {
+				*earlier == alteration
+					|| (earlier.degree() == alteration.degree()
+						&& [*earlier, alteration]
+							.iter()
+							.any(|a| matches!(a, Alteration::Omit(_))))
+			});
+			if let Some(earlier) = clash {
+				return Err(ChordCraftError::InvalidChordName(format!(
+					"Alteration {alteration} repeats or contradicts {earlier}: {s}"
+				)));
+			}
+		}
+		Ok((quality, alterations))
+	}
+
+	/// A quality, optionally with sus or stacked alterations after it ("7sus4", "7b9#11")
+	fn parse_base_quality(s: &str) -> Result<(ChordQuality, Vec<Alteration>)> {
+		let err = match Self::parse_quality(s) {
+			Ok(quality) => return Ok((quality, Vec::new())),
+			Err(err) => err,
+		};
+
+		// sus replaces a major third, so the quality needs one ("7sus", not "dimsus")
+		for (suffix, sus) in [("sus4", 4), ("sus2", 2), ("sus", 4)] {
+			if let Some(quality) = s
+				.strip_suffix(suffix)
+				.filter(|base| !base.is_empty())
+				.and_then(|base| Self::parse_quality(base).ok())
+				&& quality.intervals().0.contains(&MAJOR_THIRD)
+			{
+				return Ok((quality, vec![Alteration::Sus(sus)]));
+			}
+		}
+
+		// The longest quality followed by flat or sharp degrees, so "7b9#11"
+		// reads as 7b9 with a #11, like "7b9(#11)"
+		let accidentals: Vec<usize> = s
+			.char_indices()
+			.filter(|&(i, c)| i > 0 && matches!(c, 'b' | '#' | '♭' | '♯'))
+			.map(|(i, _)| i)
+			.collect();
+		for (n, &start) in accidentals.iter().enumerate().rev() {
+			let Ok(quality) = Self::parse_quality(&s[..start]) else {
+				continue;
+			};
+			let ends = accidentals[n + 1..].iter().copied().chain([s.len()]);
+			let stacked = accidentals[n..]
+				.iter()
+				.zip(ends)
+				.map(|(&from, to)| Alteration::parse(&s[from..to]))
+				.collect::<Result<Vec<_>>>();
+			if let Ok(alterations) = stacked
+				&& alterations.iter().all(|a| matches!(a, Alteration::Add(_)))
+			{
+				return Ok((quality, alterations));
+			}
+		}
+		Err(err)
 	}
 
 	fn parse_quality(s: &str) -> Result<ChordQuality> {
@@ -444,29 +1020,35 @@ impl Chord {
 			return Ok(Major);
 		}
 
+		let written = s;
 		let s = s.replace(['♭', '♯'], "");
 		let s_lower = s.to_lowercase();
 
 		// Order matters - check longer patterns first!
 		match s_lower.as_str() {
 			// Minor variations
-			"m(maj7)" | "mmaj7" | "mM7" | "minmaj7" => Ok(MinorMajor7),
-			"m7b5" | "m7♭5" | "ø" | "half-dim" | "halfdim" => Ok(HalfDiminished7),
-			"madd9" | "m(add9)" => Ok(MinorAdd9),
-			"m13" | "min13" => Ok(Minor13),
-			"m11" | "min11" => Ok(Minor11),
-			"m9" | "min9" => Ok(Minor9),
-			"m7" | "min7" => Ok(Minor7),
-			"m6" | "min6" => Ok(Minor6),
+			"m(maj7)" | "mmaj7" | "mM7" | "minmaj7" | "-∆7" => Ok(MinorMajor7),
+			"m7b5" | "m7♭5" | "ø" | "ø7" | "half-dim" | "halfdim" => Ok(HalfDiminished7),
+			"madd9" | "m(add9)" | "-(add9)" | "m2" => Ok(MinorAdd9),
+			"m13" | "min13" | "-13" => Ok(Minor13),
+			"m11" | "min11" | "-11" => Ok(Minor11),
+			"m9" | "min9" | "-9" => Ok(Minor9),
+			"m7" | "min7" | "-7" => Ok(Minor7),
+			"m6" | "min6" | "-6" => Ok(Minor6),
 			"m" | "min" | "-" => Ok(Minor),
 
 			// Major 7th variations
-			"maj13" | "M13" | "Δ13" => Ok(Major13),
-			"maj9" | "M9" | "Δ9" => Ok(Major9),
-			"maj7" | "M7" | "Δ7" | "Δ" => Ok(Major7),
+			"maj13" | "M13" | "Δ13" | "∆13" => Ok(Major13),
+			"maj9" | "M9" | "Δ9" | "∆9" => Ok(Major9),
+			"maj7" | "M7" | "Δ7" | "Δ" | "∆7" | "∆" => Ok(Major7),
 			"maj" | "M" => Ok(Major),
 
+			// Major 7th #11
+			"maj7#11" | "M7#11" | "Δ7#11" | "∆7#11" | "maj7(#11)" => Ok(Major7sharp11),
+
 			// Dominant variations
+			"9sus4" | "9sus" => Ok(Dominant9sus4),
+			"7alt" | "alt" => Ok(Dominant7alt),
 			"13" => Ok(Dominant13),
 			"11" => Ok(Dominant11),
 			"9" => Ok(Dominant9),
@@ -489,32 +1071,83 @@ impl Chord {
 
 			// Add chords
 			"add11" => Ok(Add11),
-			"add9" => Ok(Add9),
+			"add9" | "2" => Ok(Add9),
 
 			// 6th chords
+			"6/9" | "69" | "6add9" => Ok(SixNine),
 			"6" => Ok(Major6),
 
-			_ => Err(ChordCraftError::InvalidChordName(format!(
-				"Unknown chord quality: {s}"
-			))),
+			// Power chord
+			"5" => Ok(Power),
+
+			_ => formula::lookup(written).ok_or_else(|| {
+				ChordCraftError::InvalidChordName(format!("Unknown chord quality: {s}"))
+			}),
 		}
 	}
 }
 
 impl fmt::Display for Chord {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{}{}", self.root, self.quality.display_name())?;
-		if let Some(bass) = self.bass {
-			write!(f, "/{bass}")?;
+		let accidentals = self.accidentals();
+		write!(
+			f,
+			"{}{}{}",
+			accidentals.spell(self.root),
+			self.quality.display_name(),
+			self.alterations_suffix()
+		)?;
+		if let Some(bass) = self.slash_bass() {
+			write!(f, "/{}", accidentals.spell(bass))?;
 		}
 		Ok(())
 	}
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Chord {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Chord {
+	fn deserialize<D: serde::Deserializer<'de>>(
+		deserializer: D,
+	) -> std::result::Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Chord::parse(&s).map_err(serde::de::Error::custom)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::note::PitchClass;
+	use strum::IntoEnumIterator;
+
+	#[test]
+	fn test_parse_keeps_spelling() {
+		let abm7 = Chord::parse("Abm7").unwrap();
+		assert_eq!(abm7.to_string(), "Abm7");
+		assert_eq!(abm7.name(NamingProfile::Jazz), "Ab-7");
+		assert_eq!(abm7, Chord::parse("G#m7").unwrap());
+		assert_eq!(Chord::parse("G#m7").unwrap().to_string(), "G#m7");
+
+		assert_eq!(abm7.transpose(2).to_string(), "Bbm7");
+		assert_eq!(Chord::parse("C/Bb").unwrap().to_string(), "C/Bb");
+		assert_eq!(
+			Chord::parse("D#")
+				.unwrap()
+				.with_spelling(Accidentals::Flats)
+				.to_string(),
+			"Eb"
+		);
+	}
 
 	#[test]
 	fn test_chord_parse_basic() {
@@ -539,6 +1172,176 @@ mod tests {
 		assert_eq!(dm7.quality, ChordQuality::Minor7);
 	}
 
+	#[test]
+	fn test_chord_parse_alterations() {
+		let chord = Chord::parse("C7(b9,#11)").unwrap();
+		assert_eq!(chord.quality, ChordQuality::Dominant7);
+		assert_eq!(chord.to_string(), "C7(b9,#11)");
+		let notes = chord.required_notes();
+		assert!(notes.contains(&PitchClass::CSharp) && notes.contains(&PitchClass::FSharp));
+		assert_eq!(chord.transpose(2).to_string(), "D7(b9,#11)");
+
+		let gm7 = Chord::parse("Gm7(add11)/F").unwrap();
+		assert_eq!(gm7.quality, ChordQuality::Minor7);
+		assert_eq!(gm7.alterations, [Alteration::Add(PERFECT_ELEVENTH)]);
+		assert_eq!(gm7.bass, Some(PitchClass::F));
+		assert!(gm7.core_notes().contains(&PitchClass::C));
+
+		// A flat 5th replaces the natural one; no3 drops the third
+		let c7b5 = Chord::parse("C7(b5,no3)").unwrap();
+		assert!(!c7b5.notes().contains(&PitchClass::G));
+		assert!(!c7b5.notes().contains(&PitchClass::E));
+		assert!(c7b5.notes().contains(&PitchClass::FSharp));
+
+		// Qualities written with parentheses still parse as before
+		assert_eq!(
+			Chord::parse("Cm(maj7)").unwrap().quality,
+			ChordQuality::MinorMajor7
+		);
+		assert!(Chord::parse("C7(b9,x)").is_err());
+		assert!(Chord::parse("C7()").is_err());
+		assert_ne!(Chord::parse("C7").unwrap(), chord);
+
+		// Any base quality takes alterations, including a sus
+		let dim = Chord::parse("Cdim(maj7)").unwrap();
+		assert_eq!(dim.quality, ChordQuality::Diminished);
+		assert!(dim.notes().contains(&PitchClass::B));
+		assert_eq!(dim.to_string(), "Cdim(maj7)");
+		let sus = Chord::parse("C7sus(b9)").unwrap();
+		assert_eq!(sus.quality, ChordQuality::Dominant7);
+		assert_eq!(sus.to_string(), "C7sus4(b9)");
+		assert!(sus.notes().contains(&PitchClass::F));
+		assert!(!sus.notes().contains(&PitchClass::E));
+		assert_eq!(Chord::parse("C7sus4").unwrap().to_string(), "C7sus4");
+		assert_eq!(Chord::parse(&sus.to_string()).unwrap(), sus);
+
+		// Stacked alterations read like a parenthesized list after the longest quality
+		assert_eq!(
+			Chord::parse("C7b9#11").unwrap(),
+			Chord::parse("C7b9(#11)").unwrap()
+		);
+		assert_eq!(
+			Chord::parse("C9b5").unwrap(),
+			Chord::parse("C9(b5)").unwrap()
+		);
+
+		// b9 and #9 are both played, but nothing is written twice or both
+		// altered and left out
+		let altered = Chord::parse("C7(b9,#9)").unwrap().notes();
+		assert!(altered.contains(&PitchClass::CSharp) && altered.contains(&PitchClass::DSharp));
+		assert!(Chord::parse("C7(b9,b9)").is_err());
+		assert!(Chord::parse("C7(no5,b5)").is_err());
+		assert!(Chord::parse("C7b9(b9)").is_err());
+		assert!(Chord::parse("C7#9(#9)").is_err());
+		assert!(Chord::parse("Cmaj7#11(#11)").is_err());
+		assert!(Chord::parse("C7#9(b9)").is_ok());
+		assert!(Chord::parse("Cdimsus").is_err());
+	}
+
+	#[test]
+	fn test_chord_from_intervals() {
+		let c = PitchClass::C;
+		assert_eq!(
+			Chord::from_intervals(c, &[MAJOR_THIRD, PERFECT_FIFTH]).unwrap(),
+			Chord::new(c, ChordQuality::Major)
+		);
+		assert_eq!(
+			Chord::from_intervals(c, &[UNISON, MINOR_THIRD, MINOR_SEVENTH])
+				.unwrap()
+				.quality,
+			ChordQuality::Minor7
+		);
+		let dim7 = [
+			MINOR_THIRD,
+			Interval::new(IntervalQuality::Diminished, 5),
+			Interval::new(IntervalQuality::Diminished, 7),
+		];
+		assert_eq!(
+			Chord::from_intervals(c, &dim7).unwrap().quality,
+			ChordQuality::Diminished7
+		);
+		assert_eq!(
+			Chord::from_intervals(c, &[MAJOR_SECOND, MAJOR_THIRD, PERFECT_FIFTH])
+				.unwrap()
+				.quality,
+			ChordQuality::Add9
+		);
+
+		// The minor 6th is an augmented 5th by pitch class
+		assert_eq!(
+			Chord::from_intervals(c, &[MAJOR_THIRD, MINOR_SIXTH])
+				.unwrap()
+				.quality,
+			ChordQuality::Augmented
+		);
+
+		let custom =
+			Chord::from_intervals(PitchClass::D, &[MAJOR_SIXTH, MAJOR_THIRD, MINOR_SEVENTH])
+				.unwrap();
+		assert!(matches!(custom.quality, ChordQuality::Custom(_)));
+		assert_eq!(custom.to_string(), "D{1,3,6,b7}");
+		assert_eq!(Chord::parse("D{1,3,6,b7}").unwrap(), custom);
+		assert!(Chord::from_intervals(c, &[UNISON]).is_err());
+	}
+
+	#[test]
+	fn test_inversions() {
+		let c = Chord::parse("C").unwrap();
+		let first = c.clone().with_inversion(Inversion::First).unwrap();
+		assert_eq!(first.to_string(), "C/E");
+		assert_eq!(first.inversion(), Some(Inversion::First));
+		assert_eq!(
+			c.clone()
+				.with_inversion(Inversion::Second)
+				.unwrap()
+				.to_string(),
+			"C/G"
+		);
+		assert!(c.clone().with_inversion(Inversion::Third).is_err());
+
+		// Root position pins the root to the bass without a slash
+		let root = c.with_inversion(Inversion::Root).unwrap();
+		assert_eq!(root.bass, Some(PitchClass::C));
+		assert_eq!(root.to_string(), "C");
+		assert_eq!(root.inversion(), Some(Inversion::Root));
+
+		let g7 = Chord::parse("G7").unwrap().with_inversion(Inversion::Third);
+		assert_eq!(g7.unwrap().to_string(), "G7/F");
+		assert_eq!(Chord::parse("Am/G").unwrap().inversion(), None);
+		assert_eq!(Chord::parse("Am").unwrap().inversion(), None);
+	}
+
+	#[test]
+	fn test_chord_parse_power_and_colour_chords() {
+		for (name, quality) in [
+			("C5", ChordQuality::Power),
+			("C6/9", ChordQuality::SixNine),
+			("C69", ChordQuality::SixNine),
+			("C9sus4", ChordQuality::Dominant9sus4),
+			("C7alt", ChordQuality::Dominant7alt),
+			("Cmaj7#11", ChordQuality::Major7sharp11),
+		] {
+			let chord = Chord::parse(name).unwrap();
+			assert_eq!(chord.quality, quality, "{name}");
+			assert_eq!(chord.bass, None, "{name}");
+		}
+
+		assert_eq!(
+			Chord::parse("C5").unwrap().notes(),
+			[PitchClass::C, PitchClass::G]
+		);
+		assert_eq!(Chord::parse("C6/9").unwrap().to_string(), "C6/9");
+		let slash = Chord::parse("C6/9/E").unwrap();
+		assert_eq!(slash.quality, ChordQuality::SixNine);
+		assert_eq!(slash.bass, Some(PitchClass::E));
+		assert!(
+			Chord::parse("Cmaj7#11")
+				.unwrap()
+				.required_notes()
+				.contains(&PitchClass::FSharp)
+		);
+	}
+
 	#[test]
 	fn test_chord_parse_extended() {
 		let cmaj9 = Chord::parse("Cmaj9").unwrap();
@@ -574,6 +1377,53 @@ mod tests {
 		assert!(notes.contains(&PitchClass::G));
 	}
 
+	#[test]
+	fn test_naming_profiles_roundtrip() {
+		for profile in [
+			NamingProfile::Standard,
+			NamingProfile::Pop,
+			NamingProfile::Jazz,
+		] {
+			for quality in ChordQuality::iter() {
+				let chord = Chord::new(PitchClass::D, quality);
+				let name = chord.name(profile);
+				assert_eq!(Chord::parse(&name).unwrap(), chord, "{name} ({profile:?})");
+			}
+		}
+
+		let chord = Chord::parse("Cmaj9").unwrap();
+		assert_eq!(chord.name(NamingProfile::Jazz), "C∆9");
+		assert_eq!(
+			Chord::parse("Cadd9").unwrap().name(NamingProfile::Pop),
+			"C2"
+		);
+		assert_eq!(
+			Chord::parse("Bm7b5/F").unwrap().name(NamingProfile::Jazz),
+			"Bø7/F"
+		);
+	}
+
+	#[test]
+	fn test_chord_formats() {
+		let format = |name: &str, format: ChordFormat| Chord::parse(name).unwrap().format(&format);
+		let unicode = ChordFormat {
+			unicode_accidentals: true,
+			..Default::default()
+		};
+		let superscript = ChordFormat {
+			superscript: true,
+			..Default::default()
+		};
+
+		assert_eq!(format("Abm7", ChordFormat::default()), "Abm7");
+		assert_eq!(format("Bbm7b5/Fb", unicode), "B♭m7♭5/E");
+		assert_eq!(format("F#7(#9)", unicode), "F♯7(♯9)");
+		assert_eq!(format("C7(b9,#11)", superscript), "C⁷⁽b⁹,#¹¹⁾");
+		assert_eq!(format("Cmaj9", superscript), "Cmaj⁹");
+		assert_eq!(format("Dbmaj7/Ab", ChordFormat::lead_sheet()), "D♭∆⁷/A♭");
+		assert_eq!(format("Ebm", ChordFormat::lead_sheet()), "E♭-");
+	}
+
 	#[test]
 	fn test_chord_display() {
 		assert_eq!(Chord::parse("Cmaj7").unwrap().to_string(), "Cmaj7");
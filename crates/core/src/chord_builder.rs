@@ -0,0 +1,332 @@
+//! Interactive chord building
+//!
+//! [`ChordBuilder`] holds a concrete voicing (tones as semitone offsets from a
+//! root note) and applies small edits to it, like adding a 9th, dropping the
+//! 5th or moving the 3rd up an octave. After each edit the chord is renamed
+//! with the analyzer, and fingerings for the new sound can be regenerated near
+//! the previous one, so learning UIs can explore how chords are put together.
+
+use crate::analyzer::{ChordMatch, analyze_fingering, analyze_pitches};
+use crate::chord::{Chord, ChordQuality};
+use crate::error::{ChordCraftError, Result};
+use crate::fingering::Fingering;
+use crate::generator::{GeneratorOptions, ScoredFingering, generate_fingerings};
+use crate::instrument::Instrument;
+use crate::interval::Interval;
+use crate::note::{Note, PitchClass};
+
+/// Octave of the root when building from a chord name
+const DEFAULT_ROOT_OCTAVE: i8 = 3;
+
+/// Fingerings generated before filtering down to the exact pitch set
+const CANDIDATE_LIMIT: usize = 100;
+
+/// An incremental change to a [`ChordBuilder`] voicing
+///
+/// Tones are addressed by interval above the root; octaves don't matter when
+/// matching (a 9th and a 2nd address the same tone).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordEdit {
+	/// Add a tone at this interval above the root
+	Add(Interval),
+	/// Remove every tone with this interval's pitch class
+	Drop(Interval),
+	/// Move the lowest matching tone up an octave
+	RaiseOctave(Interval),
+	/// Move the highest matching tone down an octave
+	LowerOctave(Interval),
+}
+
+#[derive(Debug, Clone)]
+pub struct ChordBuilder {
+	root: Note,
+	/// Semitone offsets from `root`, sorted (may be negative below the root)
+	tones: Vec<i32>,
+	history: Vec<Vec<i32>>,
+}
+
+impl ChordBuilder {
+	/// Start from a close-position voicing of root + quality
+	pub fn new(root: PitchClass, quality: ChordQuality) -> Self {
+		let (required, optional) = quality.intervals();
+		let mut tones: Vec<i32> = required
+			.iter()
+			.chain(&optional)
+			.map(|i| i.to_semitones() as i32)
+			.collect();
+		tones.sort_unstable();
+		tones.dedup();
+
+		ChordBuilder {
+			root: Note::new(root, DEFAULT_ROOT_OCTAVE),
+			tones,
+			history: Vec::new(),
+		}
+	}
+
+	pub fn from_chord(chord: &Chord) -> Self {
+		ChordBuilder::new(chord.root, chord.quality)
+	}
+
+	/// Start from the notes a fingering sounds, rooted on its best chord match
+	///
+	/// Returns None if the fingering can't be identified as a chord.
+	pub fn from_fingering<I: Instrument>(fingering: &Fingering, instrument: &I) -> Option<Self> {
+		let best = analyze_fingering(fingering, instrument)
+			.into_iter()
+			.next()?;
+		let mut notes = fingering.notes(instrument);
+		notes.sort_by_key(|n| n.to_midi());
+		let lowest = *notes.first()?;
+
+		// Root at or below the lowest note, so the offsets stay non-negative
+		let below = best.chord.root.semitone_distance_to(&lowest.pitch) as i32;
+		let root = lowest.add_semitones(-below);
+
+		let mut tones: Vec<i32> = notes
+			.iter()
+			.map(|n| n.to_midi() as i32 - root.to_midi() as i32)
+			.collect();
+		tones.dedup();
+
+		Some(ChordBuilder {
+			root,
+			tones,
+			history: Vec::new(),
+		})
+	}
+
+	/// Apply an edit; invalid edits leave the voicing unchanged
+	pub fn apply(&mut self, edit: ChordEdit) -> Result<()> {
+		let mut tones = self.tones.clone();
+		let find = |tones: &[i32], interval: &Interval| -> Vec<usize> {
+			let pc = interval.to_semitones() as i32 % 12;
+			(0..tones.len())
+				.filter(|&i| tones[i].rem_euclid(12) == pc)
+				.collect()
+		};
+
+		match edit {
+			ChordEdit::Add(interval) => {
+				let offset = interval.to_semitones() as i32;
+				if tones.contains(&offset) {
+					return Err(ChordCraftError::InvalidEdit(format!(
+						"{} is already in the chord",
+						interval.full_name()
+					)));
+				}
+				tones.push(offset);
+			}
+			ChordEdit::Drop(interval) => {
+				let found = find(&tones, &interval);
+				if found.is_empty() || found.len() == tones.len() {
+					return Err(missing_tone(&interval));
+				}
+				tones.retain(|t| t.rem_euclid(12) != interval.to_semitones() as i32 % 12);
+			}
+			ChordEdit::RaiseOctave(interval) => {
+				let &i = find(&tones, &interval)
+					.first()
+					.ok_or_else(|| missing_tone(&interval))?;
+				tones[i] += 12;
+			}
+			ChordEdit::LowerOctave(interval) => {
+				let &i = find(&tones, &interval)
+					.last()
+					.ok_or_else(|| missing_tone(&interval))?;
+				tones[i] -= 12;
+			}
+		}
+
+		tones.sort_unstable();
+		tones.dedup();
+		self.history.push(std::mem::replace(&mut self.tones, tones));
+		Ok(())
+	}
+
+	/// Revert the last edit; returns false if there is nothing to undo
+	pub fn undo(&mut self) -> bool {
+		match self.history.pop() {
+			Some(tones) => {
+				self.tones = tones;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Sounding notes, lowest first
+	pub fn notes(&self) -> Vec<Note> {
+		self.tones
+			.iter()
+			.map(|&t| self.root.add_semitones(t))
+			.collect()
+	}
+
+	/// Unique pitch classes, in order of first appearance from the bass up
+	pub fn pitch_classes(&self) -> Vec<PitchClass> {
+		let mut pitches = Vec::new();
+		for note in self.notes() {
+			if !pitches.contains(&note.pitch) {
+				pitches.push(note.pitch);
+			}
+		}
+		pitches
+	}
+
+	pub fn bass(&self) -> Option<Note> {
+		self.notes().first().copied()
+	}
+
+	/// Chord interpretations of the current voicing, best first
+	pub fn matches(&self) -> Vec<ChordMatch> {
+		analyze_pitches(&self.pitch_classes(), self.bass().map(|n| n.pitch))
+	}
+
+	/// Best chord interpretation, as a slash chord when the root isn't in the bass
+	///
+	/// A complete chord on the builder's root wins over other readings, so
+	/// moving a tone into the bass gives an inversion (Cadd9/G) rather than a
+	/// new chord (Gsus4 with an extra note).
+	pub fn chord(&self) -> Option<Chord> {
+		let matches = self.matches();
+		let best = matches
+			.iter()
+			.find(|m| m.chord.root == self.root.pitch && m.completeness >= 1.0)
+			.or(matches.first())?
+			.clone();
+		let bass = self.bass()?.pitch;
+		Some(if bass == best.chord.root {
+			best.chord
+		} else {
+			Chord::with_bass(best.chord.root, best.chord.quality, bass)
+		})
+	}
+
+	pub fn name(&self) -> Option<String> {
+		self.chord().map(|c| c.to_string())
+	}
+
+	/// Fingerings sounding exactly the current pitch classes
+	///
+	/// Fingerings with the voicing's bass note in the bass come first, then
+	/// the ones closest to `near` (e.g., the fingering shown before the edit),
+	/// then by score.
+	pub fn fingerings<I: Instrument>(
+		&self,
+		instrument: &I,
+		near: Option<&Fingering>,
+		options: &GeneratorOptions,
+	) -> Vec<ScoredFingering> {
+		let Some(chord) = self.chord() else {
+			return vec![];
+		};
+		let pitches = self.pitch_classes();
+		let bass = self.bass().map(|n| n.pitch);
+		let near_position = near.and_then(|f| f.min_fret()).unwrap_or(0) as i32;
+
+		let mut fingerings: Vec<ScoredFingering> = generate_fingerings(
+			&chord,
+			instrument,
+			&GeneratorOptions {
+				limit: CANDIDATE_LIMIT,
+				preferred_position: options
+					.preferred_position
+					.or(near.and_then(|f| f.min_fret())),
+				..options.clone()
+			},
+		)
+		.into_iter()
+		.filter(|f| {
+			let sounding = f.fingering.unique_pitch_classes(instrument);
+			sounding.len() == pitches.len() && pitches.iter().all(|p| sounding.contains(p))
+		})
+		.collect();
+
+		fingerings.sort_by_key(|f| {
+			let bass_matches = f.fingering.bass_note(instrument).map(|n| n.pitch) == bass;
+			(
+				std::cmp::Reverse(bass_matches),
+				(f.position as i32 - near_position).abs(),
+				std::cmp::Reverse(f.score),
+			)
+		});
+		fingerings.truncate(options.limit);
+		fingerings
+	}
+}
+
+fn missing_tone(interval: &Interval) -> ChordCraftError {
+	ChordCraftError::InvalidEdit(format!("no {} to change", interval.full_name()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+	use crate::interval::{MAJOR_NINTH, MAJOR_THIRD, PERFECT_FIFTH};
+
+	#[test]
+	fn test_edits_rename_chord() {
+		let mut builder = ChordBuilder::new(PitchClass::C, ChordQuality::Major);
+		assert_eq!(builder.name().as_deref(), Some("C"));
+
+		builder.apply(ChordEdit::Add(MAJOR_NINTH)).unwrap();
+		assert_eq!(builder.name().as_deref(), Some("Cadd9"));
+
+		builder.apply(ChordEdit::Drop(MAJOR_THIRD)).unwrap();
+		assert_eq!(builder.name().as_deref(), Some("Csus2"));
+
+		assert!(builder.undo());
+		assert_eq!(builder.name().as_deref(), Some("Cadd9"));
+
+		// Moving the 5th below the root makes it the bass
+		builder
+			.apply(ChordEdit::LowerOctave(PERFECT_FIFTH))
+			.unwrap();
+		assert_eq!(builder.notes()[0].to_string(), "G2");
+		assert_eq!(builder.name().as_deref(), Some("Cadd9/G"));
+	}
+
+	#[test]
+	fn test_invalid_edits() {
+		let mut builder = ChordBuilder::new(PitchClass::A, ChordQuality::Minor);
+		assert!(builder.apply(ChordEdit::Drop(MAJOR_THIRD)).is_err());
+		assert!(builder.apply(ChordEdit::Add(PERFECT_FIFTH)).is_err());
+		assert!(!builder.undo());
+	}
+
+	#[test]
+	fn test_from_fingering_and_nearby_fingerings() {
+		let guitar = Guitar::default();
+		let c_major = Fingering::parse("x32010").unwrap();
+
+		let mut builder = ChordBuilder::from_fingering(&c_major, &guitar).unwrap();
+		assert_eq!(builder.notes()[0].to_string(), "C3");
+		assert_eq!(builder.name().as_deref(), Some("C"));
+
+		builder.apply(ChordEdit::Add(MAJOR_NINTH)).unwrap();
+		let fingerings = builder.fingerings(
+			&guitar,
+			Some(&c_major),
+			&GeneratorOptions {
+				limit: 3,
+				..Default::default()
+			},
+		);
+		assert!(!fingerings.is_empty());
+
+		let best = &fingerings[0];
+		assert_eq!(
+			best.fingering.bass_note(&guitar).map(|n| n.pitch),
+			Some(PitchClass::C)
+		);
+		assert!(best.position <= 3);
+		let mut pitches = best.fingering.unique_pitch_classes(&guitar);
+		pitches.sort_by_key(|p| p.to_semitone());
+		assert_eq!(
+			pitches,
+			vec![PitchClass::C, PitchClass::D, PitchClass::E, PitchClass::G]
+		);
+	}
+}
@@ -0,0 +1,209 @@
+//! ChordPro import
+//!
+//! Reads the chords of a ChordPro (`.cho`) song into a [`Song`]. Chords are
+//! the bracketed `[C]` marks in lyric lines; ChordPro carries no durations, so
+//! each chord lasts one bar. Sections come from `{start_of_*}` environments,
+//! from comments (`{c: Verse 2}`) outside them, and from blank lines between
+//! paragraphs. `{chorus}` repeats the last chorus.
+
+use crate::chord::Chord;
+use crate::error::{ChordCraftError, Result};
+use crate::song::{Song, SongSection};
+
+/// Import the chords of a ChordPro document
+///
+/// Annotations (`[*riff]`) and no-chord marks (`[N.C.]`) are skipped; any
+/// other chord that doesn't parse is an error.
+pub fn import_chordpro(text: &str) -> Result<Song> {
+	let mut song = Song::new(None);
+	let mut current: Option<SongSection> = None;
+	// Inside a `{start_of_*}` environment, which ends only at its `{end_of_*}`
+	let mut in_environment = false;
+	let mut in_tab = false;
+
+	for (line_number, line) in text.lines().enumerate() {
+		let line = line.trim();
+
+		if let Some((name, value)) = parse_directive(line) {
+			match name.as_str() {
+				"title" | "t" => song.title = value,
+				"time" => {
+					if let Some(beats) = value
+						.as_deref()
+						.and_then(|v| v.split('/').next())
+						.and_then(|b| b.trim().parse::<u8>().ok())
+						.filter(|&b| b > 0)
+					{
+						song.beats_per_bar = beats;
+					}
+				}
+				"start_of_tab" | "sot" => in_tab = true,
+				"end_of_tab" | "eot" => in_tab = false,
+				"chorus" => {
+					finish_section(&mut song, current.take());
+					if let Some(chorus) = song
+						.sections
+						.iter()
+						.rev()
+						.find(|s| s.label.as_deref() == Some("Chorus"))
+						.cloned()
+					{
+						song.sections.push(chorus);
+					}
+				}
+				"comment" | "c" | "comment_italic" | "ci" if !in_environment => {
+					finish_section(&mut song, current.take());
+					current = Some(SongSection::new(value));
+				}
+				_ => {
+					if let Some(label) = environment_start(&name) {
+						finish_section(&mut song, current.take());
+						current = Some(SongSection::new(value.or(label)));
+						in_environment = true;
+					} else if is_environment_end(&name) {
+						finish_section(&mut song, current.take());
+						in_environment = false;
+					}
+				}
+			}
+			continue;
+		}
+
+		if in_tab || line.starts_with('#') {
+			continue;
+		}
+
+		if line.is_empty() {
+			if !in_environment && current.as_ref().is_some_and(|s| !s.chords.is_empty()) {
+				finish_section(&mut song, current.take());
+			}
+			continue;
+		}
+
+		for name in bracketed_chords(line) {
+			if name.starts_with('*') || is_no_chord(name) {
+				continue;
+			}
+			let chord = Chord::parse(name).map_err(|_| {
+				ChordCraftError::InvalidChordPro(format!(
+					"line {}: unknown chord [{name}]",
+					line_number + 1
+				))
+			})?;
+			let beats = song.beats_per_bar as f32;
+			current
+				.get_or_insert_with(|| SongSection::new(None))
+				.push(chord, beats);
+		}
+	}
+
+	finish_section(&mut song, current);
+	Ok(song)
+}
+
+fn finish_section(song: &mut Song, section: Option<SongSection>) {
+	if let Some(section) = section
+		&& !section.chords.is_empty()
+	{
+		song.sections.push(section);
+	}
+}
+
+/// Directive name (lowercased) and value of a `{name: value}` line
+fn parse_directive(line: &str) -> Option<(String, Option<String>)> {
+	let inner = line.strip_prefix('{')?.strip_suffix('}')?.trim();
+	let (name, value) = match inner.split_once([':', ' ']) {
+		Some((name, value)) => (name, Some(value.trim().to_string())),
+		None => (inner, None),
+	};
+	Some((name.trim().to_lowercase(), value.filter(|v| !v.is_empty())))
+}
+
+/// Default label of the section a `{start_of_*}` directive opens
+fn environment_start(name: &str) -> Option<Option<String>> {
+	let label = match name {
+		"soc" => "chorus",
+		"sov" => "verse",
+		"sob" => "bridge",
+		_ => name.strip_prefix("start_of_")?,
+	};
+	Some(Some(capitalize(label)))
+}
+
+fn is_environment_end(name: &str) -> bool {
+	matches!(name, "eoc" | "eov" | "eob") || name.starts_with("end_of_")
+}
+
+fn capitalize(s: &str) -> String {
+	let mut chars = s.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+fn bracketed_chords(line: &str) -> impl Iterator<Item = &str> {
+	line.split('[')
+		.skip(1)
+		.filter_map(|part| part.split_once(']').map(|(chord, _)| chord.trim()))
+		.filter(|chord| !chord.is_empty())
+}
+
+fn is_no_chord(name: &str) -> bool {
+	matches!(
+		name.to_uppercase().as_str(),
+		"N.C." | "NC" | "N.C" | "X" | "%"
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SONG: &str = "\
+{title: Test Song}
+{time: 3/4}
+
+{start_of_verse}
+[G]Down by the [C]river, [D]rolling [G]on
+{end_of_verse}
+
+{start_of_chorus: Refrain}
+[Em]Sing it [C]loud [*riff]
+{end_of_chorus}
+
+{c: Outro}
+[G]End [N.C.]
+";
+
+	#[test]
+	fn test_import_sections_and_chords() {
+		let song = import_chordpro(SONG).unwrap();
+		assert_eq!(song.title.as_deref(), Some("Test Song"));
+		assert_eq!(song.beats_per_bar, 3);
+
+		let labels: Vec<_> = song
+			.sections
+			.iter()
+			.map(|s| s.label.as_deref().unwrap_or(""))
+			.collect();
+		assert_eq!(labels, vec!["Verse", "Refrain", "Outro"]);
+		assert_eq!(song.chord_names(), vec!["G", "C", "D", "G", "Em", "C", "G"]);
+		assert_eq!(song.sections[0].chords[0].beats, 3.0);
+	}
+
+	#[test]
+	fn test_paragraphs_and_chorus_repeats() {
+		let text = "[C]One [F]two\n\n[G]three\n{soc}\n[Am]chorus\n{eoc}\n{chorus}\n";
+		let song = import_chordpro(text).unwrap();
+		assert_eq!(song.sections.len(), 4);
+		assert_eq!(song.sections[3].label.as_deref(), Some("Chorus"));
+		assert_eq!(song.sections[3].chords, song.sections[2].chords);
+	}
+
+	#[test]
+	fn test_unknown_chord_reports_line() {
+		let err = import_chordpro("[C]fine\n[Qzz]bad").unwrap_err();
+		assert!(err.to_string().contains("line 2"));
+	}
+}
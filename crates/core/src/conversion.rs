@@ -0,0 +1,238 @@
+//! Converting fingerings between tunings and instruments
+//!
+//! Tabs are tied to a tuning: `x32010` is a C chord only in standard tuning.
+//! [`convert_fingering`] finds the fingering on another instrument that comes
+//! closest to the original sound, and reports what had to change.
+
+use crate::fingering::{Fingering, StringState};
+use crate::instrument::Instrument;
+use crate::note::{Note, PitchClass};
+
+/// Highest fret searched on the target instrument
+const CONVERSION_MAX_FRET: u8 = 12;
+
+/// Result of [`convert_fingering`]
+#[derive(Debug, Clone)]
+pub struct FingeringConversion {
+	pub fingering: Fingering,
+	/// Source notes reproduced at the same octave
+	pub preserved_notes: Vec<Note>,
+	/// Source notes not sounding at their original octave on the target
+	pub lost_notes: Vec<Note>,
+	/// Source pitch classes missing entirely from the target fingering
+	pub lost_pitch_classes: Vec<PitchClass>,
+	/// The lowest source note is still the lowest note
+	pub bass_preserved: bool,
+}
+
+impl FingeringConversion {
+	/// Every source note sounds at its original octave
+	pub fn is_exact(&self) -> bool {
+		self.lost_notes.is_empty()
+	}
+}
+
+/// Find the fingering on `to` that sounds closest to `fingering` played on `from`
+///
+/// Candidates use only the source's pitch classes and must be playable on the
+/// target. They are ranked by how many source notes sound at the same octave,
+/// then by keeping the bass note, covering every pitch class, adding few
+/// foreign octaves and leaving strings as they were, preferring fuller,
+/// compact shapes low on the neck. Returns None if the source has no sounding notes
+/// or nothing playable exists on the target.
+pub fn convert_fingering<A: Instrument, B: Instrument>(
+	fingering: &Fingering,
+	from: &A,
+	to: &B,
+) -> Option<FingeringConversion> {
+	let mut source_notes = fingering.notes(from);
+	source_notes.sort_by_key(|n| n.to_midi());
+	source_notes.dedup();
+	let source_bass = *source_notes.first()?;
+	let source_pitches = fingering.unique_pitch_classes(from);
+
+	let max_fret = CONVERSION_MAX_FRET.min(to.fret_range().1);
+	let string_options: Vec<Vec<StringState>> = to
+		.tuning()
+		.iter()
+		.enumerate()
+		.map(|(string, &open)| {
+			let nut = to.string_nut_fret(string);
+			let top = max_fret.min(to.string_fret_range(string).1);
+			let mut states = vec![StringState::Muted];
+			states.extend(
+				(0..=top)
+					.filter(|&f| f == 0 || f > nut)
+					.map(StringState::Fretted)
+					.filter(|state| {
+						state
+							.note_above_nut(open, nut)
+							.is_some_and(|n| source_pitches.contains(&n.pitch))
+					}),
+			);
+			states
+		})
+		.collect();
+
+	let mut candidates = Vec::new();
+	collect_candidates(
+		&string_options,
+		to.max_stretch(),
+		&mut vec![],
+		&mut candidates,
+	);
+
+	let min_played = to.min_played_strings().min(source_notes.len());
+	candidates
+		.into_iter()
+		.map(Fingering::new)
+		.filter(|f| {
+			f.strings().iter().filter(|s| s.is_played()).count() >= min_played
+				&& f.is_playable_for(to)
+		})
+		.map(|f| {
+			evaluate(
+				f,
+				fingering,
+				&source_notes,
+				source_bass,
+				&source_pitches,
+				to,
+			)
+		})
+		.max_by_key(|(conversion, foreign, unchanged)| {
+			let fingering = &conversion.fingering;
+			(
+				conversion.preserved_notes.len(),
+				conversion.bass_preserved,
+				std::cmp::Reverse(conversion.lost_pitch_classes.len()),
+				std::cmp::Reverse(*foreign),
+				*unchanged,
+				fingering.strings().iter().filter(|s| s.is_played()).count(),
+				std::cmp::Reverse(fingering.fret_span()),
+				std::cmp::Reverse(fingering.min_fret().unwrap_or(0)),
+			)
+		})
+		.map(|(conversion, _, _)| conversion)
+}
+
+/// Build the conversion report, plus the foreign note count and unchanged string count
+fn evaluate<B: Instrument>(
+	candidate: Fingering,
+	source: &Fingering,
+	source_notes: &[Note],
+	source_bass: Note,
+	source_pitches: &[PitchClass],
+	to: &B,
+) -> (FingeringConversion, usize, usize) {
+	let notes = candidate.notes(to);
+	let pitches = candidate.unique_pitch_classes(to);
+
+	let (preserved_notes, lost_notes): (Vec<Note>, Vec<Note>) =
+		source_notes.iter().partition(|n| notes.contains(n));
+	let lost_pitch_classes = source_pitches
+		.iter()
+		.copied()
+		.filter(|p| !pitches.contains(p))
+		.collect();
+	let bass_preserved = notes.iter().min_by_key(|n| n.to_midi()) == Some(&source_bass);
+
+	let mut foreign: Vec<&Note> = notes.iter().filter(|n| !source_notes.contains(n)).collect();
+	foreign.sort_by_key(|n| n.to_midi());
+	foreign.dedup();
+	let unchanged = candidate
+		.strings()
+		.iter()
+		.zip(source.strings())
+		.filter(|(a, b)| a == b)
+		.count();
+
+	let foreign = foreign.len();
+	(
+		FingeringConversion {
+			fingering: candidate,
+			preserved_notes,
+			lost_notes,
+			lost_pitch_classes,
+			bass_preserved,
+		},
+		foreign,
+		unchanged,
+	)
+}
+
+/// Enumerate string states, pruning branches whose fretted span exceeds `max_stretch`
+fn collect_candidates(
+	string_options: &[Vec<StringState>],
+	max_stretch: u8,
+	current: &mut Vec<StringState>,
+	results: &mut Vec<Vec<StringState>>,
+) {
+	if current.len() == string_options.len() {
+		if current.iter().any(|s| s.is_played()) {
+			results.push(current.clone());
+		}
+		return;
+	}
+
+	let fretted = current.iter().filter_map(|s| s.fret()).filter(|&f| f > 0);
+	let (low, high) = fretted.fold((u8::MAX, 0), |(lo, hi), f| (lo.min(f), hi.max(f)));
+
+	for &state in &string_options[current.len()] {
+		if let StringState::Fretted(f) = state
+			&& f > 0 && f.max(high).saturating_sub(f.min(low)) > max_stretch
+		{
+			continue;
+		}
+		current.push(state);
+		collect_candidates(string_options, max_stretch, current, results);
+		current.pop();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::{ConfigurableInstrument, Guitar, Ukulele};
+
+	#[test]
+	fn test_convert_standard_to_drop_d() {
+		let guitar = Guitar::default();
+		let drop_d = ConfigurableInstrument::guitar_drop_d();
+
+		// Open E: the low E moves up two frets on the dropped string
+		let e_major = Fingering::parse("022100").unwrap();
+		let converted = convert_fingering(&e_major, &guitar, &drop_d).unwrap();
+		assert_eq!(converted.fingering.to_string(), "222100");
+		assert!(converted.is_exact());
+		assert!(converted.bass_preserved);
+
+		// Strings that didn't change keep their frets
+		let c_major = Fingering::parse("x32010").unwrap();
+		let converted = convert_fingering(&c_major, &guitar, &drop_d).unwrap();
+		assert_eq!(converted.fingering.to_string(), "x32010");
+	}
+
+	#[test]
+	fn test_convert_guitar_to_ukulele_reports_losses() {
+		let guitar = Guitar::default();
+		let ukulele = Ukulele::default();
+
+		let g_major = Fingering::parse("320003").unwrap();
+		let converted = convert_fingering(&g_major, &guitar, &ukulele).unwrap();
+
+		// The ukulele can't reach the low G, but keeps the chord's pitch classes
+		assert_eq!(converted.fingering.to_string(), "0232");
+		assert!(!converted.is_exact());
+		assert!(!converted.bass_preserved);
+		assert!(converted.lost_pitch_classes.is_empty());
+		assert!(!converted.preserved_notes.is_empty());
+	}
+
+	#[test]
+	fn test_convert_muted_fingering() {
+		let guitar = Guitar::default();
+		let muted = Fingering::parse("xxxxxx").unwrap();
+		assert!(convert_fingering(&muted, &guitar, &guitar).is_none());
+	}
+}
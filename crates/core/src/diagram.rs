@@ -0,0 +1,146 @@
+//! SVG chord diagrams
+//!
+//! [`svg_diagram`] draws the classic chord box: strings run top to bottom
+//! (lowest string on the left), frets left to right, with dots for fretted
+//! notes, bars for barres, hollow dots for harmonics, and `x`/`o` above the
+//! nut for muted and open strings. Shapes too high for the first frets start at their lowest
+//! fret, labelled at the side (e.g., `5fr`).
+
+use crate::fingering::{Fingering, StringState};
+use crate::instrument::Instrument;
+
+/// Frets drawn at least, even for compact shapes
+const MIN_FRETS_SHOWN: u8 = 4;
+const MARGIN_LEFT: u32 = 28;
+const MARGIN_RIGHT: u32 = 12;
+const MARGIN_TOP: u32 = 24;
+const MARGIN_BOTTOM: u32 = 20;
+const STRING_GAP: u32 = 16;
+const FRET_GAP: u32 = 20;
+const DOT_RADIUS: u32 = 6;
+
+/// Render a fingering as a standalone SVG chord box
+///
+/// The SVG is a single line with no external styles, so it can be embedded
+/// in HTML, CSV fields or flashcards as is.
+pub fn svg_diagram<I: Instrument>(fingering: &Fingering, instrument: &I) -> String {
+	let strings = fingering.strings();
+	let string_count = strings.len().max(instrument.string_count()).max(1) as u32;
+
+	let hand_frets: Vec<u8> = strings
+		.iter()
+		.filter_map(|s| match s {
+			StringState::Fretted(f) if *f > 0 => Some(*f),
+			StringState::Harmonic(f) => Some(*f),
+			_ => None,
+		})
+		.collect();
+	let highest = hand_frets.iter().copied().max().unwrap_or(0);
+	let base = if highest <= MIN_FRETS_SHOWN {
+		1
+	} else {
+		hand_frets.iter().copied().min().unwrap_or(1)
+	};
+	let frets_shown = MIN_FRETS_SHOWN.max(highest.saturating_sub(base) + 1) as u32;
+
+	let width = MARGIN_LEFT + (string_count - 1) * STRING_GAP + MARGIN_RIGHT;
+	let height = MARGIN_TOP + frets_shown * FRET_GAP + MARGIN_BOTTOM;
+	let x = |string: usize| MARGIN_LEFT + string as u32 * STRING_GAP;
+	let right = x(string_count as usize - 1);
+	let bottom = MARGIN_TOP + frets_shown * FRET_GAP;
+
+	let mut svg = format!(
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="10">"#
+	);
+
+	for string in 0..string_count as usize {
+		svg.push_str(&format!(
+			r#"<line x1="{0}" y1="{MARGIN_TOP}" x2="{0}" y2="{bottom}" stroke="black"/>"#,
+			x(string)
+		));
+	}
+	for fret in 0..=frets_shown {
+		let y = MARGIN_TOP + fret * FRET_GAP;
+		let stroke = if fret == 0 && base == 1 { 4 } else { 1 };
+		svg.push_str(&format!(
+			r#"<line x1="{MARGIN_LEFT}" y1="{y}" x2="{right}" y2="{y}" stroke="black" stroke-width="{stroke}"/>"#
+		));
+	}
+	if base > 1 {
+		svg.push_str(&format!(
+			r#"<text x="2" y="{}">{base}fr</text>"#,
+			MARGIN_TOP + FRET_GAP / 2 + 4
+		));
+	}
+
+	let fret_y = |fret: u8| MARGIN_TOP + (fret - base) as u32 * FRET_GAP + FRET_GAP / 2;
+	for barre in fingering.barres() {
+		svg.push_str(&format!(
+			r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{DOT_RADIUS}" fill="black"/>"#,
+			x(barre.from_string) - DOT_RADIUS,
+			fret_y(barre.fret) - DOT_RADIUS,
+			x(barre.to_string) - x(barre.from_string) + 2 * DOT_RADIUS,
+			2 * DOT_RADIUS
+		));
+	}
+	let marker_y = MARGIN_TOP - 8;
+	for (string, state) in strings.iter().enumerate() {
+		let cx = x(string);
+		match state {
+			StringState::Muted => svg.push_str(&format!(
+				r#"<text x="{cx}" y="{}" text-anchor="middle">x</text>"#,
+				marker_y + 4
+			)),
+			StringState::Fretted(0) => svg.push_str(&format!(
+				r#"<circle cx="{cx}" cy="{marker_y}" r="4" fill="none" stroke="black"/>"#
+			)),
+			StringState::Fretted(fret) => svg.push_str(&format!(
+				r#"<circle cx="{cx}" cy="{}" r="{DOT_RADIUS}" fill="black"/>"#,
+				fret_y(*fret)
+			)),
+			StringState::Harmonic(fret) => svg.push_str(&format!(
+				r#"<circle cx="{cx}" cy="{}" r="{DOT_RADIUS}" fill="white" stroke="black"/>"#,
+				fret_y(*fret)
+			)),
+		}
+	}
+
+	svg.push_str("</svg>");
+	svg
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+
+	#[test]
+	fn test_open_chord_diagram() {
+		let guitar = Guitar::default();
+		let svg = svg_diagram(&Fingering::parse("x32010").unwrap(), &guitar);
+
+		assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+		assert_eq!(svg.matches(r#"fill="black""#).count(), 3);
+		assert_eq!(svg.matches(">x</text>").count(), 1);
+		assert_eq!(svg.matches(r#"r="4""#).count(), 2);
+		// Drawn from the nut
+		assert!(svg.contains(r#"stroke-width="4""#));
+		assert!(!svg.contains("fr</text>"));
+	}
+
+	#[test]
+	fn test_barre_chord_diagram() {
+		let guitar = Guitar::default();
+		let svg = svg_diagram(&Fingering::parse("133211").unwrap(), &guitar);
+		assert_eq!(svg.matches("<rect").count(), 1);
+		assert!(!svg_diagram(&Fingering::parse("x32010").unwrap(), &guitar).contains("<rect"));
+	}
+
+	#[test]
+	fn test_high_chord_diagram_shows_base_fret() {
+		let guitar = Guitar::default();
+		let svg = svg_diagram(&Fingering::parse("x(10)(12)(12)(12)x").unwrap(), &guitar);
+		assert!(svg.contains(">10fr</text>"));
+		assert!(!svg.contains(r#"stroke-width="4""#));
+	}
+}
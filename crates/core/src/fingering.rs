@@ -4,40 +4,468 @@
 //! in tab notation format (e.g., "x32010" for C major on guitar).
 
 use crate::error::{ChordCraftError, Result};
+use crate::fingers::{Finger, FingerAssignment, assign_fingers};
+use crate::generator::PlayerProfile;
 use crate::instrument::Instrument;
 use crate::note::{Note, PitchClass};
 use std::fmt;
 
+/// Frets where natural harmonics are played, strongest first
+pub const NATURAL_HARMONIC_FRETS: [u8; 4] = [12, 7, 5, 4];
+
+/// Fingerings starting above this fret count as high up the neck
+const DIFFICULT_POSITION: u8 = 7;
+
+/// Semitones above the open string sounded by a natural harmonic touched at `fret`
+pub fn harmonic_semitones(fret: u8) -> Option<i32> {
+	match fret {
+		12 => Some(12),
+		7 => Some(19),
+		5 => Some(24),
+		4 => Some(28),
+		_ => None,
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StringState {
 	Muted,
 	Fretted(u8), // 0 = open string
+	/// Natural harmonic touched (not pressed) over this fret
+	Harmonic(u8),
 }
 
 impl StringState {
 	pub fn is_played(&self) -> bool {
-		matches!(self, StringState::Fretted(_))
+		matches!(self, StringState::Fretted(_) | StringState::Harmonic(_))
 	}
 
+	/// Pressed fret (0 = open); None for muted strings and harmonics
 	pub fn fret(&self) -> Option<u8> {
 		match self {
-			StringState::Muted => None,
 			StringState::Fretted(f) => Some(*f),
+			StringState::Muted | StringState::Harmonic(_) => None,
+		}
+	}
+
+	/// Fret where the fretting hand is needed: pressed frets and harmonic nodes
+	pub(crate) fn hand_fret(&self) -> Option<u8> {
+		match self {
+			StringState::Fretted(f) if *f > 0 => Some(*f),
+			StringState::Harmonic(f) => Some(*f),
+			_ => None,
+		}
+	}
+
+	/// Sounding note on a string with the given open note
+	pub fn note_on(&self, open: Note) -> Option<Note> {
+		match self {
+			StringState::Muted => None,
+			StringState::Fretted(fret) => Some(open.add_semitones(*fret as i32)),
+			StringState::Harmonic(fret) => harmonic_semitones(*fret).map(|s| open.add_semitones(s)),
+		}
+	}
+
+	/// Sounding note on a short string whose nut sits at neck fret `nut`
+	/// (see [`Instrument::string_nut_fret`]); frets still count on the neck
+	pub fn note_above_nut(&self, open: Note, nut: u8) -> Option<Note> {
+		match self {
+			StringState::Fretted(fret) if *fret > 0 && nut > 0 => {
+				Some(open.add_semitones(*fret as i32 - nut as i32))
+			}
+			_ => self.note_on(open),
+		}
+	}
+}
+
+impl fmt::Display for StringState {
+	/// The string's symbol in tab notation: "x", "3", "(10)" or "<12>"
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StringState::Muted => write!(f, "x"),
+			StringState::Fretted(fret) if *fret < 10 => write!(f, "{fret}"),
+			StringState::Fretted(fret) => write!(f, "({fret})"),
+			StringState::Harmonic(fret) => write!(f, "<{fret}>"),
+		}
+	}
+}
+
+/// How one string changes from one fingering to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringChange {
+	/// Muted, open, fretted or touched the same way in both
+	Same(StringState),
+	/// Played in both, but differently (another fret, open to fretted, ...)
+	Moved { from: StringState, to: StringState },
+	/// Muted before, played after
+	Added(StringState),
+	/// Played before, muted after
+	Removed(StringState),
+}
+
+impl StringChange {
+	/// Whether the hand has to do something on this string
+	pub fn is_movement(&self) -> bool {
+		!matches!(self, StringChange::Same(_))
+	}
+
+	/// Whether the string keeps sounding the same way, so a finger can stay put
+	pub fn is_anchor(&self) -> bool {
+		matches!(self, StringChange::Same(state) if state.is_played())
+	}
+}
+
+/// Why a fingering can't be played on an instrument
+///
+/// Strings are indexed from the bass, starting at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlayabilityIssue {
+	/// The fingering has a different number of strings than the instrument
+	WrongStringCount { expected: usize, found: usize },
+	/// A fret beyond the instrument's neck
+	FretOutOfRange { string: usize, fret: u8, max: u8 },
+	/// A fret below a short string's own nut (see [`Instrument::string_fret_range`])
+	FretBelowNut { string: usize, fret: u8, nut: u8 },
+	/// The fretting hand would have to span more frets than it can reach
+	StretchTooWide { span: u8, max: u8 },
+	/// More fingers than the hand has, even barring strings at the same fret
+	TooManyFingers { needed: u8, available: u8 },
+	/// Fitting the fingers needs a barre that would sound this muted string
+	MutedUnderBarre { string: usize, fret: u8 },
+}
+
+impl PlayabilityIssue {
+	/// Whether no hand could play the fingering on this instrument, as opposed
+	/// to it being beyond the instrument's default hand
+	pub fn is_structural(&self) -> bool {
+		matches!(
+			self,
+			PlayabilityIssue::WrongStringCount { .. }
+				| PlayabilityIssue::FretOutOfRange { .. }
+				| PlayabilityIssue::FretBelowNut { .. }
+		)
+	}
+}
+
+impl fmt::Display for PlayabilityIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PlayabilityIssue::WrongStringCount { expected, found } => {
+				write!(
+					f,
+					"{found} strings given for a {expected}-string instrument"
+				)
+			}
+			PlayabilityIssue::FretOutOfRange { string, fret, max } => {
+				write!(
+					f,
+					"string {}: fret {fret} is past the last fret ({max})",
+					string + 1
+				)
+			}
+			PlayabilityIssue::FretBelowNut { string, fret, nut } => {
+				write!(
+					f,
+					"string {}: fret {fret} is below the string's nut at fret {nut}",
+					string + 1
+				)
+			}
+			PlayabilityIssue::StretchTooWide { span, max } => {
+				write!(f, "spans {span} frets, more than the {max}-fret stretch")
+			}
+			PlayabilityIssue::TooManyFingers { needed, available } => {
+				write!(f, "needs {needed} fingers, only {available} available")
+			}
+			PlayabilityIssue::MutedUnderBarre { string, fret } => write!(
+				f,
+				"string {} is muted but lies under the barre at fret {fret}",
+				string + 1
+			),
+		}
+	}
+}
+
+/// How hard a fingering is to play, from a first-week chord to a stretch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+	/// Open-position shapes with at most three fingers and no barre (C, G, D, Am)
+	#[default]
+	Beginner,
+	/// Barres, all four fingers, a wider stretch or high up the neck (F, Bm)
+	Intermediate,
+	/// Barres with a stretch, very wide stretches, the thumb, or shapes no hand fits
+	Advanced,
+}
+
+impl fmt::Display for Difficulty {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Difficulty::Beginner => write!(f, "beginner"),
+			Difficulty::Intermediate => write!(f, "intermediate"),
+			Difficulty::Advanced => write!(f, "advanced"),
 		}
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// One finger pressing a span of strings at the same fret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Barre {
+	pub finger: Finger,
+	pub fret: u8,
+	/// Lowest string under the finger
+	pub from_string: usize,
+	/// Highest string under the finger (inclusive)
+	pub to_string: usize,
+}
+
+impl Barre {
+	pub fn new(finger: Finger, fret: u8, from_string: usize, to_string: usize) -> Self {
+		Barre {
+			finger,
+			fret,
+			from_string,
+			to_string,
+		}
+	}
+
+	pub fn covers(&self, string: usize) -> bool {
+		(self.from_string..=self.to_string).contains(&string)
+	}
+
+	pub fn string_count(&self) -> usize {
+		self.to_string - self.from_string + 1
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Fingering {
 	strings: Vec<StringState>, // Ordered lowest (bass) to highest (treble)
+	/// Barres given explicitly; when empty they are inferred from the frets
+	barres: Vec<Barre>,
 }
 
 impl Fingering {
 	pub fn new(strings: Vec<StringState>) -> Self {
-		Fingering { strings }
+		Fingering {
+			strings,
+			barres: Vec::new(),
+		}
+	}
+
+	/// Mark a barre explicitly instead of leaving it to be inferred
+	///
+	/// Both ends must be fretted at the barre's fret, and every string
+	/// between them fretted at or above it. A finger can only lie across
+	/// one barre.
+	pub fn with_barre(mut self, barre: Barre) -> Result<Self> {
+		let invalid = |reason: &str| {
+			Err(ChordCraftError::InvalidFingering(format!(
+				"{reason} for barre at fret {} over strings {}-{}",
+				barre.fret, barre.from_string, barre.to_string
+			)))
+		};
+		if barre.fret == 0 || barre.from_string >= barre.to_string {
+			return invalid("needs a fret and at least two strings");
+		}
+		let Some(span) = self.strings.get(barre.from_string..=barre.to_string) else {
+			return invalid("strings out of range");
+		};
+		let pressed =
+			|state: &StringState| matches!(state, StringState::Fretted(f) if *f == barre.fret);
+		if !pressed(&span[0]) || !pressed(&span[span.len() - 1]) {
+			return invalid("ends not fretted at the barre's fret");
+		}
+		if !span
+			.iter()
+			.all(|s| matches!(s, StringState::Fretted(f) if *f >= barre.fret))
+		{
+			return invalid("open, muted or lower strings under the finger");
+		}
+		if self.barres.iter().any(|b| b.finger == barre.finger) {
+			return invalid("finger already barres");
+		}
+		self.barres.push(barre);
+		Ok(self)
+	}
+
+	/// Barres of the fingering: the explicit ones, or those of its most natural finger assignment
+	pub fn barres(&self) -> Vec<Barre> {
+		if !self.barres.is_empty() {
+			return self.barres.clone();
+		}
+		assign_fingers(self, 4, false).map_or_else(Vec::new, |a| a.barres(self))
+	}
+
+	/// Barres given with [`Fingering::with_barre`]
+	pub fn explicit_barres(&self) -> &[Barre] {
+		&self.barres
 	}
 
-	/// Format: 'x'=muted, '0'-'9'=fret, '(10)'=high frets. Ordered low to high string.
+	/// Difficulty grade, from barres, stretch, finger count and position
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::fingering::{Difficulty, Fingering};
+	///
+	/// assert_eq!(Fingering::parse("x32010").unwrap().difficulty(), Difficulty::Beginner);
+	/// assert_eq!(Fingering::parse("133211").unwrap().difficulty(), Difficulty::Intermediate);
+	/// ```
+	pub fn difficulty(&self) -> Difficulty {
+		self.difficulty_with(assign_fingers(self, 4, false).as_ref())
+	}
+
+	/// Difficulty with the fingers already worked out (None: the hand can't manage it)
+	pub(crate) fn difficulty_with(&self, fingers: Option<&FingerAssignment>) -> Difficulty {
+		let Some(fingers) = fingers else {
+			return Difficulty::Advanced;
+		};
+		let span = self.fret_span();
+		let barres = fingers.barre_fingers();
+		let mut used: Vec<Finger> = fingers.fingers().iter().flatten().copied().collect();
+		used.sort_unstable();
+		used.dedup();
+
+		let mut points = match span {
+			0..=2 => 0,
+			3 => 1,
+			_ => 4,
+		};
+		if !barres.is_empty() {
+			points += 2;
+			if span >= 3 {
+				points += 2;
+			}
+			if barres.iter().any(|&f| f != Finger::Index) {
+				points += 1;
+			}
+		}
+		if used.contains(&Finger::Thumb) {
+			points += 2;
+		}
+		if used.iter().filter(|&&f| f != Finger::Thumb).count() >= 4 {
+			points += 1;
+		}
+		if self.min_fret().is_some_and(|f| f > DIFFICULT_POSITION) {
+			points += 1;
+		}
+
+		match points {
+			0..=1 => Difficulty::Beginner,
+			2..=3 => Difficulty::Intermediate,
+			_ => Difficulty::Advanced,
+		}
+	}
+
+	/// Change on each string from this fingering to `other`, lowest string first
+	///
+	/// Only strings both fingerings have are compared.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::fingering::{Fingering, StringChange, StringState};
+	///
+	/// let c = Fingering::parse("x32010").unwrap();
+	/// let am = Fingering::parse("x02210").unwrap();
+	/// let changes = c.diff(&am);
+	/// assert_eq!(changes[0], StringChange::Same(StringState::Muted));
+	/// assert_eq!(
+	///     changes[1],
+	///     StringChange::Moved {
+	///         from: StringState::Fretted(3),
+	///         to: StringState::Fretted(0)
+	///     }
+	/// );
+	/// assert_eq!(changes.iter().filter(|c| c.is_anchor()).count(), 3);
+	/// ```
+	pub fn diff(&self, other: &Fingering) -> Vec<StringChange> {
+		self.strings
+			.iter()
+			.zip(&other.strings)
+			.map(|(&from, &to)| match (from, to) {
+				_ if from == to => StringChange::Same(from),
+				(StringState::Muted, to) => StringChange::Added(to),
+				(from, StringState::Muted) => StringChange::Removed(from),
+				(from, to) => StringChange::Moved { from, to },
+			})
+			.collect()
+	}
+
+	/// The same shape moved up (or down, for negative `frets`) the neck
+	///
+	/// Open strings move too, so x32010 up two frets is x54232. Fails if a
+	/// string would go below the nut, or the shape has harmonics, whose
+	/// nodes don't move with it.
+	pub fn transpose(&self, frets: i8) -> Result<Self> {
+		let shift = |fret: u8| {
+			fret.checked_add_signed(frets).ok_or_else(|| {
+				ChordCraftError::InvalidFingering(format!(
+					"{self} can't move {frets} frets: a string would leave the neck"
+				))
+			})
+		};
+
+		let mut strings = Vec::with_capacity(self.strings.len());
+		for state in &self.strings {
+			strings.push(match state {
+				StringState::Muted => StringState::Muted,
+				StringState::Fretted(fret) => StringState::Fretted(shift(*fret)?),
+				StringState::Harmonic(_) if frets != 0 => {
+					return Err(ChordCraftError::InvalidFingering(format!(
+						"{self} has harmonics, which can't be moved"
+					)));
+				}
+				harmonic => *harmonic,
+			});
+		}
+
+		let mut barres = Vec::with_capacity(self.barres.len());
+		for barre in &self.barres {
+			barres.push(Barre {
+				fret: shift(barre.fret)?,
+				..*barre
+			});
+		}
+		Ok(Fingering { strings, barres })
+	}
+
+	/// An open shape moved up `frets` frets, with the index finger barring where the nut was
+	///
+	/// The E shape 022100 becomes the F barre chord 133211 one fret up. The
+	/// barre runs from the lowest to the highest open string, so the strings
+	/// between them can't be muted.
+	pub fn barre_shape(&self, frets: u8) -> Result<Self> {
+		if frets == 0 {
+			return Err(ChordCraftError::InvalidFingering(
+				"a barre shape needs to move at least one fret".to_string(),
+			));
+		}
+		let open: Vec<usize> = (0..self.strings.len())
+			.filter(|&i| self.strings[i] == StringState::Fretted(0))
+			.collect();
+		let moved = self.transpose(i8::try_from(frets).map_err(|_| {
+			ChordCraftError::InvalidFingering(format!("can't move {self} {frets} frets"))
+		})?)?;
+
+		match (open.first(), open.last()) {
+			(Some(&from), Some(&to)) if from < to => {
+				moved.with_barre(Barre::new(Finger::Index, frets, from, to))
+			}
+			_ => Ok(moved),
+		}
+	}
+
+	/// Format: 'x'=muted, '0'-'9'=fret, '(10)'=high frets, '<12>'=natural harmonic.
+	/// Ordered low to high string.
+	///
+	/// Strings may also be separated by spaces, commas or dashes, and then a
+	/// number of up to 24 is one string's fret: "x 10 10 9 10 x", "x-3-2-0-1-0".
 	pub fn parse(s: &str) -> Result<Self> {
 		let s = s.trim();
 		if s.is_empty() {
@@ -47,6 +475,30 @@ impl Fingering {
 		}
 
 		let mut strings = Vec::new();
+		for token in s
+			.split(|c: char| c.is_whitespace() || matches!(c, ',' | '-'))
+			.filter(|t| !t.is_empty())
+		{
+			match token.parse::<u8>() {
+				// "010" stays three strings, like the compact form
+				Ok(fret) if token.len() > 1 && !token.starts_with('0') && fret <= 24 => {
+					strings.push(StringState::Fretted(fret));
+				}
+				_ => Self::parse_compact(token, &mut strings)?,
+			}
+		}
+
+		if strings.is_empty() {
+			return Err(ChordCraftError::InvalidFingering(
+				"No strings found".to_string(),
+			));
+		}
+
+		Ok(Fingering::new(strings))
+	}
+
+	/// Parse unseparated states ("x32010", "x(10)(12)"), appending them to `strings`
+	fn parse_compact(s: &str, strings: &mut Vec<StringState>) -> Result<()> {
 		let mut chars = s.chars().peekable();
 
 		while let Some(c) = chars.next() {
@@ -79,7 +531,32 @@ impl Fingering {
 					}
 					StringState::Fretted(fret)
 				}
-				' ' | '-' => continue, // Allow separators
+				'<' => {
+					let mut num_str = String::new();
+					let mut found_closing = false;
+					for next in chars.by_ref() {
+						if next == '>' {
+							found_closing = true;
+							break;
+						}
+						num_str.push(next);
+					}
+					if !found_closing {
+						return Err(ChordCraftError::InvalidFingering(
+							"Unclosed angle bracket in harmonic notation".to_string(),
+						));
+					}
+					let fret = num_str
+						.parse::<u8>()
+						.ok()
+						.filter(|f| harmonic_semitones(*f).is_some());
+					let Some(fret) = fret else {
+						return Err(ChordCraftError::InvalidFingering(format!(
+							"Invalid natural harmonic fret: {num_str} (expected 12, 7, 5 or 4)"
+						)));
+					};
+					StringState::Harmonic(fret)
+				}
 				_ => {
 					return Err(ChordCraftError::InvalidFingering(format!(
 						"Invalid character in fingering: '{c}'"
@@ -88,14 +565,7 @@ impl Fingering {
 			};
 			strings.push(state);
 		}
-
-		if strings.is_empty() {
-			return Err(ChordCraftError::InvalidFingering(
-				"No strings found".to_string(),
-			));
-		}
-
-		Ok(Fingering { strings })
+		Ok(())
 	}
 
 	pub fn strings(&self) -> &[StringState] {
@@ -136,15 +606,9 @@ impl Fingering {
 		self.strings.iter().filter_map(|s| s.fret()).max()
 	}
 
+	/// Span of the fretting hand, including harmonic nodes
 	pub fn fret_span(&self) -> u8 {
-		let fretted: Vec<u8> = self
-			.strings
-			.iter()
-			.filter_map(|s| match s {
-				StringState::Fretted(f) if *f > 0 => Some(*f),
-				_ => None,
-			})
-			.collect();
+		let fretted: Vec<u8> = self.strings.iter().filter_map(|s| s.hand_fret()).collect();
 
 		if fretted.is_empty() {
 			return 0;
@@ -193,6 +657,10 @@ impl Fingering {
 			None => return false,
 		};
 
+		if let Some(widest) = self.barres.iter().max_by_key(|b| b.string_count()) {
+			return widest.string_count() >= threshold && widest.fret > min_fret;
+		}
+
 		let mut fret_groups: HashMap<u8, Vec<usize>> = HashMap::new();
 
 		for (string_idx, state) in self.strings.iter().enumerate() {
@@ -247,20 +715,38 @@ impl Fingering {
 		// Group non-open fretted positions by fret number
 		let mut frets_map: BTreeMap<u8, Vec<usize>> = BTreeMap::new();
 
+		// Explicit barres take one finger each, whatever they cover
+		let under_barre = |string: usize, fret: u8| {
+			self.barres
+				.iter()
+				.any(|b| b.fret == fret && b.covers(string))
+		};
 		for (string_idx, state) in self.strings.iter().enumerate() {
 			if let StringState::Fretted(fret) = state
-				&& *fret > 0
+				&& *fret > 0 && !under_barre(string_idx, *fret)
 			{
 				frets_map.entry(*fret).or_default().push(string_idx);
 			}
 		}
 
-		let mut total_fingers = 0;
+		let mut total_fingers = self.barres.len() as u8;
 		for (_fret, strings) in frets_map.iter() {
 			total_fingers += Self::count_fingers_for_strings(strings);
 		}
 
-		total_fingers
+		// One finger lightly touches all harmonics at the same node
+		let mut harmonic_nodes: Vec<u8> = self
+			.strings
+			.iter()
+			.filter_map(|s| match s {
+				StringState::Harmonic(f) => Some(*f),
+				_ => None,
+			})
+			.collect();
+		harmonic_nodes.sort_unstable();
+		harmonic_nodes.dedup();
+
+		total_fingers + harmonic_nodes.len() as u8
 	}
 
 	/// Count fingers needed for strings at the same fret.
@@ -282,11 +768,27 @@ impl Fingering {
 		1
 	}
 
+	/// What is left for the fingers when the thumb wraps over the neck to fret
+	/// the lowest string (which is muted in the result)
+	///
+	/// None unless the lowest string is fretted at, or one fret below, the
+	/// lowest fret of the other strings; the thumb can't reach further up.
+	pub fn without_thumbed_bass(&self) -> Option<Fingering> {
+		let StringState::Fretted(thumb) = *self.strings.first()? else {
+			return None;
+		};
+		let mut rest = self.clone();
+		rest.strings[0] = StringState::Muted;
+		rest.barres.retain(|b| !b.covers(0));
+		let lowest = rest.strings.iter().filter_map(|s| s.hand_fret()).min()?;
+		(thumb > 0 && thumb <= lowest && lowest - thumb <= 1).then_some(rest)
+	}
+
 	pub fn is_playable_for<I: Instrument>(&self, instrument: &I) -> bool {
 		self.is_playable_with_constraints(instrument.max_stretch(), instrument.max_fingers())
 	}
 
-	fn is_playable_with_constraints(&self, max_stretch: u8, max_fingers: u8) -> bool {
+	pub(crate) fn is_playable_with_constraints(&self, max_stretch: u8, max_fingers: u8) -> bool {
 		if self.fret_span() > max_stretch {
 			return false;
 		}
@@ -297,6 +799,105 @@ impl Fingering {
 		true
 	}
 
+	/// Everything that keeps this fingering from being played on the
+	/// instrument; empty if it is playable
+	///
+	/// Stricter than [`Fingering::is_playable_for`], which only checks the hand.
+	pub fn validate_for<I: Instrument>(&self, instrument: &I) -> Vec<PlayabilityIssue> {
+		let mut issues = Vec::new();
+
+		let expected = instrument.string_count();
+		if self.strings.len() != expected {
+			issues.push(PlayabilityIssue::WrongStringCount {
+				expected,
+				found: self.strings.len(),
+			});
+		}
+
+		for (string, state) in self.strings.iter().enumerate() {
+			let (_, max) = instrument.string_fret_range(string);
+			let nut = instrument.string_nut_fret(string);
+			match state.fret() {
+				Some(fret) if fret > max => {
+					issues.push(PlayabilityIssue::FretOutOfRange { string, fret, max });
+				}
+				Some(fret) if fret > 0 && fret <= nut => {
+					issues.push(PlayabilityIssue::FretBelowNut { string, fret, nut });
+				}
+				_ => {}
+			}
+		}
+
+		let span = self.fret_span();
+		if span > instrument.max_stretch() {
+			issues.push(PlayabilityIssue::StretchTooWide {
+				span,
+				max: instrument.max_stretch(),
+			});
+		}
+
+		let needed = self.min_fingers_required();
+		let available = instrument.max_fingers();
+		if needed > available {
+			issues.push(PlayabilityIssue::TooManyFingers { needed, available });
+		} else if let Some((string, fret)) = self.muted_string_under_barre()
+			&& needed + self.barre_splits_at_mutes() > available
+		{
+			issues.push(PlayabilityIssue::MutedUnderBarre { string, fret });
+		}
+
+		issues
+	}
+
+	/// Fail if the fingering doesn't fit the instrument at all: wrong string
+	/// count, or frets past the end of the neck (above a capo, the neck is shorter)
+	///
+	/// Unlike [`Fingering::validate_for`], a hand that can't reach is not an error.
+	pub fn check_for<I: Instrument>(&self, instrument: &I) -> Result<()> {
+		let structural: Vec<String> = self
+			.validate_for(instrument)
+			.iter()
+			.filter(|i| i.is_structural())
+			.map(|i| i.to_string())
+			.collect();
+		if structural.is_empty() {
+			Ok(())
+		} else {
+			Err(ChordCraftError::InvalidFingering(format!(
+				"{self}: {}",
+				structural.join("; ")
+			)))
+		}
+	}
+
+	/// Strings fretted at each fret above the nut
+	fn fret_groups(&self) -> std::collections::BTreeMap<u8, Vec<usize>> {
+		let mut groups: std::collections::BTreeMap<u8, Vec<usize>> = Default::default();
+		for (string, fret) in self.fretted_positions() {
+			groups.entry(fret).or_default().push(string);
+		}
+		groups
+	}
+
+	/// First muted string lying between two strings fretted at the same fret
+	fn muted_string_under_barre(&self) -> Option<(usize, u8)> {
+		self.fret_groups().into_iter().find_map(|(fret, strings)| {
+			let (first, last) = (*strings.first()?, *strings.last()?);
+			(first..last)
+				.find(|&i| self.strings[i] == StringState::Muted)
+				.map(|string| (string, fret))
+		})
+	}
+
+	/// Extra fingers needed if barres stop short of muted strings
+	fn barre_splits_at_mutes(&self) -> u8 {
+		self.fret_groups()
+			.values()
+			.flat_map(|strings| strings.windows(2))
+			.filter(|pair| (pair[0]..pair[1]).any(|i| self.strings[i] == StringState::Muted))
+			.count() as u8
+	}
+
 	pub fn notes<I: Instrument>(&self, instrument: &I) -> Vec<Note> {
 		let tuning = instrument.tuning();
 
@@ -307,14 +908,33 @@ impl Fingering {
 				if i >= tuning.len() {
 					return None;
 				}
-				match state {
-					StringState::Muted => None,
-					StringState::Fretted(fret) => Some(tuning[i].add_semitones(*fret as i32)),
-				}
+				state.note_above_nut(tuning[i], instrument.string_nut_fret(i))
 			})
 			.collect()
 	}
 
+	/// Every pitch that sounds, lowest string first, with both strings of
+	/// doubled courses (see [`Instrument::course_pairs`])
+	///
+	/// Fretting a course stops both of its strings at the same fret, so the
+	/// notes of a 12-string guitar's lower courses come in octave pairs.
+	pub fn sounding_notes<I: Instrument>(&self, instrument: &I) -> Vec<Note> {
+		let pairs = instrument.course_pairs();
+		let mut notes = Vec::new();
+		for (string, ((state, &open), pair)) in self
+			.strings
+			.iter()
+			.zip(instrument.tuning())
+			.zip(pairs)
+			.enumerate()
+		{
+			let nut = instrument.string_nut_fret(string);
+			notes.extend(state.note_above_nut(open, nut));
+			notes.extend(pair.and_then(|partner| state.note_above_nut(partner, nut)));
+		}
+		notes
+	}
+
 	pub fn pitch_classes<I: Instrument>(&self, instrument: &I) -> Vec<PitchClass> {
 		self.notes(instrument)
 			.into_iter()
@@ -339,6 +959,40 @@ impl Fingering {
 		)
 	}
 
+	/// Playability for a particular player's hand, 0-100
+	///
+	/// Like [`Fingering::playability_score_for`], but with the profile's
+	/// stretch limit, and barres costing what the profile says they do.
+	pub fn playability_score_for_player<I: Instrument>(
+		&self,
+		instrument: &I,
+		player: &PlayerProfile,
+	) -> u8 {
+		let max_stretch = player.max_stretch(instrument.max_stretch());
+		let score =
+			self.playability_score_with_limits(instrument, max_stretch, instrument.max_fingers());
+		if score > 0 && self.requires_barre() {
+			(score as i32 - player.barre_penalty()).clamp(0, 100) as u8
+		} else {
+			score
+		}
+	}
+
+	/// Playability with overridden stretch and finger limits (e.g., for hand size)
+	pub(crate) fn playability_score_with_limits<I: Instrument>(
+		&self,
+		instrument: &I,
+		max_stretch: u8,
+		max_fingers: u8,
+	) -> u8 {
+		self.playability_score_with_params(
+			max_stretch,
+			max_fingers,
+			instrument.main_barre_threshold(),
+			instrument.open_position_threshold(),
+		)
+	}
+
 	fn playability_score_with_params(
 		&self,
 		max_stretch: u8,
@@ -440,42 +1094,46 @@ impl Fingering {
 		let bass_idx = instrument.bass_string_index();
 		let string_count = self.strings.len().min(tuning.len());
 
-		for (string_state, open_note) in self.strings[bass_idx..string_count]
-			.iter()
-			.zip(tuning[bass_idx..string_count].iter())
-		{
-			if let StringState::Fretted(fret) = string_state {
-				return Some(open_note.add_semitones(*fret as i32));
-			}
-		}
-
-		// Fallback: check strings before bass_idx (for re-entrant tunings)
-		for (string_state, open_note) in self.strings[..bass_idx]
-			.iter()
-			.zip(tuning[..bass_idx].iter())
-		{
-			if let StringState::Fretted(fret) = string_state {
-				return Some(open_note.add_semitones(*fret as i32));
-			}
-		}
-
-		None
+		// From the bass string up, then the strings before it (re-entrant tunings)
+		(bass_idx..string_count)
+			.chain(0..bass_idx.min(string_count))
+			.find_map(|string| {
+				self.strings[string]
+					.note_above_nut(tuning[string], instrument.string_nut_fret(string))
+			})
 	}
 }
 
 impl fmt::Display for Fingering {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		for state in &self.strings {
-			match state {
-				StringState::Muted => write!(f, "x")?,
-				StringState::Fretted(fret) if *fret < 10 => write!(f, "{fret}")?,
-				StringState::Fretted(fret) => write!(f, "({fret})")?,
-			}
+			write!(f, "{state}")?;
 		}
 		Ok(())
 	}
 }
 
+/// Serialized as its tab; explicit barres aren't kept and are inferred again
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fingering {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fingering {
+	fn deserialize<D: serde::Deserializer<'de>>(
+		deserializer: D,
+	) -> std::result::Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Fingering::parse(&s).map_err(serde::de::Error::custom)
+	}
+}
+
 pub struct FingeringBuilder {
 	strings: Vec<StringState>,
 }
@@ -538,6 +1196,25 @@ mod tests {
 		assert_eq!(f.strings[3], StringState::Fretted(9));
 	}
 
+	#[test]
+	fn test_parse_separated() {
+		let high = Fingering::parse("x(10)(10)9(10)x").unwrap();
+		assert_eq!(Fingering::parse("x 10 10 9 10 x").unwrap(), high);
+		assert_eq!(Fingering::parse("x,10,10,9,10,x").unwrap(), high);
+		assert_eq!(Fingering::parse("x-10-10-9-10-x").unwrap(), high);
+
+		let c = Fingering::parse("x32010").unwrap();
+		assert_eq!(Fingering::parse("x-3-2-0-1-0").unwrap(), c);
+		assert_eq!(Fingering::parse("x 3, 2, 0, 1, 0").unwrap(), c);
+		// Leading zeros and numbers past the last fret stay compact
+		assert_eq!(Fingering::parse("x32 010").unwrap(), c);
+		assert_eq!(Fingering::parse("x 32 010").unwrap(), c);
+
+		let harmonics = Fingering::parse("<12> <12> 0 x x x").unwrap();
+		assert_eq!(harmonics.strings[0], StringState::Harmonic(12));
+		assert_eq!(harmonics.string_count(), 6);
+	}
+
 	#[test]
 	fn test_display() {
 		let f = Fingering::parse("x32010").unwrap();
@@ -607,6 +1284,33 @@ mod tests {
 		assert_eq!(f.to_string(), "x32010");
 	}
 
+	#[test]
+	fn test_parse_harmonics() {
+		let f = Fingering::parse("0<7>2<12>00").unwrap();
+		assert_eq!(f.get_string(1), Some(&StringState::Harmonic(7)));
+		assert_eq!(f.to_string(), "0<7>2<12>00");
+
+		// A harmonic over the 7th fret sounds an octave and a fifth up
+		let guitar = crate::instrument::Guitar::default();
+		let notes = f.notes(&guitar);
+		assert_eq!(notes[1].to_string(), "E4");
+		assert_eq!(notes[3].to_string(), "G4");
+
+		assert!(Fingering::parse("<3>00000").is_err());
+		assert!(Fingering::parse("<12000000").is_err());
+	}
+
+	#[test]
+	fn test_harmonics_count_toward_hand_position() {
+		// Harmonics at one node take one finger, but far from the fretted notes
+		let f = Fingering::parse("<12><12><12>xxx").unwrap();
+		assert_eq!(f.min_fingers_required(), 1);
+		assert_eq!(f.fret_span(), 0);
+
+		let f = Fingering::parse("3<12>xxxx").unwrap();
+		assert_eq!(f.fret_span(), 9);
+	}
+
 	#[test]
 	fn test_bass_note() {
 		let guitar = Guitar::default();
@@ -714,6 +1418,171 @@ mod tests {
 		assert_eq!(fingers, 3, "Barre F should require 3 fingers");
 	}
 
+	#[test]
+	fn test_without_thumbed_bass() {
+		// Five frets in a row: one too many for the fingers alone
+		let f = Fingering::parse("1x2345").unwrap();
+		assert!(!f.is_playable_with_constraints(4, 4));
+		let rest = f.without_thumbed_bass().unwrap();
+		assert_eq!(rest.to_string(), "xx2345");
+		assert!(rest.is_playable_with_constraints(4, 4));
+
+		// The thumb stays at the bottom of the hand, on the lowest string
+		assert!(
+			Fingering::parse("3x2345")
+				.unwrap()
+				.without_thumbed_bass()
+				.is_none()
+		);
+		assert!(
+			Fingering::parse("0x2345")
+				.unwrap()
+				.without_thumbed_bass()
+				.is_none()
+		);
+		assert!(
+			Fingering::parse("x13331")
+				.unwrap()
+				.without_thumbed_bass()
+				.is_none()
+		);
+	}
+
+	#[test]
+	fn test_difficulty() {
+		let grade = |tab: &str| Fingering::parse(tab).unwrap().difficulty();
+		for open in ["x32010", "320003", "xx0232", "022100", "x02210"] {
+			assert_eq!(grade(open), Difficulty::Beginner, "{open}");
+		}
+		for barre in ["133211", "x24432", "688766"] {
+			assert_eq!(grade(barre), Difficulty::Intermediate, "{barre}");
+		}
+		// Wide stretch, barre plus stretch, thumb-only shape
+		for hard in ["x3x0x7", "1x4211", "1x3211"] {
+			assert_eq!(grade(hard), Difficulty::Advanced, "{hard}");
+		}
+		assert!(Difficulty::Beginner < Difficulty::Advanced);
+	}
+
+	#[test]
+	fn test_diff() {
+		let g = Fingering::parse("320003").unwrap();
+		let harmonics = Fingering::parse("x3<12>00x").unwrap();
+		assert_eq!(
+			g.diff(&harmonics),
+			vec![
+				StringChange::Removed(StringState::Fretted(3)),
+				StringChange::Moved {
+					from: StringState::Fretted(2),
+					to: StringState::Fretted(3)
+				},
+				StringChange::Moved {
+					from: StringState::Fretted(0),
+					to: StringState::Harmonic(12)
+				},
+				StringChange::Same(StringState::Fretted(0)),
+				StringChange::Same(StringState::Fretted(0)),
+				StringChange::Removed(StringState::Fretted(3)),
+			]
+		);
+		assert!(harmonics.diff(&g)[0] == StringChange::Added(StringState::Fretted(3)));
+		assert!(g.diff(&g).iter().all(|c| c.is_anchor()));
+		assert_eq!(StringState::Fretted(12).to_string(), "(12)");
+	}
+
+	#[test]
+	fn test_transpose() {
+		let tab = |f: Result<Fingering>| f.unwrap().to_string();
+		let c = Fingering::parse("x32010").unwrap();
+		assert_eq!(tab(c.transpose(2)), "x54232");
+		assert_eq!(tab(c.transpose(0)), "x32010");
+		assert!(c.transpose(-1).is_err());
+		assert_eq!(
+			tab(Fingering::parse("133211").unwrap().transpose(-1)),
+			"022100"
+		);
+		assert_eq!(tab(c.transpose(9)), "x(12)(11)9(10)9");
+		assert!(
+			Fingering::parse("<12><12>xxxx")
+				.unwrap()
+				.transpose(1)
+				.is_err()
+		);
+
+		let barred = Fingering::parse("133211")
+			.unwrap()
+			.with_barre(Barre::new(Finger::Index, 1, 0, 5))
+			.unwrap();
+		assert_eq!(barred.transpose(2).unwrap().explicit_barres()[0].fret, 3);
+	}
+
+	#[test]
+	fn test_barre_shape() {
+		let e = Fingering::parse("022100").unwrap();
+		let f = e.barre_shape(1).unwrap();
+		assert_eq!(f.to_string(), "133211");
+		assert_eq!(f.explicit_barres(), [Barre::new(Finger::Index, 1, 0, 5)]);
+
+		let a = Fingering::parse("x02220").unwrap().barre_shape(2).unwrap();
+		assert_eq!(a.to_string(), "x24442");
+		assert_eq!(a.explicit_barres(), [Barre::new(Finger::Index, 2, 1, 5)]);
+
+		// A single open string needs no barre
+		let d = Fingering::parse("xx0232").unwrap().barre_shape(2).unwrap();
+		assert_eq!(d.to_string(), "xx2454");
+		assert!(d.explicit_barres().is_empty());
+
+		assert!(e.barre_shape(0).is_err());
+		assert!(Fingering::parse("0x2220").unwrap().barre_shape(1).is_err());
+	}
+
+	#[test]
+	fn test_explicit_barres() {
+		let guitar = Guitar::default();
+		let a_shape = Fingering::parse("x13331").unwrap();
+		assert!(a_shape.has_high_barre_for(&guitar));
+		assert_eq!(
+			a_shape.barres(),
+			vec![Barre::new(Finger::Index, 1, 1, 5)],
+			"inferred from the finger assignment"
+		);
+
+		// With the index barre spelled out, the widest barre sits at the base
+		let barred = a_shape
+			.clone()
+			.with_barre(Barre::new(Finger::Index, 1, 1, 5))
+			.unwrap();
+		assert!(!barred.has_high_barre_for(&guitar));
+		assert_eq!(barred.min_fingers_required(), 2);
+		assert_ne!(barred, a_shape);
+
+		// The heuristic lets one finger cross the muted string; a real barre can't
+		let split = Fingering::parse("x1x111").unwrap();
+		assert_eq!(split.min_fingers_required(), 1);
+		assert!(
+			split
+				.clone()
+				.with_barre(Barre::new(Finger::Index, 1, 1, 5))
+				.is_err()
+		);
+		let split = split
+			.with_barre(Barre::new(Finger::Index, 1, 3, 5))
+			.unwrap();
+		assert_eq!(split.min_fingers_required(), 2);
+
+		assert!(
+			a_shape
+				.clone()
+				.with_barre(Barre::new(Finger::Ring, 3, 1, 4))
+				.is_err()
+		);
+		assert!(
+			a_shape
+				.with_barre(Barre::new(Finger::Index, 1, 1, 9))
+				.is_err()
+		);
+	}
+
 	#[test]
 	fn test_unplayable_too_many_fingers() {
 		let guitar = Guitar::default();
@@ -729,6 +1598,71 @@ mod tests {
 		assert!(!f.is_playable_for(&guitar), "Should be marked unplayable");
 	}
 
+	#[test]
+	fn test_validate_for_reports_issues() {
+		let guitar = Guitar::default();
+		let issues = |tab: &str| Fingering::parse(tab).unwrap().validate_for(&guitar);
+
+		assert!(issues("x32010").is_empty());
+		assert!(issues("1x1111").is_empty());
+		assert_eq!(
+			issues("x3201"),
+			vec![PlayabilityIssue::WrongStringCount {
+				expected: 6,
+				found: 5
+			}]
+		);
+		assert_eq!(
+			issues("123456"),
+			vec![
+				PlayabilityIssue::StretchTooWide { span: 5, max: 4 },
+				PlayabilityIssue::TooManyFingers {
+					needed: 6,
+					available: 4
+				}
+			]
+		);
+		// Four fingers only fit with a barre at fret 1, which would sound the A string
+		assert_eq!(
+			issues("1x1243"),
+			vec![PlayabilityIssue::MutedUnderBarre { string: 1, fret: 1 }]
+		);
+
+		let ukulele = crate::instrument::Ukulele::default();
+		let issues = Fingering::parse("(20)000").unwrap().validate_for(&ukulele);
+		assert_eq!(
+			issues,
+			vec![PlayabilityIssue::FretOutOfRange {
+				string: 0,
+				fret: 20,
+				max: 15
+			}]
+		);
+		assert!(issues[0].is_structural());
+	}
+
+	#[test]
+	fn test_check_for() {
+		let guitar = Guitar::default();
+		assert!(
+			Fingering::parse("123456")
+				.unwrap()
+				.check_for(&guitar)
+				.is_ok()
+		);
+		let err = Fingering::parse("0003")
+			.unwrap()
+			.check_for(&guitar)
+			.unwrap_err();
+		assert!(err.to_string().contains("4 strings"), "{err}");
+
+		// Above a capo at 7 the guitar's 24 frets end at 17
+		let capoed = guitar.with_capo(7).unwrap();
+		let high = Fingering::parse("x(19)(19)(18)(19)x").unwrap();
+		assert!(high.check_for(&guitar).is_ok());
+		assert!(high.check_for(&capoed).is_err());
+	}
+
 	#[test]
 	fn test_playability_prefers_fewer_fingers() {
 		let guitar = Guitar::default();
@@ -870,4 +1804,27 @@ mod tests {
 			"C chord should score well despite single interior open"
 		);
 	}
+
+	#[test]
+	fn test_sounding_notes_of_courses() {
+		use crate::instrument::ConfigurableInstrument;
+		let twelve = ConfigurableInstrument::guitar_12_string();
+		let c = Fingering::parse("x32010").unwrap();
+
+		// One fretted position per course, two strings sounding
+		assert_eq!(c.notes(&twelve).len(), 5);
+		let sounding = c.sounding_notes(&twelve);
+		assert_eq!(sounding.len(), 10);
+		let names: Vec<String> = sounding.iter().map(|n| n.to_string()).collect();
+		assert_eq!(names[..4], ["C3", "C4", "E3", "E4"]);
+		// Same chord tones as on a six-string
+		assert_eq!(
+			c.unique_pitch_classes(&twelve),
+			c.unique_pitch_classes(&Guitar::default())
+		);
+		assert_eq!(
+			c.sounding_notes(&Guitar::default()),
+			c.notes(&Guitar::default())
+		);
+	}
 }
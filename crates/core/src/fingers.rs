@@ -0,0 +1,368 @@
+//! Finger assignment
+//!
+//! A [`Fingering`] only says which frets sound. [`assign_fingers`] works out
+//! which finger presses each of them: lower frets take lower fingers, fingers
+//! side by side in one fret rise toward the higher strings (a finger may sit
+//! on either side of a barre, unless another finger is in the way), one finger
+//! may barre several strings at the same fret (as long as nothing under it is
+//! open, muted or pressed lower), and the hand prefers one finger per fret
+//! starting from the lowest fretted note. Index barres reaching the highest
+//! string are the cheapest barres, as on a chart.
+
+use std::fmt;
+
+use crate::fingering::{Barre, Fingering, StringState};
+
+/// Cost per fret a finger reaches past one finger per fret (a stretch)
+const STRETCH_COST: u32 = 3;
+/// Cost per fret a finger sits short of one finger per fret (crowding)
+const CROWD_COST: u32 = 2;
+/// Cost of an index barre reaching the highest string (full and partial barres)
+const TOP_BARRE_COST: u32 = 5;
+/// Cost of any other barre
+const BARRE_COST: u32 = 8;
+/// Extra cost of barring with a finger other than the index
+const OTHER_FINGER_BARRE_COST: u32 = 4;
+/// Cost of wrapping the thumb over the neck
+const THUMB_COST: u32 = 6;
+
+/// A finger of the fretting hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Finger {
+	Thumb,
+	Index,
+	Middle,
+	Ring,
+	Pinky,
+}
+
+impl Finger {
+	const FRETTING: [Finger; 4] = [Finger::Index, Finger::Middle, Finger::Ring, Finger::Pinky];
+
+	/// Number used in chord charts: 1 (index) to 4 (pinky), 0 for the thumb
+	pub fn number(self) -> u8 {
+		self as u8
+	}
+}
+
+impl fmt::Display for Finger {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Finger::Thumb => write!(f, "T"),
+			finger => write!(f, "{}", finger.number()),
+		}
+	}
+}
+
+/// The finger on each string of a fingering
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FingerAssignment {
+	/// One entry per string, lowest first; None for open and muted strings
+	fingers: Vec<Option<Finger>>,
+}
+
+impl FingerAssignment {
+	pub fn fingers(&self) -> &[Option<Finger>] {
+		&self.fingers
+	}
+
+	pub fn finger(&self, string: usize) -> Option<Finger> {
+		self.fingers.get(string).copied().flatten()
+	}
+
+	/// Fingers lying across more than one string
+	pub fn barre_fingers(&self) -> Vec<Finger> {
+		let mut barres: Vec<Finger> = self
+			.fingers
+			.iter()
+			.flatten()
+			.filter(|f| self.fingers.iter().flatten().filter(|g| g == f).count() > 1)
+			.copied()
+			.collect();
+		barres.sort_unstable();
+		barres.dedup();
+		barres
+	}
+
+	/// Barres this assignment lays on the fingering it was made for
+	pub fn barres(&self, fingering: &Fingering) -> Vec<Barre> {
+		self.barre_fingers()
+			.into_iter()
+			.filter_map(|finger| {
+				let strings: Vec<usize> = (0..self.fingers.len())
+					.filter(|&s| self.finger(s) == Some(finger))
+					.collect();
+				let (&from, &to) = (strings.first()?, strings.last()?);
+				let fret = fingering.strings().get(from)?.fret()?;
+				Some(Barre::new(finger, fret, from, to))
+			})
+			.collect()
+	}
+}
+
+impl fmt::Display for FingerAssignment {
+	/// Finger per string like a tab, with `-` for strings no finger touches ("-32-1-")
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for finger in &self.fingers {
+			match finger {
+				Some(finger) => write!(f, "{finger}")?,
+				None => write!(f, "-")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A string the fretting hand has to touch
+struct Stop {
+	string: usize,
+	fret: u8,
+}
+
+/// Assign fingers to every fretted note and harmonic of a fingering
+///
+/// Uses at most `max_fingers` fingers, plus the thumb on the lowest string
+/// when `allow_thumb` is set. Explicit barres of the fingering keep their
+/// finger. Returns None if no assignment fits, e.g. when
+/// a barre would have to cross a muted string and no finger is left over.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::fingering::Fingering;
+/// use chordcraft_core::fingers::assign_fingers;
+///
+/// let c = Fingering::parse("x32010").unwrap();
+/// assert_eq!(assign_fingers(&c, 4, false).unwrap().to_string(), "-32-1-");
+///
+/// let f = Fingering::parse("133211").unwrap();
+/// assert_eq!(assign_fingers(&f, 4, false).unwrap().to_string(), "134211");
+/// ```
+pub fn assign_fingers(
+	fingering: &Fingering,
+	max_fingers: u8,
+	allow_thumb: bool,
+) -> Option<FingerAssignment> {
+	let strings = fingering.strings();
+	let stops: Vec<Stop> = strings
+		.iter()
+		.enumerate()
+		.filter_map(|(string, state)| state.hand_fret().map(|fret| Stop { string, fret }))
+		// Highest string first, so a barre's top string is known when it forms
+		.rev()
+		.collect();
+	let base = stops.iter().map(|s| s.fret).min().unwrap_or(0);
+
+	let mut candidates: Vec<Finger> = Finger::FRETTING
+		.into_iter()
+		.take(max_fingers as usize)
+		.collect();
+	if allow_thumb {
+		candidates.insert(0, Finger::Thumb);
+	}
+
+	let mut search = Search {
+		strings,
+		barres: fingering.explicit_barres(),
+		stops: &stops,
+		base,
+		candidates: &candidates,
+		current: Vec::with_capacity(stops.len()),
+		best: None,
+	};
+	search.run(0);
+
+	let (_, chosen) = search.best?;
+	let mut fingers = vec![None; strings.len()];
+	for (stop, finger) in stops.iter().zip(chosen) {
+		fingers[stop.string] = Some(finger);
+	}
+	Some(FingerAssignment { fingers })
+}
+
+/// Depth-first search over fingers for each stop, keeping the cheapest
+struct Search<'a> {
+	strings: &'a [StringState],
+	barres: &'a [Barre],
+	stops: &'a [Stop],
+	base: u8,
+	candidates: &'a [Finger],
+	current: Vec<Finger>,
+	best: Option<(u32, Vec<Finger>)>,
+}
+
+impl Search<'_> {
+	fn run(&mut self, cost: u32) {
+		if self.best.as_ref().is_some_and(|(best, _)| cost >= *best) {
+			return;
+		}
+		let index = self.current.len();
+		if index == self.stops.len() {
+			self.best = Some((cost, self.current.clone()));
+			return;
+		}
+
+		for &finger in self.candidates {
+			let Some(extra) = self.cost_of(index, finger) else {
+				continue;
+			};
+			self.current.push(finger);
+			self.run(cost + extra);
+			self.current.pop();
+		}
+	}
+
+	/// Extra cost of putting `finger` on stop `index`, or None if the hand can't do it
+	fn cost_of(&self, index: usize, finger: Finger) -> Option<u32> {
+		let stop = &self.stops[index];
+		if finger == Finger::Thumb && stop.string != 0 {
+			return None;
+		}
+		match self
+			.barres
+			.iter()
+			.find(|b| b.fret == stop.fret && b.covers(stop.string))
+		{
+			Some(barre) if barre.finger != finger => return None,
+			None if self.barres.iter().any(|b| b.finger == finger) => return None,
+			_ => {}
+		}
+
+		let mut cost = match finger {
+			Finger::Thumb => THUMB_COST,
+			_ => {
+				let ideal = (stop.fret - self.base) as i32;
+				let off = finger.number() as i32 - 1 - ideal;
+				if off < 0 {
+					off.unsigned_abs() * STRETCH_COST
+				} else {
+					off as u32 * CROWD_COST
+				}
+			}
+		};
+
+		let barres = |f: Finger| self.current.iter().filter(|&&c| c == f).count() > 1;
+		let joins_barre = self.current.contains(&finger);
+		// Another finger pressing a string between the two
+		let crossed = |a: usize, b: usize, other_finger: Finger| {
+			let (low, high) = (a.min(b), a.max(b));
+			self.stops.iter().zip(&self.current).any(|(s, &f)| {
+				low < s.string && s.string < high && f != finger && f != other_finger
+			})
+		};
+		for (other, &other_finger) in self.stops.iter().zip(&self.current) {
+			if other_finger == finger {
+				if other.fret != stop.fret || !self.can_barre(stop.string, other.string) {
+					return None;
+				}
+			} else if other.fret != stop.fret {
+				// Lower frets need lower fingers
+				if (other.fret < stop.fret) != (other_finger < finger) {
+					return None;
+				}
+			} else if (other.string < stop.string) != (other_finger < finger)
+				&& (!(joins_barre || barres(other_finger))
+					|| crossed(other.string, stop.string, other_finger))
+			{
+				// Side by side in one fret, higher fingers on higher strings; a
+				// barre lies flat, so a finger may sit on either side of it as
+				// long as no other finger is in the way
+				return None;
+			}
+		}
+		// Charge a barre once, when its second string joins
+		if self.current.iter().filter(|&&f| f == finger).count() == 1 {
+			let top = self.strings.len() - 1;
+			let reaches_top = self.stops[0].string == top && self.current[0] == finger;
+			cost += match finger {
+				Finger::Index if reaches_top => TOP_BARRE_COST,
+				Finger::Index => BARRE_COST,
+				_ => BARRE_COST + OTHER_FINGER_BARRE_COST,
+			};
+		}
+		Some(cost)
+	}
+
+	/// Whether one finger can press both strings and everything between them
+	fn can_barre(&self, from: usize, to: usize) -> bool {
+		let state = self.strings[from];
+		if self.strings[to] != state {
+			return false;
+		}
+		self.strings[from + 1..to]
+			.iter()
+			.all(|between| match (state, between) {
+				(StringState::Fretted(barre), StringState::Fretted(f)) => *f >= barre,
+				(StringState::Harmonic(node), StringState::Harmonic(f)) => *f == node,
+				_ => false,
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fingers(tab: &str) -> Option<String> {
+		assign_fingers(&Fingering::parse(tab).unwrap(), 4, false).map(|a| a.to_string())
+	}
+
+	#[test]
+	fn test_open_chords() {
+		assert_eq!(fingers("x32010").as_deref(), Some("-32-1-"));
+		assert_eq!(fingers("xx0232").as_deref(), Some("---132"));
+		assert_eq!(fingers("022100").as_deref(), Some("-231--"));
+		assert_eq!(fingers("x02210").as_deref(), Some("--231-"));
+		assert_eq!(fingers("000000").as_deref(), Some("------"));
+	}
+
+	#[test]
+	fn test_barres() {
+		let f = assign_fingers(&Fingering::parse("133211").unwrap(), 4, false).unwrap();
+		assert_eq!(f.to_string(), "134211");
+		assert_eq!(f.barre_fingers(), vec![Finger::Index]);
+		let shape = Fingering::parse("133211").unwrap();
+		assert_eq!(f.barres(&shape), vec![Barre::new(Finger::Index, 1, 0, 5)]);
+		assert_eq!(f.finger(3), Some(Finger::Middle));
+
+		// Bm: index barre from the A string, ring and pinky above it
+		assert_eq!(fingers("x24432").as_deref(), Some("-13421"));
+
+		// No barre across an open or muted string
+		let one_finger = |tab: &str| assign_fingers(&Fingering::parse(tab).unwrap(), 1, false);
+		assert!(one_finger("x1x111").is_none());
+		assert!(one_finger("101111").is_none());
+		assert!(one_finger("111111").is_some());
+		// The index barres the top strings, as on a chart, leaving the middle
+		// finger for the low string rather than barring with it
+		assert_eq!(fingers("1x1111").as_deref(), Some("2-1111"));
+		// ...but not when it would have to reach under the fingers in between
+		assert_eq!(fingers("1x3211").as_deref(), Some("1-4322"));
+	}
+
+	#[test]
+	fn test_explicit_barres_keep_their_finger() {
+		let a = Fingering::parse("x02220").unwrap();
+		assert_eq!(fingers("x02220").as_deref(), Some("--123-"));
+		let barred = a.with_barre(Barre::new(Finger::Index, 2, 2, 4)).unwrap();
+		assert_eq!(
+			assign_fingers(&barred, 4, false).unwrap().to_string(),
+			"--111-"
+		);
+	}
+
+	#[test]
+	fn test_thumb_frets_the_lowest_string() {
+		let hendrix = Fingering::parse("1x3211").unwrap();
+		let with_thumb = assign_fingers(&hendrix, 4, true).unwrap();
+		assert_eq!(with_thumb.finger(0), Some(Finger::Thumb));
+		assert_eq!(with_thumb.to_string(), "T-3211");
+
+		// Only the lowest string can be thumbed
+		let thumbless = Fingering::parse("x13331").unwrap();
+		let assignment = assign_fingers(&thumbless, 4, true).unwrap();
+		assert!(!assignment.fingers().contains(&Some(Finger::Thumb)));
+	}
+}
@@ -0,0 +1,280 @@
+//! Flashcard decks of chord voicings
+//!
+//! Builds one card per voicing, with the chord name on the front and an SVG
+//! diagram, the tab and the notes on the back, from a chord list, a shape
+//! library, or the voicings a song is played with. Decks export as CSV (for
+//! Anki-style imports, one column per field) or JSON.
+
+use crate::analyzer::analyze_fingering;
+use crate::chord::Chord;
+use crate::diagram::svg_diagram;
+use crate::error::Result;
+use crate::fingering::{Fingering, StringState};
+use crate::generator::{GeneratorOptions, generate_fingerings};
+use crate::instrument::Instrument;
+use crate::progression::{ProgressionOptions, generate_progression};
+use crate::shapes::StandardShape;
+use crate::song::Song;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flashcard {
+	/// Chord name, shown on the front
+	pub front: String,
+	/// Tab notation (e.g., "x32010")
+	pub tab: String,
+	/// Chord box diagram
+	pub svg: String,
+	/// Distinct notes sounded, lowest first
+	pub notes: Vec<String>,
+}
+
+impl Flashcard {
+	pub fn new<I: Instrument>(
+		name: impl Into<String>,
+		fingering: &Fingering,
+		instrument: &I,
+	) -> Self {
+		let mut notes: Vec<String> = Vec::new();
+		for note in fingering.notes(instrument) {
+			let name = note.pitch.to_string();
+			if !notes.contains(&name) {
+				notes.push(name);
+			}
+		}
+
+		Flashcard {
+			front: name.into(),
+			tab: fingering.to_string(),
+			svg: svg_diagram(fingering, instrument),
+			notes,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct FlashcardOptions {
+	/// Cards per chord, one for each of its best voicings
+	pub voicings_per_chord: usize,
+	pub generator_options: GeneratorOptions,
+}
+
+impl Default for FlashcardOptions {
+	fn default() -> Self {
+		FlashcardOptions {
+			voicings_per_chord: 1,
+			generator_options: GeneratorOptions::default(),
+		}
+	}
+}
+
+/// Cards for the best voicings of each chord
+///
+/// Chords without a playable voicing get no card.
+pub fn flashcards_for_chords<I: Instrument>(
+	chord_names: &[&str],
+	instrument: &I,
+	options: &FlashcardOptions,
+) -> Result<Vec<Flashcard>> {
+	let generator_options = GeneratorOptions {
+		limit: options.voicings_per_chord,
+		..options.generator_options.clone()
+	};
+
+	let mut cards = Vec::new();
+	for name in chord_names {
+		let chord = Chord::parse(name)?;
+		for scored in generate_fingerings(&chord, instrument, &generator_options) {
+			cards.push(Flashcard::new(*name, &scored.fingering, instrument));
+		}
+	}
+	Ok(cards)
+}
+
+/// Cards for the open-position form of each shape
+///
+/// Each card is named after the chord the shape plays in open position, or
+/// after the shape when the analyzer can't name it. Shapes for another
+/// string count are skipped.
+pub fn flashcards_for_shapes<I: Instrument>(
+	shapes: &[&StandardShape],
+	instrument: &I,
+) -> Vec<Flashcard> {
+	shapes
+		.iter()
+		.filter(|shape| shape.string_count == instrument.string_count())
+		.map(|shape| {
+			let fingering = Fingering::new(
+				shape
+					.pattern
+					.iter()
+					.map(|fret| fret.map_or(StringState::Muted, StringState::Fretted))
+					.collect(),
+			);
+			let name = analyze_fingering(&fingering, instrument)
+				.first()
+				.map_or_else(|| shape.name.to_string(), |m| m.name.clone());
+			Flashcard::new(name, &fingering, instrument)
+		})
+		.collect()
+}
+
+/// Cards for the voicings a song is played with
+///
+/// Each section is optimized as a progression, and every distinct chord and
+/// voicing pair gets one card, in order of first appearance.
+pub fn flashcards_for_song<I: Instrument>(
+	song: &Song,
+	instrument: &I,
+	options: &FlashcardOptions,
+) -> Vec<Flashcard> {
+	let progression_options = ProgressionOptions {
+		limit: 1,
+		generator_options: options.generator_options.clone(),
+		..Default::default()
+	};
+
+	let mut cards: Vec<Flashcard> = Vec::new();
+	for section in &song.sections {
+		let names: Vec<String> = section.chords.iter().map(|c| c.chord.to_string()).collect();
+		let names: Vec<&str> = names.iter().map(String::as_str).collect();
+		let Some(best) = generate_progression(&names, instrument, &progression_options)
+			.into_iter()
+			.next()
+		else {
+			continue;
+		};
+
+		for (name, scored) in best.chords.iter().zip(&best.fingerings) {
+			let card = Flashcard::new(name.as_str(), &scored.fingering, instrument);
+			if !cards
+				.iter()
+				.any(|c| c.front == card.front && c.tab == card.tab)
+			{
+				cards.push(card);
+			}
+		}
+	}
+	cards
+}
+
+/// Deck as CSV with a header row: front, tab, notes, diagram
+pub fn flashcards_to_csv(cards: &[Flashcard]) -> String {
+	let mut csv = String::from("front,tab,notes,diagram\n");
+	for card in cards {
+		let fields = [
+			card.front.as_str(),
+			card.tab.as_str(),
+			&card.notes.join(" "),
+			card.svg.as_str(),
+		];
+		let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+		csv.push_str(&row.join(","));
+		csv.push('\n');
+	}
+	csv
+}
+
+/// Deck as a JSON array of `{front, tab, notes, svg}` objects
+pub fn flashcards_to_json(cards: &[Flashcard]) -> String {
+	let objects: Vec<String> = cards
+		.iter()
+		.map(|card| {
+			let notes: Vec<String> = card.notes.iter().map(|n| json_string(n)).collect();
+			format!(
+				r#"{{"front":{},"tab":{},"notes":[{}],"svg":{}}}"#,
+				json_string(&card.front),
+				json_string(&card.tab),
+				notes.join(","),
+				json_string(&card.svg)
+			)
+		})
+		.collect();
+	format!("[{}]", objects.join(","))
+}
+
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+fn json_string(s: &str) -> String {
+	let mut out = String::with_capacity(s.len() + 2);
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chordpro::import_chordpro;
+	use crate::instrument::Guitar;
+	use crate::shapes;
+
+	#[test]
+	fn test_chord_and_shape_cards() {
+		let guitar = Guitar::default();
+		let options = FlashcardOptions {
+			voicings_per_chord: 2,
+			..Default::default()
+		};
+		let cards = flashcards_for_chords(&["C", "Am"], &guitar, &options).unwrap();
+		assert_eq!(cards.len(), 4);
+		assert_eq!(cards[0].front, "C");
+		assert!(cards[0].svg.starts_with("<svg"));
+		assert!(flashcards_for_chords(&["Qzz"], &guitar, &options).is_err());
+
+		let shape_cards = flashcards_for_shapes(&[&shapes::guitar::AM_SHAPE], &guitar);
+		assert_eq!(shape_cards[0].front, "Am");
+		assert_eq!(shape_cards[0].tab, "x02210");
+		assert_eq!(shape_cards[0].notes, vec!["A", "E", "C"]);
+		assert!(flashcards_for_shapes(shapes::ukulele::ALL_SHAPES, &guitar).is_empty());
+	}
+
+	#[test]
+	fn test_song_cards_are_distinct() {
+		let guitar = Guitar::default();
+		let song = import_chordpro("[G]One [C]two [G]three\n\n[G]four [D]five").unwrap();
+		let cards = flashcards_for_song(&song, &guitar, &FlashcardOptions::default());
+		let fronts: Vec<&str> = cards.iter().map(|c| c.front.as_str()).collect();
+		assert!(fronts.starts_with(&["G", "C"]));
+		assert!(fronts.contains(&"D"));
+		assert!(cards.len() <= 4);
+	}
+
+	#[test]
+	fn test_csv_and_json_export() {
+		let card = Flashcard {
+			front: "C, \"open\"".to_string(),
+			tab: "x32010".to_string(),
+			svg: "<svg/>".to_string(),
+			notes: vec!["C".to_string(), "E".to_string()],
+		};
+
+		let csv = flashcards_to_csv(std::slice::from_ref(&card));
+		assert_eq!(
+			csv,
+			"front,tab,notes,diagram\n\"C, \"\"open\"\"\",x32010,C E,<svg/>\n"
+		);
+
+		let json = flashcards_to_json(&[card]);
+		let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+		assert_eq!(parsed[0]["front"], "C, \"open\"");
+		assert_eq!(parsed[0]["notes"][1], "E");
+	}
+}
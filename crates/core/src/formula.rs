@@ -0,0 +1,205 @@
+//! User-defined chord qualities
+//!
+//! Songbooks don't all agree on chord symbols. [`register_quality`] teaches
+//! the library a new symbol and its formula at runtime, after which
+//! [`Chord::parse`](crate::chord::Chord::parse), the fingering generator and
+//! the analyzer treat it like a built-in quality. The registry is shared by
+//! the whole process; registering a symbol again replaces its formula.
+
+use std::sync::{PoisonError, RwLock};
+
+use crate::chord::{Chord, ChordQuality};
+use crate::error::{ChordCraftError, Result};
+use crate::interval::{Interval, UNISON};
+
+/// Tones of a chord quality, as intervals above the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChordFormula {
+	pub required: Vec<Interval>,
+	/// Tones a voicing may leave out
+	pub optional: Vec<Interval>,
+}
+
+impl ChordFormula {
+	/// Parse a formula of chord degrees: "1 3 5 b7", "1 b3 #5 (9)"
+	///
+	/// Degrees in parentheses are optional. The root is added if missing.
+	pub fn parse(s: &str) -> Result<Self> {
+		let mut required = Vec::new();
+		let mut optional = Vec::new();
+		for token in s.split([' ', ',']).filter(|t| !t.is_empty()) {
+			match token.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+				Some(degree) => optional.push(Interval::from_degree(degree)?),
+				None => required.push(Interval::from_degree(token)?),
+			}
+		}
+		ChordFormula::new(required, optional)
+	}
+
+	/// Formula from intervals above the root; the root is added if missing
+	pub fn new(mut required: Vec<Interval>, optional: Vec<Interval>) -> Result<Self> {
+		if !required.contains(&UNISON) {
+			required.insert(0, UNISON);
+		}
+		if required.len() + optional.len() < 2 {
+			return Err(ChordCraftError::InvalidInterval(
+				"formula needs a tone besides the root".to_string(),
+			));
+		}
+		Ok(ChordFormula { required, optional })
+	}
+}
+
+struct CustomQuality {
+	/// Leaked once per symbol so it can be handed out like the built-in ones
+	symbol: &'static str,
+	formula: ChordFormula,
+}
+
+static CUSTOM_QUALITIES: RwLock<Vec<CustomQuality>> = RwLock::new(Vec::new());
+
+/// Register a chord symbol with its formula, returning its quality
+///
+/// Fails if the symbol already names a built-in quality, or can't follow a
+/// root in a chord name (it is empty, starts with an accidental, or contains
+/// whitespace, a slash or parentheses).
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::chord::Chord;
+/// use chordcraft_core::formula::{ChordFormula, register_quality};
+///
+/// let mu = register_quality("mu", ChordFormula::parse("1 2 3 5").unwrap()).unwrap();
+/// let chord = Chord::parse("Dmu").unwrap();
+/// assert_eq!(chord.quality, mu);
+/// assert_eq!(chord.to_string(), "Dmu");
+/// ```
+pub fn register_quality(symbol: &str, formula: ChordFormula) -> Result<ChordQuality> {
+	let symbol = symbol.trim();
+	let unusable = symbol.is_empty()
+		|| symbol.starts_with(['b', '#', '♭', '♯'])
+		|| symbol.contains(|c: char| c.is_whitespace() || matches!(c, '/' | '(' | ')'));
+	if unusable {
+		return Err(ChordCraftError::InvalidChordName(format!(
+			"'{symbol}' can't be used as a chord symbol"
+		)));
+	}
+
+	if lookup(symbol).is_none() && Chord::parse(&format!("C{symbol}")).is_ok() {
+		return Err(ChordCraftError::InvalidChordName(format!(
+			"'{symbol}' already names a chord quality"
+		)));
+	}
+
+	let mut registry = CUSTOM_QUALITIES
+		.write()
+		.unwrap_or_else(PoisonError::into_inner);
+	if let Some(id) = registry.iter().position(|q| q.symbol == symbol) {
+		registry[id].formula = formula;
+		return Ok(ChordQuality::Custom(id as u16));
+	}
+	registry.push(CustomQuality {
+		symbol: Box::leak(symbol.to_string().into_boxed_str()),
+		formula,
+	});
+	Ok(ChordQuality::Custom((registry.len() - 1) as u16))
+}
+
+/// Every registered quality, in registration order
+pub fn custom_qualities() -> Vec<ChordQuality> {
+	let registry = CUSTOM_QUALITIES
+		.read()
+		.unwrap_or_else(PoisonError::into_inner);
+	(0..registry.len() as u16)
+		.map(ChordQuality::Custom)
+		.collect()
+}
+
+/// Quality registered under a symbol
+pub(crate) fn lookup(symbol: &str) -> Option<ChordQuality> {
+	let registry = CUSTOM_QUALITIES
+		.read()
+		.unwrap_or_else(PoisonError::into_inner);
+	registry
+		.iter()
+		.position(|q| q.symbol == symbol)
+		.map(|id| ChordQuality::Custom(id as u16))
+}
+
+/// Formula of a registered quality
+pub(crate) fn formula(id: u16) -> Option<ChordFormula> {
+	let registry = CUSTOM_QUALITIES
+		.read()
+		.unwrap_or_else(PoisonError::into_inner);
+	registry.get(id as usize).map(|q| q.formula.clone())
+}
+
+/// Symbol of a registered quality ("?" for an id that was never registered)
+pub(crate) fn symbol(id: u16) -> &'static str {
+	let registry = CUSTOM_QUALITIES
+		.read()
+		.unwrap_or_else(PoisonError::into_inner);
+	registry.get(id as usize).map_or("?", |q| q.symbol)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::analyzer::analyze_fingering;
+	use crate::fingering::Fingering;
+	use crate::generator::{GeneratorOptions, generate_fingerings};
+	use crate::instrument::Guitar;
+	use crate::interval::{MAJOR_NINTH, MINOR_SEVENTH};
+	use crate::note::PitchClass;
+
+	#[test]
+	fn test_parse_formula() {
+		let formula = ChordFormula::parse("3, b7 (9)").unwrap();
+		assert_eq!(formula.required[0], UNISON);
+		assert_eq!(formula.required[2], MINOR_SEVENTH);
+		assert_eq!(formula.optional, vec![MAJOR_NINTH]);
+		assert!(ChordFormula::parse("1").is_err());
+		assert!(ChordFormula::parse("1 q3").is_err());
+	}
+
+	#[test]
+	fn test_registered_quality_everywhere() {
+		// Quartal voicing: stacked fourths
+		let formula = ChordFormula::parse("1 4 b7").unwrap();
+		let quartal = register_quality("qrt", formula.clone()).unwrap();
+		assert_eq!(register_quality("qrt", formula).unwrap(), quartal);
+		assert!(custom_qualities().contains(&quartal));
+
+		let chord = Chord::parse("Aqrt").unwrap();
+		assert_eq!(chord, Chord::new(PitchClass::A, quartal));
+		assert_eq!(
+			chord.notes(),
+			vec![PitchClass::A, PitchClass::D, PitchClass::G]
+		);
+
+		let guitar = Guitar::default();
+		let fingerings = generate_fingerings(&chord, &guitar, &GeneratorOptions::default());
+		assert!(!fingerings.is_empty());
+		let notes: Vec<PitchClass> = fingerings[0]
+			.fingering
+			.notes(&guitar)
+			.iter()
+			.map(|n| n.pitch)
+			.collect();
+		assert!(notes.iter().all(|n| chord.notes().contains(n)));
+
+		// Open A, D and G strings
+		let matches = analyze_fingering(&Fingering::parse("x000xx").unwrap(), &guitar);
+		assert!(matches.iter().any(|m| m.name == "Aqrt"), "{matches:?}");
+	}
+
+	#[test]
+	fn test_reject_unusable_symbols() {
+		let formula = ChordFormula::parse("1 3 5").unwrap();
+		assert!(register_quality("maj7", formula.clone()).is_err());
+		assert!(register_quality("b5", formula.clone()).is_err());
+		assert!(register_quality("x/y", formula.clone()).is_err());
+		assert!(register_quality("", formula).is_err());
+	}
+}
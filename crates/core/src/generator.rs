@@ -3,26 +3,322 @@
 //! This module contains the algorithm for generating all possible fingerings
 //! for a given chord on a specific instrument.
 
-use crate::chord::{Chord, VoicingType};
-use crate::fingering::{Fingering, StringState};
-use crate::instrument::Instrument;
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::chord::{Alteration, Chord, VoicingType};
+use crate::error::{ChordCraftError, Result};
+use crate::fingering::{
+	Difficulty, Fingering, NATURAL_HARMONIC_FRETS, StringState, harmonic_semitones,
+};
+use crate::fingers::{FingerAssignment, assign_fingers};
+use crate::instrument::{CapoedInstrument, Instrument};
+use crate::note::PitchClass;
+use crate::progress::{
+	BudgetObserver, NoopObserver, Partial, Progress, SearchBudget, SearchObserver, SearchPhase,
+};
 use crate::shapes;
+use crate::spelling::Accidentals;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayingContext {
 	#[default]
 	Solo,
 	Band,
+	/// Plucked patterns: separated voices and ringing open strings, skipped strings are fine
+	Fingerstyle,
+	/// Full strums: every string between the outer ones gets hit, so interior mutes hurt
+	Strumming,
+	/// Studio takes: full, balanced voicings without lopsided doublings
+	Recording,
+}
+
+/// Player hand size, adjusting how far the generator lets fingers stretch
+///
+/// Stretch adjustments are in frets on top of the instrument's own limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandSize {
+	/// One fret less stretch, and barre chords are penalized
+	Small,
+	#[default]
+	Medium,
+	/// One fret more stretch, and the thumb can fret the bass string
+	Large,
+}
+
+impl HandSize {
+	/// Effective maximum stretch for an instrument whose default is `instrument_stretch`
+	pub fn max_stretch(&self, instrument_stretch: u8) -> u8 {
+		match self {
+			HandSize::Small => instrument_stretch.saturating_sub(1).max(2),
+			HandSize::Medium => instrument_stretch,
+			HandSize::Large => instrument_stretch.saturating_add(1),
+		}
+	}
+
+	/// Extra score penalty applied to fingerings that need a barre
+	pub fn barre_penalty(&self) -> i32 {
+		match self {
+			HandSize::Small => SMALL_HAND_BARRE_PENALTY,
+			HandSize::Medium | HandSize::Large => 0,
+		}
+	}
+
+	/// Whether wrapping the thumb over the neck to fret the bass string is realistic
+	pub fn allows_thumb_fretting(&self) -> bool {
+		matches!(self, HandSize::Large)
+	}
+}
+
+/// How well a player holds barres down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarreStrength {
+	/// Barres tire the hand quickly and rank well below other shapes
+	Weak,
+	/// Barres cost what the hand size says
+	#[default]
+	Normal,
+	/// Barres cost nothing extra, even for small hands
+	Strong,
+}
+
+/// A player's hand: its size, plus how far it stretches and how well it barres
+///
+/// Kids and small-handed players find very different chords easy, so the
+/// profile changes both which fingerings the generator keeps and how
+/// [`Fingering::playability_score_for_player`] rates them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerProfile {
+	pub hand_size: HandSize,
+	/// Frets of stretch on top of the hand size (negative: less)
+	pub stretch_tolerance: i8,
+	pub barre_strength: BarreStrength,
+}
+
+impl PlayerProfile {
+	/// A child's hand: small, one fret less stretch, and weak barres
+	pub fn child() -> Self {
+		PlayerProfile {
+			hand_size: HandSize::Small,
+			stretch_tolerance: -1,
+			barre_strength: BarreStrength::Weak,
+		}
+	}
+
+	/// Effective maximum stretch for an instrument whose default is `instrument_stretch`
+	pub fn max_stretch(&self, instrument_stretch: u8) -> u8 {
+		self.hand_size
+			.max_stretch(instrument_stretch)
+			.saturating_add_signed(self.stretch_tolerance)
+			.max(MIN_PLAYER_STRETCH)
+	}
+
+	/// Score penalty applied to fingerings that need a barre
+	pub fn barre_penalty(&self) -> i32 {
+		match self.barre_strength {
+			BarreStrength::Weak => self.hand_size.barre_penalty() + WEAK_BARRE_PENALTY,
+			BarreStrength::Normal => self.hand_size.barre_penalty(),
+			BarreStrength::Strong => 0,
+		}
+	}
+}
+
+impl From<HandSize> for PlayerProfile {
+	fn from(hand_size: HandSize) -> Self {
+		PlayerProfile {
+			hand_size,
+			..Default::default()
+		}
+	}
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneratorOptions {
 	pub limit: usize,
 	pub preferred_position: Option<u8>,
 	pub voicing_type: Option<VoicingType>,
 	pub root_in_bass: bool,
+	/// Lowest fret a finger may press; open strings are still allowed
+	/// (rule them out with `movable_only`)
+	pub min_fret: u8,
 	pub max_fret: u8,
 	pub playing_context: PlayingContext,
+	pub hand_size: HandSize,
+	/// Frets of stretch on top of the hand size (see [`PlayerProfile`])
+	pub stretch_tolerance: i8,
+	pub barre_strength: BarreStrength,
+	/// Also use natural harmonics (over frets 12, 7, 5 and 4) as note sources
+	pub include_harmonics: bool,
+	/// Gentle mode for players with arthritis or hand injuries: no barres, at most
+	/// three fingers, one fret less stretch than the hand size allows, and a
+	/// preference for open strings
+	pub accessible: bool,
+	/// Beginner preset: no barres, at most three fingers, nothing above fret
+	/// 3, and a strong preference for the shapes players learn first (see
+	/// [`crate::shapes`])
+	pub beginner: bool,
+	/// How strictly a slash chord's bass note (C/G) must sound lowest
+	pub slash_bass: SlashBass,
+	/// Let the thumb wrap over the neck to fret the lowest string (as in 1x3211 for F)
+	pub thumb_fretting: bool,
+	/// Strings every result must play a given way, e.g. a melody note on top
+	pub pins: Vec<StringPin>,
+	/// Strings results may play, e.g. the top four for ukulele-style voicings
+	/// or comping in a band; the others are muted (pins still apply)
+	pub string_set: Option<StringSet>,
+	/// Note the highest played string must sound, for chord-melody voicings;
+	/// it may be a note outside the chord, which then sounds only on top
+	pub top_note: Option<PitchClass>,
+	/// Chord degrees to leave out: 1 (root), 3 or 5, as in a no3 or no5
+	/// chord; useful with a bass player or for big extended chords
+	pub omit: Vec<u8>,
+	/// Movable shapes only: no open strings or harmonics, so every result
+	/// can slide up or down the neck to another root
+	pub movable_only: bool,
+	/// Spread results over different shapes and positions: a fingering that
+	/// only differs from a better one on a string or two is passed over
+	/// while different enough ones are left
+	pub diverse: bool,
+	/// Best voicing per region of the neck instead of a flat list: one for
+	/// open position, then one per [`NeckRegion`] up the neck, lowest first
+	/// and at most `limit` of them
+	pub by_position: bool,
+	/// Grips and shapes the player won't play, left out of every result
+	pub avoid: Vec<Avoid>,
+}
+
+/// A string the generator must play a particular way
+///
+/// The pinned state is kept even if its note isn't in the chord, so a voicing
+/// can be built around a melody or bass note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StringPin {
+	/// String index, lowest string first
+	pub string: usize,
+	pub state: StringState,
+}
+
+impl StringPin {
+	pub fn new(string: usize, state: StringState) -> Self {
+		StringPin { string, state }
+	}
+}
+
+/// A grip or shape the player refuses to play (see [`GeneratorOptions::avoid`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Avoid {
+	/// This exact fingering, e.g. the full F barre 133211
+	Tab(Fingering),
+	/// A standard shape (see [`shapes`]), e.g. "E" for every E-shape barre,
+	/// or only where it is barred at `fret` (0 for the open chord)
+	Shape { name: String, fret: Option<u8> },
+}
+
+impl Avoid {
+	/// Parse a tab ("133211") or a shape: "E shape", "E shape at 1", or
+	/// "E shape barred at fret 1" as shape matches are displayed
+	pub fn parse(s: &str) -> Result<Self> {
+		let s = s.trim();
+		let Some((name, rest)) = s.split_once(" shape") else {
+			return Fingering::parse(s).map(Avoid::Tab);
+		};
+		let fret = rest
+			.trim()
+			.trim_start_matches("barred")
+			.trim_start()
+			.trim_start_matches("at")
+			.trim_start()
+			.trim_start_matches("fret")
+			.trim();
+		let fret = match fret {
+			"" => None,
+			fret => Some(fret.parse().map_err(|_| {
+				ChordCraftError::InvalidFingering(format!("unknown fret in '{s}'"))
+			})?),
+		};
+		Ok(Avoid::Shape {
+			name: name.trim().to_string(),
+			fret,
+		})
+	}
+
+	/// Whether `fingering` is this grip or shape
+	pub fn matches<I: Instrument>(&self, fingering: &Fingering, instrument: &I) -> bool {
+		match self {
+			Avoid::Tab(tab) => tab == fingering,
+			Avoid::Shape { name, fret } => shapes::find_matching_shape(fingering, instrument)
+				.is_some_and(|(shape, at)| shape == name && fret.is_none_or(|fret| fret == at)),
+		}
+	}
+}
+
+/// Strings a voicing may use (see [`GeneratorOptions::string_set`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringSet {
+	/// The highest strings, this many of them
+	Top(usize),
+	/// This many strings in the middle, one string higher if they can't be centred
+	Middle(usize),
+	/// The lowest strings, this many of them
+	Bottom(usize),
+	/// These strings, lowest string first (0)
+	Only(Vec<usize>),
+}
+
+impl StringSet {
+	/// Parse "top4", "middle 4", "bottom3", or strings counted from the
+	/// lowest starting at 1 ("2,3,4")
+	pub fn parse(s: &str) -> Result<Self> {
+		let invalid = || ChordCraftError::InvalidFingering(format!("unknown string set '{s}'"));
+		let normalized = s.trim().to_lowercase().replace([' ', '-', '_'], "");
+		let count = |rest: &str| rest.parse::<usize>().ok().filter(|&n| n > 0);
+		let set = if let Some(rest) = normalized.strip_prefix("top") {
+			count(rest).map(StringSet::Top)
+		} else if let Some(rest) = normalized.strip_prefix("middle") {
+			count(rest).map(StringSet::Middle)
+		} else if let Some(rest) = normalized.strip_prefix("bottom") {
+			count(rest).map(StringSet::Bottom)
+		} else {
+			normalized
+				.split(',')
+				.map(|string| count(string).map(|n| n - 1))
+				.collect::<Option<Vec<usize>>>()
+				.map(StringSet::Only)
+		};
+		set.ok_or_else(invalid)
+	}
+
+	/// Whether a voicing may play `string` on an instrument with `string_count` strings
+	pub fn allows(&self, string: usize, string_count: usize) -> bool {
+		match self {
+			StringSet::Top(n) => string + n >= string_count,
+			StringSet::Middle(n) => {
+				let first = string_count.saturating_sub(*n).div_ceil(2);
+				(first..first + n).contains(&string)
+			}
+			StringSet::Bottom(n) => string < *n,
+			StringSet::Only(strings) => strings.contains(&string),
+		}
+	}
+}
+
+/// How strictly the bass of a slash chord or inversion is honoured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlashBass {
+	/// Only voicings whose lowest sounding note is the bass
+	#[default]
+	Require,
+	/// Voicings with another bass are kept but ranked lower
+	Prefer,
 }
 
 impl Default for GeneratorOptions {
@@ -32,19 +328,222 @@ impl Default for GeneratorOptions {
 			preferred_position: None,
 			voicing_type: None,
 			root_in_bass: true,
+			min_fret: 0,
 			max_fret: 12,
 			playing_context: PlayingContext::default(),
+			hand_size: HandSize::default(),
+			stretch_tolerance: 0,
+			barre_strength: BarreStrength::default(),
+			include_harmonics: false,
+			accessible: false,
+			beginner: false,
+			slash_bass: SlashBass::default(),
+			thumb_fretting: false,
+			pins: Vec::new(),
+			string_set: None,
+			top_note: None,
+			omit: Vec::new(),
+			movable_only: false,
+			diverse: false,
+			by_position: false,
+			avoid: Vec::new(),
+		}
+	}
+}
+
+impl GeneratorOptions {
+	/// The player these options generate for
+	pub fn player(&self) -> PlayerProfile {
+		PlayerProfile {
+			hand_size: self.hand_size,
+			stretch_tolerance: self.stretch_tolerance,
+			barre_strength: self.barre_strength,
+		}
+	}
+
+	/// Generate for the given player
+	pub fn with_player(self, player: PlayerProfile) -> Self {
+		GeneratorOptions {
+			hand_size: player.hand_size,
+			stretch_tolerance: player.stretch_tolerance,
+			barre_strength: player.barre_strength,
+			..self
+		}
+	}
+
+	/// Maximum fret span after applying the player profile and accessible mode
+	pub fn effective_max_stretch<I: Instrument>(&self, instrument: &I) -> u8 {
+		let stretch = self.player().max_stretch(instrument.max_stretch());
+		if self.accessible {
+			stretch.saturating_sub(1).max(ACCESSIBLE_MIN_STRETCH)
+		} else {
+			stretch
+		}
+	}
+
+	/// Highest fret to search after applying beginner mode
+	pub fn effective_max_fret(&self) -> u8 {
+		if self.beginner {
+			self.max_fret.min(BEGINNER_MAX_FRET)
+		} else {
+			self.max_fret
+		}
+	}
+
+	/// Whether results must do without barres
+	fn forbids_barres(&self) -> bool {
+		self.accessible || self.beginner
+	}
+
+	/// Candidates to keep while searching: more than `limit` when results are
+	/// picked for diversity, so there is something to pick from
+	fn pool_size(&self) -> usize {
+		if self.diverse {
+			self.limit.saturating_mul(DIVERSITY_POOL)
+		} else {
+			self.limit
+		}
+	}
+
+	fn candidate_pool(&self) -> CandidatePool {
+		if self.by_position {
+			CandidatePool::ByRegion(BTreeMap::new())
+		} else {
+			CandidatePool::Ranked(TopFingerings::new(self.pool_size()))
+		}
+	}
+
+	/// Whether the thumb may fret the lowest string, by request or for large hands
+	///
+	/// Never in accessible or beginner mode.
+	pub fn allows_thumb(&self) -> bool {
+		(self.thumb_fretting || self.hand_size.allows_thumb_fretting())
+			&& !self.accessible
+			&& !self.beginner
+	}
+
+	/// Maximum number of fretting fingers after applying accessible and beginner mode
+	pub fn effective_max_fingers<I: Instrument>(&self, instrument: &I) -> u8 {
+		if self.accessible || self.beginner {
+			instrument.max_fingers().min(ACCESSIBLE_MAX_FINGERS)
+		} else {
+			instrument.max_fingers()
 		}
 	}
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScoredFingering {
 	pub fingering: Fingering,
 	pub score: u16,
 	pub voicing_type: VoicingType,
 	pub has_root_in_bass: bool,
 	pub position: u8,
+	/// Finger on each string, if the hand can manage the shape
+	pub fingers: Option<FingerAssignment>,
+	pub difficulty: Difficulty,
+	/// Degrees of the chord left out on request (see [`GeneratorOptions::omit`])
+	pub omitted: Vec<u8>,
+}
+
+impl ScoredFingering {
+	/// Region of the neck the fingering is played in
+	pub fn region<I: Instrument>(&self, instrument: &I) -> NeckRegion {
+		NeckRegion::of_fingering(&self.fingering, instrument)
+	}
+
+	/// How the fingering sounds and feels: open, barre or compact
+	pub fn character(&self) -> VoicingCharacter {
+		VoicingCharacter::of(&self.fingering)
+	}
+}
+
+/// Sound and feel of a grip, for keeping a progression coherent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VoicingCharacter {
+	/// Rings open strings
+	Open,
+	/// Barre chord
+	Barre,
+	/// Movable grip without a barre, like the compact voicings used in bands
+	Compact,
+}
+
+impl VoicingCharacter {
+	pub fn of(fingering: &Fingering) -> Self {
+		if fingering.strings().contains(&StringState::Fretted(0)) {
+			VoicingCharacter::Open
+		} else if fingering.requires_barre() {
+			VoicingCharacter::Barre
+		} else {
+			VoicingCharacter::Compact
+		}
+	}
+}
+
+impl fmt::Display for VoicingCharacter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			VoicingCharacter::Open => write!(f, "open"),
+			VoicingCharacter::Barre => write!(f, "barre"),
+			VoicingCharacter::Compact => write!(f, "compact"),
+		}
+	}
+}
+
+/// Part of the neck results are grouped by (see [`GeneratorOptions::by_position`]):
+/// open position, then three frets at a time from the 2nd fret (2–4, 5–7,
+/// 8–10 and so on)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NeckRegion {
+	pub lowest_fret: u8,
+	pub highest_fret: u8,
+}
+
+impl NeckRegion {
+	/// Region of a fingering whose lowest fretted note is at `position`
+	pub fn of(position: u8) -> Self {
+		if position <= 1 {
+			return NeckRegion {
+				lowest_fret: 0,
+				highest_fret: 1,
+			};
+		}
+		let lowest_fret = position - (position - 2) % REGION_FRETS;
+		NeckRegion {
+			lowest_fret,
+			highest_fret: lowest_fret.saturating_add(REGION_FRETS - 1),
+		}
+	}
+
+	/// Region of a fingering: open position if it rings open strings below
+	/// the instrument's open-position threshold (G at 320003), otherwise by
+	/// its lowest fretted note
+	pub fn of_fingering<I: Instrument>(fingering: &Fingering, instrument: &I) -> Self {
+		if fingering.is_open_position_for(instrument) {
+			NeckRegion::of(0)
+		} else {
+			NeckRegion::of(fingering.min_fret().unwrap_or(0))
+		}
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.lowest_fret == 0
+	}
+}
+
+impl fmt::Display for NeckRegion {
+	/// "Open position" or "Frets 5-7"
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_open() {
+			write!(f, "Open position")
+		} else {
+			write!(f, "Frets {}-{}", self.lowest_fret, self.highest_fret)
+		}
+	}
 }
 
 pub fn generate_fingerings<I: Instrument>(
@@ -52,200 +551,612 @@ pub fn generate_fingerings<I: Instrument>(
 	instrument: &I,
 	options: &GeneratorOptions,
 ) -> Vec<ScoredFingering> {
-	let tuning = instrument.tuning();
-	let string_count = tuning.len();
-	let all_notes = chord.notes();
-	let core_notes = chord.core_notes();
-	let root = chord.root;
-	let max_fret = options.max_fret;
-	let string_options: Vec<Vec<StringState>> = tuning
-		.iter()
-		.map(|open_note| {
-			let mut fret_options = vec![StringState::Muted];
+	// A no-op observer never cancels
+	generate_fingerings_with(chord, instrument, options, &NoopObserver).unwrap_or_default()
+}
 
-			for fret in 0..=max_fret {
-				let note_at_fret = open_note.pitch.add_semitones(fret as i32);
-				if all_notes.contains(&note_at_fret) {
-					fret_options.push(StringState::Fretted(fret));
-				}
-			}
+/// Fingerings found with a capo on, with the chord their shapes make
+#[derive(Debug, Clone)]
+pub struct CapoFingerings {
+	pub capo: u8,
+	/// Chord the shapes make as if the capo were the nut (G for A with a capo at 2)
+	pub shape_chord: Chord,
+	/// Chord that sounds
+	pub sounding_chord: Chord,
+	/// Fingerings with frets counted from the capo, so each tab is the shape
+	pub fingerings: Vec<ScoredFingering>,
+}
 
-			fret_options
-		})
-		.collect();
+impl CapoFingerings {
+	pub(crate) fn new(chord: &Chord, capo: u8, fingerings: Vec<ScoredFingering>) -> Self {
+		CapoFingerings {
+			capo,
+			shape_chord: chord.transpose(-(capo as i32)),
+			sounding_chord: chord.clone(),
+			fingerings,
+		}
+	}
+}
+
+/// Generate fingerings for a chord with a capo at the given fret
+///
+/// The search runs on the neck above the capo, so results respect the frets
+/// left and the pitches that actually sound. Fails if the instrument can't
+/// take a capo that high.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::chord::Chord;
+/// use chordcraft_core::generator::{GeneratorOptions, generate_fingerings_with_capo};
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let a = Chord::parse("A").unwrap();
+/// let options = GeneratorOptions::default();
+/// let found = generate_fingerings_with_capo(&a, &Guitar::default(), 2, &options).unwrap();
+/// assert_eq!(found.shape_chord.to_string(), "G");
+/// assert!(found.fingerings.iter().any(|f| f.fingering.to_string() == "320003"));
+/// ```
+pub fn generate_fingerings_with_capo<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	capo: u8,
+	options: &GeneratorOptions,
+) -> Result<CapoFingerings> {
+	let capoed = CapoedInstrument::new(instrument, capo)?;
+	let fingerings = generate_fingerings(chord, &capoed, options);
+	Ok(CapoFingerings::new(chord, capo, fingerings))
+}
+
+/// Generate fingerings, reporting progress to and polling `observer` for cancellation
+///
+/// Progress is reported once per first-string branch while enumerating, then
+/// in batches while scoring. Returns [`ChordCraftError::Cancelled`] as soon as
+/// the observer asks to stop.
+pub fn generate_fingerings_with<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	options: &GeneratorOptions,
+	observer: &dyn SearchObserver,
+) -> Result<Vec<ScoredFingering>> {
+	match search_fingerings(chord, instrument, options, observer, false) {
+		(fingerings, true) => Ok(fingerings),
+		(_, false) => Err(ChordCraftError::Cancelled),
+	}
+}
 
-	let mut fingerings = Vec::new();
-	generate_combinations_for_instrument(
-		&string_options,
-		&mut vec![],
-		&mut fingerings,
-		string_count,
+/// Generate fingerings within a time or node budget
+///
+/// Every combination counts against the budget as it is enumerated and
+/// scored. When the budget runs out, the search stops and the best
+/// combinations found so far are ranked as usual, with `complete` set to false.
+pub fn generate_fingerings_within<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	options: &GeneratorOptions,
+	budget: &SearchBudget,
+) -> Partial<Vec<ScoredFingering>> {
+	let (results, complete) = search_fingerings(
+		chord,
 		instrument,
+		options,
+		&BudgetObserver::new(*budget),
+		true,
 	);
+	Partial { results, complete }
+}
 
-	let mut scored: Vec<ScoredFingering> = fingerings
-		.into_iter()
-		.filter_map(|states| {
-			let fingering = Fingering::new(states);
+/// Run the search until done or `observer` asks to stop; the flag is false if stopped
+///
+/// Each combination is scored as soon as it is enumerated and only the best
+/// are kept, so memory scales with the number of results rather than the
+/// number of combinations. The observer is polled once per combination, so a
+/// budget bounds scoring as well as enumeration. With `keep_partial`, the
+/// best combinations found before stopping are returned; otherwise a stopped
+/// search returns nothing.
+pub(crate) fn search_fingerings<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	options: &GeneratorOptions,
+	observer: &dyn SearchObserver,
+	keep_partial: bool,
+) -> (Vec<ScoredFingering>, bool) {
+	let search = FingeringSearch::new(chord, instrument, options);
+
+	let mut scored = options.candidate_pool();
+	let mut complete = true;
+	let mut total = 0;
+	let first_options = search.first_options();
+	for i in 0..first_options.len() {
+		observer.on_progress(Progress {
+			phase: SearchPhase::Enumerating,
+			done: i,
+			total: first_options.len(),
+		});
 
-			if !fingering.is_playable_for(instrument) {
-				return None;
-			}
+		let (done, count) = search.search_branch(i, &mut scored, observer);
+		total += count;
+		if !done {
+			complete = false;
+			break;
+		}
+	}
+	if !complete && !keep_partial {
+		return (vec![], false);
+	}
+	if complete {
+		observer.on_progress(Progress {
+			phase: SearchPhase::Enumerating,
+			done: first_options.len(),
+			total: first_options.len(),
+		});
+		// Scoring kept pace with enumeration
+		observer.on_progress(Progress {
+			phase: SearchPhase::Scoring,
+			done: total,
+			total,
+		});
+	}
 
-			let played_count = fingering.strings().iter().filter(|s| s.is_played()).count();
-			if played_count < instrument.min_played_strings() {
-				return None;
-			}
+	let mut results = select_results(scored.into_sorted_vec(), options);
+	for result in &mut results {
+		search.finish(result);
+	}
+	(results, complete)
+}
 
-			let pitches = fingering.unique_pitch_classes(instrument);
-			let has_all_core = core_notes.iter().all(|n| pitches.contains(n));
-			let has_all_notes = all_notes.iter().all(|n| pitches.contains(n));
-
-			let has_root = pitches.contains(&root);
-			let voicing_type = if has_all_notes {
-				VoicingType::Full
-			} else if has_all_core {
-				VoicingType::Core
-			} else if has_root && pitches.len() >= 2 {
-				// Has root and at least one other chord tone: intentional voicing
-				VoicingType::Jazzy
-			} else {
-				// Missing root or too few notes: incomplete voicing
-				VoicingType::Incomplete
-			};
+/// Generate fingerings on all cores
+///
+/// Returns the same fingerings as [`generate_fingerings`]: the first-string
+/// branches are searched in parallel, each keeping its own best candidates,
+/// which are then merged in branch order. Without progress reports or
+/// cancellation, this suits searches that always run to the end, like 7- and
+/// 8-string instruments or a high `max_fret`.
+#[cfg(feature = "rayon")]
+pub fn generate_fingerings_parallel<I: Instrument + Sync>(
+	chord: &Chord,
+	instrument: &I,
+	options: &GeneratorOptions,
+) -> Vec<ScoredFingering> {
+	use rayon::prelude::*;
+
+	let search = FingeringSearch::new(chord, instrument, options);
+	let branches: Vec<Vec<ScoredFingering>> = (0..search.first_options().len())
+		.into_par_iter()
+		.map(|first| {
+			let mut pool = options.candidate_pool();
+			search.search_branch(first, &mut pool, &NoopObserver);
+			pool.into_sorted_vec()
+		})
+		.collect();
+	// Branches merge in enumeration order, so ties rank as in the serial search
+	let mut scored = options.candidate_pool();
+	for candidate in branches.into_iter().flatten() {
+		scored.push(candidate, instrument);
+	}
+	let mut results = select_results(scored.into_sorted_vec(), options);
+	results
+		.par_iter_mut()
+		.for_each(|result| search.finish(result));
+	results
+}
 
-			if let Some(required_voicing) = &options.voicing_type
-				&& voicing_type != *required_voicing
-			{
-				return None;
-			}
+/// One chord on one instrument: the limits that apply and what each string can play
+struct FingeringSearch<'a, I> {
+	instrument: &'a I,
+	options: &'a GeneratorOptions,
+	chord_notes: Vec<PitchClass>,
+	core_notes: Vec<PitchClass>,
+	root: PitchClass,
+	// A slash chord or inversion sounds this note lowest, chord tone or not
+	bass: Option<PitchClass>,
+	max_stretch: u8,
+	max_fingers: u8,
+	allow_thumb: bool,
+	/// States each string may take, lowest string first
+	string_options: Vec<Vec<StringState>>,
+	/// Pitch classes each of those states sounds, course partners included
+	/// (see [`pitch_mask`])
+	option_pitches: Vec<Vec<u16>>,
+	/// Pitch classes the strings from each one up can still add
+	reachable: Vec<u16>,
+	/// Pitch classes every result must sound to pass the options
+	required: u16,
+	min_played: usize,
+	omitted: Vec<u8>,
+}
+
+/// A combination being built: what the strings chosen so far commit to
+#[derive(Debug, Clone, Copy, Default)]
+struct Branch {
+	played: usize,
+	/// Lowest and highest fret the hand is needed at
+	window: Option<(u8, u8)>,
+	/// Frets pressed and harmonic nodes touched, a bit per fret; each takes a finger
+	pressed: u64,
+	touched: u64,
+	/// Pitch classes sounding (see [`pitch_mask`])
+	pitches: u16,
+}
 
-			let bass_pitch = fingering.bass_note(instrument).map(|n| n.pitch);
-			let has_root_in_bass = bass_pitch == Some(root);
+/// Pitch classes as a bit per semitone above C
+fn pitch_mask(pitches: impl IntoIterator<Item = PitchClass>) -> u16 {
+	pitches
+		.into_iter()
+		.fold(0, |mask, p| mask | 1 << p.to_semitone())
+}
 
-			let position = fingering.min_fret().unwrap_or(0);
+/// Bit for a fret in [`Branch::pressed`]; frets 64 apart share a bit, but
+/// never within the reach of one hand
+fn fret_bit(fret: u8) -> u64 {
+	1 << (fret % 64)
+}
 
-			let score = score_fingering(
-				&fingering,
-				instrument,
-				options,
-				FingeringScorerOptions {
-					has_all_notes,
-					has_all_core,
-					has_root_in_bass,
-					position,
-					played_count,
-					voicing_type,
-				},
-			);
+impl<'a, I: Instrument> FingeringSearch<'a, I> {
+	fn new(chord: &Chord, instrument: &'a I, options: &'a GeneratorOptions) -> Self {
+		// Only degrees the chord has count as omitted
+		let mut chord = chord.clone();
+		let mut omitted = Vec::new();
+		for &degree in &options.omit {
+			let reduced = chord.clone().with_alteration(Alteration::Omit(degree));
+			if reduced.notes().len() < chord.notes().len() {
+				chord = reduced;
+				omitted.push(degree);
+			}
+		}
 
-			Some(ScoredFingering {
-				fingering,
-				score: score.max(0) as u16,
-				voicing_type,
-				has_root_in_bass,
-				position,
+		let all_notes = chord.notes();
+		let bass = chord.bass;
+		let (min_fret, max_fret) = (options.min_fret.max(1), options.effective_max_fret());
+		let open = (!options.movable_only).then_some(0);
+		let string_options = instrument
+			.tuning()
+			.iter()
+			.enumerate()
+			.map(|(string, open_note)| {
+				if let Some(pin) = options.pins.iter().find(|p| p.string == string) {
+					return vec![pin.state];
+				}
+				let string_count = instrument.string_count();
+				if let Some(set) = &options.string_set
+					&& !set.allows(string, string_count)
+				{
+					return vec![StringState::Muted];
+				}
+				let mut fret_options = vec![StringState::Muted];
+				// A short string is stopped only above its own nut
+				let (lowest, highest) = instrument.string_fret_range(string);
+				let nut = instrument.string_nut_fret(string);
+				let stopped = min_fret.max(lowest)..=max_fret.min(highest);
+
+				for fret in open.into_iter().chain(stopped) {
+					let Some(note_at_fret) = StringState::Fretted(fret)
+						.note_above_nut(*open_note, nut)
+						.map(|n| n.pitch)
+					else {
+						continue;
+					};
+					if all_notes.contains(&note_at_fret)
+						|| bass == Some(note_at_fret)
+						|| options.top_note == Some(note_at_fret)
+					{
+						fret_options.push(StringState::Fretted(fret));
+					}
+				}
+
+				// Natural harmonics are counted from the neck's nut
+				if options.include_harmonics && !options.movable_only && nut == 0 {
+					for fret in NATURAL_HARMONIC_FRETS {
+						let sounding =
+							harmonic_semitones(fret).map(|s| open_note.pitch.add_semitones(s));
+						if (min_fret..=max_fret).contains(&fret)
+							&& sounding.is_some_and(|p| all_notes.contains(&p))
+						{
+							fret_options.push(StringState::Harmonic(fret));
+						}
+					}
+				}
+
+				fret_options
 			})
-		})
-		.collect();
+			.collect::<Vec<Vec<StringState>>>();
 
-	scored.sort_by(|a, b| b.score.cmp(&a.score));
-	scored = deduplicate_fingerings(scored);
-	scored.truncate(options.limit);
+		let tuning = instrument.tuning();
+		let partners = instrument.course_pairs();
+		let option_pitches: Vec<Vec<u16>> = string_options
+			.iter()
+			.enumerate()
+			.map(|(string, states)| {
+				let courses = [
+					Some(tuning[string]),
+					partners.get(string).copied().flatten(),
+				];
+				let nut = instrument.string_nut_fret(string);
+				states
+					.iter()
+					.map(|state| {
+						pitch_mask(
+							courses
+								.iter()
+								.flatten()
+								.filter_map(|&open| state.note_above_nut(open, nut))
+								.map(|n| n.pitch),
+						)
+					})
+					.collect()
+			})
+			.collect();
+		let mut reachable = vec![0; string_options.len() + 1];
+		for string in (0..string_options.len()).rev() {
+			reachable[string] =
+				reachable[string + 1] | option_pitches[string].iter().fold(0, |all, m| all | m);
+		}
 
-	scored
-}
+		// Voicing types the options ask for and slash basses they insist on
+		// can't be met without these notes
+		let required = match options.voicing_type {
+			Some(VoicingType::Full) => pitch_mask(all_notes.iter().copied()),
+			Some(VoicingType::Core) => pitch_mask(chord.core_notes()),
+			Some(VoicingType::Jazzy) => pitch_mask([chord.root]),
+			_ => 0,
+		} | match bass {
+			Some(bass) if options.slash_bass == SlashBass::Require => pitch_mask([bass]),
+			_ => 0,
+		};
 
-fn generate_combinations_for_instrument<I: Instrument>(
-	string_options: &[Vec<StringState>],
-	current: &mut Vec<StringState>,
-	results: &mut Vec<Vec<StringState>>,
-	total_strings: usize,
-	instrument: &I,
-) {
-	generate_combinations_pruned(
-		string_options,
-		current,
-		results,
-		total_strings,
-		instrument.max_stretch(),
-		instrument.min_played_strings(),
-	);
-}
+		FingeringSearch {
+			instrument,
+			options,
+			chord_notes: all_notes,
+			core_notes: chord.core_notes(),
+			root: chord.root,
+			bass,
+			max_stretch: options.effective_max_stretch(instrument),
+			max_fingers: options.effective_max_fingers(instrument),
+			allow_thumb: options.allows_thumb(),
+			string_options,
+			option_pitches,
+			reachable,
+			required,
+			min_played: instrument.min_played_strings(),
+			omitted,
+		}
+	}
 
-fn generate_combinations_pruned(
-	string_options: &[Vec<StringState>],
-	current: &mut Vec<StringState>,
-	results: &mut Vec<Vec<StringState>>,
-	total_strings: usize,
-	max_stretch: u8,
-	min_played: usize,
-) {
-	if current.len() == total_strings {
-		results.push(current.clone());
-		return;
+	fn first_options(&self) -> &[StringState] {
+		self.string_options.first().map_or(&[], Vec::as_slice)
 	}
 
-	let string_idx = current.len();
+	/// Score the combinations starting with the first string's option `first`
+	/// into `pool` as they are enumerated; false if `observer` stopped the
+	/// search, with the number of combinations enumerated
+	fn search_branch(
+		&self,
+		first: usize,
+		pool: &mut CandidatePool,
+		observer: &dyn SearchObserver,
+	) -> (bool, usize) {
+		let mut count = 0;
+		let done = self.enumerate_branch(
+			first,
+			&mut |states| {
+				count += 1;
+				if let Some(candidate) = self.score(states.to_vec()) {
+					pool.push(candidate, self.instrument);
+				}
+			},
+			observer,
+		);
+		(done, count)
+	}
 
-	for state in &string_options[string_idx] {
-		current.push(*state);
+	/// Hand each combination starting with the first string's option `first`
+	/// to `visit`; false if `observer` stopped the search
+	fn enumerate_branch(
+		&self,
+		first: usize,
+		visit: &mut dyn FnMut(&[StringState]),
+		observer: &dyn SearchObserver,
+	) -> bool {
+		let mut current = Vec::with_capacity(self.string_options.len());
+		self.extend(Branch::default(), 0, first)
+			.is_none_or(|branch| {
+				current.push(self.string_options[0][first]);
+				self.enumerate_from(branch, &mut current, visit, observer)
+			})
+	}
 
-		if should_continue_branch(current, total_strings, max_stretch, min_played) {
-			generate_combinations_pruned(
-				string_options,
-				current,
-				results,
-				total_strings,
-				max_stretch,
-				min_played,
-			);
+	/// Enumerate the completions of `current` depth-first, branch and bound:
+	/// a state is only tried if some completion could still be played and
+	/// pass the options. False if `observer` stopped the search
+	fn enumerate_from(
+		&self,
+		branch: Branch,
+		current: &mut Vec<StringState>,
+		visit: &mut dyn FnMut(&[StringState]),
+		observer: &dyn SearchObserver,
+	) -> bool {
+		let string = current.len();
+		if string == self.string_options.len() {
+			if observer.is_cancelled() {
+				return false;
+			}
+			visit(current);
+			return true;
 		}
 
-		current.pop();
+		for (option, &state) in self.string_options[string].iter().enumerate() {
+			let Some(next) = self.extend(branch, string, option) else {
+				continue;
+			};
+			current.push(state);
+			let done = self.enumerate_from(next, current, visit, observer);
+			current.pop();
+			if !done {
+				return false;
+			}
+		}
+		true
 	}
-}
 
-#[inline]
-fn should_continue_branch(
-	current: &[StringState],
-	total_strings: usize,
-	max_stretch: u8,
-	min_played: usize,
-) -> bool {
-	let played = current.iter().filter(|s| s.is_played()).count();
-	let remaining = total_strings - current.len();
+	/// The branch with `string` set to its state `option`, or None if no
+	/// completion can fit the hand (stretch, fingers), play enough strings or
+	/// still sound the notes the options require
+	fn extend(&self, branch: Branch, string: usize, option: usize) -> Option<Branch> {
+		let state = self.string_options[string][option];
+		let mut next = Branch {
+			played: branch.played + state.is_played() as usize,
+			pitches: branch.pitches | self.option_pitches[string][option],
+			..branch
+		};
+		match state {
+			StringState::Fretted(fret) if fret > 0 => next.pressed |= fret_bit(fret),
+			StringState::Harmonic(node) => next.touched |= fret_bit(node),
+			_ => {}
+		}
+		// A thumb on the lowest string sits outside the fingers' stretch
+		let thumbable = string == 0 && self.allow_thumb;
+		if let Some(fret) = state.hand_fret().filter(|_| !thumbable) {
+			let (low, high) = branch
+				.window
+				.map_or((fret, fret), |(low, high)| (low.min(fret), high.max(fret)));
+			if high - low > self.max_stretch {
+				return None;
+			}
+			next.window = Some((low, high));
+		}
 
-	if played + remaining < min_played {
-		return false;
+		// Every pressed fret and harmonic node takes a finger, less one the thumb can take
+		let fingers = next.pressed.count_ones() + next.touched.count_ones();
+		let finger_budget = self.max_fingers as u32 + self.allow_thumb as u32;
+		let remaining = self.string_options.len() - string - 1;
+		let missing = self.required & !next.pitches;
+		(fingers <= finger_budget
+			&& next.played + remaining >= self.min_played
+			&& missing & !self.reachable[string + 1] == 0)
+			.then_some(next)
 	}
 
-	if played < 2 {
-		return true;
-	}
+	/// Score a combination, or None if it doesn't fit the hand or the options
+	fn score(&self, states: Vec<StringState>) -> Option<ScoredFingering> {
+		let fingering = Fingering::new(states);
+
+		let playable = fingering.is_playable_with_constraints(self.max_stretch, self.max_fingers)
+			|| (self.allow_thumb
+				&& fingering.without_thumbed_bass().is_some_and(|rest| {
+					rest.is_playable_with_constraints(self.max_stretch, self.max_fingers)
+				}));
+		if !playable {
+			return None;
+		}
 
-	let mut min = u8::MAX;
-	let mut max = 0u8;
-	let mut has_fretted = false;
+		if self.options.forbids_barres() && fingering.requires_barre() {
+			return None;
+		}
 
-	for state in current {
-		if let StringState::Fretted(f) = state
-			&& *f > 0
+		if self
+			.options
+			.avoid
+			.iter()
+			.any(|avoid| avoid.matches(&fingering, self.instrument))
 		{
-			has_fretted = true;
-			min = min.min(*f);
-			max = max.max(*f);
+			return None;
 		}
-	}
 
-	if !has_fretted {
-		return true;
-	}
+		let played_count = fingering.strings().iter().filter(|s| s.is_played()).count();
+		if played_count < self.instrument.min_played_strings() {
+			return None;
+		}
 
-	max - min <= max_stretch
-}
+		let pitches = fingering.unique_pitch_classes(self.instrument);
+		let has_all_core = self.core_notes.iter().all(|n| pitches.contains(n));
+		let has_all_notes = self.chord_notes.iter().all(|n| pitches.contains(n));
+
+		let has_root = pitches.contains(&self.root);
+		let voicing_type = if has_all_notes {
+			VoicingType::Full
+		} else if has_all_core {
+			VoicingType::Core
+		} else if has_root && pitches.len() >= 2 {
+			// Has root and at least one other chord tone: intentional voicing
+			VoicingType::Jazzy
+		} else {
+			// Missing root or too few notes: incomplete voicing
+			VoicingType::Incomplete
+		};
 
-// Fingering scoring constants
+		if let Some(required_voicing) = &self.options.voicing_type
+			&& voicing_type != *required_voicing
+		{
+			return None;
+		}
+
+		let notes = fingering.notes(self.instrument);
+		if let Some(top) = self.options.top_note {
+			let (highest, below) = notes.split_last()?;
+			let melody_only = !self.chord_notes.contains(&top) && self.bass != Some(top);
+			if highest.pitch != top || melody_only && below.iter().any(|n| n.pitch == top) {
+				return None;
+			}
+		}
+
+		// Re-entrant tunings can sound lower on an inner string than on the bass
+		// string, and a course's partner string can sound lower than the course
+		let lowest = fingering
+			.sounding_notes(self.instrument)
+			.into_iter()
+			.min_by_key(|n| n.to_midi())
+			.map(|n| n.pitch);
+		let wrong_bass = self.bass.is_some() && lowest != self.bass;
+		if wrong_bass && self.options.slash_bass == SlashBass::Require {
+			return None;
+		}
+
+		let bass_pitch = fingering.bass_note(self.instrument).map(|n| n.pitch);
+		let has_root_in_bass = bass_pitch == Some(self.root);
+
+		let position = fingering.min_fret().unwrap_or(0);
+
+		let score = score_fingering(
+			&fingering,
+			self.instrument,
+			self.options,
+			FingeringScorerOptions {
+				has_all_notes,
+				has_all_core,
+				has_root_in_bass,
+				position,
+				played_count,
+				voicing_type,
+			},
+		);
+
+		let score = if wrong_bass {
+			score - WRONG_SLASH_BASS_PENALTY
+		} else {
+			score
+		};
+
+		Some(ScoredFingering {
+			fingering,
+			score: score.max(0) as u16,
+			voicing_type,
+			has_root_in_bass,
+			position,
+			fingers: None,
+			difficulty: Difficulty::default(),
+			omitted: self.omitted.clone(),
+		})
+	}
+
+	/// Assign fingers and grade difficulty; only the survivors are worth the finger search
+	fn finish(&self, result: &mut ScoredFingering) {
+		result.fingers = assign_fingers(&result.fingering, self.max_fingers, self.allow_thumb);
+		result.difficulty = result.fingering.difficulty_with(result.fingers.as_ref());
+	}
+}
+
+// Fingering scoring constants
 // Context-independent weights
 const STRING_USAGE_BONUS: i32 = 8;
 const INTERIOR_MUTE_PENALTY: i32 = 30;
@@ -261,27 +1172,35 @@ const BAND_ROOT_IN_BASS_BONUS: i32 = 5;
 const BAND_COMPACT_VOICING_BONUS: i32 = 20;
 const BAND_FULL_VOICING_BONUS: i32 = 5;
 const BAND_AVOID_LOW_STRINGS_BONUS: i32 = 10;
+/// Bass-register strings of a standard guitar (low E and A), the count
+/// BAND_AVOID_LOW_STRINGS_BONUS is weighed against
+const GUITAR_BASS_STRINGS: usize = 2;
 const BAND_MID_NECK_MIN: u8 = 3;
 const BAND_MID_NECK_MAX: u8 = 10;
 const BAND_POSITION_PENALTY: i32 = 3;
-
-/// Check if a fingering matches a standard chord shape for the given instrument.
-/// Returns the shape name if found, None otherwise.
-fn matches_standard_shape<I: Instrument>(
-	fingering: &Fingering,
-	instrument: &I,
-) -> Option<&'static str> {
-	match instrument.string_count() {
-		6 => shapes::guitar::find_matching_shape(fingering).map(|(name, _)| name),
-		// For 4-string instruments, try both ukulele and mandolin shapes
-		// The matching will naturally pick the right one based on the fingering pattern
-		4 => shapes::ukulele::find_matching_shape(fingering)
-			.or_else(|| shapes::mandolin::find_matching_shape(fingering))
-			.map(|(name, _)| name),
-		5 => shapes::banjo::find_matching_shape(fingering).map(|(name, _)| name),
-		_ => None, // No standard shapes defined for other string counts
-	}
-}
+const FINGERSTYLE_INTERIOR_MUTE_PENALTY: i32 = 5;
+const FINGERSTYLE_OPEN_STRING_BONUS: i32 = 10;
+const FINGERSTYLE_SEPARATED_VOICES_BONUS: i32 = 10;
+const STRUMMING_INTERIOR_MUTE_PENALTY: i32 = 60;
+const STRUMMING_FULL_VOICING_BONUS: i32 = 20;
+const STRUMMING_ALL_STRINGS_BONUS: i32 = 15;
+const RECORDING_FULL_VOICING_BONUS: i32 = 35;
+const RECORDING_CORE_VOICING_BONUS: i32 = 5;
+const RECORDING_DOUBLING_PENALTY: i32 = 10;
+const SMALL_HAND_BARRE_PENALTY: i32 = 25;
+const WEAK_BARRE_PENALTY: i32 = 20;
+const MIN_PLAYER_STRETCH: u8 = 2;
+const THUMB_FRETTING_PENALTY: i32 = 10;
+const ACCESSIBLE_MAX_FINGERS: u8 = 3;
+const ACCESSIBLE_MIN_STRETCH: u8 = 2;
+const ACCESSIBLE_OPEN_STRING_BONUS: i32 = 10;
+const BEGINNER_MAX_FRET: u8 = 3;
+const BEGINNER_SHAPE_BONUS: i32 = 40;
+const WRONG_SLASH_BASS_PENALTY: i32 = 60;
+/// Candidates kept per requested result when picking for diversity
+const DIVERSITY_POOL: usize = 5;
+/// Frets per neck region above open position
+const REGION_FRETS: u8 = 3;
 
 pub struct FingeringScorerOptions {
 	pub has_all_notes: bool,
@@ -298,10 +1217,41 @@ fn score_fingering<I: Instrument>(
 	options: &GeneratorOptions,
 	fingering_options: FingeringScorerOptions,
 ) -> i32 {
-	let mut score = fingering.playability_score_for(instrument) as i32;
+	let max_stretch = options.effective_max_stretch(instrument);
+	let max_fingers = options.effective_max_fingers(instrument);
+	let mut score =
+		fingering.playability_score_with_limits(instrument, max_stretch, max_fingers) as i32;
+	// Shapes only the thumb makes playable are scored on what the fingers do
+	if score == 0
+		&& options.allows_thumb()
+		&& let Some(rest) = fingering.without_thumbed_bass()
+	{
+		score = rest.playability_score_with_limits(instrument, max_stretch, max_fingers) as i32
+			- THUMB_FRETTING_PENALTY;
+	}
 	score += (fingering_options.played_count as i32) * STRING_USAGE_BONUS;
 
-	// Penalize interior mutes (leading mutes like xx0232 are fine)
+	if fingering.requires_barre() {
+		score -= options.player().barre_penalty();
+	}
+
+	if options.accessible {
+		let open_strings = fingering
+			.strings()
+			.iter()
+			.filter(|s| matches!(s, StringState::Fretted(0)))
+			.count();
+		score += (open_strings as i32) * ACCESSIBLE_OPEN_STRING_BONUS;
+	}
+
+	// Penalize interior mutes (leading mutes like xx0232 are fine).
+	// Fingerstyle barely minds them since the skipped string is simply not plucked,
+	// while a strummed chord sounds the string unless it is perfectly damped.
+	let interior_mute_penalty = match options.playing_context {
+		PlayingContext::Fingerstyle => FINGERSTYLE_INTERIOR_MUTE_PENALTY,
+		PlayingContext::Strumming => STRUMMING_INTERIOR_MUTE_PENALTY,
+		_ => INTERIOR_MUTE_PENALTY,
+	};
 	let strings = fingering.strings();
 	let first_played = strings.iter().position(|s| s.is_played());
 	let last_played = strings.iter().rposition(|s| s.is_played());
@@ -310,13 +1260,16 @@ fn score_fingering<I: Instrument>(
 			.iter()
 			.filter(|s| !s.is_played())
 			.count();
-		score -= (interior_mutes as i32) * INTERIOR_MUTE_PENALTY;
+		score -= (interior_mutes as i32) * interior_mute_penalty;
 	}
 
 	// Bonus for matching a standard chord shape (Am, E, Em, etc.)
 	// These shapes are well-known and easier to learn/remember
-	if matches_standard_shape(fingering, instrument).is_some() {
+	if shapes::find_matching_shape(fingering, instrument).is_some() {
 		score += STANDARD_SHAPE_BONUS;
+		if options.beginner {
+			score += BEGINNER_SHAPE_BONUS;
+		}
 	}
 
 	match options.playing_context {
@@ -339,13 +1292,7 @@ fn score_fingering<I: Instrument>(
 				score -= SOLO_JAZZY_WITHOUT_ROOT_PENALTY;
 			}
 
-			if let Some(pref_pos) = options.preferred_position {
-				let distance = (fingering_options.position as i32 - pref_pos as i32).abs();
-				score -= distance * POSITION_DISTANCE_PENALTY;
-			} else if fingering_options.position > SOLO_POSITION_THRESHOLD {
-				score -= ((fingering_options.position - SOLO_POSITION_THRESHOLD) as i32)
-					* SOLO_HIGH_POSITION_PENALTY;
-			}
+			score -= low_position_penalty(options, fingering_options.position);
 		}
 		PlayingContext::Band => {
 			if fingering_options.has_root_in_bass {
@@ -370,7 +1317,10 @@ fn score_fingering<I: Instrument>(
 					.iter()
 					.any(|&i| strings.get(i).map(|s| s.is_played()).unwrap_or(false));
 				if !uses_bass {
-					score += BAND_AVOID_LOW_STRINGS_BONUS;
+					// Extended-range instruments give up more strings to stay
+					// clear; make up their usage bonus beyond a guitar's two
+					let extra = bass_indices.len().saturating_sub(GUITAR_BASS_STRINGS);
+					score += BAND_AVOID_LOW_STRINGS_BONUS + extra as i32 * STRING_USAGE_BONUS;
 				}
 			}
 
@@ -386,29 +1336,249 @@ fn score_fingering<I: Instrument>(
 				}
 			}
 		}
+		PlayingContext::Fingerstyle => {
+			if fingering_options.has_root_in_bass {
+				score += SOLO_ROOT_IN_BASS_BONUS;
+			}
+
+			if fingering_options.has_all_core {
+				score += SOLO_CORE_VOICING_BONUS;
+			}
+
+			let open_strings = strings
+				.iter()
+				.filter(|s| matches!(s, StringState::Fretted(0)))
+				.count();
+			score += (open_strings as i32) * FINGERSTYLE_OPEN_STRING_BONUS;
+
+			// Separated voices: every plucked string carries a different pitch class
+			let pitch_count = fingering.unique_pitch_classes(instrument).len();
+			if pitch_count == fingering_options.played_count && pitch_count >= 3 {
+				score += FINGERSTYLE_SEPARATED_VOICES_BONUS;
+			}
+
+			score -= low_position_penalty(options, fingering_options.position);
+		}
+		PlayingContext::Strumming => {
+			if fingering_options.has_root_in_bass {
+				score += SOLO_ROOT_IN_BASS_BONUS;
+			}
+
+			if fingering_options.has_all_notes {
+				score += STRUMMING_FULL_VOICING_BONUS;
+			} else if fingering_options.has_all_core {
+				score += SOLO_CORE_VOICING_BONUS;
+			}
+
+			if fingering_options.played_count == instrument.string_count() {
+				score += STRUMMING_ALL_STRINGS_BONUS;
+			}
+
+			score -= low_position_penalty(options, fingering_options.position);
+		}
+		PlayingContext::Recording => {
+			if fingering_options.has_root_in_bass {
+				score += SOLO_ROOT_IN_BASS_BONUS;
+			}
+
+			if fingering_options.has_all_notes {
+				score += RECORDING_FULL_VOICING_BONUS;
+			} else if fingering_options.has_all_core {
+				score += RECORDING_CORE_VOICING_BONUS;
+			}
+
+			// Balance: penalize any pitch class doubled more than once
+			let mut counts = [0usize; 12];
+			for note in fingering.notes(instrument) {
+				counts[note.pitch.to_semitone() as usize] += 1;
+			}
+			let excess: usize = counts.iter().map(|&c| c.saturating_sub(2)).sum();
+			score -= (excess as i32) * RECORDING_DOUBLING_PENALTY;
+
+			score -= low_position_penalty(options, fingering_options.position);
+		}
 	}
 
 	score
 }
 
-fn deduplicate_fingerings(mut fingerings: Vec<ScoredFingering>) -> Vec<ScoredFingering> {
-	use std::collections::HashSet;
+/// Position penalty shared by contexts that favor the lower neck (like solo playing)
+fn low_position_penalty(options: &GeneratorOptions, position: u8) -> i32 {
+	if let Some(pref_pos) = options.preferred_position {
+		(position as i32 - pref_pos as i32).abs() * POSITION_DISTANCE_PENALTY
+	} else if position > SOLO_POSITION_THRESHOLD {
+		((position - SOLO_POSITION_THRESHOLD) as i32) * SOLO_HIGH_POSITION_PENALTY
+	} else {
+		0
+	}
+}
+
+/// Bounded selection of the best fingerings, so the kept pool scales with `limit`
+///
+/// Keeps the `limit` highest scores, earliest generated first on ties (the
+/// order a stable sort of every candidate would give), skipping fingerings
+/// already held.
+struct TopFingerings {
+	limit: usize,
+	/// Max-heap on rank, so the worst kept fingering is on top
+	heap: BinaryHeap<RankedFingering>,
+	/// Fingerings in the heap, so duplicates are found without a scan
+	held: HashSet<Fingering>,
+	generated: usize,
+}
+
+struct RankedFingering {
+	rank: (std::cmp::Reverse<u16>, usize),
+	scored: ScoredFingering,
+}
+
+impl PartialEq for RankedFingering {
+	fn eq(&self, other: &Self) -> bool {
+		self.rank == other.rank
+	}
+}
+
+impl Eq for RankedFingering {}
+
+impl PartialOrd for RankedFingering {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for RankedFingering {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		self.rank.cmp(&other.rank)
+	}
+}
+
+impl TopFingerings {
+	fn new(limit: usize) -> Self {
+		TopFingerings {
+			limit,
+			heap: BinaryHeap::new(),
+			held: HashSet::new(),
+			generated: 0,
+		}
+	}
+
+	fn push(&mut self, scored: ScoredFingering) {
+		let rank = (std::cmp::Reverse(scored.score), self.generated);
+		self.generated += 1;
+
+		if self.limit == 0
+			|| self.heap.len() == self.limit && self.heap.peek().is_some_and(|w| rank > w.rank)
+			|| self.held.contains(&scored.fingering)
+		{
+			return;
+		}
+
+		self.held.insert(scored.fingering.clone());
+		self.heap.push(RankedFingering { rank, scored });
+		if self.heap.len() > self.limit
+			&& let Some(worst) = self.heap.pop()
+		{
+			self.held.remove(&worst.scored.fingering);
+		}
+	}
+
+	/// Kept fingerings, best first
+	fn into_sorted_vec(self) -> Vec<ScoredFingering> {
+		self.heap
+			.into_sorted_vec()
+			.into_iter()
+			.map(|r| r.scored)
+			.collect()
+	}
+}
+
+/// Where scored candidates collect: the best ones overall, or the best one
+/// per region of the neck
+enum CandidatePool {
+	Ranked(TopFingerings),
+	ByRegion(BTreeMap<NeckRegion, TopFingerings>),
+}
 
-	let mut seen = HashSet::new();
-	let mut unique = Vec::new();
+impl CandidatePool {
+	fn push<I: Instrument>(&mut self, scored: ScoredFingering, instrument: &I) {
+		match self {
+			CandidatePool::Ranked(top) => top.push(scored),
+			CandidatePool::ByRegion(regions) => regions
+				.entry(scored.region(instrument))
+				.or_insert_with(|| TopFingerings::new(1))
+				.push(scored),
+		}
+	}
 
-	for f in fingerings.drain(..) {
-		let key: Vec<_> = f.fingering.strings().to_vec();
+	/// Kept fingerings, best first, or lowest region first when grouped by region
+	fn into_sorted_vec(self) -> Vec<ScoredFingering> {
+		match self {
+			CandidatePool::Ranked(top) => top.into_sorted_vec(),
+			CandidatePool::ByRegion(regions) => regions
+				.into_values()
+				.flat_map(TopFingerings::into_sorted_vec)
+				.collect(),
+		}
+	}
+}
 
-		if seen.insert(key) {
-			unique.push(f);
+/// The best `limit` of the ranked candidates; with `by_position` set, the
+/// lowest `limit` regions; with `diverse` set, the best ones that aren't
+/// near copies of a better pick (topped up with near copies, best first, if
+/// there aren't enough)
+fn select_results(
+	ranked: Vec<ScoredFingering>,
+	options: &GeneratorOptions,
+) -> Vec<ScoredFingering> {
+	if options.by_position {
+		return ranked.into_iter().take(options.limit).collect();
+	}
+	if !options.diverse {
+		return ranked;
+	}
+	let (mut picked, mut passed_over) = (Vec::new(), Vec::new());
+	for candidate in ranked {
+		if picked.len() == options.limit {
+			break;
+		}
+		if picked
+			.iter()
+			.any(|p: &ScoredFingering| is_near_copy(&p.fingering, &candidate.fingering))
+		{
+			passed_over.push(candidate);
+		} else {
+			picked.push(candidate);
 		}
 	}
+	let missing = options.limit - picked.len();
+	picked.extend(passed_over.into_iter().take(missing));
+	picked.sort_by_key(|f| std::cmp::Reverse(f.score));
+	picked
+}
 
-	unique
+/// Whether two fingerings share more than 80% of their strings (and differ
+/// on at most one string in five, or one string on smaller instruments)
+fn is_near_copy(a: &Fingering, b: &Fingering) -> bool {
+	let strings = a.strings().len();
+	let differing = a
+		.strings()
+		.iter()
+		.zip(b.strings())
+		.filter(|(x, y)| x != y)
+		.count();
+	differing <= (strings / 5).max(1)
 }
 
 pub fn format_fingering_diagram<I: Instrument>(scored: &ScoredFingering, instrument: &I) -> String {
+	format_fingering_diagram_spelled(scored, instrument, Accidentals::Sharps)
+}
+
+/// Text diagram with note names spelled with the given accidentals (e.g., the chord's own)
+pub fn format_fingering_diagram_spelled<I: Instrument>(
+	scored: &ScoredFingering,
+	instrument: &I,
+	accidentals: Accidentals,
+) -> String {
 	let fingering = &scored.fingering;
 	let strings = fingering.strings();
 	let string_names = instrument.string_names();
@@ -425,15 +1595,20 @@ pub fn format_fingering_diagram<I: Instrument>(scored: &ScoredFingering, instrum
 		let fret_str = match state {
 			StringState::Muted => "x".to_string(),
 			StringState::Fretted(f) => format!("{f}"),
+			StringState::Harmonic(f) => format!("<{f}>"),
 		};
 
-		lines.push(format!("{name}|---{fret_str}---"));
+		match scored.fingers.as_ref().and_then(|a| a.finger(i)) {
+			Some(finger) => lines.push(format!("{name}|---{fret_str}---  [{finger}]")),
+			None => lines.push(format!("{name}|---{fret_str}---")),
+		}
 	}
 
 	lines.push(String::new());
+	let omitted: String = scored.omitted.iter().map(|d| format!(" no{d}")).collect();
 	lines.push(format!(
-		"Score: {} | Position: Fret {} | Voicing: {:?}",
-		scored.score, scored.position, scored.voicing_type
+		"Score: {} | Position: Fret {} | Voicing: {:?}{omitted} | Difficulty: {:?}",
+		scored.score, scored.position, scored.voicing_type, scored.difficulty
 	));
 
 	if scored.has_root_in_bass {
@@ -441,9 +1616,32 @@ pub fn format_fingering_diagram<I: Instrument>(scored: &ScoredFingering, instrum
 	}
 
 	let pitches = fingering.unique_pitch_classes(instrument);
-	let pitch_names: Vec<String> = pitches.iter().map(|p| p.to_string()).collect();
+	let pitch_names: Vec<&str> = pitches.iter().map(|p| accidentals.spell(*p)).collect();
 	lines.push(format!("Notes: {}", pitch_names.join(", ")));
 
+	if strings
+		.iter()
+		.any(|s| matches!(s, StringState::Harmonic(_)))
+	{
+		lines.push("Harmonics: <n> = natural harmonic over fret n".to_string());
+	}
+
+	if let Some(assignment) = &scored.fingers {
+		let name = |string: usize| string_names.get(string).map_or("?", |n| n.as_str());
+		for barre in assignment.barres(fingering) {
+			lines.push(format!(
+				"Barre: finger {} across fret {} ({} to {})",
+				barre.finger,
+				barre.fret,
+				name(barre.from_string),
+				name(barre.to_string)
+			));
+		}
+		if assignment.fingers().iter().any(Option::is_some) {
+			lines.push("Fingers: [1]-[4] = index to pinky, [T] = thumb".to_string());
+		}
+	}
+
 	lines.join("\n")
 }
 
@@ -451,8 +1649,8 @@ pub fn format_fingering_diagram<I: Instrument>(scored: &ScoredFingering, instrum
 mod tests {
 	use super::*;
 	use crate::chord::Chord;
-	use crate::instrument::Guitar;
-	use crate::note::PitchClass;
+	use crate::instrument::{ConfigurableInstrument, Guitar, Ukulele};
+	use crate::note::{Note, PitchClass};
 
 	#[test]
 	fn test_generate_c_major() {
@@ -543,6 +1741,72 @@ mod tests {
 		assert!(has_valid);
 	}
 
+	#[test]
+	fn test_inversion_sets_bass_note() {
+		use crate::chord::Inversion;
+
+		let guitar = Guitar::default();
+		let options = GeneratorOptions::default();
+		for (chord, bass) in [
+			(
+				Chord::parse("C").unwrap().with_inversion(Inversion::First),
+				PitchClass::E,
+			),
+			(
+				Chord::parse("G7").unwrap().with_inversion(Inversion::Third),
+				PitchClass::F,
+			),
+			(
+				Chord::parse("Am").unwrap().with_inversion(Inversion::Root),
+				PitchClass::A,
+			),
+		] {
+			let chord = chord.unwrap();
+			let fingerings = generate_fingerings(&chord, &guitar, &options);
+			assert!(!fingerings.is_empty(), "{chord}");
+			for sf in &fingerings {
+				let lowest = sf.fingering.bass_note(&guitar).map(|n| n.pitch);
+				assert_eq!(lowest, Some(bass), "{chord}: {}", sf.fingering);
+			}
+		}
+	}
+
+	#[test]
+	fn test_slash_bass_require_or_prefer() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("C/G").unwrap();
+		let lowest = |sf: &ScoredFingering| {
+			sf.fingering
+				.notes(&guitar)
+				.into_iter()
+				.min_by_key(|n| n.to_midi())
+				.map(|n| n.pitch)
+		};
+
+		let required = generate_fingerings(&chord, &guitar, &GeneratorOptions::default());
+		assert!(!required.is_empty());
+		assert!(required.iter().all(|sf| lowest(sf) == Some(PitchClass::G)));
+
+		let options = GeneratorOptions {
+			slash_bass: SlashBass::Prefer,
+			limit: 50,
+			..Default::default()
+		};
+		let preferred = generate_fingerings(&chord, &guitar, &options);
+		assert_eq!(lowest(&preferred[0]), Some(PitchClass::G));
+		assert!(preferred.iter().any(|sf| lowest(sf) != Some(PitchClass::G)));
+
+		// A bass outside the chord is still found
+		let c_bb = Chord::parse("C/Bb").unwrap();
+		let fingerings = generate_fingerings(&c_bb, &guitar, &GeneratorOptions::default());
+		assert!(!fingerings.is_empty());
+		assert!(
+			fingerings
+				.iter()
+				.all(|sf| lowest(sf) == Some(PitchClass::ASharp))
+		);
+	}
+
 	#[test]
 	fn test_format_diagram() {
 		let chord = Chord::parse("C").unwrap();
@@ -560,6 +1824,48 @@ mod tests {
 		assert!(diagram.contains("Score:"));
 	}
 
+	#[test]
+	fn test_fingerings_come_with_fingers() {
+		let chord = Chord::parse("C").unwrap();
+		let guitar = Guitar::default();
+		let fingerings = generate_fingerings(&chord, &guitar, &GeneratorOptions::default());
+		let open_c = fingerings
+			.iter()
+			.find(|f| f.fingering.to_string() == "x32010")
+			.unwrap();
+		assert_eq!(open_c.fingers.as_ref().unwrap().to_string(), "-32-1-");
+
+		let diagram = format_fingering_diagram(open_c, &guitar);
+		assert!(diagram.contains("A|---3---  [3]"), "{diagram}");
+		assert!(diagram.contains("Fingers:"));
+		assert!(!diagram.contains("Barre:"));
+
+		let f = Chord::parse("F").unwrap();
+		let fingerings = generate_fingerings(&f, &guitar, &GeneratorOptions::default());
+		let barre_f = fingerings
+			.iter()
+			.find(|f| f.fingering.to_string() == "133211")
+			.unwrap();
+		let diagram = format_fingering_diagram(barre_f, &guitar);
+		assert!(
+			diagram.contains("Barre: finger 1 across fret 1 (E to e)"),
+			"{diagram}"
+		);
+		assert!(fingerings.iter().all(|f| f.fingers.is_some()));
+	}
+
+	#[test]
+	fn test_format_diagram_keeps_chord_spelling() {
+		let chord = Chord::parse("Abm7").unwrap();
+		let guitar = Guitar::default();
+		let fingerings = generate_fingerings(&chord, &guitar, &GeneratorOptions::default());
+
+		let diagram =
+			format_fingering_diagram_spelled(&fingerings[0], &guitar, chord.accidentals());
+		assert!(diagram.contains("Ab"));
+		assert!(!diagram.contains("G#"));
+	}
+
 	#[test]
 	fn test_generate_ukulele_c_major() {
 		use crate::instrument::Ukulele;
@@ -876,233 +2182,1217 @@ mod tests {
 	}
 
 	#[test]
-	fn test_voicing_type_filter_core_only() {
-		let chord = Chord::parse("Cmaj7").unwrap();
+	fn test_voicing_type_filter_core_only() {
+		let chord = Chord::parse("Cmaj7").unwrap();
+		let guitar = Guitar::default();
+
+		// Request only Core voicings
+		let core_options = GeneratorOptions {
+			limit: 20,
+			voicing_type: Some(VoicingType::Core),
+			..Default::default()
+		};
+		let core_fingerings = generate_fingerings(&chord, &guitar, &core_options);
+
+		// ALL results should be Core voicing type
+		assert!(
+			!core_fingerings.is_empty(),
+			"Should find some core voicings"
+		);
+
+		for fingering in &core_fingerings {
+			assert_eq!(
+				fingering.voicing_type,
+				VoicingType::Core,
+				"Requested Core but got {:?} for fingering: {}",
+				fingering.voicing_type,
+				fingering.fingering
+			);
+		}
+	}
+
+	#[test]
+	fn test_voicing_type_filter_full_only() {
+		let chord = Chord::parse("Cmaj7").unwrap();
+		let guitar = Guitar::default();
+
+		// Request only Full voicings
+		let full_options = GeneratorOptions {
+			limit: 20,
+			voicing_type: Some(VoicingType::Full),
+			..Default::default()
+		};
+		let full_fingerings = generate_fingerings(&chord, &guitar, &full_options);
+
+		// ALL results should be Full voicing type
+		assert!(
+			!full_fingerings.is_empty(),
+			"Should find some full voicings"
+		);
+
+		for fingering in &full_fingerings {
+			assert_eq!(
+				fingering.voicing_type,
+				VoicingType::Full,
+				"Requested Full but got {:?} for fingering: {}",
+				fingering.voicing_type,
+				fingering.fingering
+			);
+		}
+	}
+
+	#[test]
+	fn test_voicing_type_filter_jazzy_only() {
+		let chord = Chord::parse("Cmaj7").unwrap();
+		let guitar = Guitar::default();
+
+		// Request only Jazzy voicings
+		let jazzy_options = GeneratorOptions {
+			limit: 20,
+			voicing_type: Some(VoicingType::Jazzy),
+			..Default::default()
+		};
+		let jazzy_fingerings = generate_fingerings(&chord, &guitar, &jazzy_options);
+
+		// ALL results should be Jazzy voicing type
+		assert!(
+			!jazzy_fingerings.is_empty(),
+			"Should find some jazzy voicings"
+		);
+
+		for fingering in &jazzy_fingerings {
+			assert_eq!(
+				fingering.voicing_type,
+				VoicingType::Jazzy,
+				"Requested Jazzy but got {:?} for fingering: {}",
+				fingering.voicing_type,
+				fingering.fingering
+			);
+		}
+	}
+
+	// ===================================================================
+	// Golden-file tests: verify well-known fingerings rank in top results
+	// ===================================================================
+
+	/// Helper: check if a specific tab notation appears in the top N results
+	fn assert_in_top_n(chord_name: &str, expected_tab: &str, top_n: usize) {
+		let chord = Chord::parse(chord_name).unwrap();
+		let guitar = Guitar::default();
+		let options = GeneratorOptions {
+			limit: top_n,
+			..Default::default()
+		};
+		let fingerings = generate_fingerings(&chord, &guitar, &options);
+		let tabs: Vec<String> = fingerings.iter().map(|f| f.fingering.to_string()).collect();
+		assert!(
+			tabs.contains(&expected_tab.to_string()),
+			"{chord_name}: expected {expected_tab} in top {top_n}, got: {tabs:?}"
+		);
+	}
+
+	#[test]
+	fn test_golden_c_major() {
+		assert_in_top_n("C", "x32010", 3);
+	}
+
+	#[test]
+	fn test_golden_g_major() {
+		assert_in_top_n("G", "320003", 3);
+	}
+
+	#[test]
+	fn test_golden_d_major() {
+		assert_in_top_n("D", "xx0232", 3);
+	}
+
+	#[test]
+	fn test_golden_a_major() {
+		assert_in_top_n("A", "x02220", 3);
+	}
+
+	#[test]
+	fn test_golden_e_major() {
+		assert_in_top_n("E", "022100", 3);
+	}
+
+	#[test]
+	fn test_golden_am() {
+		assert_in_top_n("Am", "x02210", 3);
+	}
+
+	#[test]
+	fn test_golden_em() {
+		assert_in_top_n("Em", "022000", 3);
+	}
+
+	#[test]
+	fn test_golden_dm() {
+		assert_in_top_n("Dm", "xx0231", 3);
+	}
+
+	#[test]
+	fn test_golden_f_barre() {
+		// F barre (133211) should rank in top 5 (it's harder than open chords)
+		assert_in_top_n("F", "133211", 5);
+	}
+
+	#[test]
+	fn test_golden_cmaj7() {
+		assert_in_top_n("Cmaj7", "x32000", 5);
+	}
+
+	#[test]
+	fn test_golden_am7() {
+		assert_in_top_n("Am7", "x02010", 5);
+	}
+
+	#[test]
+	fn test_golden_g7() {
+		// Open G7 (320001) has 3 interior open strings which penalizes its ranking.
+		// Barre E7-shape variants at fret 3 rank higher.
+		// Verify the E7-shape barre G7 (353433) ranks high, and 320001 still appears.
+		assert_in_top_n("G7", "353433", 3);
+		assert_in_top_n("G7", "320001", 20);
+	}
+
+	#[test]
+	fn test_golden_d7() {
+		assert_in_top_n("D7", "xx0212", 5);
+	}
+
+	#[test]
+	fn test_golden_e7() {
+		assert_in_top_n("E7", "020100", 5);
+	}
+
+	#[test]
+	fn test_voicing_type_combinations_with_context() {
+		let chord = Chord::parse("Gmaj7").unwrap();
+		let guitar = Guitar::default();
+
+		// Core + Solo
+		let core_solo = GeneratorOptions {
+			limit: 10,
+			voicing_type: Some(VoicingType::Core),
+			playing_context: PlayingContext::Solo,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &core_solo);
+		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Core));
+
+		// Core + Band
+		let core_band = GeneratorOptions {
+			limit: 10,
+			voicing_type: Some(VoicingType::Core),
+			playing_context: PlayingContext::Band,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &core_band);
+		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Core));
+
+		// Full + Solo
+		let full_solo = GeneratorOptions {
+			limit: 10,
+			voicing_type: Some(VoicingType::Full),
+			playing_context: PlayingContext::Solo,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &full_solo);
+		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Full));
+
+		// Full + Band
+		let full_band = GeneratorOptions {
+			limit: 10,
+			voicing_type: Some(VoicingType::Full),
+			playing_context: PlayingContext::Band,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &full_band);
+		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Full));
+	}
+
+	fn interior_mutes(fingering: &Fingering) -> usize {
+		let strings = fingering.strings();
+		let first = strings.iter().position(|s| s.is_played()).unwrap_or(0);
+		let last = strings.iter().rposition(|s| s.is_played()).unwrap_or(0);
+		strings[first..=last]
+			.iter()
+			.filter(|s| !s.is_played())
+			.count()
+	}
+
+	#[test]
+	fn test_strumming_avoids_interior_mutes() {
+		let guitar = Guitar::default();
+		let options = GeneratorOptions {
+			limit: 5,
+			playing_context: PlayingContext::Strumming,
+			..Default::default()
+		};
+
+		for name in ["C", "G", "D", "Am7", "Fmaj7"] {
+			let chord = Chord::parse(name).unwrap();
+			let results = generate_fingerings(&chord, &guitar, &options);
+			assert!(!results.is_empty());
+			for sf in &results {
+				println!("{name} strumming: {}", sf.fingering);
+				assert_eq!(interior_mutes(&sf.fingering), 0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_fingerstyle_tolerates_skipped_strings() {
+		let guitar = Guitar::default();
+		// C major plucked with the D string skipped
+		let fingering = Fingering::parse("x3x010").unwrap();
+
+		let score_for = |context| {
+			let options = GeneratorOptions {
+				playing_context: context,
+				..Default::default()
+			};
+			score_fingering(
+				&fingering,
+				&guitar,
+				&options,
+				FingeringScorerOptions {
+					has_all_notes: true,
+					has_all_core: true,
+					has_root_in_bass: true,
+					position: 0,
+					played_count: 4,
+					voicing_type: VoicingType::Full,
+				},
+			)
+		};
+
+		let fingerstyle = score_for(PlayingContext::Fingerstyle);
+		let solo = score_for(PlayingContext::Solo);
+		let strumming = score_for(PlayingContext::Strumming);
+		println!("fingerstyle {fingerstyle}, solo {solo}, strumming {strumming}");
+		assert!(fingerstyle > solo);
+		assert!(solo > strumming);
+	}
+
+	#[test]
+	fn test_fingerstyle_favors_open_strings() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("G").unwrap();
+		let options = GeneratorOptions {
+			limit: 3,
+			playing_context: PlayingContext::Fingerstyle,
+			..Default::default()
+		};
+
+		let results = generate_fingerings(&chord, &guitar, &options);
+		assert!(!results.is_empty());
+		for sf in &results {
+			println!("G fingerstyle: {} ({})", sf.fingering, sf.score);
+		}
+		assert!(
+			results[0]
+				.fingering
+				.strings()
+				.iter()
+				.any(|s| matches!(s, StringState::Fretted(0)))
+		);
+	}
+
+	#[test]
+	fn test_recording_prefers_full_voicings() {
+		let guitar = Guitar::default();
+		let options = GeneratorOptions {
+			limit: 5,
+			playing_context: PlayingContext::Recording,
+			..Default::default()
+		};
+
+		for name in ["C", "G", "Am", "E7"] {
+			let chord = Chord::parse(name).unwrap();
+			let results = generate_fingerings(&chord, &guitar, &options);
+			println!("{name} recording: {}", results[0].fingering);
+			assert_eq!(results[0].voicing_type, VoicingType::Full);
+		}
+	}
+
+	#[test]
+	fn test_hand_size_limits_stretch() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Cmaj9").unwrap();
+
+		let max_span = |hand_size: HandSize| {
+			let options = GeneratorOptions {
+				limit: 200,
+				hand_size,
+				..Default::default()
+			};
+			generate_fingerings(&chord, &guitar, &options)
+				.iter()
+				.map(|f| f.fingering.fret_span())
+				.max()
+				.unwrap()
+		};
+
+		assert!(max_span(HandSize::Small) <= HandSize::Small.max_stretch(guitar.max_stretch()));
+		assert!(max_span(HandSize::Small) < max_span(HandSize::Large));
+		assert!(HandSize::Large.allows_thumb_fretting());
+		assert!(!HandSize::Small.allows_thumb_fretting());
+	}
+
+	#[test]
+	fn test_pinned_strings() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("C").unwrap();
+		let generate = |pins: Vec<StringPin>| {
+			let options = GeneratorOptions {
+				limit: 20,
+				pins,
+				..Default::default()
+			};
+			generate_fingerings(&chord, &guitar, &options)
+		};
+
+		// G on top, open D string
+		let pins = vec![
+			StringPin::new(5, StringState::Fretted(3)),
+			StringPin::new(2, StringState::Fretted(0)),
+		];
+		let results = generate(pins.clone());
+		assert!(!results.is_empty());
+		for f in &results {
+			for pin in &pins {
+				assert_eq!(
+					f.fingering.strings()[pin.string],
+					pin.state,
+					"{}",
+					f.fingering
+				);
+			}
+		}
+
+		// A melody note outside the chord (A over C) is kept
+		let results = generate(vec![StringPin::new(5, StringState::Fretted(5))]);
+		assert!(!results.is_empty());
+		assert!(
+			results
+				.iter()
+				.all(|f| f.fingering.strings()[5] == StringState::Fretted(5))
+		);
+	}
+
+	#[test]
+	fn test_fret_window() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Am7").unwrap();
+		let options = GeneratorOptions {
+			limit: 20,
+			min_fret: 5,
+			max_fret: 9,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &options);
+		assert!(!results.is_empty());
+		for result in &results {
+			assert!(
+				result
+					.fingering
+					.fretted_positions()
+					.iter()
+					.all(|&(_, fret)| (5..=9).contains(&fret)),
+				"{}",
+				result.fingering
+			);
+		}
+		// Open strings ring along unless shapes must be movable
+		assert!(
+			results
+				.iter()
+				.any(|r| r.fingering.strings().contains(&StringState::Fretted(0)))
+		);
+		let closed = GeneratorOptions {
+			movable_only: true,
+			..options
+		};
+		assert!(
+			generate_fingerings(&chord, &guitar, &closed)
+				.iter()
+				.all(|r| r.position >= 5)
+		);
+	}
+
+	#[test]
+	fn test_diverse_results() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("C").unwrap();
+		let options = GeneratorOptions {
+			limit: 6,
+			..Default::default()
+		};
+		let plain = generate_fingerings(&chord, &guitar, &options);
+		let diverse = generate_fingerings(
+			&chord,
+			&guitar,
+			&GeneratorOptions {
+				diverse: true,
+				..options
+			},
+		);
+		assert_eq!(diverse.len(), plain.len());
+		// The best fingering always stays first
+		assert_eq!(diverse[0].fingering, plain[0].fingering);
+		// Plain results include variants of one shape (x32010, x32013, ...)
+		let near_copies = |results: &[ScoredFingering]| {
+			results.iter().enumerate().any(|(i, a)| {
+				results[i + 1..]
+					.iter()
+					.any(|b| is_near_copy(&a.fingering, &b.fingering))
+			})
+		};
+		assert!(near_copies(&plain));
+		assert!(!near_copies(&diverse));
+		assert!(diverse.windows(2).all(|w| w[0].score >= w[1].score));
+	}
+
+	#[test]
+	fn test_pruning_keeps_every_result() {
+		let ukulele = Ukulele::default();
+		let every_combination = |search: &FingeringSearch<Ukulele>| {
+			let mut combinations: Vec<Vec<StringState>> = vec![vec![]];
+			for options in &search.string_options {
+				combinations = combinations
+					.into_iter()
+					.flat_map(|c| {
+						options.iter().map(move |&state| {
+							let mut next = c.clone();
+							next.push(state);
+							next
+						})
+					})
+					.collect();
+			}
+			combinations
+		};
+		let voicings = [None, Some(VoicingType::Full), Some(VoicingType::Jazzy)];
+		for (chord, voicing_type) in ["C", "G7", "Bb9", "D/F#"]
+			.iter()
+			.zip(voicings.iter().cycle())
+		{
+			let options = GeneratorOptions {
+				voicing_type: *voicing_type,
+				thumb_fretting: true,
+				include_harmonics: true,
+				..Default::default()
+			};
+			let chord = Chord::parse(chord).unwrap();
+			let search = FingeringSearch::new(&chord, &ukulele, &options);
+			let tabs = |combinations: Vec<Vec<StringState>>| -> Vec<String> {
+				let mut tabs: Vec<String> = combinations
+					.into_iter()
+					.filter_map(|c| search.score(c))
+					.map(|f| f.fingering.to_string())
+					.collect();
+				tabs.sort();
+				tabs
+			};
+
+			let mut pruned = Vec::new();
+			for first in 0..search.first_options().len() {
+				search.enumerate_branch(
+					first,
+					&mut |states| pruned.push(states.to_vec()),
+					&NoopObserver,
+				);
+			}
+			let brute_force = every_combination(&search);
+			assert!(pruned.len() < brute_force.len());
+			assert_eq!(tabs(pruned), tabs(brute_force), "{chord}");
+		}
+	}
+
+	#[test]
+	fn test_eight_string_high_frets() {
+		let eight_string = ConfigurableInstrument::builder()
+			.tuning(
+				["F#1", "B1", "E2", "A2", "D3", "G3", "B3", "E4"]
+					.iter()
+					.map(|n| Note::parse(n).unwrap())
+					.collect(),
+			)
+			.fret_range(0, 24)
+			.max_stretch(4)
+			.build()
+			.unwrap();
+		let chord = Chord::parse("Cmaj9").unwrap();
+		let options = GeneratorOptions {
+			max_fret: 15,
+			voicing_type: Some(VoicingType::Full),
+			limit: 5,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &eight_string, &options);
+		assert_eq!(results.len(), 5);
+		for result in &results {
+			assert_eq!(result.voicing_type, VoicingType::Full);
+			assert!(result.fingering.fret_span() <= 4);
+		}
+	}
+
+	#[test]
+	fn test_string_sets() {
+		assert_eq!(StringSet::parse("top4").unwrap(), StringSet::Top(4));
+		assert_eq!(StringSet::parse("Middle 4").unwrap(), StringSet::Middle(4));
+		assert_eq!(
+			StringSet::parse("2,3,4").unwrap(),
+			StringSet::Only(vec![1, 2, 3])
+		);
+		assert!(StringSet::parse("top").is_err());
+		assert!(StringSet::parse("0,1").is_err());
+
+		let allowed =
+			|set: StringSet| -> Vec<usize> { (0..6).filter(|&s| set.allows(s, 6)).collect() };
+		assert_eq!(allowed(StringSet::Top(4)), vec![2, 3, 4, 5]);
+		assert_eq!(allowed(StringSet::Middle(4)), vec![1, 2, 3, 4]);
+		assert_eq!(allowed(StringSet::Middle(3)), vec![2, 3, 4]);
+		assert_eq!(allowed(StringSet::Bottom(3)), vec![0, 1, 2]);
+
+		let guitar = Guitar::default();
+		let chord = Chord::parse("G").unwrap();
+		let options = GeneratorOptions {
+			string_set: Some(StringSet::Top(4)),
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &options);
+		assert!(!results.is_empty());
+		for result in &results {
+			let strings = result.fingering.strings();
+			assert!(strings[..2].iter().all(|s| *s == StringState::Muted));
+		}
+	}
+
+	#[test]
+	fn test_by_position() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("G").unwrap();
+		let options = GeneratorOptions {
+			limit: 4,
+			by_position: true,
+			..Default::default()
+		};
+		let grouped = generate_fingerings(&chord, &guitar, &options);
+		let regions: Vec<NeckRegion> = grouped.iter().map(|f| f.region(&guitar)).collect();
+		assert_eq!(regions.len(), 4);
+		// The open G shape, though its lowest fretted note is at fret 2
+		assert!(regions[0].is_open());
+		assert_eq!(grouped[0].position, 2);
+		assert!(regions.windows(2).all(|w| w[0] < w[1]));
+
+		// Each pick is the best the plain search finds in its region
+		let everything = generate_fingerings(
+			&chord,
+			&guitar,
+			&GeneratorOptions {
+				limit: 10_000,
+				..Default::default()
+			},
+		);
+		for best in &grouped {
+			let first = everything
+				.iter()
+				.find(|f| f.region(&guitar) == best.region(&guitar))
+				.unwrap();
+			assert_eq!(first.score, best.score);
+		}
+
+		assert_eq!(NeckRegion::of(1).to_string(), "Open position");
+		assert_eq!(NeckRegion::of(2).to_string(), "Frets 2-4");
+		assert_eq!(NeckRegion::of(7), NeckRegion::of(5));
+		assert_eq!(NeckRegion::of(8).to_string(), "Frets 8-10");
+	}
+
+	#[test]
+	fn test_top_note() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("C").unwrap();
+		let top_notes = |top: PitchClass| {
+			let options = GeneratorOptions {
+				limit: 20,
+				top_note: Some(top),
+				..Default::default()
+			};
+			let results = generate_fingerings(&chord, &guitar, &options);
+			assert!(!results.is_empty(), "nothing with {top} on top");
+			results
+				.iter()
+				.map(|r| r.fingering.notes(&guitar))
+				.collect::<Vec<_>>()
+		};
+
+		for notes in top_notes(PitchClass::E) {
+			assert_eq!(notes.last().unwrap().pitch, PitchClass::E);
+		}
+		// A melody note outside the chord sounds on top only
+		for notes in top_notes(PitchClass::D) {
+			let (top, below) = notes.split_last().unwrap();
+			assert_eq!(top.pitch, PitchClass::D);
+			assert!(below.iter().all(|n| n.pitch != PitchClass::D));
+		}
+	}
+
+	#[test]
+	fn test_omit_degrees() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("C9").unwrap();
+		let options = GeneratorOptions {
+			limit: 10,
+			omit: vec![1, 5],
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &guitar, &options);
+		assert!(!results.is_empty());
+		for result in &results {
+			let pitches = result.fingering.unique_pitch_classes(&guitar);
+			assert!(!pitches.contains(&PitchClass::C), "{}", result.fingering);
+			assert!(!pitches.contains(&PitchClass::G), "{}", result.fingering);
+			assert_eq!(result.omitted, vec![1, 5]);
+		}
+		// Rootless voicings with every remaining tone are full, not jazzy
+		assert!(results.iter().any(|r| r.voicing_type == VoicingType::Full));
+		let diagram = format_fingering_diagram(&results[0], &guitar);
+		assert!(diagram.contains("no1 no5"), "{diagram}");
+
+		// A degree the chord doesn't have isn't recorded
+		let power = Chord::parse("C5").unwrap();
+		let options = GeneratorOptions {
+			omit: vec![3],
+			..Default::default()
+		};
+		let results = generate_fingerings(&power, &guitar, &options);
+		assert!(results.iter().all(|r| r.omitted.is_empty()));
+	}
+
+	#[test]
+	fn test_generate_with_capo() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Bb").unwrap();
+		let options = GeneratorOptions::default();
+		let found = generate_fingerings_with_capo(&chord, &guitar, 3, &options).unwrap();
+		assert_eq!(found.capo, 3);
+		assert_eq!(found.sounding_chord, chord);
+		assert_eq!(found.shape_chord, Chord::parse("G").unwrap());
+
+		// Shapes are G shapes, but what sounds is Bb
+		let capoed = guitar.with_capo(3).unwrap();
+		assert!(!found.fingerings.is_empty());
+		for result in &found.fingerings {
+			let pitches = result.fingering.unique_pitch_classes(&capoed);
+			assert!(pitches.iter().all(|p| chord.notes().contains(p)));
+		}
+		let plain = generate_fingerings(&Chord::parse("G").unwrap(), &guitar, &options);
+		assert_eq!(found.fingerings[0].fingering, plain[0].fingering);
+
+		assert!(generate_fingerings_with_capo(&chord, &guitar, 30, &options).is_err());
+	}
+
+	#[test]
+	fn test_avoid() {
+		let guitar = Guitar::default();
+		let f = Chord::parse("F").unwrap();
+		let barre = Fingering::parse("133211").unwrap();
+		let has = |options: &GeneratorOptions, tab: &Fingering| {
+			generate_fingerings(&f, &guitar, options)
+				.iter()
+				.any(|r| &r.fingering == tab)
+		};
+		assert!(has(&GeneratorOptions::default(), &barre));
+
+		let options = GeneratorOptions {
+			avoid: vec![Avoid::parse("133211").unwrap()],
+			..Default::default()
+		};
+		assert!(!has(&options, &barre));
+
+		// Every E-shape barre, or only the one at fret 1
+		let options = GeneratorOptions {
+			avoid: vec![Avoid::parse("E shape").unwrap()],
+			..Default::default()
+		};
+		let results = generate_fingerings(&f, &guitar, &options);
+		assert!(!results.is_empty());
+		assert!(results.iter().all(|r| {
+			!Avoid::parse("E shape")
+				.unwrap()
+				.matches(&r.fingering, &guitar)
+		}));
+
+		assert_eq!(
+			Avoid::parse("E shape barred at fret 1").unwrap(),
+			Avoid::Shape {
+				name: "E".to_string(),
+				fret: Some(1)
+			}
+		);
+		assert_eq!(
+			Avoid::parse("A shape at 3").unwrap(),
+			Avoid::parse("A shape 3").unwrap()
+		);
+		let at_one = Avoid::parse("E shape at 1").unwrap();
+		assert!(at_one.matches(&barre, &guitar));
+		assert!(!at_one.matches(&Fingering::parse("355433").unwrap(), &guitar));
+		assert!(Avoid::parse("E shape at top").is_err());
+		assert!(Avoid::parse("not a tab").is_err());
+	}
+
+	#[test]
+	fn test_movable_only() {
 		let guitar = Guitar::default();
-
-		// Request only Core voicings
-		let core_options = GeneratorOptions {
-			limit: 20,
-			voicing_type: Some(VoicingType::Core),
+		let eb = Chord::parse("Eb").unwrap();
+		let options = GeneratorOptions {
+			movable_only: true,
+			include_harmonics: true,
 			..Default::default()
 		};
-		let core_fingerings = generate_fingerings(&chord, &guitar, &core_options);
-
-		// ALL results should be Core voicing type
-		assert!(
-			!core_fingerings.is_empty(),
-			"Should find some core voicings"
-		);
+		let results = generate_fingerings(&eb, &guitar, &options);
+		assert!(!results.is_empty());
 
-		for fingering in &core_fingerings {
-			assert_eq!(
-				fingering.voicing_type,
-				VoicingType::Core,
-				"Requested Core but got {:?} for fingering: {}",
-				fingering.voicing_type,
-				fingering.fingering
+		let f = Chord::parse("F").unwrap().notes();
+		for result in &results {
+			let strings = result.fingering.strings();
+			assert!(
+				strings
+					.iter()
+					.all(|s| matches!(s, StringState::Muted | StringState::Fretted(1..))),
+				"{}",
+				result.fingering
 			);
+			// Two frets up, every shape plays F
+			let moved = result.fingering.transpose(2).unwrap();
+			let pitches = moved.unique_pitch_classes(&guitar);
+			assert!(pitches.iter().all(|p| f.contains(p)), "{moved}");
 		}
 	}
 
 	#[test]
-	fn test_voicing_type_filter_full_only() {
-		let chord = Chord::parse("Cmaj7").unwrap();
+	fn test_thumb_fretting() {
+		use crate::fingers::Finger;
+
+		let chord = Chord::parse("F").unwrap();
 		let guitar = Guitar::default();
+		let uses_thumb = |options: &GeneratorOptions| {
+			generate_fingerings(&chord, &guitar, options)
+				.iter()
+				.filter_map(|f| f.fingers.as_ref())
+				.any(|a| a.finger(0) == Some(Finger::Thumb))
+		};
 
-		// Request only Full voicings
-		let full_options = GeneratorOptions {
+		let mut options = GeneratorOptions {
 			limit: 20,
-			voicing_type: Some(VoicingType::Full),
 			..Default::default()
 		};
-		let full_fingerings = generate_fingerings(&chord, &guitar, &full_options);
+		assert!(!uses_thumb(&options));
+		options.thumb_fretting = true;
+		assert!(uses_thumb(&options));
+
+		options.accessible = true;
+		assert!(!options.allows_thumb());
+		let large = GeneratorOptions {
+			hand_size: HandSize::Large,
+			..Default::default()
+		};
+		assert!(large.allows_thumb());
+	}
 
-		// ALL results should be Full voicing type
+	#[test]
+	fn test_small_hands_penalize_barres() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("F").unwrap();
+		let barre = Fingering::parse("133211").unwrap();
+
+		let score_for = |hand_size: HandSize| {
+			let options = GeneratorOptions {
+				limit: 50,
+				hand_size,
+				..Default::default()
+			};
+			generate_fingerings(&chord, &guitar, &options)
+				.into_iter()
+				.find(|f| f.fingering == barre)
+				.map(|f| f.score)
+				.unwrap()
+		};
+
+		assert!(score_for(HandSize::Small) < score_for(HandSize::Medium));
+	}
+
+	#[test]
+	fn test_player_profile() {
+		let guitar = Guitar::default();
+		let barre = Fingering::parse("133211").unwrap();
+		let open = Fingering::parse("x32010").unwrap();
+		let medium = PlayerProfile::default();
+		let child = PlayerProfile::child();
+
+		assert!(child.max_stretch(4) < medium.max_stretch(4));
+		assert_eq!(PlayerProfile::from(HandSize::Large).max_stretch(4), 5);
 		assert!(
-			!full_fingerings.is_empty(),
-			"Should find some full voicings"
+			barre.playability_score_for_player(&guitar, &child)
+				< barre.playability_score_for_player(&guitar, &medium)
+		);
+		assert_eq!(
+			open.playability_score_for_player(&guitar, &child),
+			open.playability_score_for_player(&guitar, &medium)
 		);
 
-		for fingering in &full_fingerings {
-			assert_eq!(
-				fingering.voicing_type,
-				VoicingType::Full,
-				"Requested Full but got {:?} for fingering: {}",
-				fingering.voicing_type,
-				fingering.fingering
-			);
+		// Strong barres cancel the small-hand penalty
+		let strong_small = PlayerProfile {
+			barre_strength: BarreStrength::Strong,
+			..PlayerProfile::from(HandSize::Small)
+		};
+		assert_eq!(strong_small.barre_penalty(), 0);
+
+		let options = GeneratorOptions::default().with_player(child);
+		assert_eq!(options.player(), child);
+		let chord = Chord::parse("Cmaj9").unwrap();
+		for f in generate_fingerings(&chord, &guitar, &options) {
+			assert!(f.fingering.fret_span() <= child.max_stretch(guitar.max_stretch()));
 		}
 	}
 
 	#[test]
-	fn test_voicing_type_filter_jazzy_only() {
-		let chord = Chord::parse("Cmaj7").unwrap();
+	fn test_accessible_mode() {
 		let guitar = Guitar::default();
-
-		// Request only Jazzy voicings
-		let jazzy_options = GeneratorOptions {
+		let options = GeneratorOptions {
 			limit: 20,
-			voicing_type: Some(VoicingType::Jazzy),
+			accessible: true,
 			..Default::default()
 		};
-		let jazzy_fingerings = generate_fingerings(&chord, &guitar, &jazzy_options);
-
-		// ALL results should be Jazzy voicing type
-		assert!(
-			!jazzy_fingerings.is_empty(),
-			"Should find some jazzy voicings"
-		);
 
-		for fingering in &jazzy_fingerings {
-			assert_eq!(
-				fingering.voicing_type,
-				VoicingType::Jazzy,
-				"Requested Jazzy but got {:?} for fingering: {}",
-				fingering.voicing_type,
-				fingering.fingering
+		for name in ["F", "Bm", "G", "C7"] {
+			let chord = Chord::parse(name).unwrap();
+			let results = generate_fingerings(&chord, &guitar, &options);
+			assert!(
+				!results.is_empty(),
+				"{name} should have accessible fingerings"
 			);
+			println!("{name} accessible: {}", results[0].fingering);
+
+			for f in &results {
+				assert!(!f.fingering.requires_barre());
+				assert!(f.fingering.min_fingers_required() <= 3);
+				assert!(f.fingering.fret_span() <= options.effective_max_stretch(&guitar));
+			}
 		}
 	}
 
-	// ===================================================================
-	// Golden-file tests: verify well-known fingerings rank in top results
-	// ===================================================================
-
-	/// Helper: check if a specific tab notation appears in the top N results
-	fn assert_in_top_n(chord_name: &str, expected_tab: &str, top_n: usize) {
-		let chord = Chord::parse(chord_name).unwrap();
+	#[test]
+	fn test_beginner_mode() {
 		let guitar = Guitar::default();
 		let options = GeneratorOptions {
-			limit: top_n,
+			limit: 20,
+			beginner: true,
+			thumb_fretting: true,
 			..Default::default()
 		};
-		let fingerings = generate_fingerings(&chord, &guitar, &options);
-		let tabs: Vec<String> = fingerings.iter().map(|f| f.fingering.to_string()).collect();
-		assert!(
-			tabs.contains(&expected_tab.to_string()),
-			"{chord_name}: expected {expected_tab} in top {top_n}, got: {tabs:?}"
-		);
-	}
+		assert_eq!(options.effective_max_fingers(&guitar), 3);
+		assert!(!options.allows_thumb());
 
-	#[test]
-	fn test_golden_c_major() {
-		assert_in_top_n("C", "x32010", 3);
+		for name in ["C", "G", "Am", "E7"] {
+			let chord = Chord::parse(name).unwrap();
+			let results = generate_fingerings(&chord, &guitar, &options);
+			assert!(
+				!results.is_empty(),
+				"{name} should have beginner fingerings"
+			);
+			// The open shape everyone learns first comes out on top
+			assert!(
+				shapes::find_matching_shape(&results[0].fingering, &guitar).is_some(),
+				"{name}: {}",
+				results[0].fingering
+			);
+			for f in &results {
+				assert!(!f.fingering.requires_barre());
+				assert!(f.fingering.min_fingers_required() <= 3);
+				assert!(f.fingering.max_fret().unwrap_or(0) <= 3, "{}", f.fingering);
+			}
+		}
 	}
 
 	#[test]
-	fn test_golden_g_major() {
-		assert_in_top_n("G", "320003", 3);
-	}
+	fn test_accessible_combines_with_hand_size() {
+		let guitar = Guitar::default();
+		let stretch = |hand_size: HandSize, accessible: bool| {
+			GeneratorOptions {
+				hand_size,
+				accessible,
+				..Default::default()
+			}
+			.effective_max_stretch(&guitar)
+		};
 
-	#[test]
-	fn test_golden_d_major() {
-		assert_in_top_n("D", "xx0232", 3);
+		assert!(stretch(HandSize::Medium, true) < stretch(HandSize::Medium, false));
+		assert!(stretch(HandSize::Small, true) <= stretch(HandSize::Medium, true));
+		assert!(stretch(HandSize::Large, true) > stretch(HandSize::Small, true));
 	}
 
 	#[test]
-	fn test_golden_a_major() {
-		assert_in_top_n("A", "x02220", 3);
-	}
+	fn test_include_harmonics() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Em").unwrap();
 
-	#[test]
-	fn test_golden_e_major() {
-		assert_in_top_n("E", "022100", 3);
-	}
+		let without = generate_fingerings(&chord, &guitar, &GeneratorOptions::default());
+		assert!(without.iter().all(|f| {
+			!f.fingering
+				.strings()
+				.iter()
+				.any(|s| matches!(s, StringState::Harmonic(_)))
+		}));
 
-	#[test]
-	fn test_golden_am() {
-		assert_in_top_n("Am", "x02210", 3);
-	}
+		let options = GeneratorOptions {
+			limit: 200,
+			include_harmonics: true,
+			..Default::default()
+		};
+		let with = generate_fingerings(&chord, &guitar, &options);
+		let harmonic = with
+			.iter()
+			.find(|f| {
+				f.fingering
+					.strings()
+					.iter()
+					.any(|s| matches!(s, StringState::Harmonic(_)))
+			})
+			.expect("should find harmonic voicings");
 
-	#[test]
-	fn test_golden_em() {
-		assert_in_top_n("Em", "022000", 3);
-	}
+		let pitches = harmonic.fingering.unique_pitch_classes(&guitar);
+		assert!(pitches.iter().all(|p| chord.notes().contains(p)));
 
-	#[test]
-	fn test_golden_dm() {
-		assert_in_top_n("Dm", "xx0231", 3);
+		let diagram = format_fingering_diagram(harmonic, &guitar);
+		assert!(diagram.contains('<'));
+		assert!(diagram.contains("Harmonics:"));
 	}
 
 	#[test]
-	fn test_golden_f_barre() {
-		// F barre (133211) should rank in top 5 (it's harder than open chords)
-		assert_in_top_n("F", "133211", 5);
-	}
+	fn test_generate_with_observer() {
+		use crate::progress::CancellationToken;
+		use std::cell::RefCell;
 
-	#[test]
-	fn test_golden_cmaj7() {
-		assert_in_top_n("Cmaj7", "x32000", 5);
+		let guitar = Guitar::default();
+		let chord = Chord::parse("G7").unwrap();
+		let options = GeneratorOptions::default();
+
+		let updates = RefCell::new(Vec::new());
+		let observer = |p: Progress| updates.borrow_mut().push(p);
+		let observed = generate_fingerings_with(&chord, &guitar, &options, &observer).unwrap();
+		assert_eq!(
+			observed.len(),
+			generate_fingerings(&chord, &guitar, &options).len()
+		);
+
+		let updates = updates.into_inner();
+		let last = updates.last().unwrap();
+		assert_eq!(last.phase, SearchPhase::Scoring);
+		assert_eq!(last.done, last.total);
+		assert!(
+			updates
+				.windows(2)
+				.all(|w| w[0].phase != w[1].phase || w[0].done <= w[1].done)
+		);
+
+		let token = CancellationToken::new();
+		token.cancel();
+		assert!(matches!(
+			generate_fingerings_with(&chord, &guitar, &options, &token),
+			Err(ChordCraftError::Cancelled)
+		));
 	}
 
 	#[test]
-	fn test_golden_am7() {
-		assert_in_top_n("Am7", "x02010", 5);
+	fn test_search_scores_as_it_enumerates() {
+		// However many combinations the search visits, it holds `limit` of them
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Cmaj9").unwrap();
+		let options = GeneratorOptions {
+			limit: 3,
+			..Default::default()
+		};
+		let search = FingeringSearch::new(&chord, &guitar, &options);
+		let mut pool = options.candidate_pool();
+		let mut total = 0;
+		for first in 0..search.first_options().len() {
+			let (done, count) = search.search_branch(first, &mut pool, &NoopObserver);
+			assert!(done);
+			total += count;
+		}
+		assert!(total > 100);
+		let kept = pool.into_sorted_vec();
+		assert_eq!(kept.len(), 3);
+		let tabs = |results: &[ScoredFingering]| -> Vec<String> {
+			results.iter().map(|f| f.fingering.to_string()).collect()
+		};
+		assert_eq!(
+			tabs(&kept),
+			tabs(&generate_fingerings(&chord, &guitar, &options))
+		);
 	}
 
 	#[test]
-	fn test_golden_g7() {
-		// Open G7 (320001) has 3 interior open strings which penalizes its ranking.
-		// Barre E7-shape variants at fret 3 rank higher.
-		// Verify the E7-shape barre G7 (353433) ranks high, and 320001 still appears.
-		assert_in_top_n("G7", "353433", 3);
-		assert_in_top_n("G7", "320001", 20);
+	fn test_generate_within_budget() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Cmaj7").unwrap();
+		let options = GeneratorOptions::default();
+
+		let unlimited =
+			generate_fingerings_within(&chord, &guitar, &options, &SearchBudget::default());
+		assert!(unlimited.complete);
+		assert_eq!(
+			unlimited.results.len(),
+			generate_fingerings(&chord, &guitar, &options).len()
+		);
+
+		// A tight budget still ranks whatever it reached
+		let partial =
+			generate_fingerings_within(&chord, &guitar, &options, &SearchBudget::nodes(500));
+		assert!(!partial.complete);
+		assert!(!partial.results.is_empty());
+		assert!(partial.results.windows(2).all(|w| w[0].score >= w[1].score));
+
+		// Nothing past the budget is scored, even with room for more results
+		let roomy = GeneratorOptions {
+			limit: 1000,
+			..Default::default()
+		};
+		let partial = generate_fingerings_within(&chord, &guitar, &roomy, &SearchBudget::nodes(50));
+		assert!(!partial.complete);
+		assert!(!partial.results.is_empty() && partial.results.len() <= 50);
 	}
 
 	#[test]
-	fn test_golden_d7() {
-		assert_in_top_n("D7", "xx0212", 5);
+	fn test_band_mode_on_extended_range_guitars() {
+		// Band mode favors keeping off every string in the bass's register,
+		// however many the instrument has
+		let chord = Chord::parse("C").unwrap();
+		for instrument in [
+			ConfigurableInstrument::guitar_8_string(),
+			ConfigurableInstrument::baritone_guitar(),
+		] {
+			let bass_strings = instrument.bass_string_indices().unwrap();
+			assert!(bass_strings.len() >= 3);
+			let clear_of_bass = |context| {
+				let options = GeneratorOptions {
+					limit: 10,
+					playing_context: context,
+					..Default::default()
+				};
+				generate_fingerings(&chord, &instrument, &options)
+					.iter()
+					.filter(|f| {
+						bass_strings
+							.iter()
+							.all(|&s| !f.fingering.strings()[s].is_played())
+					})
+					.count()
+			};
+			let (band, solo) = (
+				clear_of_bass(PlayingContext::Band),
+				clear_of_bass(PlayingContext::Solo),
+			);
+			assert!(band > 0 && band >= solo, "{}", instrument.name());
+		}
 	}
 
 	#[test]
-	fn test_golden_e7() {
-		assert_in_top_n("E7", "020100", 5);
+	fn test_banjo_fifth_string_stops_above_its_nut() {
+		let banjo = ConfigurableInstrument::banjo();
+		let chord = Chord::parse("A").unwrap();
+		let options = GeneratorOptions {
+			limit: 100,
+			max_fret: 12,
+			..Default::default()
+		};
+		let results = generate_fingerings(&chord, &banjo, &options);
+		assert!(!results.is_empty());
+
+		let mut stopped_fifth = false;
+		for result in &results {
+			let fifth = result.fingering.strings()[0];
+			if let StringState::Fretted(fret) = fifth {
+				assert!(fret == 0 || fret >= 6, "{}", result.fingering);
+				stopped_fifth |= fret > 0;
+			}
+			assert!(result.fingering.validate_for(&banjo).is_empty());
+			let chord_notes = chord.notes();
+			assert!(
+				result
+					.fingering
+					.pitch_classes(&banjo)
+					.iter()
+					.all(|p| chord_notes.contains(p)),
+				"{}",
+				result.fingering
+			);
+		}
+		// The drone can't ring in A, so it's stopped at the 7th fret (A) or muted
+		assert!(stopped_fifth);
 	}
 
+	#[cfg(feature = "rayon")]
 	#[test]
-	fn test_voicing_type_combinations_with_context() {
-		let chord = Chord::parse("Gmaj7").unwrap();
+	fn test_parallel_matches_serial() {
 		let guitar = Guitar::default();
-
-		// Core + Solo
-		let core_solo = GeneratorOptions {
-			limit: 10,
-			voicing_type: Some(VoicingType::Core),
-			playing_context: PlayingContext::Solo,
-			..Default::default()
+		let seven = crate::instrument::ConfigurableInstrument::guitar_7_string();
+		let tabs = |results: Vec<ScoredFingering>| -> Vec<(String, u16)> {
+			results
+				.iter()
+				.map(|f| (f.fingering.to_string(), f.score))
+				.collect()
 		};
-		let results = generate_fingerings(&chord, &guitar, &core_solo);
-		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Core));
 
-		// Core + Band
-		let core_band = GeneratorOptions {
-			limit: 10,
-			voicing_type: Some(VoicingType::Core),
-			playing_context: PlayingContext::Band,
-			..Default::default()
-		};
-		let results = generate_fingerings(&chord, &guitar, &core_band);
-		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Core));
+		for name in ["C", "F#m7b5", "G/B", "Cmaj9"] {
+			let chord = Chord::parse(name).unwrap();
+			let options = GeneratorOptions {
+				limit: 50,
+				..Default::default()
+			};
+			assert_eq!(
+				tabs(generate_fingerings_parallel(&chord, &guitar, &options)),
+				tabs(generate_fingerings(&chord, &guitar, &options)),
+				"{name}"
+			);
+			assert_eq!(
+				tabs(generate_fingerings_parallel(&chord, &seven, &options)),
+				tabs(generate_fingerings(&chord, &seven, &options)),
+				"{name} on 7 strings"
+			);
+		}
+	}
 
-		// Full + Solo
-		let full_solo = GeneratorOptions {
-			limit: 10,
-			voicing_type: Some(VoicingType::Full),
-			playing_context: PlayingContext::Solo,
-			..Default::default()
-		};
-		let results = generate_fingerings(&chord, &guitar, &full_solo);
-		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Full));
+	#[test]
+	fn test_limit_keeps_best_in_full_order() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("Am7").unwrap();
+
+		let all = generate_fingerings(
+			&chord,
+			&guitar,
+			&GeneratorOptions {
+				limit: usize::MAX,
+				..Default::default()
+			},
+		);
+		let top = generate_fingerings(
+			&chord,
+			&guitar,
+			&GeneratorOptions {
+				limit: 5,
+				..Default::default()
+			},
+		);
 
-		// Full + Band
-		let full_band = GeneratorOptions {
-			limit: 10,
-			voicing_type: Some(VoicingType::Full),
-			playing_context: PlayingContext::Band,
-			..Default::default()
+		let strings = |fs: &[ScoredFingering]| -> Vec<String> {
+			fs.iter().map(|f| f.fingering.to_string()).collect()
 		};
-		let results = generate_fingerings(&chord, &guitar, &full_band);
-		assert!(results.iter().all(|f| f.voicing_type == VoicingType::Full));
+		assert_eq!(strings(&top), strings(&all[..5]));
+		assert!(all.windows(2).all(|w| w[0].score >= w[1].score));
 	}
 }
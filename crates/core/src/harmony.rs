@@ -0,0 +1,620 @@
+//! Functional harmony
+//!
+//! A [`RomanNumeral`] names a chord by the scale degree of its root in a key,
+//! upper case for chords with a major third and lower case for minor ones
+//! (I, vi, IV, V7, ii°). Degrees count from the key's own scale, so in a
+//! minor key the relative major is III and Bb in A minor is bII. Roots
+//! outside the scale get an accidental against it.
+//!
+//! Each numeral also has a [`HarmonicFunction`]: the tonic, subdominant and
+//! dominant groups of the key, secondary dominants that lead to another
+//! degree (V7/ii), and chromatic chords outside those roles.
+
+use std::fmt;
+
+use crate::chord::{Chord, ChordQuality};
+use crate::error::{ChordCraftError, Result};
+use crate::interval::{MAJOR_SEVENTH, MAJOR_SIXTH, MAJOR_THIRD, MINOR_THIRD};
+use crate::key::{Key, Mode, detect_key};
+use crate::scale::{Scale, ScaleKind};
+use crate::spelling::Accidentals;
+
+const MAJOR_DEGREES: [(u8, i8); 12] = [
+	(1, 0),
+	(2, -1),
+	(2, 0),
+	(3, -1),
+	(3, 0),
+	(4, 0),
+	(4, 1),
+	(5, 0),
+	(6, -1),
+	(6, 0),
+	(7, -1),
+	(7, 0),
+];
+const MINOR_DEGREES: [(u8, i8); 12] = [
+	(1, 0),
+	(2, -1),
+	(2, 0),
+	(3, 0),
+	(3, 1),
+	(4, 0),
+	(4, 1),
+	(5, 0),
+	(6, 0),
+	(6, 1),
+	(7, 0),
+	(7, 1),
+];
+
+/// A chord as a scale degree of a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RomanNumeral {
+	/// Scale degree of the root (1-7)
+	pub degree: u8,
+	/// Semitones the root sits above (+1) or below (-1) the scale degree
+	pub accidental: i8,
+	pub quality: ChordQuality,
+}
+
+impl RomanNumeral {
+	/// Numeral of a chord in a key; slash basses are ignored
+	pub fn from_chord(chord: &Chord, key: &Key) -> Self {
+		let semitones = key.tonic.semitone_distance_to(&chord.root);
+		let (degree, accidental) = degree_table(key.mode)[semitones as usize];
+		RomanNumeral {
+			degree,
+			accidental,
+			quality: chord.quality,
+		}
+	}
+
+	/// Parse a numeral: "I", "vi", "bVII", "V7", "viiø7", "#iv°", "IVmaj7"
+	///
+	/// Case sets the third (upper major, lower minor) and the figure after it
+	/// the rest of the quality, as written by [`RomanNumeral`]'s Display.
+	pub fn parse(s: &str) -> Result<Self> {
+		let invalid = || ChordCraftError::InvalidChordName(format!("invalid roman numeral '{s}'"));
+		let s = s.trim();
+
+		let (accidental, rest) = if let Some(rest) = s.strip_prefix(['b', '♭']) {
+			(-1, rest)
+		} else if let Some(rest) = s.strip_prefix(['#', '♯']) {
+			(1, rest)
+		} else {
+			(0, s)
+		};
+		let numeral_end = rest
+			.find(|c: char| !matches!(c, 'I' | 'V' | 'i' | 'v'))
+			.unwrap_or(rest.len());
+		let (numeral, figure) = rest.split_at(numeral_end);
+
+		const NUMERALS: [&str; 7] = ["i", "ii", "iii", "iv", "v", "vi", "vii"];
+		let minor = numeral.chars().all(|c| c.is_lowercase());
+		if !minor && !numeral.chars().all(|c| c.is_uppercase()) {
+			return Err(invalid());
+		}
+		let degree = NUMERALS
+			.iter()
+			.position(|n| *n == numeral.to_lowercase())
+			.ok_or_else(invalid)? as u8
+			+ 1;
+
+		let quality = quality_of(minor, figure).ok_or_else(invalid)?;
+		Ok(RomanNumeral {
+			degree,
+			accidental,
+			quality,
+		})
+	}
+
+	/// The chord this numeral stands for in a key, spelled for the key
+	pub fn to_chord(&self, key: &Key) -> Chord {
+		let root = Scale::from(*key)
+			.degree(self.degree as usize)
+			.unwrap_or(key.tonic)
+			.add_semitones(self.accidental as i32);
+		Chord::new(root, self.quality).with_spelling(Accidentals::for_key(key))
+	}
+
+	/// Role of the chord in a key
+	///
+	/// A major or dominant chord with tones outside the key is a secondary
+	/// dominant when it sits a fifth above another degree of the scale (D7 in
+	/// C is V7/V). Other chords on a chromatic root are [`HarmonicFunction::Chromatic`];
+	/// the rest fall in the tonic (I, iii, vi), subdominant (ii, IV) or
+	/// dominant (V, vii°) group by degree.
+	pub fn function(&self, key: &Key) -> HarmonicFunction {
+		let chord = self.to_chord(key);
+		let (required, _) = chord.intervals();
+		let dominant_quality = required.contains(&MAJOR_THIRD)
+			&& !required
+				.iter()
+				.any(|i| *i == MAJOR_SEVENTH || *i == MAJOR_SIXTH);
+		let diatonic = chord.notes().iter().all(|&n| key.contains(n));
+
+		if dominant_quality && !diatonic {
+			let target = chord.root.add_semitones(5);
+			if target != key.tonic
+				&& let Some(degree) = Scale::from(*key).degree_of(target)
+			{
+				return HarmonicFunction::SecondaryDominant {
+					target: diatonic_numeral(degree as u8, key),
+				};
+			}
+		}
+		if self.accidental != 0 {
+			return HarmonicFunction::Chromatic;
+		}
+		match self.degree {
+			1 | 3 | 6 => HarmonicFunction::Tonic,
+			2 | 4 => HarmonicFunction::Subdominant,
+			_ => HarmonicFunction::Dominant,
+		}
+	}
+}
+
+/// What a chord does in a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HarmonicFunction {
+	/// Rest: I, iii, vi
+	Tonic,
+	/// Moving away: ii, IV
+	Subdominant,
+	/// Tension that resolves to the tonic: V, vii°
+	Dominant,
+	/// Dominant of another degree, resolving to it (V/V, V7/ii)
+	SecondaryDominant { target: RomanNumeral },
+	/// Root outside the key with none of the roles above (bVII, bVI)
+	Chromatic,
+}
+
+impl fmt::Display for HarmonicFunction {
+	/// Short label: T, S, D, V/ii or chr
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			HarmonicFunction::Tonic => write!(f, "T"),
+			HarmonicFunction::Subdominant => write!(f, "S"),
+			HarmonicFunction::Dominant => write!(f, "D"),
+			HarmonicFunction::SecondaryDominant { target } => write!(f, "V/{target}"),
+			HarmonicFunction::Chromatic => write!(f, "chr"),
+		}
+	}
+}
+
+/// Numeral of the key's own triad on a scale degree (V is major in minor keys too)
+fn diatonic_numeral(degree: u8, key: &Key) -> RomanNumeral {
+	let scale = match key.mode {
+		Mode::Minor if degree == 5 => Scale::new(key.tonic, ScaleKind::HarmonicMinor),
+		_ => Scale::from(*key),
+	};
+	let tone = |n: u8| scale.degree(n as usize).unwrap_or(key.tonic);
+	let root = tone(degree);
+	let quality = match (
+		root.semitone_distance_to(&tone(degree + 2)),
+		root.semitone_distance_to(&tone(degree + 4)),
+	) {
+		(3, 6) => ChordQuality::Diminished,
+		(4, 8) => ChordQuality::Augmented,
+		(3, _) => ChordQuality::Minor,
+		_ => ChordQuality::Major,
+	};
+	RomanNumeral {
+		degree,
+		accidental: 0,
+		quality,
+	}
+}
+
+/// Quality from the case of a numeral and the figure written after it
+fn quality_of(minor: bool, figure: &str) -> Option<ChordQuality> {
+	use ChordQuality::*;
+	let figure = figure.replace('o', "°").replace("dim", "°");
+	let quality = if minor {
+		match figure.as_str() {
+			"" | "m" => Minor,
+			"°" => Diminished,
+			"7" => Minor7,
+			"(maj7)" | "maj7" => MinorMajor7,
+			"°7" => Diminished7,
+			"ø" | "ø7" => HalfDiminished7,
+			"9" => Minor9,
+			"11" => Minor11,
+			"13" => Minor13,
+			"add9" => MinorAdd9,
+			"6" => Minor6,
+			_ => return None,
+		}
+	} else {
+		match figure.as_str() {
+			"" => Major,
+			"+" | "aug" => Augmented,
+			"sus2" => Sus2,
+			"sus4" | "sus" => Sus4,
+			"7" => Dominant7,
+			"maj7" => Major7,
+			"9" => Dominant9,
+			"maj9" => Major9,
+			"11" => Dominant11,
+			"13" => Dominant13,
+			"maj13" => Major13,
+			"7b9" => Dominant7b9,
+			"7#9" => Dominant7sharp9,
+			"7b5" => Dominant7b5,
+			"+7" | "7#5" => Dominant7sharp5,
+			"add9" => Add9,
+			"add11" => Add11,
+			"6" => Major6,
+			"6/9" => SixNine,
+			"5" => Power,
+			"9sus4" => Dominant9sus4,
+			"maj7#11" => Major7sharp11,
+			"7alt" => Dominant7alt,
+			_ => return None,
+		}
+	};
+	Some(quality)
+}
+
+/// Chords for a sequence of numerals in a key
+///
+/// A numeral followed by `/` and another numeral is a secondary chord, read
+/// in the key of the chord it resolves to: V7/V in C is D7.
+pub fn resolve_numerals(numerals: &[&str], key: &Key) -> Result<Vec<Chord>> {
+	numerals
+		.iter()
+		.map(|numeral| {
+			match numeral
+				.split_once('/')
+				.filter(|(_, target)| !target.starts_with('9'))
+			{
+				Some((chord, target)) => {
+					let target = RomanNumeral::parse(target)?;
+					let mode = if has_minor_third(target.quality) {
+						Mode::Minor
+					} else {
+						Mode::Major
+					};
+					let local = Key::new(target.to_chord(key).root, mode);
+					Ok(RomanNumeral::parse(chord)?
+						.to_chord(&local)
+						.with_spelling(Accidentals::for_key(key)))
+				}
+				None => Ok(RomanNumeral::parse(numeral)?.to_chord(key)),
+			}
+		})
+		.collect()
+}
+
+/// Degree and accidental of each semitone above the tonic
+///
+/// Chromatic roots follow common usage: flattened degrees in major except
+/// #IV, raised degrees in minor except bII.
+fn degree_table(mode: Mode) -> &'static [(u8, i8); 12] {
+	match mode {
+		Mode::Major => &MAJOR_DEGREES,
+		Mode::Minor => &MINOR_DEGREES,
+	}
+}
+
+/// Whether a quality is built on a minor third
+fn has_minor_third(quality: ChordQuality) -> bool {
+	use ChordQuality::*;
+	if let Custom(_) = quality {
+		let (required, _) = quality.intervals();
+		return required.contains(&MINOR_THIRD) && !required.contains(&MAJOR_THIRD);
+	}
+	matches!(
+		quality,
+		Minor
+			| Diminished
+			| Minor7 | MinorMajor7
+			| Diminished7
+			| HalfDiminished7
+			| Minor9 | Minor11
+			| Minor13 | MinorAdd9
+			| Minor6
+	)
+}
+
+/// Figure written after the numeral
+fn figure(quality: ChordQuality) -> &'static str {
+	use ChordQuality::*;
+	match quality {
+		Major | Minor => "",
+		Diminished => "°",
+		Augmented => "+",
+		Sus2 => "sus2",
+		Sus4 => "sus4",
+		Dominant7 | Minor7 => "7",
+		Major7 => "maj7",
+		MinorMajor7 => "(maj7)",
+		Diminished7 => "°7",
+		HalfDiminished7 => "ø7",
+		Dominant9 | Minor9 => "9",
+		Major9 => "maj9",
+		Dominant11 | Minor11 => "11",
+		Dominant13 | Minor13 => "13",
+		Major13 => "maj13",
+		Dominant7b9 => "7b9",
+		Dominant7sharp9 => "7#9",
+		Dominant7b5 => "7b5",
+		Dominant7sharp5 => "+7",
+		Add9 | MinorAdd9 => "add9",
+		Add11 => "add11",
+		Major6 | Minor6 => "6",
+		SixNine => "6/9",
+		Power => "5",
+		Dominant9sus4 => "9sus4",
+		Major7sharp11 => "maj7#11",
+		Dominant7alt => "7alt",
+		Custom(_) => quality.display_name(),
+	}
+}
+
+impl fmt::Display for RomanNumeral {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		const NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+		let accidental = match self.accidental {
+			a if a < 0 => "b",
+			a if a > 0 => "#",
+			_ => "",
+		};
+		let numeral = NUMERALS[(self.degree.clamp(1, 7) - 1) as usize];
+		if has_minor_third(self.quality) {
+			write!(
+				f,
+				"{accidental}{}{}",
+				numeral.to_lowercase(),
+				figure(self.quality)
+			)
+		} else {
+			write!(f, "{accidental}{numeral}{}", figure(self.quality))
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RomanNumeral {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RomanNumeral {
+	fn deserialize<D: serde::Deserializer<'de>>(
+		deserializer: D,
+	) -> std::result::Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		RomanNumeral::parse(&s).map_err(serde::de::Error::custom)
+	}
+}
+
+/// Roman numerals of a chord sequence in a key
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumeralAnalysis {
+	pub key: Key,
+	/// One numeral per chord
+	pub numerals: Vec<RomanNumeral>,
+}
+
+impl NumeralAnalysis {
+	/// Numerals as display labels (e.g., "I", "vi", "V7")
+	pub fn labels(&self) -> Vec<String> {
+		self.numerals.iter().map(|n| n.to_string()).collect()
+	}
+
+	/// Harmonic function of each chord
+	pub fn functions(&self) -> Vec<HarmonicFunction> {
+		self.numerals
+			.iter()
+			.map(|n| n.function(&self.key))
+			.collect()
+	}
+
+	/// Cadences in the sequence, with the index of the chord each one lands on
+	///
+	/// V or vii° into I is authentic, IV into I plagal, V into vi deceptive;
+	/// a sequence ending on V closes with a half cadence.
+	pub fn cadences(&self) -> Vec<(usize, Cadence)> {
+		let degree = |n: &RomanNumeral| (n.accidental == 0).then_some(n.degree);
+		let mut cadences: Vec<(usize, Cadence)> = self
+			.numerals
+			.windows(2)
+			.enumerate()
+			.filter_map(|(i, pair)| {
+				let cadence = match (degree(&pair[0])?, degree(&pair[1])?) {
+					(5 | 7, 1) => Cadence::Authentic,
+					(4, 1) => Cadence::Plagal,
+					(5, 6) => Cadence::Deceptive,
+					_ => return None,
+				};
+				Some((i + 1, cadence))
+			})
+			.collect();
+		if let Some(last) = self.numerals.last()
+			&& degree(last) == Some(5)
+		{
+			cadences.push((self.numerals.len() - 1, Cadence::Half));
+		}
+		cadences
+	}
+}
+
+/// Closing gesture between two chords
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cadence {
+	/// Dominant resolving to the tonic: V-I
+	Authentic,
+	/// Subdominant resolving to the tonic: IV-I
+	Plagal,
+	/// Ending on the dominant, left unresolved
+	Half,
+	/// Dominant moving to vi instead of the tonic
+	Deceptive,
+}
+
+impl fmt::Display for Cadence {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Cadence::Authentic => write!(f, "authentic"),
+			Cadence::Plagal => write!(f, "plagal"),
+			Cadence::Half => write!(f, "half"),
+			Cadence::Deceptive => write!(f, "deceptive"),
+		}
+	}
+}
+
+/// Label chords with roman numerals in `key`, or in their detected key when None
+///
+/// Returns None only if no key is given and the sequence is empty.
+pub fn analyze_numerals(chords: &[Chord], key: Option<Key>) -> Option<NumeralAnalysis> {
+	let key = match key {
+		Some(key) => key,
+		None => detect_key(chords)?.key,
+	};
+	Some(NumeralAnalysis {
+		key,
+		numerals: chords
+			.iter()
+			.map(|chord| RomanNumeral::from_chord(chord, &key))
+			.collect(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::note::PitchClass;
+
+	fn labels(names: &str, key: &str) -> Vec<String> {
+		let chords: Vec<Chord> = names
+			.split_whitespace()
+			.map(|n| Chord::parse(n).unwrap())
+			.collect();
+		analyze_numerals(&chords, Some(Key::parse(key).unwrap()))
+			.unwrap()
+			.labels()
+	}
+
+	#[test]
+	fn test_numerals_in_major_and_minor() {
+		assert_eq!(labels("C Am F G7", "C"), ["I", "vi", "IV", "V7"]);
+		assert_eq!(
+			labels("Dm7 G7 Cmaj7 Bm7b5", "C"),
+			["ii7", "V7", "Imaj7", "viiø7"]
+		);
+		assert_eq!(
+			labels("Am Dm E7 C F G", "Am"),
+			["i", "iv", "V7", "III", "VI", "VII"]
+		);
+		assert_eq!(labels("Bdim", "Am"), ["ii°"]);
+	}
+
+	#[test]
+	fn test_chromatic_numerals() {
+		assert_eq!(
+			labels("Bb Ab Eb F#dim", "C"),
+			["bVII", "bVI", "bIII", "#iv°"]
+		);
+		assert_eq!(labels("Bb G#dim7 F#m", "Am"), ["bII", "#vii°7", "#vi"]);
+	}
+
+	#[test]
+	fn test_parse_and_resolve_numerals() {
+		for numeral in [
+			"I", "vi", "bVII", "V7", "viiø7", "#iv°", "IVmaj7", "i(maj7)",
+		] {
+			assert_eq!(RomanNumeral::parse(numeral).unwrap().to_string(), numeral);
+		}
+		assert!(RomanNumeral::parse("IIII").is_err());
+		assert!(RomanNumeral::parse("Vi").is_err());
+		assert!(RomanNumeral::parse("V°").is_err());
+
+		let names = |numerals: &[&str], key: &str| -> Vec<String> {
+			resolve_numerals(numerals, &Key::parse(key).unwrap())
+				.unwrap()
+				.iter()
+				.map(|c| c.to_string())
+				.collect()
+		};
+		assert_eq!(names(&["I", "vi", "IV", "V"], "G"), ["G", "Em", "C", "D"]);
+		assert_eq!(
+			names(&["I", "IV", "bVII", "V7/V"], "F"),
+			["F", "Bb", "Eb", "G7"]
+		);
+		assert_eq!(
+			names(&["i", "VI", "III", "VII"], "Am"),
+			["Am", "F", "C", "G"]
+		);
+		assert_eq!(names(&["V7/ii", "ii"], "C"), ["A7", "Dm"]);
+	}
+
+	#[test]
+	fn test_harmonic_functions() {
+		let functions = |names: &str, key: &str| -> Vec<String> {
+			let chords: Vec<Chord> = names
+				.split_whitespace()
+				.map(|n| Chord::parse(n).unwrap())
+				.collect();
+			analyze_numerals(&chords, Some(Key::parse(key).unwrap()))
+				.unwrap()
+				.functions()
+				.iter()
+				.map(|f| f.to_string())
+				.collect()
+		};
+		assert_eq!(
+			functions("C Em Am Dm F G7 Bdim", "C"),
+			["T", "T", "T", "S", "S", "D", "D"]
+		);
+		assert_eq!(
+			functions("C A7 Dm D7 G7 C7 F", "C"),
+			["T", "V/ii", "S", "V/V", "D", "V/IV", "S"]
+		);
+		assert_eq!(functions("Bb Ab", "C"), ["chr", "chr"]);
+		assert_eq!(functions("Am Dm E7 B7", "Am"), ["T", "S", "D", "V/V"]);
+	}
+
+	#[test]
+	fn test_cadences() {
+		let cadences = |names: &str, key: &str| {
+			let chords: Vec<Chord> = names
+				.split_whitespace()
+				.map(|n| Chord::parse(n).unwrap())
+				.collect();
+			analyze_numerals(&chords, Some(Key::parse(key).unwrap()))
+				.unwrap()
+				.cadences()
+		};
+		assert_eq!(cadences("C F G7 C", "C"), [(3, Cadence::Authentic)]);
+		assert_eq!(
+			cadences("C G Am F C", "C"),
+			[(2, Cadence::Deceptive), (4, Cadence::Plagal)]
+		);
+		assert_eq!(cadences("Am Dm E7", "Am"), [(2, Cadence::Half)]);
+		assert_eq!(cadences("Am E7 Am", "Am"), [(2, Cadence::Authentic)]);
+		assert!(cadences("C Bb F", "C").is_empty());
+	}
+
+	#[test]
+	fn test_detected_key() {
+		let chords: Vec<Chord> = ["G", "Em", "C", "D"]
+			.iter()
+			.map(|n| Chord::parse(n).unwrap())
+			.collect();
+		let analysis = analyze_numerals(&chords, None).unwrap();
+		assert_eq!(analysis.key, Key::new(PitchClass::G, Mode::Major));
+		assert_eq!(analysis.labels(), ["I", "vi", "IV", "V"]);
+		assert!(analyze_numerals(&[], None).is_none());
+	}
+}
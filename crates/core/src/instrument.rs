@@ -1,7 +1,10 @@
 //! Instrument models and abstractions
 //!
 //! This module defines the Instrument trait and specific instrument implementations
-//! like Guitar, Bass, Ukulele, etc.
+//! like Guitar, Bass, Ukulele, etc. Built-in instruments can be listed with
+//! [`presets`] and looked up by name with [`ConfigurableInstrument::by_name`].
+
+use std::fmt;
 
 use crate::error::{ChordCraftError, Result};
 use crate::note::Note;
@@ -48,6 +51,32 @@ pub trait Instrument {
 		0
 	}
 
+	/// Frets a string can be stopped at, counted on the neck; open strings always ring
+	///
+	/// Default: the whole neck ([`fret_range`](Self::fret_range)). A range
+	/// starting above the 1st fret is a short string with its own nut just
+	/// below it, like the banjo's fifth string: its nut sits at the 5th fret,
+	/// it's stopped from the 6th, and at the 7th it sounds two semitones above
+	/// open.
+	fn string_fret_range(&self, _string: usize) -> (u8, u8) {
+		self.fret_range()
+	}
+
+	/// Neck fret a string's nut sits at: 0, or higher for a short string (see
+	/// [`string_fret_range`](Self::string_fret_range))
+	fn string_nut_fret(&self, string: usize) -> u8 {
+		self.string_fret_range(string).0.saturating_sub(1)
+	}
+
+	/// Second string of each course on doubled-course instruments, in tuning order
+	///
+	/// On a 12-string guitar or a mandolin each entry of [`tuning`](Self::tuning)
+	/// is a course: two strings fretted together as one. The partner sounds
+	/// in unison or an octave up; None marks a single string.
+	fn course_pairs(&self) -> Vec<Option<Note>> {
+		vec![None; self.string_count()]
+	}
+
 	/// Returns indices of strings whose open note is in the bass register (below C3).
 	///
 	/// This is used for band mode scoring - when playing with a bass player,
@@ -74,33 +103,125 @@ pub trait Instrument {
 	}
 }
 
+/// Lets borrowed and trait-object instruments (`&dyn Instrument`) be passed
+/// where an instrument is expected, e.g. to mix instrument types in one list
+impl<I: Instrument + ?Sized> Instrument for &I {
+	fn tuning(&self) -> &[Note] {
+		(**self).tuning()
+	}
+
+	fn fret_range(&self) -> (u8, u8) {
+		(**self).fret_range()
+	}
+
+	fn max_stretch(&self) -> u8 {
+		(**self).max_stretch()
+	}
+
+	fn string_count(&self) -> usize {
+		(**self).string_count()
+	}
+
+	fn max_fingers(&self) -> u8 {
+		(**self).max_fingers()
+	}
+
+	fn open_position_threshold(&self) -> u8 {
+		(**self).open_position_threshold()
+	}
+
+	fn main_barre_threshold(&self) -> usize {
+		(**self).main_barre_threshold()
+	}
+
+	fn min_played_strings(&self) -> usize {
+		(**self).min_played_strings()
+	}
+
+	fn max_capo_fret(&self) -> u8 {
+		(**self).max_capo_fret()
+	}
+
+	fn string_names(&self) -> Vec<String> {
+		(**self).string_names()
+	}
+
+	fn bass_string_index(&self) -> usize {
+		(**self).bass_string_index()
+	}
+
+	fn string_fret_range(&self, string: usize) -> (u8, u8) {
+		(**self).string_fret_range(string)
+	}
+
+	fn string_nut_fret(&self, string: usize) -> u8 {
+		(**self).string_nut_fret(string)
+	}
+
+	fn course_pairs(&self) -> Vec<Option<Note>> {
+		(**self).course_pairs()
+	}
+
+	fn bass_string_indices(&self) -> Option<Vec<usize>> {
+		(**self).bass_string_indices()
+	}
+}
+
 /// Transposes tuning up and reduces fret range. Delegates other properties to inner instrument.
 #[derive(Debug, Clone)]
 pub struct CapoedInstrument<I: Instrument> {
 	inner: I,
+	capo: u8,
 	tuning: Vec<Note>,
+	course_pairs: Vec<Option<Note>>,
 	fret_range: (u8, u8),
 }
 
 impl<I: Instrument> CapoedInstrument<I> {
 	pub fn new(instrument: I, fret: u8) -> Result<Self> {
-		let max_capo = instrument.max_capo_fret();
+		// A short string passes under the capo untouched, so the capo has to
+		// stay below its nut
+		let short_nut = (0..instrument.string_count())
+			.map(|string| instrument.string_nut_fret(string))
+			.filter(|&nut| nut > 0)
+			.min();
+		let max_capo = short_nut.map_or(instrument.max_capo_fret(), |nut| {
+			instrument.max_capo_fret().min(nut - 1)
+		});
 
 		if fret > max_capo {
 			return Err(ChordCraftError::InvalidCapoPosition(fret, 0, max_capo));
 		}
 
+		let transpose = |string: usize, note: Note| {
+			if instrument.string_nut_fret(string) > 0 {
+				note
+			} else {
+				note.add_semitones(fret as i32)
+			}
+		};
+
 		let tuning: Vec<Note> = instrument
 			.tuning()
 			.iter()
-			.map(|note| note.add_semitones(fret as i32))
+			.enumerate()
+			.map(|(string, &note)| transpose(string, note))
+			.collect();
+
+		let course_pairs = instrument
+			.course_pairs()
+			.into_iter()
+			.enumerate()
+			.map(|(string, pair)| pair.map(|note| transpose(string, note)))
 			.collect();
 
 		let fret_range = (0, instrument.fret_range().1.saturating_sub(fret));
 
 		Ok(CapoedInstrument {
 			inner: instrument,
+			capo: fret,
 			tuning,
+			course_pairs,
 			fret_range,
 		})
 	}
@@ -146,6 +267,20 @@ impl<I: Instrument> Instrument for CapoedInstrument<I> {
 	fn bass_string_index(&self) -> usize {
 		self.inner.bass_string_index()
 	}
+
+	/// Frets count from the capo, so a short string's range and nut move down
+	/// with it while its open pitch stays put
+	fn string_fret_range(&self, string: usize) -> (u8, u8) {
+		let (low, high) = self.inner.string_fret_range(string);
+		(
+			low.saturating_sub(self.capo),
+			high.saturating_sub(self.capo),
+		)
+	}
+
+	fn course_pairs(&self) -> Vec<Option<Note>> {
+		self.course_pairs.clone()
+	}
 }
 
 /// A fully configurable instrument where all parameters can be set.
@@ -172,19 +307,48 @@ impl<I: Instrument> Instrument for CapoedInstrument<I> {
 ///     .build()
 ///     .unwrap();
 /// ```
-#[derive(Debug, Clone)]
+///
+/// With the `serde` feature an instrument is stored with the builder's field
+/// names, leaving out overrides that aren't set, so it can live in a config
+/// file. Loading one goes through [`ConfigurableInstrumentBuilder::build`],
+/// and the tuning may be written as a string such as `"DADGAD"`:
+///
+/// ```toml
+/// name = "Tenor Banjo"
+/// tuning = "C3 G3 D4 A4"
+/// fret_range = [0, 19]
+/// max_stretch = 4
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(try_from = "ConfigurableInstrumentBuilder")
+)]
 pub struct ConfigurableInstrument {
 	name: String,
 	tuning: Vec<Note>,
 	fret_range: (u8, u8),
 	max_stretch: u8,
 	// Optional overrides (None = use default formula/value)
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	max_fingers: Option<u8>,
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	open_position_threshold: Option<u8>,
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	main_barre_threshold: Option<usize>,
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	min_played_strings: Option<usize>,
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	bass_string_index: Option<usize>,
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	string_names: Option<Vec<String>>,
+	/// Partner string of each course, for doubled-course instruments
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+	course_pairs: Option<Vec<Option<Note>>>,
+	/// Frets each string can be stopped at, for short strings
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+	string_fret_ranges: Option<Vec<(u8, u8)>>,
 }
 
 impl ConfigurableInstrument {
@@ -203,8 +367,115 @@ impl ConfigurableInstrument {
 		&self.name
 	}
 
+	/// Look up a built-in instrument by name or alias, case-insensitively
+	///
+	/// Names are those listed by [`presets`]: `"banjo"`, `"drop-d"`,
+	/// `"guitar-12"`, with aliases such as `"guitar-drop-d"`. Spaces and
+	/// underscores read as dashes.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use chordcraft_core::instrument::{ConfigurableInstrument, Instrument};
+	///
+	/// let banjo = ConfigurableInstrument::by_name("banjo").unwrap();
+	/// assert_eq!(banjo.string_count(), 5);
+	/// assert_eq!(
+	///     ConfigurableInstrument::by_name("Guitar Drop D"),
+	///     Some(ConfigurableInstrument::guitar_drop_d())
+	/// );
+	/// ```
+	pub fn by_name(name: &str) -> Option<Self> {
+		preset(name).map(InstrumentPreset::instrument)
+	}
+
 	// ==================== INSTRUMENT PRESETS ====================
 
+	/// Standard 6-string guitar (E2-A2-D3-G3-B3-E4), as [`Guitar`]
+	pub fn guitar() -> Self {
+		use crate::note::PitchClass::*;
+		ConfigurableInstrument {
+			name: "Guitar".to_string(),
+			tuning: vec![
+				Note::new(E, 2),
+				Note::new(A, 2),
+				Note::new(D, 3),
+				Note::new(G, 3),
+				Note::new(B, 3),
+				Note::new(E, 4),
+			],
+			fret_range: (0, 24),
+			max_stretch: 4,
+			max_fingers: None,
+			open_position_threshold: None,
+			main_barre_threshold: None,
+			min_played_strings: None,
+			bass_string_index: None,
+			string_names: Some(vec![
+				"E".to_string(),
+				"A".to_string(),
+				"D".to_string(),
+				"G".to_string(),
+				"B".to_string(),
+				"e".to_string(),
+			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// Standard re-entrant ukulele (G4-C4-E4-A4), as [`Ukulele`]
+	pub fn ukulele() -> Self {
+		Self::ukulele_sized(UkuleleSize::Soprano, false)
+	}
+
+	/// Ukulele of any size, re-entrant (G4-C4-E4-A4) or low G (G3-C4-E4-A4)
+	///
+	/// Low G makes the tuning linear: the G string is the lowest, so it
+	/// carries the bass instead of the C string.
+	pub fn ukulele_sized(size: UkuleleSize, low_g: bool) -> Self {
+		use crate::note::PitchClass::*;
+		let (first, name) = if low_g {
+			(Note::new(G, 3), format!("{size} (Low G)"))
+		} else {
+			(Note::new(G, 4), size.to_string())
+		};
+		ConfigurableInstrument {
+			name,
+			tuning: vec![first, Note::new(C, 4), Note::new(E, 4), Note::new(A, 4)],
+			fret_range: (0, size.frets()),
+			max_stretch: size.max_stretch(),
+			max_fingers: None,
+			open_position_threshold: Some(5),
+			main_barre_threshold: Some(2),
+			min_played_strings: Some(1),
+			bass_string_index: (!low_g).then_some(1),
+			string_names: None,
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// Low-G ukulele (G3-C4-E4-A4), with a linear tuning
+	pub fn ukulele_low_g() -> Self {
+		Self::ukulele_sized(UkuleleSize::Soprano, true)
+	}
+
+	/// Concert ukulele (G4-C4-E4-A4), a longer neck than the soprano's
+	pub fn concert_ukulele() -> Self {
+		Self::ukulele_sized(UkuleleSize::Concert, false)
+	}
+
+	/// Tenor ukulele (G4-C4-E4-A4)
+	pub fn tenor_ukulele() -> Self {
+		Self::ukulele_sized(UkuleleSize::Tenor, false)
+	}
+
+	/// Tenor ukulele strung low G (G3-C4-E4-A4)
+	pub fn tenor_ukulele_low_g() -> Self {
+		Self::ukulele_sized(UkuleleSize::Tenor, true)
+	}
+
 	/// Standard 4-string bass guitar (E1-A1-D2-G2)
 	pub fn bass() -> Self {
 		use crate::note::PitchClass::*;
@@ -229,6 +500,8 @@ impl ConfigurableInstrument {
 				"D".to_string(),
 				"G".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
 		}
 	}
 
@@ -258,10 +531,12 @@ impl ConfigurableInstrument {
 				"D".to_string(),
 				"G".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
 		}
 	}
 
-	/// Standard mandolin (G3-D4-A4-E5)
+	/// Standard mandolin (G3-D4-A4-E5), in doubled courses
 	pub fn mandolin() -> Self {
 		use crate::note::PitchClass::*;
 		ConfigurableInstrument {
@@ -285,6 +560,14 @@ impl ConfigurableInstrument {
 				"A".to_string(),
 				"E".to_string(),
 			]),
+			// Four courses of unison pairs
+			course_pairs: Some(vec![
+				Some(Note::new(G, 3)),
+				Some(Note::new(D, 4)),
+				Some(Note::new(A, 4)),
+				Some(Note::new(E, 5)),
+			]),
+			string_fret_ranges: None,
 		}
 	}
 
@@ -314,6 +597,9 @@ impl ConfigurableInstrument {
 				"B".to_string(),
 				"d".to_string(),
 			]),
+			course_pairs: None,
+			// The short fifth string's nut is at the 5th fret
+			string_fret_ranges: Some(vec![(6, 22), (0, 22), (0, 22), (0, 22), (0, 22)]),
 		}
 	}
 
@@ -341,6 +627,8 @@ impl ConfigurableInstrument {
 				"B".to_string(),
 				"E".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
 		}
 	}
 
@@ -374,6 +662,152 @@ impl ConfigurableInstrument {
 				"B".to_string(),
 				"e".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// 8-string guitar (F#1-B1-E2-A2-D3-G3-B3-E4)
+	pub fn guitar_8_string() -> Self {
+		use crate::note::PitchClass::*;
+		ConfigurableInstrument {
+			name: "Guitar (8-string)".to_string(),
+			tuning: vec![
+				Note::new(FSharp, 1),
+				Note::new(B, 1),
+				Note::new(E, 2),
+				Note::new(A, 2),
+				Note::new(D, 3),
+				Note::new(G, 3),
+				Note::new(B, 3),
+				Note::new(E, 4),
+			],
+			fret_range: (0, 24),
+			max_stretch: 4,
+			max_fingers: None,
+			open_position_threshold: None,
+			main_barre_threshold: None,
+			min_played_strings: None,
+			bass_string_index: None,
+			string_names: Some(vec![
+				"F#".to_string(),
+				"B".to_string(),
+				"E".to_string(),
+				"A".to_string(),
+				"D".to_string(),
+				"G".to_string(),
+				"B".to_string(),
+				"e".to_string(),
+			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// Baritone guitar in B standard (B1-E2-A2-D3-F#3-B3), a fourth below a guitar
+	pub fn baritone_guitar() -> Self {
+		use crate::note::PitchClass::*;
+		ConfigurableInstrument {
+			name: "Baritone Guitar (B)".to_string(),
+			tuning: vec![
+				Note::new(B, 1),
+				Note::new(E, 2),
+				Note::new(A, 2),
+				Note::new(D, 3),
+				Note::new(FSharp, 3),
+				Note::new(B, 3),
+			],
+			fret_range: (0, 24),
+			max_stretch: 4,
+			max_fingers: None,
+			open_position_threshold: None,
+			main_barre_threshold: None,
+			min_played_strings: None,
+			bass_string_index: None,
+			string_names: Some(vec![
+				"B".to_string(),
+				"E".to_string(),
+				"A".to_string(),
+				"D".to_string(),
+				"F#".to_string(),
+				"b".to_string(),
+			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// Baritone guitar in A standard (A1-D2-G2-C3-E3-A3), a fifth below a guitar
+	pub fn baritone_guitar_a() -> Self {
+		use crate::note::PitchClass::*;
+		ConfigurableInstrument {
+			name: "Baritone Guitar (A)".to_string(),
+			tuning: vec![
+				Note::new(A, 1),
+				Note::new(D, 2),
+				Note::new(G, 2),
+				Note::new(C, 3),
+				Note::new(E, 3),
+				Note::new(A, 3),
+			],
+			fret_range: (0, 24),
+			max_stretch: 4,
+			max_fingers: None,
+			open_position_threshold: None,
+			main_barre_threshold: None,
+			min_played_strings: None,
+			bass_string_index: None,
+			string_names: Some(vec![
+				"A".to_string(),
+				"D".to_string(),
+				"G".to_string(),
+				"C".to_string(),
+				"E".to_string(),
+				"a".to_string(),
+			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+
+	/// 12-string guitar: six courses, the lower four with an octave string
+	/// and the B and E courses in unison
+	pub fn guitar_12_string() -> Self {
+		use crate::note::PitchClass::*;
+		ConfigurableInstrument {
+			name: "Guitar (12-string)".to_string(),
+			tuning: vec![
+				Note::new(E, 2),
+				Note::new(A, 2),
+				Note::new(D, 3),
+				Note::new(G, 3),
+				Note::new(B, 3),
+				Note::new(E, 4),
+			],
+			fret_range: (0, 20),
+			max_stretch: 4,
+			max_fingers: None,
+			open_position_threshold: None,
+			main_barre_threshold: None,
+			min_played_strings: None,
+			bass_string_index: None,
+			string_names: Some(vec![
+				"E".to_string(),
+				"A".to_string(),
+				"D".to_string(),
+				"G".to_string(),
+				"B".to_string(),
+				"e".to_string(),
+			]),
+			course_pairs: Some(vec![
+				Some(Note::new(E, 3)),
+				Some(Note::new(A, 3)),
+				Some(Note::new(D, 4)),
+				Some(Note::new(G, 4)),
+				Some(Note::new(B, 3)),
+				Some(Note::new(E, 4)),
+			]),
+			string_fret_ranges: None,
 		}
 	}
 
@@ -405,6 +839,8 @@ impl ConfigurableInstrument {
 				"B".to_string(),
 				"e".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
 		}
 	}
 
@@ -436,6 +872,8 @@ impl ConfigurableInstrument {
 				"B".to_string(),
 				"d".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
 		}
 	}
 
@@ -467,10 +905,204 @@ impl ConfigurableInstrument {
 				"A".to_string(),
 				"d".to_string(),
 			]),
+			course_pairs: None,
+			string_fret_ranges: None,
+		}
+	}
+}
+
+/// Ukulele body size, smallest first: bigger ones have longer necks and
+/// frets further apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum UkuleleSize {
+	#[default]
+	Soprano,
+	Concert,
+	Tenor,
+}
+
+impl UkuleleSize {
+	/// Last fret of a typical neck
+	pub fn frets(self) -> u8 {
+		match self {
+			UkuleleSize::Soprano => 15,
+			UkuleleSize::Concert => 18,
+			UkuleleSize::Tenor => 19,
+		}
+	}
+
+	/// Frets the hand can span
+	pub fn max_stretch(self) -> u8 {
+		match self {
+			UkuleleSize::Soprano | UkuleleSize::Concert => 5,
+			UkuleleSize::Tenor => 4,
+		}
+	}
+}
+
+impl fmt::Display for UkuleleSize {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			UkuleleSize::Soprano => write!(f, "Ukulele"),
+			UkuleleSize::Concert => write!(f, "Concert Ukulele"),
+			UkuleleSize::Tenor => write!(f, "Tenor Ukulele"),
 		}
 	}
 }
 
+/// A built-in instrument, as listed by [`presets`]
+#[derive(Debug, Clone, Copy)]
+pub struct InstrumentPreset {
+	/// Lookup name, lowercase and dashed (e.g., "drop-d")
+	pub name: &'static str,
+	/// Other names it's found by (e.g., "guitar-drop-d")
+	pub aliases: &'static [&'static str],
+	/// One-line description for menus and help text
+	pub description: &'static str,
+	build: fn() -> ConfigurableInstrument,
+}
+
+impl InstrumentPreset {
+	/// Build the instrument
+	pub fn instrument(&self) -> ConfigurableInstrument {
+		(self.build)()
+	}
+
+	fn matches(&self, name: &str) -> bool {
+		self.name == name || self.aliases.contains(&name)
+	}
+}
+
+const PRESETS: &[InstrumentPreset] = &[
+	InstrumentPreset {
+		name: "guitar",
+		aliases: &["guitar-standard"],
+		description: "Standard 6-string guitar (EADGBE tuning)",
+		build: ConfigurableInstrument::guitar,
+	},
+	InstrumentPreset {
+		name: "ukulele",
+		aliases: &["uke"],
+		description: "Standard ukulele (GCEA tuning)",
+		build: ConfigurableInstrument::ukulele,
+	},
+	InstrumentPreset {
+		name: "ukulele-low-g",
+		aliases: &["low-g-ukulele", "uke-low-g"],
+		description: "Low-G ukulele (GCEA tuning, linear)",
+		build: ConfigurableInstrument::ukulele_low_g,
+	},
+	InstrumentPreset {
+		name: "concert-ukulele",
+		aliases: &["ukulele-concert"],
+		description: "Concert ukulele (GCEA tuning, 18 frets)",
+		build: ConfigurableInstrument::concert_ukulele,
+	},
+	InstrumentPreset {
+		name: "tenor-ukulele",
+		aliases: &["ukulele-tenor"],
+		description: "Tenor ukulele (GCEA tuning, 19 frets)",
+		build: ConfigurableInstrument::tenor_ukulele,
+	},
+	InstrumentPreset {
+		name: "tenor-ukulele-low-g",
+		aliases: &["ukulele-tenor-low-g"],
+		description: "Tenor ukulele strung low G (GCEA tuning, linear)",
+		build: ConfigurableInstrument::tenor_ukulele_low_g,
+	},
+	InstrumentPreset {
+		name: "baritone-ukulele",
+		aliases: &["bari-uke"],
+		description: "Baritone ukulele (DGBE tuning)",
+		build: ConfigurableInstrument::baritone_ukulele,
+	},
+	InstrumentPreset {
+		name: "bass",
+		aliases: &["bass-4"],
+		description: "4-string bass guitar (EADG tuning)",
+		build: ConfigurableInstrument::bass,
+	},
+	InstrumentPreset {
+		name: "bass-5",
+		aliases: &["bass5"],
+		description: "5-string bass guitar (BEADG tuning)",
+		build: ConfigurableInstrument::bass_5_string,
+	},
+	InstrumentPreset {
+		name: "mandolin",
+		aliases: &[],
+		description: "Standard mandolin (GDAE tuning)",
+		build: ConfigurableInstrument::mandolin,
+	},
+	InstrumentPreset {
+		name: "banjo",
+		aliases: &[],
+		description: "5-string banjo (gDGBD tuning)",
+		build: ConfigurableInstrument::banjo,
+	},
+	InstrumentPreset {
+		name: "guitar-7",
+		aliases: &["guitar7"],
+		description: "7-string guitar (BEADGBE tuning)",
+		build: ConfigurableInstrument::guitar_7_string,
+	},
+	InstrumentPreset {
+		name: "guitar-8",
+		aliases: &["guitar8"],
+		description: "8-string guitar (F#BEADGBE tuning)",
+		build: ConfigurableInstrument::guitar_8_string,
+	},
+	InstrumentPreset {
+		name: "baritone-guitar",
+		aliases: &["baritone-guitar-b", "bari-guitar"],
+		description: "Baritone guitar (BEADF#B tuning)",
+		build: ConfigurableInstrument::baritone_guitar,
+	},
+	InstrumentPreset {
+		name: "baritone-guitar-a",
+		aliases: &["bari-guitar-a"],
+		description: "Baritone guitar (ADGCEA tuning)",
+		build: ConfigurableInstrument::baritone_guitar_a,
+	},
+	InstrumentPreset {
+		name: "guitar-12",
+		aliases: &["guitar12"],
+		description: "12-string guitar (EADGBE in doubled courses)",
+		build: ConfigurableInstrument::guitar_12_string,
+	},
+	InstrumentPreset {
+		name: "drop-d",
+		aliases: &["guitar-drop-d"],
+		description: "Drop D guitar (DADGBE tuning)",
+		build: ConfigurableInstrument::guitar_drop_d,
+	},
+	InstrumentPreset {
+		name: "open-g",
+		aliases: &["guitar-open-g"],
+		description: "Open G guitar (DGDGBD tuning)",
+		build: ConfigurableInstrument::guitar_open_g,
+	},
+	InstrumentPreset {
+		name: "dadgad",
+		aliases: &["guitar-dadgad"],
+		description: "DADGAD guitar tuning",
+		build: ConfigurableInstrument::guitar_dadgad,
+	},
+];
+
+/// Every built-in instrument, in menu order
+pub fn presets() -> &'static [InstrumentPreset] {
+	PRESETS
+}
+
+/// Find a built-in instrument by name or alias, see [`ConfigurableInstrument::by_name`]
+pub fn preset(name: &str) -> Option<&'static InstrumentPreset> {
+	let name = name.trim().to_lowercase().replace([' ', '_'], "-");
+	PRESETS.iter().find(|p| p.matches(&name))
+}
+
 impl Instrument for ConfigurableInstrument {
 	fn tuning(&self) -> &[Note] {
 		&self.tuning
@@ -514,12 +1146,31 @@ impl Instrument for ConfigurableInstrument {
 				.collect()
 		})
 	}
+
+	fn string_fret_range(&self, string: usize) -> (u8, u8) {
+		self.string_fret_ranges
+			.as_ref()
+			.and_then(|ranges| ranges.get(string).copied())
+			.unwrap_or(self.fret_range)
+	}
+
+	fn course_pairs(&self) -> Vec<Option<Note>> {
+		self.course_pairs
+			.clone()
+			.unwrap_or_else(|| vec![None; self.tuning.len()])
+	}
 }
 
 /// Builder for creating ConfigurableInstrument instances
 #[derive(Debug, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Deserialize),
+	serde(default, deny_unknown_fields)
+)]
 pub struct ConfigurableInstrumentBuilder {
 	name: Option<String>,
+	#[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_tuning"))]
 	tuning: Option<Vec<Note>>,
 	fret_range: Option<(u8, u8)>,
 	max_stretch: Option<u8>,
@@ -529,6 +1180,8 @@ pub struct ConfigurableInstrumentBuilder {
 	min_played_strings: Option<usize>,
 	bass_string_index: Option<usize>,
 	string_names: Option<Vec<String>>,
+	course_pairs: Option<Vec<Option<Note>>>,
+	string_fret_ranges: Option<Vec<(u8, u8)>>,
 }
 
 impl ConfigurableInstrumentBuilder {
@@ -592,6 +1245,19 @@ impl ConfigurableInstrumentBuilder {
 		self
 	}
 
+	/// Make the strings doubled courses: one partner note (or None) per tuning entry
+	pub fn course_pairs(mut self, pairs: Vec<Option<Note>>) -> Self {
+		self.course_pairs = Some(pairs);
+		self
+	}
+
+	/// Frets each string can be stopped at, one range per tuning entry (default:
+	/// the fret range); see [`Instrument::string_fret_range`]
+	pub fn string_fret_ranges(mut self, ranges: Vec<(u8, u8)>) -> Self {
+		self.string_fret_ranges = Some(ranges);
+		self
+	}
+
 	/// Build the ConfigurableInstrument, returning an error if required fields are missing
 	pub fn build(self) -> Result<ConfigurableInstrument> {
 		let tuning = self
@@ -623,6 +1289,31 @@ impl ConfigurableInstrumentBuilder {
 			)));
 		}
 
+		if let Some(ref pairs) = self.course_pairs
+			&& pairs.len() != tuning.len()
+		{
+			return Err(ChordCraftError::InvalidInstrument(format!(
+				"course_pairs length ({}) must match tuning length ({})",
+				pairs.len(),
+				tuning.len()
+			)));
+		}
+
+		if let Some(ref ranges) = self.string_fret_ranges {
+			if ranges.len() != tuning.len() {
+				return Err(ChordCraftError::InvalidInstrument(format!(
+					"string_fret_ranges length ({}) must match tuning length ({})",
+					ranges.len(),
+					tuning.len()
+				)));
+			}
+			if let Some((low, high)) = ranges.iter().find(|(low, high)| low > high) {
+				return Err(ChordCraftError::InvalidInstrument(format!(
+					"string fret range {low}-{high} is empty"
+				)));
+			}
+		}
+
 		// Validate bass_string_index if provided
 		if let Some(index) = self.bass_string_index
 			&& index >= tuning.len()
@@ -645,10 +1336,42 @@ impl ConfigurableInstrumentBuilder {
 			min_played_strings: self.min_played_strings,
 			bass_string_index: self.bass_string_index,
 			string_names: self.string_names,
+			course_pairs: self.course_pairs,
+			string_fret_ranges: self.string_fret_ranges,
 		})
 	}
 }
 
+impl TryFrom<ConfigurableInstrumentBuilder> for ConfigurableInstrument {
+	type Error = ChordCraftError;
+
+	fn try_from(builder: ConfigurableInstrumentBuilder) -> Result<Self> {
+		builder.build()
+	}
+}
+
+/// A tuning written as a list of notes, or as a string for [`Tuning::parse`]
+///
+/// [`Tuning::parse`]: crate::tuning::Tuning::parse
+#[cfg(feature = "serde")]
+fn deserialize_tuning<'de, D: serde::Deserializer<'de>>(
+	deserializer: D,
+) -> std::result::Result<Option<Vec<Note>>, D::Error> {
+	#[derive(serde::Deserialize)]
+	#[serde(untagged)]
+	enum TuningSpec {
+		Text(String),
+		Notes(Vec<Note>),
+	}
+
+	match <TuningSpec as serde::Deserialize>::deserialize(deserializer)? {
+		TuningSpec::Text(text) => crate::tuning::Tuning::parse(&text)
+			.map(Some)
+			.map_err(serde::de::Error::custom),
+		TuningSpec::Notes(notes) => Ok(Some(notes)),
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct Guitar {
 	tuning: Vec<Note>,
@@ -771,6 +1494,7 @@ impl Instrument for Ukulele {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::fingering::{Fingering, PlayabilityIssue};
 	use crate::note::PitchClass;
 
 	#[test]
@@ -1063,6 +1787,82 @@ mod tests {
 		assert_eq!(banjo.string_count(), 5);
 		// Bass string is index 1 (D3), not 0 (high G drone)
 		assert_eq!(banjo.bass_string_index(), 1);
+
+		// The short fifth string's nut is at the 5th fret
+		assert_eq!(banjo.string_fret_range(0), (6, 22));
+		assert_eq!(banjo.string_nut_fret(0), 5);
+		assert_eq!(banjo.string_nut_fret(1), 0);
+		let notes = Fingering::parse("70000").unwrap().notes(&banjo);
+		assert_eq!(notes[0], Note::new(PitchClass::A, 4));
+		assert_eq!(
+			Fingering::parse("20000").unwrap().validate_for(&banjo),
+			vec![PlayabilityIssue::FretBelowNut {
+				string: 0,
+				fret: 2,
+				nut: 5
+			}]
+		);
+
+		// A capo moves the range down with the frets but leaves the short
+		// string's pitch alone
+		let capoed = banjo.with_capo(2).unwrap();
+		assert_eq!(capoed.string_fret_range(0), (4, 20));
+		assert_eq!(capoed.string_nut_fret(0), 3);
+		assert_eq!(capoed.tuning()[0], Note::new(PitchClass::G, 4));
+		assert_eq!(capoed.tuning()[1], Note::new(PitchClass::E, 3));
+		let notes = Fingering::parse("40000").unwrap().notes(&capoed);
+		assert_eq!(notes[0], Note::new(PitchClass::GSharp, 4));
+		assert_eq!(notes[1], Note::new(PitchClass::E, 3));
+		let notes = Fingering::parse("00000").unwrap().notes(&capoed);
+		assert_eq!(notes[0], Note::new(PitchClass::G, 4));
+
+		// The capo can't reach the short string's nut
+		assert!(banjo.with_capo(4).is_ok());
+		assert!(matches!(
+			banjo.with_capo(5),
+			Err(ChordCraftError::InvalidCapoPosition(5, 0, 4))
+		));
+
+		assert!(
+			ConfigurableInstrument::builder()
+				.tuning(banjo.tuning().to_vec())
+				.fret_range(0, 22)
+				.max_stretch(4)
+				.string_fret_ranges(vec![(6, 22)])
+				.build()
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn test_ukulele_variants() {
+		use crate::fingering::Fingering;
+
+		// Low G moves the bass from the C string to the G string
+		let (standard, low_g) = (
+			ConfigurableInstrument::ukulele(),
+			ConfigurableInstrument::ukulele_low_g(),
+		);
+		assert_eq!(standard.bass_string_index(), 1);
+		assert_eq!(low_g.bass_string_index(), 0);
+		assert_eq!(low_g.tuning()[0], Note::new(PitchClass::G, 3));
+		let c = Fingering::parse("0003").unwrap();
+		assert_eq!(c.bass_note(&standard), Some(Note::new(PitchClass::C, 4)));
+		assert_eq!(c.bass_note(&low_g), Some(Note::new(PitchClass::G, 3)));
+		assert_eq!(low_g.name(), "Ukulele (Low G)");
+
+		// Sizes change the neck, not the tuning
+		let tenor = ConfigurableInstrument::ukulele_sized(UkuleleSize::Tenor, false);
+		assert_eq!(tenor, ConfigurableInstrument::tenor_ukulele());
+		assert_eq!(tenor.tuning(), standard.tuning());
+		assert_eq!(tenor.fret_range(), (0, 19));
+		assert!(tenor.max_stretch() < standard.max_stretch());
+		assert_eq!(
+			ConfigurableInstrument::by_name("tenor-ukulele-low-g")
+				.unwrap()
+				.name(),
+			"Tenor Ukulele (Low G)"
+		);
 	}
 
 	#[test]
@@ -1082,6 +1882,27 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_extended_range_guitar_presets() {
+		// Band mode avoids every string below C3, not just a guitar's low two
+		let eight = ConfigurableInstrument::guitar_8_string();
+		assert_eq!(eight.string_count(), 8);
+		assert_eq!(eight.bass_string_indices(), Some(vec![0, 1, 2, 3]));
+
+		let baritone = ConfigurableInstrument::baritone_guitar();
+		assert_eq!(baritone.tuning()[0], Note::new(PitchClass::B, 1));
+		assert_eq!(baritone.bass_string_indices(), Some(vec![0, 1, 2]));
+		let baritone_a = ConfigurableInstrument::baritone_guitar_a();
+		assert_eq!(baritone_a.tuning()[3], Note::new(PitchClass::C, 3));
+		assert_eq!(baritone_a.bass_string_indices(), Some(vec![0, 1, 2]));
+
+		// Each string sits a fourth below the guitar's
+		let guitar = Guitar::default();
+		for (low, high) in baritone.tuning().iter().zip(guitar.tuning()) {
+			assert_eq!(high.to_midi() - low.to_midi(), 5);
+		}
+	}
+
 	#[test]
 	fn test_drop_d_preset() {
 		let drop_d = ConfigurableInstrument::guitar_drop_d();
@@ -1092,6 +1913,111 @@ mod tests {
 		assert_eq!(drop_d.tuning()[0].octave, 2);
 	}
 
+	#[test]
+	fn test_preset_registry() {
+		let banjo = ConfigurableInstrument::by_name("banjo").unwrap();
+		assert_eq!(banjo, ConfigurableInstrument::banjo());
+		assert_eq!(
+			ConfigurableInstrument::by_name("guitar-drop-d"),
+			Some(ConfigurableInstrument::guitar_drop_d())
+		);
+		assert_eq!(
+			ConfigurableInstrument::by_name(" Baritone_Ukulele"),
+			Some(ConfigurableInstrument::baritone_ukulele())
+		);
+		assert!(ConfigurableInstrument::by_name("theremin").is_none());
+
+		// Names and aliases are unique and already in lookup form
+		let mut names: Vec<&str> = presets()
+			.iter()
+			.flat_map(|p| std::iter::once(p.name).chain(p.aliases.iter().copied()))
+			.collect();
+		assert!(names.iter().all(|n| preset(n).is_some()));
+		let count = names.len();
+		names.sort();
+		names.dedup();
+		assert_eq!(names.len(), count);
+
+		// The configurable guitar and ukulele behave like the dedicated types
+		let (guitar, configurable) = (Guitar::default(), ConfigurableInstrument::guitar());
+		assert_eq!(guitar.tuning(), configurable.tuning());
+		assert_eq!(guitar.string_names(), configurable.string_names());
+		assert_eq!(guitar.fret_range(), configurable.fret_range());
+		let (uke, configurable) = (Ukulele::default(), ConfigurableInstrument::ukulele());
+		assert_eq!(uke.tuning(), configurable.tuning());
+		assert_eq!(uke.string_names(), configurable.string_names());
+		assert_eq!(uke.max_stretch(), configurable.max_stretch());
+		assert_eq!(
+			uke.open_position_threshold(),
+			configurable.open_position_threshold()
+		);
+		assert_eq!(
+			uke.main_barre_threshold(),
+			configurable.main_barre_threshold()
+		);
+		assert_eq!(uke.min_played_strings(), configurable.min_played_strings());
+		assert_eq!(uke.bass_string_index(), configurable.bass_string_index());
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_configurable_instrument_serde() {
+		for preset in presets() {
+			let instrument = preset.instrument();
+			let json = serde_json::to_string(&instrument).unwrap();
+			let parsed: ConfigurableInstrument = serde_json::from_str(&json).unwrap();
+			assert_eq!(parsed, instrument, "{}", preset.name);
+		}
+
+		// Unset overrides are left out; the tuning can be a string
+		let json = serde_json::to_string(&ConfigurableInstrument::mandolin()).unwrap();
+		assert!(json.contains("\"G3\"") && !json.contains("max_fingers"));
+		let tenor: ConfigurableInstrument = serde_json::from_str(
+			r#"{"name": "Tenor Banjo", "tuning": "C3 G3 D4 A4", "fret_range": [0, 19], "max_stretch": 4}"#,
+		)
+		.unwrap();
+		assert_eq!(tenor.tuning()[3], Note::new(PitchClass::A, 4));
+		assert_eq!(tenor.fret_range(), (0, 19));
+
+		// Loading validates like the builder
+		for bad in [
+			r#"{"tuning": [], "fret_range": [0, 12], "max_stretch": 4}"#,
+			r#"{"tuning": "EADG", "max_stretch": 4}"#,
+			r#"{"tuning": "EADG", "fret_range": [0, 12], "max_stretch": 4, "bass_string_index": 9}"#,
+			r#"{"tuning": "EADG", "fret_range": [0, 12], "max_stretch": 4, "frets": 12}"#,
+			r#"{"tuning": "EAXG", "fret_range": [0, 12], "max_stretch": 4}"#,
+		] {
+			assert!(
+				serde_json::from_str::<ConfigurableInstrument>(bad).is_err(),
+				"{bad}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_course_pairs() {
+		use crate::note::PitchClass::*;
+		let twelve = ConfigurableInstrument::guitar_12_string();
+		let pairs = twelve.course_pairs();
+		assert_eq!(pairs.len(), twelve.string_count());
+		assert_eq!(pairs[0], Some(Note::new(E, 3)));
+		assert_eq!(pairs[5], Some(Note::new(E, 4)));
+		// Single strings have no partners
+		assert!(Guitar::default().course_pairs().iter().all(Option::is_none));
+
+		// A capo stops both strings of a course
+		let capoed = twelve.with_capo(2).unwrap();
+		assert_eq!(capoed.course_pairs()[0], Some(Note::new(FSharp, 3)));
+
+		let mismatched = ConfigurableInstrument::builder()
+			.tuning(vec![Note::new(G, 3), Note::new(D, 4)])
+			.fret_range(0, 17)
+			.max_stretch(4)
+			.course_pairs(vec![Some(Note::new(G, 3))])
+			.build();
+		assert!(mismatched.is_err());
+	}
+
 	#[test]
 	fn test_configurable_instrument_with_capo() {
 		let bass = ConfigurableInstrument::bass();
@@ -5,9 +5,11 @@
 //! distance.
 
 use crate::error::{ChordCraftError, Result};
+use crate::spelling::NoteName;
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntervalQuality {
 	Perfect,
 	Major,
@@ -17,6 +19,7 @@ pub enum IntervalQuality {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interval {
 	pub quality: IntervalQuality,
 	pub distance: u8, // 1-based: 1=unison, 2=second, 3=third, etc.
@@ -82,6 +85,81 @@ impl Interval {
 		}
 	}
 
+	/// Chord degree as written in chord formulas ("3", "b7", "#11"); the inverse of [`Interval::from_degree`]
+	pub fn to_degree(&self) -> String {
+		let accidental = match (self.quality, self.is_perfect_interval()) {
+			(IntervalQuality::Minor, _) | (IntervalQuality::Diminished, true) => "b",
+			(IntervalQuality::Diminished, false) => "bb",
+			(IntervalQuality::Augmented, _) => "#",
+			_ => "",
+		};
+		format!("{accidental}{}", self.distance)
+	}
+
+	/// Interval spelled between two note names, ascending from `from` within an octave
+	///
+	/// The letters decide the distance, so C to F# is an augmented 4th and C to
+	/// Gb a diminished 5th. Returns None for doubly augmented or diminished
+	/// intervals (like C# to Fb).
+	pub fn between(from: NoteName, to: NoteName) -> Option<Self> {
+		let steps = (to.letter as i32 - from.letter as i32).rem_euclid(7);
+		let semitones = from.pitch_class().semitone_distance_to(&to.pitch_class());
+		Interval::with_semitones((steps + 1) as u8, semitones as i32)
+	}
+
+	/// Interval that turns this one upside down, complementing it to an octave
+	///
+	/// Major becomes minor and augmented diminished (M3 → m6, A4 → d5).
+	/// Compound intervals are inverted as their simple form (M9 → m7).
+	pub fn invert(&self) -> Self {
+		use IntervalQuality::*;
+
+		let quality = match self.quality {
+			Perfect => Perfect,
+			Major => Minor,
+			Minor => Major,
+			Augmented => Diminished,
+			Diminished => Augmented,
+		};
+		let simple = (self.distance - 1) % 7 + 1;
+		Interval::new(quality, 9 - simple)
+	}
+
+	/// Stack another interval on top (M3 + m3 = P5, P5 + M3 = M7)
+	///
+	/// Returns None if the result would be doubly augmented or diminished.
+	pub fn add(&self, other: &Interval) -> Option<Self> {
+		Interval::with_semitones(
+			self.distance + other.distance - 1,
+			self.to_semitones() as i32 + other.to_semitones() as i32,
+		)
+	}
+
+	/// Interval of the given distance spanning `semitones` (compared modulo the octave)
+	fn with_semitones(distance: u8, semitones: i32) -> Option<Self> {
+		use IntervalQuality::*;
+
+		let natural = Interval::new(Major, distance);
+		let perfect = natural.is_perfect_interval();
+		let base = if perfect {
+			Interval::new(Perfect, distance)
+		} else {
+			natural
+		}
+		.to_semitones() as i32;
+		let offset = (semitones - base + 6).rem_euclid(12) - 6;
+
+		let quality = match (offset, perfect) {
+			(0, true) => Perfect,
+			(0, false) => Major,
+			(-1, false) => Minor,
+			(1, _) => Augmented,
+			(-1, true) | (-2, false) => Diminished,
+			_ => return None,
+		};
+		Some(Interval::new(quality, distance))
+	}
+
 	fn is_perfect_interval(&self) -> bool {
 		// Normalize to 1-7 range, then check if it's 1, 4, or 5
 		let normalized = (self.distance - 1) % 7 + 1;
@@ -163,6 +241,44 @@ impl Interval {
 
 		Ok(Interval::new(quality, distance))
 	}
+
+	/// Parse a chord degree as written in chord formulas (e.g., "3", "b7", "#11", "bb7")
+	///
+	/// Unaltered degrees are major or perfect; each flat lowers and a sharp
+	/// raises them, so "b3" is a minor third and "bb7" a diminished seventh.
+	pub fn from_degree(s: &str) -> Result<Self> {
+		let s = s.trim();
+		let invalid = || ChordCraftError::InvalidInterval(s.to_string());
+		let digits = s.find(|c: char| c.is_ascii_digit()).ok_or_else(invalid)?;
+		let (accidentals, degree) = s.split_at(digits);
+		let distance: u8 = degree.parse().map_err(|_| invalid())?;
+		if distance == 0 {
+			return Err(invalid());
+		}
+
+		let flats = accidentals
+			.chars()
+			.filter(|c| matches!(c, 'b' | '♭'))
+			.count();
+		let sharps = accidentals
+			.chars()
+			.filter(|c| matches!(c, '#' | '♯' | '+'))
+			.count();
+		if flats + sharps != accidentals.chars().count() {
+			return Err(invalid());
+		}
+
+		let perfect = Interval::new(IntervalQuality::Perfect, distance).is_perfect_interval();
+		let quality = match (flats, sharps, perfect) {
+			(0, 0, true) => IntervalQuality::Perfect,
+			(0, 0, false) => IntervalQuality::Major,
+			(0, 1, _) => IntervalQuality::Augmented,
+			(1, 0, true) | (2, 0, false) => IntervalQuality::Diminished,
+			(1, 0, false) => IntervalQuality::Minor,
+			_ => return Err(invalid()),
+		};
+		Ok(Interval::new(quality, distance))
+	}
 }
 
 impl fmt::Display for Interval {
@@ -276,6 +392,64 @@ mod tests {
 		assert_eq!(Interval::parse("M9").unwrap(), MAJOR_NINTH);
 	}
 
+	#[test]
+	fn test_interval_from_degree() {
+		assert_eq!(Interval::from_degree("3").unwrap(), MAJOR_THIRD);
+		assert_eq!(Interval::from_degree("b3").unwrap(), MINOR_THIRD);
+		assert_eq!(Interval::from_degree("11").unwrap(), PERFECT_ELEVENTH);
+		assert_eq!(
+			Interval::from_degree("#11").unwrap(),
+			Interval::new(IntervalQuality::Augmented, 11)
+		);
+		assert_eq!(Interval::from_degree("bb7").unwrap().to_semitones(), 9);
+		assert_eq!(Interval::from_degree("b5").unwrap().to_semitones(), 6);
+		assert!(Interval::from_degree("bb5").is_err());
+		assert!(Interval::from_degree("x3").is_err());
+		assert!(Interval::from_degree("0").is_err());
+
+		for degree in ["1", "b3", "3", "b5", "#5", "bb7", "b9", "#11", "13"] {
+			assert_eq!(Interval::from_degree(degree).unwrap().to_degree(), degree);
+		}
+	}
+
+	#[test]
+	fn test_interval_arithmetic() {
+		use IntervalQuality::*;
+
+		assert_eq!(MAJOR_THIRD.invert(), MINOR_SIXTH);
+		assert_eq!(PERFECT_FIFTH.invert(), PERFECT_FOURTH);
+		assert_eq!(TRITONE.invert(), Interval::new(Diminished, 5));
+		assert_eq!(MAJOR_NINTH.invert(), MINOR_SEVENTH);
+
+		assert_eq!(MAJOR_THIRD.add(&MINOR_THIRD), Some(PERFECT_FIFTH));
+		assert_eq!(PERFECT_FIFTH.add(&MAJOR_THIRD), Some(MAJOR_SEVENTH));
+		assert_eq!(MINOR_SEVENTH.add(&MAJOR_THIRD), Some(MAJOR_NINTH));
+		assert_eq!(
+			MINOR_THIRD.add(&MINOR_THIRD),
+			Some(Interval::new(Diminished, 5))
+		);
+		assert_eq!(
+			Interval::new(Diminished, 3).add(&Interval::new(Diminished, 3)),
+			None
+		);
+	}
+
+	#[test]
+	fn test_interval_between_note_names() {
+		use IntervalQuality::*;
+
+		let between = |a: &str, b: &str| {
+			Interval::between(NoteName::parse(a).unwrap(), NoteName::parse(b).unwrap())
+		};
+		assert_eq!(between("C", "F#"), Some(Interval::new(Augmented, 4)));
+		assert_eq!(between("C", "Gb"), Some(Interval::new(Diminished, 5)));
+		assert_eq!(between("A", "C"), Some(MINOR_THIRD));
+		assert_eq!(between("G", "F"), Some(MINOR_SEVENTH));
+		assert_eq!(between("E", "E"), Some(UNISON));
+		assert_eq!(between("C", "C#"), Some(Interval::new(Augmented, 1)));
+		assert_eq!(between("C#", "Fb"), None);
+	}
+
 	#[test]
 	fn test_interval_short_name() {
 		assert_eq!(MAJOR_THIRD.short_name(), "M3");
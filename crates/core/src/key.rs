@@ -0,0 +1,309 @@
+//! Keys and key detection
+//!
+//! [`detect_key`] estimates the key of a chord sequence by checking how well
+//! each of the 24 major and minor keys explains its chord tones, favouring
+//! keys whose tonic chord appears (especially at the start or end).
+//! [`detect_modulations`] does the same per song section to find key changes.
+
+use std::fmt;
+
+use crate::chord::Chord;
+use crate::error::{ChordCraftError, Result};
+use crate::note::PitchClass;
+use crate::song::Song;
+use crate::spelling::Accidentals;
+
+const MAJOR_SCALE: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+/// Natural minor plus the raised 7th, so V7 and vii° count as diatonic
+const MINOR_SCALE: [u8; 8] = [0, 2, 3, 5, 7, 8, 10, 11];
+
+/// Weight of a chord whose root and third match the key's tonic chord
+const TONIC_CHORD_WEIGHT: f32 = 2.0;
+/// Extra weight for the tonic chord opening or closing the sequence
+const TONIC_CADENCE_WEIGHT: f32 = 1.0;
+/// How much better (in fit) a section's own key must explain it to count as a modulation
+const MODULATION_MARGIN: f32 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+	Major,
+	Minor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key {
+	pub tonic: PitchClass,
+	pub mode: Mode,
+}
+
+impl Key {
+	pub fn new(tonic: PitchClass, mode: Mode) -> Self {
+		Key { tonic, mode }
+	}
+
+	/// Parse a key name: "G", "G major", "Em", "E minor", "Bb"
+	pub fn parse(s: &str) -> Result<Self> {
+		let s = s.trim();
+		let invalid = || ChordCraftError::InvalidNote(format!("invalid key '{s}'"));
+		let tonic_end = s
+			.char_indices()
+			.nth(1)
+			.filter(|(_, c)| matches!(c, '#' | 'b' | '♭'))
+			.map_or(1, |(i, c)| i + c.len_utf8());
+		let tonic = PitchClass::parse(s.get(..tonic_end).ok_or_else(invalid)?)?;
+		let mode = match s[tonic_end..].trim().to_lowercase().as_str() {
+			"" | "maj" | "major" => Mode::Major,
+			"m" | "min" | "minor" => Mode::Minor,
+			_ => return Err(invalid()),
+		};
+		Ok(Key::new(tonic, mode))
+	}
+
+	/// Pitch classes of the key's scale (minor includes the raised 7th)
+	pub fn scale(&self) -> Vec<PitchClass> {
+		let steps: &[u8] = match self.mode {
+			Mode::Major => &MAJOR_SCALE,
+			Mode::Minor => &MINOR_SCALE,
+		};
+		steps
+			.iter()
+			.map(|&s| self.tonic.add_semitones(s as i32))
+			.collect()
+	}
+
+	pub fn contains(&self, pitch: PitchClass) -> bool {
+		self.scale().contains(&pitch)
+	}
+
+	/// The major key sharing this key's signature
+	pub fn relative_major(&self) -> Key {
+		match self.mode {
+			Mode::Major => *self,
+			Mode::Minor => Key::new(self.tonic.add_semitones(3), Mode::Major),
+		}
+	}
+
+	/// Whether the key signature uses flats (F, Bb, Eb, Ab, Db major and their relative minors)
+	pub fn prefers_flats(&self) -> bool {
+		matches!(
+			self.relative_major().tonic.to_semitone(),
+			1 | 3 | 5 | 8 | 10
+		)
+	}
+
+	/// Tonic spelled the way the key signature would
+	pub fn tonic_name(&self) -> &'static str {
+		Accidentals::for_key(self).spell(self.tonic)
+	}
+
+	/// All 24 major and minor keys
+	pub fn all() -> impl Iterator<Item = Key> {
+		(0..12).flat_map(|s| {
+			let tonic = PitchClass::from_semitone(s);
+			[Key::new(tonic, Mode::Major), Key::new(tonic, Mode::Minor)]
+		})
+	}
+}
+
+impl fmt::Display for Key {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mode = match self.mode {
+			Mode::Major => "major",
+			Mode::Minor => "minor",
+		};
+		write!(f, "{} {mode}", self.tonic_name())
+	}
+}
+
+/// A detected key and how well it explains the chords
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyEstimate {
+	pub key: Key,
+	/// Share of chord tones (weighted by duration) inside the key's scale (0.0 - 1.0)
+	pub fit: f32,
+}
+
+/// A key change at the start of a song section
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modulation {
+	/// Index of the section in the new key
+	pub section: usize,
+	pub label: Option<String>,
+	pub from: Key,
+	pub to: Key,
+}
+
+/// Most likely key of a chord sequence, or None if it is empty
+pub fn detect_key(chords: &[Chord]) -> Option<KeyEstimate> {
+	let weighted: Vec<(&Chord, f32)> = chords.iter().map(|c| (c, 1.0)).collect();
+	rank_keys(&weighted).into_iter().next()
+}
+
+/// Most likely key of a song, weighting chords by how long they ring
+pub fn detect_song_key(song: &Song) -> Option<KeyEstimate> {
+	let weighted: Vec<(&Chord, f32)> = song.chords().map(|c| (&c.chord, c.beats)).collect();
+	rank_keys(&weighted).into_iter().next()
+}
+
+/// Sections that move to a new key
+///
+/// The song starts in the song key, unless the first section is clearly in
+/// another one. A later section modulates when its own best key explains its
+/// chords clearly better than the key in force before it.
+pub fn detect_modulations(song: &Song) -> Vec<Modulation> {
+	let Some(song_key) = detect_song_key(song) else {
+		return vec![];
+	};
+
+	let mut current: Option<Key> = None;
+	let mut modulations = Vec::new();
+	for (i, section) in song.sections.iter().enumerate() {
+		let weighted: Vec<(&Chord, f32)> =
+			section.chords.iter().map(|c| (&c.chord, c.beats)).collect();
+		let Some(best) = rank_keys(&weighted).into_iter().next() else {
+			continue;
+		};
+		let clearly_better =
+			|key: Key| best.key != key && best.fit - key_fit(key, &weighted) > MODULATION_MARGIN;
+
+		let Some(key) = current else {
+			current = Some(if clearly_better(song_key.key) {
+				best.key
+			} else {
+				song_key.key
+			});
+			continue;
+		};
+		if clearly_better(key) {
+			modulations.push(Modulation {
+				section: i,
+				label: section.label.clone(),
+				from: key,
+				to: best.key,
+			});
+			current = Some(best.key);
+		}
+	}
+	modulations
+}
+
+/// Every key, best first
+fn rank_keys(chords: &[(&Chord, f32)]) -> Vec<KeyEstimate> {
+	if chords.is_empty() {
+		return vec![];
+	}
+
+	let mut ranked: Vec<(f32, KeyEstimate)> = Key::all()
+		.map(|key| {
+			let fit = key_fit(key, chords);
+			(fit + tonic_weight(key, chords), KeyEstimate { key, fit })
+		})
+		.collect();
+	ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+	ranked.into_iter().map(|(_, estimate)| estimate).collect()
+}
+
+fn key_fit(key: Key, chords: &[(&Chord, f32)]) -> f32 {
+	let scale = key.scale();
+	let (inside, total) = chords
+		.iter()
+		.flat_map(|(chord, weight)| chord.notes().into_iter().map(move |n| (n, *weight)))
+		.fold((0.0, 0.0), |(inside, total), (note, weight)| {
+			let hit = if scale.contains(&note) { weight } else { 0.0 };
+			(inside + hit, total + weight)
+		});
+	if total > 0.0 { inside / total } else { 0.0 }
+}
+
+/// Evidence for the key's tonic chord, as a share of the sequence's length
+fn tonic_weight(key: Key, chords: &[(&Chord, f32)]) -> f32 {
+	let third = match key.mode {
+		Mode::Major => 4,
+		Mode::Minor => 3,
+	};
+	let is_tonic = |chord: &Chord| {
+		chord.root == key.tonic && chord.notes().contains(&key.tonic.add_semitones(third))
+	};
+
+	let total: f32 = chords.iter().map(|(_, w)| w).sum();
+	let tonic: f32 = chords
+		.iter()
+		.filter(|(c, _)| is_tonic(c))
+		.map(|(_, w)| w)
+		.sum();
+	let cadences = [chords.first(), chords.last()]
+		.into_iter()
+		.flatten()
+		.filter(|(c, _)| is_tonic(c))
+		.count() as f32;
+
+	(tonic * TONIC_CHORD_WEIGHT) / total + cadences * TONIC_CADENCE_WEIGHT / chords.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::song::SongSection;
+
+	fn chords(names: &str) -> Vec<Chord> {
+		names
+			.split_whitespace()
+			.map(|n| Chord::parse(n).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn test_detect_major_and_minor_keys() {
+		let g = detect_key(&chords("G C D G Em C D G")).unwrap();
+		assert_eq!(g.key, Key::new(PitchClass::G, Mode::Major));
+		assert_eq!(g.fit, 1.0);
+
+		let am = detect_key(&chords("Am Dm E7 Am")).unwrap();
+		assert_eq!(am.key, Key::new(PitchClass::A, Mode::Minor));
+
+		assert_eq!(
+			detect_key(&chords("Bb Eb F7 Bb")).unwrap().key.to_string(),
+			"Bb major"
+		);
+		assert!(detect_key(&[]).is_none());
+	}
+
+	#[test]
+	fn test_parse_key() {
+		assert_eq!(
+			Key::parse("G").unwrap(),
+			Key::new(PitchClass::G, Mode::Major)
+		);
+		assert_eq!(
+			Key::parse("Bb minor").unwrap(),
+			Key::new(PitchClass::ASharp, Mode::Minor)
+		);
+		assert_eq!(
+			Key::parse("F#m").unwrap(),
+			Key::new(PitchClass::FSharp, Mode::Minor)
+		);
+		assert!(Key::parse("H").is_err());
+		assert!(Key::parse("C lydian").is_err());
+	}
+
+	#[test]
+	fn test_detect_modulation_between_sections() {
+		let mut song = Song::new(None);
+		for (label, names) in [("Verse", "C F G C"), ("Chorus", "D G A D")] {
+			let mut section = SongSection::new(Some(label.to_string()));
+			for chord in chords(names) {
+				section.push(chord, 4.0);
+			}
+			song.sections.push(section);
+		}
+
+		let modulations = detect_modulations(&song);
+		assert_eq!(modulations.len(), 1);
+		assert_eq!(modulations[0].section, 1);
+		assert_eq!(modulations[0].to, Key::new(PitchClass::D, Mode::Major));
+	}
+}
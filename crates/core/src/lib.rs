@@ -21,23 +21,62 @@
 //! ```
 
 pub mod analyzer;
+pub mod arrangement;
+pub mod cache;
+pub mod chart;
 pub mod chord;
+pub mod chord_builder;
+pub mod chordpro;
+pub mod conversion;
+pub mod diagram;
 pub mod fingering;
+pub mod fingers;
+pub mod flashcards;
+pub mod formula;
 pub mod generator;
+pub mod harmony;
 pub mod instrument;
 pub mod interval;
+pub mod key;
+#[cfg(feature = "midi")]
+pub mod midi;
+#[cfg(feature = "musicxml")]
+pub mod musicxml;
 pub mod note;
+pub mod passing;
+pub mod progress;
 pub mod progression;
+pub mod report;
+pub mod scale;
+pub mod setlist;
 pub mod shapes;
+pub mod sheet;
+pub mod slide;
+pub mod song;
+pub mod spelling;
+pub mod transposition;
+pub mod tuning;
 
 // Re-export commonly used types
-pub use analyzer::{ChordMatch, analyze_fingering};
-pub use chord::{Chord, ChordQuality};
-pub use fingering::Fingering;
-pub use generator::PlayingContext;
+pub use analyzer::{
+	AnalyzerOptions, CapoMatch, ChordMatch, ChromaOptions, Identification, NearMiss, PartialMatch,
+	StringRole, analyze_chroma, analyze_fingering, analyze_fingering_with,
+	analyze_fingering_with_capo, analyze_midi_notes, analyze_notes, analyze_partial_tab,
+	identify_fingering, near_misses, string_roles,
+};
+pub use chord::{Alteration, Chord, ChordFormat, ChordQuality, Inversion, NamingProfile};
+pub use fingering::{Barre, Difficulty, Fingering, PlayabilityIssue, StringChange};
+pub use fingers::{Finger, FingerAssignment, assign_fingers};
+pub use formula::{ChordFormula, register_quality};
+pub use generator::{BarreStrength, HandSize, PlayerProfile, PlayingContext, StringPin};
+pub use harmony::{Cadence, HarmonicFunction, NumeralAnalysis, RomanNumeral};
 pub use instrument::{CapoedInstrument, ConfigurableInstrument, Guitar, Instrument, Ukulele};
 pub use interval::Interval;
+pub use key::{Key, Mode};
 pub use note::{Note, PitchClass};
+pub use progress::{CancellationToken, Partial, Progress, SearchBudget, SearchObserver};
+pub use scale::{Scale, ScaleKind};
+pub use spelling::{Accidentals, NoteName};
 
 /// Error types for the chordcraft-core library
 pub mod error {
@@ -48,6 +87,10 @@ pub mod error {
 		#[error("Invalid chord name: {0}")]
 		InvalidChordName(String),
 
+		/// Chords of a sequence that can't be parsed, with their positions (from 0)
+		#[error("Invalid chord names: {}", describe_chords(.0))]
+		InvalidChordNames(Vec<(usize, String)>),
+
 		#[error("Invalid note name: {0}")]
 		InvalidNote(String),
 
@@ -66,11 +109,41 @@ pub mod error {
 		#[error("Invalid capo position: {0} (must be between {1} and {2})")]
 		InvalidCapoPosition(u8, u8, u8),
 
+		#[error("Invalid tuning: {0}")]
+		InvalidTuning(String),
+
 		#[error("Invalid instrument configuration: {0}")]
 		InvalidInstrument(String),
+
+		#[error("Invalid MIDI file: {0}")]
+		InvalidMidi(String),
+
+		#[error("Invalid MusicXML: {0}")]
+		InvalidMusicXml(String),
+
+		#[error("Invalid ChordPro: {0}")]
+		InvalidChordPro(String),
+
+		#[error("Invalid chord chart: {0}")]
+		InvalidChart(String),
+
+		#[error("Invalid chord edit: {0}")]
+		InvalidEdit(String),
+
+		#[error("Search was cancelled")]
+		Cancelled,
 	}
 
 	pub type Result<T> = std::result::Result<T, ChordCraftError>;
+
+	/// "'Amaj77' (chord 2), 'Hm' (chord 5)", counting chords from 1
+	fn describe_chords(chords: &[(usize, String)]) -> String {
+		chords
+			.iter()
+			.map(|(i, name)| format!("'{name}' (chord {})", i + 1))
+			.collect::<Vec<_>>()
+			.join(", ")
+	}
 }
 
 pub use error::{ChordCraftError, Result};
@@ -0,0 +1,320 @@
+//! MIDI file import (requires the `midi` feature)
+//!
+//! Reads a Standard MIDI File, slices the sounding notes into beats or bars,
+//! and names each slice with the chord analyzer, producing a [`Song`] whose
+//! chord list can go straight into the progression optimizer.
+
+use std::collections::HashMap;
+
+use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+
+use crate::analyzer::analyze_pitches;
+use crate::error::{ChordCraftError, Result};
+use crate::note::{Note, PitchClass};
+use crate::song::{Song, SongSection};
+
+/// General MIDI percussion channel (channel 10, zero-based 9)
+const DRUM_CHANNEL: u8 = 9;
+
+/// How the timeline is sliced into chords
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MidiSegmentation {
+	/// One chord per beat (quarter note)
+	Beat,
+	/// One chord per bar
+	#[default]
+	Bar,
+}
+
+#[derive(Debug, Clone)]
+pub struct MidiImportOptions {
+	pub segmentation: MidiSegmentation,
+	/// Slices with fewer distinct pitch classes are treated as a held chord
+	pub min_pitch_classes: usize,
+	/// Include the General MIDI drum channel
+	pub include_drums: bool,
+}
+
+impl Default for MidiImportOptions {
+	fn default() -> Self {
+		MidiImportOptions {
+			segmentation: MidiSegmentation::default(),
+			min_pitch_classes: 2,
+			include_drums: false,
+		}
+	}
+}
+
+/// A note with absolute start/end ticks
+#[derive(Debug, Clone, Copy)]
+struct TimedNote {
+	key: u8,
+	start: u64,
+	end: u64,
+}
+
+/// Import a MIDI file and extract its chords
+///
+/// Notes from all tracks are merged. A note belongs to a slice if it sounds
+/// for at least a quarter of it, which filters out short passing tones and
+/// release tails. Slices without enough notes extend the previous chord, and
+/// repeated chords are merged into one longer chord.
+pub fn import_midi(bytes: &[u8], options: &MidiImportOptions) -> Result<Song> {
+	let smf = Smf::parse(bytes).map_err(|e| ChordCraftError::InvalidMidi(e.to_string()))?;
+
+	let ticks_per_beat = match smf.header.timing {
+		Timing::Metrical(tpb) => tpb.as_int() as u64,
+		Timing::Timecode(..) => {
+			return Err(ChordCraftError::InvalidMidi(
+				"SMPTE timecode timing is not supported".to_string(),
+			));
+		}
+	};
+
+	let mut title = None;
+	let mut beats_per_bar = None;
+	let mut notes = Vec::new();
+
+	for track in &smf.tracks {
+		let mut tick = 0u64;
+		let mut active: HashMap<(u8, u8), u64> = HashMap::new();
+
+		for event in track {
+			tick += event.delta.as_int() as u64;
+
+			match event.kind {
+				TrackEventKind::Midi { channel, message } => {
+					let channel = channel.as_int();
+					if channel == DRUM_CHANNEL && !options.include_drums {
+						continue;
+					}
+
+					match message {
+						MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+							active.entry((channel, key.as_int())).or_insert(tick);
+						}
+						MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+							if let Some(start) = active.remove(&(channel, key.as_int())) {
+								notes.push(TimedNote {
+									key: key.as_int(),
+									start,
+									end: tick,
+								});
+							}
+						}
+						_ => {}
+					}
+				}
+				TrackEventKind::Meta(MetaMessage::TrackName(name)) if title.is_none() => {
+					let name = String::from_utf8_lossy(name).trim().to_string();
+					if !name.is_empty() {
+						title = Some(name);
+					}
+				}
+				TrackEventKind::Meta(MetaMessage::TimeSignature(num, den_pow, _, _))
+					if beats_per_bar.is_none() =>
+				{
+					// Beats are quarter notes: 6/8 is three beats per bar
+					let quarters = (num as u32 * 4) >> den_pow.min(31);
+					beats_per_bar = Some(quarters.clamp(1, u8::MAX as u32) as u8);
+				}
+				_ => {}
+			}
+		}
+
+		// Notes still held at the end of the track end there
+		for ((_, key), start) in active {
+			notes.push(TimedNote {
+				key,
+				start,
+				end: tick,
+			});
+		}
+	}
+
+	let mut song = Song::new(title);
+	song.beats_per_bar = beats_per_bar.unwrap_or(4);
+
+	let segment_beats = match options.segmentation {
+		MidiSegmentation::Beat => 1,
+		MidiSegmentation::Bar => song.beats_per_bar as u64,
+	};
+	let segment_ticks = (ticks_per_beat * segment_beats).max(1);
+	let last_tick = notes.iter().map(|n| n.end).max().unwrap_or(0);
+
+	let mut section = SongSection::new(None);
+	let mut segment_start = 0;
+	while segment_start < last_tick {
+		let segment_end = segment_start + segment_ticks;
+
+		let mut sounding: Vec<&TimedNote> = notes
+			.iter()
+			.filter(|n| {
+				let overlap = n
+					.end
+					.min(segment_end)
+					.saturating_sub(n.start.max(segment_start));
+				overlap * 4 >= segment_ticks
+			})
+			.collect();
+		sounding.sort_by_key(|n| n.key);
+
+		let mut pitches: Vec<PitchClass> = Vec::new();
+		for n in &sounding {
+			let pitch = Note::from_midi(n.key).pitch;
+			if !pitches.contains(&pitch) {
+				pitches.push(pitch);
+			}
+		}
+
+		let best = if pitches.len() >= options.min_pitch_classes {
+			let bass = sounding.first().map(|n| Note::from_midi(n.key).pitch);
+			analyze_pitches(&pitches, bass).into_iter().next()
+		} else {
+			None
+		};
+
+		match best {
+			Some(m) => section.push(m.chord, segment_beats as f32),
+			// No new harmony: the previous chord keeps ringing
+			None => {
+				if let Some(last) = section.chords.last_mut() {
+					last.beats += segment_beats as f32;
+				}
+			}
+		}
+
+		segment_start = segment_end;
+	}
+
+	if !section.chords.is_empty() {
+		song.sections.push(section);
+	}
+
+	Ok(song)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chord::ChordQuality;
+	use midly::{Format, Header, TrackEvent};
+
+	/// Build a single-track MIDI file playing each chord (MIDI keys) for `beats` beats
+	fn build_midi(chords: &[&[u8]], beats: u32, time_sig: Option<(u8, u8)>) -> Vec<u8> {
+		let tpb = 480u32;
+		let mut track: Vec<TrackEvent> = Vec::new();
+
+		track.push(TrackEvent {
+			delta: 0.into(),
+			kind: TrackEventKind::Meta(MetaMessage::TrackName(b"Test Song")),
+		});
+		if let Some((num, den_pow)) = time_sig {
+			track.push(TrackEvent {
+				delta: 0.into(),
+				kind: TrackEventKind::Meta(MetaMessage::TimeSignature(num, den_pow, 24, 8)),
+			});
+		}
+
+		for keys in chords {
+			for &key in *keys {
+				track.push(TrackEvent {
+					delta: 0.into(),
+					kind: TrackEventKind::Midi {
+						channel: 0.into(),
+						message: MidiMessage::NoteOn {
+							key: key.into(),
+							vel: 100.into(),
+						},
+					},
+				});
+			}
+			for (i, &key) in keys.iter().enumerate() {
+				track.push(TrackEvent {
+					delta: if i == 0 {
+						(tpb * beats).into()
+					} else {
+						0.into()
+					},
+					kind: TrackEventKind::Midi {
+						channel: 0.into(),
+						message: MidiMessage::NoteOff {
+							key: key.into(),
+							vel: 0.into(),
+						},
+					},
+				});
+			}
+		}
+		track.push(TrackEvent {
+			delta: 0.into(),
+			kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+		});
+
+		let mut smf = Smf::new(Header::new(
+			Format::SingleTrack,
+			Timing::Metrical((tpb as u16).into()),
+		));
+		smf.tracks.push(track);
+
+		let mut bytes = Vec::new();
+		smf.write_std(&mut bytes).unwrap();
+		bytes
+	}
+
+	const C_MAJOR: &[u8] = &[48, 52, 55, 60];
+	const A_MINOR: &[u8] = &[45, 52, 57, 60];
+	const F_MAJOR: &[u8] = &[41, 48, 53, 57];
+	const G7: &[u8] = &[43, 50, 53, 59];
+
+	#[test]
+	fn test_import_bars() {
+		let bytes = build_midi(&[C_MAJOR, A_MINOR, F_MAJOR, G7], 4, None);
+		let song = import_midi(&bytes, &MidiImportOptions::default()).unwrap();
+
+		assert_eq!(song.title.as_deref(), Some("Test Song"));
+		assert_eq!(song.chord_names(), vec!["C", "Am", "F", "G7"]);
+		assert!(song.chords().all(|c| c.beats == 4.0));
+	}
+
+	#[test]
+	fn test_import_beats_merges_held_chords() {
+		let bytes = build_midi(&[C_MAJOR, G7], 2, None);
+		let options = MidiImportOptions {
+			segmentation: MidiSegmentation::Beat,
+			..Default::default()
+		};
+		let song = import_midi(&bytes, &options).unwrap();
+
+		let chords: Vec<_> = song.chords().collect();
+		assert_eq!(chords.len(), 2);
+		assert_eq!(chords[0].chord.quality, ChordQuality::Major);
+		assert_eq!(chords[0].beats, 2.0);
+		assert_eq!(chords[1].chord.quality, ChordQuality::Dominant7);
+	}
+
+	#[test]
+	fn test_import_time_signature() {
+		// 3/4: one chord per three-beat bar
+		let bytes = build_midi(&[C_MAJOR, F_MAJOR], 3, Some((3, 2)));
+		let song = import_midi(&bytes, &MidiImportOptions::default()).unwrap();
+
+		assert_eq!(song.beats_per_bar, 3);
+		assert_eq!(song.chord_names(), vec!["C", "F"]);
+	}
+
+	#[test]
+	fn test_import_single_notes_extend_previous_chord() {
+		let bytes = build_midi(&[C_MAJOR, &[60]], 4, None);
+		let song = import_midi(&bytes, &MidiImportOptions::default()).unwrap();
+
+		let chords: Vec<_> = song.chords().collect();
+		assert_eq!(chords.len(), 1);
+		assert_eq!(chords[0].beats, 8.0);
+	}
+
+	#[test]
+	fn test_import_invalid_bytes() {
+		assert!(import_midi(b"not a midi file", &MidiImportOptions::default()).is_err());
+	}
+}
@@ -0,0 +1,492 @@
+//! MusicXML chord symbol import (requires the `musicxml` feature)
+//!
+//! Extracts `<harmony>` elements from an uncompressed MusicXML score into a
+//! [`Song`]. Chord durations come from the note timeline of the part carrying
+//! the harmonies, and rehearsal marks start new sections.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::chord::{Chord, ChordQuality};
+use crate::error::{ChordCraftError, Result};
+use crate::note::PitchClass;
+use crate::song::{Song, SongSection};
+
+/// A harmony element being read
+#[derive(Debug, Default)]
+struct HarmonyBuilder {
+	root_step: Option<String>,
+	root_alter: i32,
+	bass_step: Option<String>,
+	bass_alter: i32,
+	kind: Option<String>,
+	kind_text: Option<String>,
+}
+
+impl HarmonyBuilder {
+	/// Resolve to a chord; `None` for "no chord" (N.C.) or unsupported symbols
+	fn build(&self) -> Option<Chord> {
+		let root = step_to_pitch(self.root_step.as_deref()?, self.root_alter)?;
+		let kind = self.kind.as_deref().unwrap_or("major");
+
+		let quality = match kind_to_quality(kind) {
+			Some(quality) => quality,
+			None if kind == "none" => return None,
+			// Fall back to the printed symbol (e.g. kind="other" text="7alt")
+			None => {
+				let text = self.kind_text.as_deref()?;
+				Chord::parse(&format!("{}{}", root.sharp_name(), text))
+					.ok()?
+					.quality
+			}
+		};
+
+		let bass = self
+			.bass_step
+			.as_deref()
+			.and_then(|step| step_to_pitch(step, self.bass_alter));
+
+		Some(match bass {
+			Some(bass) if bass != root => Chord::with_bass(root, quality, bass),
+			_ => Chord::new(root, quality),
+		})
+	}
+}
+
+fn step_to_pitch(step: &str, alter: i32) -> Option<PitchClass> {
+	let natural = PitchClass::parse(step.trim()).ok()?;
+	Some(natural.add_semitones(alter))
+}
+
+/// Map a MusicXML `<kind>` value to a chord quality
+fn kind_to_quality(kind: &str) -> Option<ChordQuality> {
+	use ChordQuality::*;
+
+	Some(match kind.trim() {
+		"major" => Major,
+		"minor" => Minor,
+		"augmented" => Augmented,
+		"diminished" => Diminished,
+		"dominant" => Dominant7,
+		"major-seventh" => Major7,
+		"minor-seventh" => Minor7,
+		"diminished-seventh" => Diminished7,
+		"augmented-seventh" => Dominant7sharp5,
+		"half-diminished" => HalfDiminished7,
+		"major-minor" => MinorMajor7,
+		"major-sixth" => Major6,
+		"minor-sixth" => Minor6,
+		"dominant-ninth" => Dominant9,
+		"major-ninth" => Major9,
+		"minor-ninth" => Minor9,
+		"dominant-11th" => Dominant11,
+		"minor-11th" => Minor11,
+		"dominant-13th" => Dominant13,
+		"major-13th" => Major13,
+		"minor-13th" => Minor13,
+		"suspended-second" => Sus2,
+		"suspended-fourth" => Sus4,
+		"power" => Power,
+		_ => return None,
+	})
+}
+
+/// Per-part import state
+#[derive(Debug, Default)]
+struct PartState {
+	sections: Vec<SongSection>,
+	beats_per_bar: Option<u8>,
+	has_harmony: bool,
+}
+
+/// Import chord symbols from a MusicXML document
+///
+/// Uses the first part that contains `<harmony>` elements. Each chord lasts
+/// until the next chord symbol; measures without chord symbols extend the
+/// previous chord.
+pub fn import_musicxml(xml: &str) -> Result<Song> {
+	let mut reader = Reader::from_str(xml);
+	reader.config_mut().trim_text(true);
+
+	let err = |e: &dyn std::fmt::Display| ChordCraftError::InvalidMusicXml(e.to_string());
+
+	let mut title: Option<String> = None;
+	let mut path: Vec<String> = Vec::new();
+
+	let mut parts: Vec<PartState> = Vec::new();
+	let mut part = PartState::default();
+
+	// Timeline within the current measure, in divisions
+	let mut divisions: u32 = 1;
+	let mut time_beats: u32 = 4;
+	let mut time_beat_type: u32 = 4;
+	let mut position: u32 = 0;
+	let mut measure_max: u32 = 0;
+	// (position, chord) for harmonies in the current measure
+	let mut measure_harmonies: Vec<(u32, Option<Chord>)> = Vec::new();
+
+	let mut harmony: Option<HarmonyBuilder> = None;
+	let mut note_is_chord = false;
+	let mut note_duration: u32 = 0;
+	let mut pending_label: Option<String> = None;
+
+	loop {
+		let event = reader.read_event().map_err(|e| err(&e))?;
+		match event {
+			Event::Start(ref e) | Event::Empty(ref e) => {
+				let name = element_name(e);
+				match name.as_str() {
+					"part" => {
+						part = PartState::default();
+						divisions = 1;
+						time_beats = 4;
+						time_beat_type = 4;
+					}
+					"measure" => {
+						position = 0;
+						measure_max = 0;
+						measure_harmonies.clear();
+					}
+					"harmony" => harmony = Some(HarmonyBuilder::default()),
+					"kind" => {
+						if let Some(h) = harmony.as_mut()
+							&& let Ok(Some(attr)) = e.try_get_attribute("text")
+						{
+							let text = attr.unescape_value().map_err(|e| err(&e))?;
+							if !text.is_empty() {
+								h.kind_text = Some(text.to_string());
+							}
+						}
+					}
+					"note" => {
+						note_is_chord = false;
+						note_duration = 0;
+					}
+					"chord" if path.last().map(|p| p == "note").unwrap_or(false) => {
+						note_is_chord = true;
+					}
+					_ => {}
+				}
+
+				if matches!(event, Event::Start(_)) {
+					path.push(name);
+				}
+			}
+			Event::Text(ref t) => {
+				let text = t.unescape().map_err(|e| err(&e))?.trim().to_string();
+				let Some(current) = path.last().map(|s| s.as_str()) else {
+					continue;
+				};
+				let parent = path
+					.len()
+					.checked_sub(2)
+					.map(|i| path[i].as_str())
+					.unwrap_or("");
+
+				match (parent, current) {
+					("work", "work-title") | ("score-partwise", "movement-title")
+						if title.is_none() && !text.is_empty() =>
+					{
+						title = Some(text);
+					}
+					("attributes", "divisions") => {
+						divisions = text.parse().unwrap_or(1).max(1);
+					}
+					("time", "beats") => {
+						// Compound values like "3+2" are summed
+						time_beats = text
+							.split('+')
+							.filter_map(|b| b.trim().parse::<u32>().ok())
+							.sum::<u32>()
+							.max(1);
+					}
+					("time", "beat-type") => {
+						time_beat_type = text.parse().unwrap_or(4).max(1);
+						let quarters = time_beats * 4 / time_beat_type;
+						part.beats_per_bar
+							.get_or_insert(quarters.clamp(1, 255) as u8);
+					}
+					("direction-type", "rehearsal") if !text.is_empty() => {
+						pending_label = Some(text);
+					}
+					("root", "root-step") => {
+						if let Some(h) = harmony.as_mut() {
+							h.root_step = Some(text);
+						}
+					}
+					("root", "root-alter") => {
+						if let Some(h) = harmony.as_mut() {
+							h.root_alter = text.parse::<f32>().unwrap_or(0.0) as i32;
+						}
+					}
+					("bass", "bass-step") => {
+						if let Some(h) = harmony.as_mut() {
+							h.bass_step = Some(text);
+						}
+					}
+					("bass", "bass-alter") => {
+						if let Some(h) = harmony.as_mut() {
+							h.bass_alter = text.parse::<f32>().unwrap_or(0.0) as i32;
+						}
+					}
+					("harmony", "kind") => {
+						if let Some(h) = harmony.as_mut() {
+							h.kind = Some(text);
+						}
+					}
+					("note", "duration") => note_duration = text.parse().unwrap_or(0),
+					("backup", "duration") => {
+						position = position.saturating_sub(text.parse().unwrap_or(0));
+					}
+					("forward", "duration") => {
+						position += text.parse::<u32>().unwrap_or(0);
+						measure_max = measure_max.max(position);
+					}
+					_ => {}
+				}
+			}
+			Event::End(ref e) => {
+				let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+				path.pop();
+
+				match name.as_str() {
+					"harmony" => {
+						if let Some(h) = harmony.take() {
+							part.has_harmony = true;
+							if let Some(label) = pending_label.take() {
+								part.sections.push(SongSection::new(Some(label)));
+							}
+							measure_harmonies.push((position, h.build()));
+						}
+					}
+					"note" if !note_is_chord => {
+						position += note_duration;
+						measure_max = measure_max.max(position);
+					}
+					"measure" => {
+						let nominal = time_beats * divisions * 4 / time_beat_type;
+						let measure_len = measure_max.max(nominal).max(1);
+						close_measure(&mut part, &measure_harmonies, measure_len, divisions);
+					}
+					"part" => {
+						parts.push(std::mem::take(&mut part));
+					}
+					_ => {}
+				}
+			}
+			Event::Eof => break,
+			_ => {}
+		}
+	}
+
+	let mut song = Song::new(title);
+	if let Some(found) = parts.into_iter().find(|p| p.has_harmony) {
+		song.beats_per_bar = found.beats_per_bar.unwrap_or(4);
+		song.sections = found
+			.sections
+			.into_iter()
+			.filter(|s| !s.chords.is_empty())
+			.collect();
+	}
+
+	Ok(song)
+}
+
+/// Distribute a finished measure's length among its chord symbols
+fn close_measure(
+	part: &mut PartState,
+	harmonies: &[(u32, Option<Chord>)],
+	measure_len: u32,
+	divisions: u32,
+) {
+	let to_beats = |d: u32| d as f32 / divisions as f32;
+
+	if part.sections.is_empty() {
+		part.sections.push(SongSection::new(None));
+	}
+
+	let first_pos = harmonies.first().map(|(p, _)| *p).unwrap_or(measure_len);
+	// Time before the first chord symbol belongs to the previous chord
+	extend_last_chord(part, to_beats(first_pos.min(measure_len)));
+
+	for (i, (pos, chord)) in harmonies.iter().enumerate() {
+		let end = harmonies
+			.get(i + 1)
+			.map(|(p, _)| *p)
+			.unwrap_or(measure_len)
+			.max(*pos);
+		let beats = to_beats(end.min(measure_len).saturating_sub(*pos));
+
+		match chord {
+			Some(chord) => part
+				.sections
+				.last_mut()
+				.expect("section exists")
+				.push(chord.clone(), beats),
+			None => extend_last_chord(part, beats),
+		}
+	}
+}
+
+fn extend_last_chord(part: &mut PartState, beats: f32) {
+	if beats <= 0.0 {
+		return;
+	}
+	if let Some(last) = part
+		.sections
+		.iter_mut()
+		.rev()
+		.find_map(|s| s.chords.last_mut())
+	{
+		last.beats += beats;
+	}
+}
+
+fn element_name(e: &BytesStart) -> String {
+	String::from_utf8_lossy(e.name().as_ref()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn harmony(step: &str, alter: Option<i32>, kind: &str) -> String {
+		let alter = alter
+			.map(|a| format!("<root-alter>{a}</root-alter>"))
+			.unwrap_or_default();
+		format!(
+			"<harmony><root><root-step>{step}</root-step>{alter}</root><kind>{kind}</kind></harmony>"
+		)
+	}
+
+	fn whole_note() -> &'static str {
+		"<note><pitch><step>C</step><octave>5</octave></pitch><duration>4</duration></note>"
+	}
+
+	fn half_note() -> &'static str {
+		"<note><pitch><step>C</step><octave>5</octave></pitch><duration>2</duration></note>"
+	}
+
+	fn score(measures: &[String]) -> String {
+		let measures: String = measures
+			.iter()
+			.enumerate()
+			.map(|(i, m)| {
+				let attributes = if i == 0 {
+					"<attributes><divisions>1</divisions><time><beats>4</beats><beat-type>4</beat-type></time></attributes>"
+				} else {
+					""
+				};
+				format!("<measure number=\"{}\">{attributes}{m}</measure>", i + 1)
+			})
+			.collect();
+
+		format!(
+			r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <work><work-title>Lead Sheet</work-title></work>
+  <part-list><score-part id="P1"><part-name>Voice</part-name></score-part></part-list>
+  <part id="P1">{measures}</part>
+</score-partwise>"#
+		)
+	}
+
+	#[test]
+	fn test_import_basic_harmonies() {
+		let xml = score(&[
+			format!("{}{}", harmony("C", None, "major"), whole_note()),
+			format!("{}{}", harmony("A", None, "minor-seventh"), whole_note()),
+			format!("{}{}", harmony("D", None, "dominant"), whole_note()),
+			format!("{}{}", harmony("G", None, "major-seventh"), whole_note()),
+		]);
+
+		let song = import_musicxml(&xml).unwrap();
+		assert_eq!(song.title.as_deref(), Some("Lead Sheet"));
+		assert_eq!(song.beats_per_bar, 4);
+		assert_eq!(song.chord_names(), vec!["C", "Am7", "D7", "Gmaj7"]);
+		assert!(song.chords().all(|c| c.beats == 4.0));
+	}
+
+	#[test]
+	fn test_import_two_chords_per_measure_and_alterations() {
+		let xml = score(&[format!(
+			"{}{}{}{}",
+			harmony("B", Some(-1), "major"),
+			half_note(),
+			harmony("F", Some(1), "half-diminished"),
+			half_note()
+		)]);
+
+		let song = import_musicxml(&xml).unwrap();
+		let chords: Vec<_> = song.chords().collect();
+		assert_eq!(chords.len(), 2);
+		assert_eq!(chords[0].chord.root, PitchClass::ASharp);
+		assert_eq!(chords[0].beats, 2.0);
+		assert_eq!(chords[1].chord.root, PitchClass::FSharp);
+		assert_eq!(chords[1].chord.quality, ChordQuality::HalfDiminished7);
+		assert_eq!(chords[1].beats, 2.0);
+	}
+
+	#[test]
+	fn test_import_measure_without_harmony_extends_chord() {
+		let xml = score(&[
+			format!("{}{}", harmony("G", None, "major"), whole_note()),
+			whole_note().to_string(),
+			format!("{}{}", harmony("C", None, "major"), whole_note()),
+		]);
+
+		let song = import_musicxml(&xml).unwrap();
+		let chords: Vec<_> = song.chords().collect();
+		assert_eq!(chords[0].beats, 8.0);
+		assert_eq!(chords[1].beats, 4.0);
+	}
+
+	#[test]
+	fn test_import_rehearsal_sections_and_bass() {
+		let rehearsal = |label: &str| {
+			format!(
+				"<direction><direction-type><rehearsal>{label}</rehearsal></direction-type></direction>"
+			)
+		};
+		let slash = "<harmony><root><root-step>C</root-step></root><kind>major</kind><bass><bass-step>E</bass-step></bass></harmony>";
+
+		let xml = score(&[
+			format!(
+				"{}{}{}",
+				rehearsal("A"),
+				harmony("C", None, "major"),
+				whole_note()
+			),
+			format!("{}{}{}", rehearsal("B"), slash, whole_note()),
+		]);
+
+		let song = import_musicxml(&xml).unwrap();
+		assert_eq!(song.sections.len(), 2);
+		assert_eq!(song.sections[0].label.as_deref(), Some("A"));
+		assert_eq!(song.sections[1].label.as_deref(), Some("B"));
+		assert_eq!(song.sections[1].chords[0].chord.bass, Some(PitchClass::E));
+	}
+
+	#[test]
+	fn test_import_kind_text_fallback_and_no_chord() {
+		let other = "<harmony><root><root-step>E</root-step></root><kind text=\"7b9\">other</kind></harmony>";
+		let none = "<harmony><root><root-step>C</root-step></root><kind>none</kind></harmony>";
+
+		let xml = score(&[
+			format!("{other}{}", whole_note()),
+			format!("{none}{}", whole_note()),
+		]);
+
+		let song = import_musicxml(&xml).unwrap();
+		let chords: Vec<_> = song.chords().collect();
+		assert_eq!(chords.len(), 1);
+		assert_eq!(chords[0].chord.quality, ChordQuality::Dominant7b9);
+		// N.C. measure keeps the previous chord ringing
+		assert_eq!(chords[0].beats, 8.0);
+	}
+
+	#[test]
+	fn test_import_invalid_xml() {
+		assert!(import_musicxml("<score-partwise><part></score-partwise>").is_err());
+	}
+}
@@ -7,6 +7,7 @@
 //! - Conversions and calculations
 
 use crate::error::{ChordCraftError, Result};
+use crate::spelling::NoteName;
 use std::fmt;
 
 /// A pitch class representing one of the 12 notes in an octave
@@ -78,7 +79,8 @@ impl PitchClass {
 			"A" => Ok(PitchClass::A),
 			"A#" | "AS" | "BB" | "B♭" => Ok(PitchClass::ASharp),
 			"B" => Ok(PitchClass::B),
-			_ => Err(ChordCraftError::InvalidNote(s.to_string())),
+			// Cb, E#, Fx and other spellings a pitch class has no name for
+			_ => NoteName::parse(s).map(|name| name.pitch_class()),
 		}
 	}
 
@@ -207,6 +209,46 @@ impl fmt::Display for Note {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for PitchClass {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PitchClass {
+	fn deserialize<D: serde::Deserializer<'de>>(
+		deserializer: D,
+	) -> std::result::Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		PitchClass::parse(&s).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Note {
+	fn serialize<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Note {
+	fn deserialize<D: serde::Deserializer<'de>>(
+		deserializer: D,
+	) -> std::result::Result<Self, D::Error> {
+		let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+		Note::parse(&s).map_err(serde::de::Error::custom)
+	}
+}
+
 // Convenience constants for common pitch classes
 pub const C: PitchClass = PitchClass::C;
 pub const C_SHARP: PitchClass = PitchClass::CSharp;
@@ -246,6 +288,9 @@ mod tests {
 		assert_eq!(PitchClass::parse("C#").unwrap(), PitchClass::CSharp);
 		assert_eq!(PitchClass::parse("Db").unwrap(), PitchClass::CSharp);
 		assert_eq!(PitchClass::parse("Ab").unwrap(), PitchClass::GSharp);
+		assert_eq!(PitchClass::parse("Cb").unwrap(), PitchClass::B);
+		assert_eq!(PitchClass::parse("E#").unwrap(), PitchClass::F);
+		assert!(PitchClass::parse("H").is_err());
 	}
 
 	#[test]
@@ -0,0 +1,236 @@
+//! Passing chords
+//!
+//! [`suggest_passing_chords`] fills the gap between two chords of a
+//! progression with an optional chord that leads into the second one:
+//!
+//! - a chromatic approach, the target chord a half step above or below it
+//!   (Ab → G, F# → G)
+//! - a diminished passing chord on the target's leading tone (C → C#dim7 → Dm)
+//! - a suspension resolving into it (Gsus4 → G)
+//!
+//! Each suggestion comes with the voicing that best bridges the two grips
+//! already in the progression, scored like any other change.
+
+use std::fmt;
+
+use crate::chord::{Chord, ChordQuality};
+use crate::generator::{ScoredFingering, generate_fingerings};
+use crate::instrument::Instrument;
+use crate::interval::{MAJOR_THIRD, MINOR_THIRD};
+use crate::progression::{ChordTransition, ProgressionOptions, transition_between};
+
+/// How a passing chord leads into its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PassingKind {
+	/// The target chord shifted a half step, sliding into place
+	ChromaticApproach,
+	/// Diminished seventh a half step below the target's root
+	DiminishedPassing,
+	/// The target with its third suspended, resolving onto it
+	SusResolution,
+}
+
+impl fmt::Display for PassingKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PassingKind::ChromaticApproach => write!(f, "chromatic approach"),
+			PassingKind::DiminishedPassing => write!(f, "diminished passing"),
+			PassingKind::SusResolution => write!(f, "sus resolution"),
+		}
+	}
+}
+
+/// An optional chord played between two others
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PassingChord {
+	pub chord: Chord,
+	pub kind: PassingKind,
+	pub fingering: ScoredFingering,
+	/// Change from the first chord into the passing chord
+	pub approach: ChordTransition,
+	/// Change from the passing chord into the second one
+	pub resolution: ChordTransition,
+}
+
+impl PassingChord {
+	/// Combined score of the two changes it adds
+	pub fn score(&self) -> i32 {
+		self.approach.score + self.resolution.score
+	}
+}
+
+/// Suggest passing chords for a change in a progression, best bridge first
+///
+/// Each candidate chord is voiced with the fingering that makes the smoothest
+/// pair of changes from `transition`'s first grip and into its second.
+/// Voicings must sound every required tone of the chord, and candidates with
+/// none within [`ProgressionOptions::max_fret_distance`] of both grips are
+/// left out. Returns at most `options.limit` suggestions, and
+/// nothing if the transition's chord names can't be parsed.
+///
+/// # Example
+///
+/// ```
+/// use chordcraft_core::instrument::Guitar;
+/// use chordcraft_core::passing::suggest_passing_chords;
+/// use chordcraft_core::progression::{ProgressionOptions, generate_progression};
+///
+/// let guitar = Guitar::default();
+/// let options = ProgressionOptions { limit: 10, ..Default::default() };
+/// let progression = &generate_progression(&["C", "Dm"], &guitar, &options)[0];
+///
+/// let passing = suggest_passing_chords(&progression.transitions[0], &guitar, &options);
+/// assert!(passing.iter().any(|p| p.chord.to_string() == "C#dim7"));
+/// ```
+pub fn suggest_passing_chords<I: Instrument>(
+	transition: &ChordTransition,
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Vec<PassingChord> {
+	let (Ok(from), Ok(to)) = (
+		Chord::parse(&transition.from_chord),
+		Chord::parse(&transition.to_chord),
+	) else {
+		return vec![];
+	};
+
+	let mut opts = options.generator_options.clone();
+	opts.limit = options.candidates_per_chord;
+
+	let mut suggestions: Vec<PassingChord> = passing_candidates(&from, &to)
+		.into_iter()
+		.filter_map(|(chord, kind)| {
+			let name = chord.to_string();
+			// A passing chord is heard for its color, so no voicing may drop a tone of it
+			let required = chord.required_notes();
+			generate_fingerings(&chord, instrument, &opts)
+				.into_iter()
+				.filter(|fingering| {
+					let sounding = fingering.fingering.pitch_classes(instrument);
+					required.iter().all(|n| sounding.contains(n))
+				})
+				.filter_map(|fingering| {
+					let approach = transition_between(
+						&transition.from_chord,
+						&transition.from_fingering,
+						&name,
+						&fingering,
+						instrument,
+						options,
+					);
+					let resolution = transition_between(
+						&name,
+						&fingering,
+						&transition.to_chord,
+						&transition.to_fingering,
+						instrument,
+						options,
+					);
+					(approach.position_distance <= options.max_fret_distance
+						&& resolution.position_distance <= options.max_fret_distance)
+						.then(|| PassingChord {
+							chord: chord.clone(),
+							kind,
+							fingering,
+							approach,
+							resolution,
+						})
+				})
+				.max_by_key(PassingChord::score)
+		})
+		.collect();
+
+	suggestions.sort_by_key(|p| std::cmp::Reverse(p.score()));
+	suggestions.truncate(options.limit);
+	suggestions
+}
+
+/// Chords that can pass from `from` into `to`, other than the two themselves
+fn passing_candidates(from: &Chord, to: &Chord) -> Vec<(Chord, PassingKind)> {
+	let mut candidates = vec![
+		(
+			Chord::new(to.root.add_semitones(1), to.quality),
+			PassingKind::ChromaticApproach,
+		),
+		(
+			Chord::new(to.root.add_semitones(-1), to.quality),
+			PassingKind::ChromaticApproach,
+		),
+		(
+			Chord::new(to.root.add_semitones(-1), ChordQuality::Diminished7),
+			PassingKind::DiminishedPassing,
+		),
+	];
+	// Only a chord with a third has one to suspend
+	let (required, _) = to.quality.intervals();
+	if required.contains(&MAJOR_THIRD) || required.contains(&MINOR_THIRD) {
+		for quality in [ChordQuality::Sus4, ChordQuality::Sus2] {
+			candidates.push((Chord::new(to.root, quality), PassingKind::SusResolution));
+		}
+	}
+
+	let same = |a: &Chord, b: &Chord| a.root == b.root && a.quality == b.quality;
+	candidates.retain(|(chord, _)| !same(chord, from) && !same(chord, to));
+	candidates
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+	use crate::progression::generate_progression;
+
+	#[test]
+	fn test_passing_chords() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions {
+			limit: 10,
+			..Default::default()
+		};
+		let progression = &generate_progression(&["C", "G"], &guitar, &options)[0];
+		let transition = &progression.transitions[0];
+		let passing = suggest_passing_chords(transition, &guitar, &options);
+
+		let names: Vec<String> = passing.iter().map(|p| p.chord.to_string()).collect();
+		for name in ["G#", "F#", "F#dim7", "Gsus4", "Gsus2"] {
+			assert!(names.contains(&name.to_string()), "{name} in {names:?}");
+		}
+		assert!(passing.windows(2).all(|w| w[0].score() >= w[1].score()));
+
+		// Each suggestion bridges the progression's own grips
+		for p in &passing {
+			assert_eq!(
+				p.approach.from_fingering.fingering,
+				transition.from_fingering.fingering
+			);
+			assert_eq!(
+				p.resolution.to_fingering.fingering,
+				transition.to_fingering.fingering
+			);
+			assert_eq!(p.approach.to_chord, p.chord.to_string());
+			assert!(p.approach.position_distance <= options.max_fret_distance);
+			assert!(p.resolution.position_distance <= options.max_fret_distance);
+		}
+		let kind = |name: &str| {
+			passing
+				.iter()
+				.find(|p| p.chord.to_string() == name)
+				.unwrap()
+				.kind
+		};
+		assert_eq!(kind("F#dim7"), PassingKind::DiminishedPassing);
+		assert_eq!(kind("Gsus4"), PassingKind::SusResolution);
+
+		// Nothing to suspend in a power chord; never the chords themselves
+		let candidates =
+			passing_candidates(&Chord::parse("G5").unwrap(), &Chord::parse("G#5").unwrap());
+		assert!(
+			candidates
+				.iter()
+				.all(|(_, k)| *k != PassingKind::SusResolution)
+		);
+		assert_eq!(candidates.len(), 2);
+	}
+}
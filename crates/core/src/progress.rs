@@ -0,0 +1,181 @@
+//! Progress reporting and cancellation for long-running searches
+//!
+//! The `*_with` variants of the search functions take a [`SearchObserver`],
+//! which receives progress updates and is polled for cancellation. A closure
+//! taking [`Progress`] works as a progress-only observer, and a
+//! [`CancellationToken`] can be cloned into another thread (or kept by a web
+//! worker) to abort a search that is no longer needed.
+//!
+//! The `*_within` variants instead take a [`SearchBudget`] and, when it runs
+//! out, return the best results found so far marked as incomplete.
+
+use std::cell::Cell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// Polls between clock reads when a time budget is set
+const CLOCK_CHECK_INTERVAL: usize = 64;
+
+/// Stage of a search that a [`Progress`] update refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchPhase {
+	/// Enumerating string combinations for a chord
+	Enumerating,
+	/// Scoring enumerated fingerings
+	Scoring,
+	/// Generating candidate fingerings for each chord of a progression
+	Candidates,
+	/// Optimizing transitions between chords
+	Optimizing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+	pub phase: SearchPhase,
+	/// Completed steps in this phase
+	pub done: usize,
+	/// Total steps in this phase
+	pub total: usize,
+}
+
+impl Progress {
+	/// Completed fraction of the phase (0.0 - 1.0)
+	pub fn fraction(&self) -> f32 {
+		if self.total == 0 {
+			1.0
+		} else {
+			self.done as f32 / self.total as f32
+		}
+	}
+}
+
+/// Receives progress updates from a search and decides whether it continues
+pub trait SearchObserver {
+	fn on_progress(&self, _progress: Progress) {}
+
+	/// Polled regularly; returning true aborts the search
+	fn is_cancelled(&self) -> bool {
+		false
+	}
+}
+
+/// Observer that ignores progress and never cancels
+pub struct NoopObserver;
+
+impl SearchObserver for NoopObserver {}
+
+impl<F: Fn(Progress)> SearchObserver for F {
+	fn on_progress(&self, progress: Progress) {
+		self(progress)
+	}
+}
+
+/// Shared flag for cancelling a search from elsewhere
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+impl SearchObserver for CancellationToken {
+	fn is_cancelled(&self) -> bool {
+		CancellationToken::is_cancelled(self)
+	}
+}
+
+/// Forwards cancellation but not progress, for nested searches whose
+/// progress would confuse the outer one
+pub(crate) struct CancelOnly<'a>(pub &'a dyn SearchObserver);
+
+impl SearchObserver for CancelOnly<'_> {
+	fn is_cancelled(&self) -> bool {
+		self.0.is_cancelled()
+	}
+}
+
+/// Limits on how much work a search may do before returning what it has
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchBudget {
+	/// Wall-clock limit in milliseconds
+	///
+	/// Needs a system clock, which `wasm32-unknown-unknown` doesn't have; use
+	/// `max_nodes` there.
+	pub time_budget_ms: Option<u64>,
+	/// Limit on search nodes (string combinations enumerated, then progression steps)
+	pub max_nodes: Option<usize>,
+}
+
+impl SearchBudget {
+	pub fn time_ms(ms: u64) -> Self {
+		SearchBudget {
+			time_budget_ms: Some(ms),
+			max_nodes: None,
+		}
+	}
+
+	pub fn nodes(max_nodes: usize) -> Self {
+		SearchBudget {
+			time_budget_ms: None,
+			max_nodes: Some(max_nodes),
+		}
+	}
+}
+
+/// Results of a budgeted search
+#[derive(Debug, Clone)]
+pub struct Partial<T> {
+	pub results: T,
+	/// False if the budget ran out and `results` are the best found so far
+	pub complete: bool,
+}
+
+/// Treats an exhausted budget as cancellation, counting one node per poll
+pub(crate) struct BudgetObserver {
+	budget: SearchBudget,
+	start: Option<Instant>,
+	nodes: Cell<usize>,
+	exhausted: Cell<bool>,
+}
+
+impl BudgetObserver {
+	pub(crate) fn new(budget: SearchBudget) -> Self {
+		BudgetObserver {
+			budget,
+			start: budget.time_budget_ms.map(|_| Instant::now()),
+			nodes: Cell::new(0),
+			exhausted: Cell::new(false),
+		}
+	}
+}
+
+impl SearchObserver for BudgetObserver {
+	fn is_cancelled(&self) -> bool {
+		if self.exhausted.get() {
+			return true;
+		}
+
+		let nodes = self.nodes.get() + 1;
+		self.nodes.set(nodes);
+		let out_of_nodes = self.budget.max_nodes.is_some_and(|max| nodes > max);
+		let out_of_time = (nodes - 1).is_multiple_of(CLOCK_CHECK_INTERVAL)
+			&& self
+				.start
+				.zip(self.budget.time_budget_ms)
+				.is_some_and(|(start, ms)| start.elapsed().as_millis() >= ms as u128);
+
+		self.exhausted.set(out_of_nodes || out_of_time);
+		self.exhausted.get()
+	}
+}
@@ -3,10 +3,21 @@
 //! This module provides algorithms for finding optimal fingering sequences
 //! for chord progressions, minimizing finger movement and maximizing smooth transitions.
 
+use crate::cache::FingeringCache;
+use crate::chart::Chart;
 use crate::chord::Chord;
+use crate::error::{ChordCraftError, Result};
 use crate::fingering::Fingering;
-use crate::generator::{GeneratorOptions, PlayingContext, ScoredFingering, generate_fingerings};
+use crate::generator::{
+	GeneratorOptions, PlayingContext, ScoredFingering, generate_fingerings, search_fingerings,
+};
+use crate::harmony::{NumeralAnalysis, analyze_numerals, resolve_numerals};
 use crate::instrument::Instrument;
+use crate::key::Key;
+use crate::progress::{
+	BudgetObserver, CancelOnly, NoopObserver, Partial, Progress, SearchBudget, SearchObserver,
+	SearchPhase,
+};
 use crate::shapes;
 
 const BASE_SCORE: i32 = 100;
@@ -19,13 +30,73 @@ const DISTANCE_PENALTY: i32 = 5;
 const SAME_SHAPE_SLIDE_BONUS: i32 = 50;
 const BAND_MOVEMENT_WEIGHT: i32 = 40;
 const BAND_DISTANCE_PENALTY: i32 = 8;
+const STRUMMING_MOVEMENT_WEIGHT: i32 = 35;
+const STRUMMING_DISTANCE_PENALTY: i32 = 6;
+
+// Rough timing model for how long a change takes a practiced player
+const CHANGE_BASE_MS: u32 = 120;
+const FINGER_MOVEMENT_MS: u32 = 70;
+const BARRE_FORMATION_MS: u32 = 150;
+const POSITION_SHIFT_MS: u32 = 35;
+
+// With a tempo, changes cost more the more of their time they take up
+const TEMPO_WEIGHT: f32 = 60.0;
+const RISKY_CHANGE_PENALTY: i32 = 100;
+
+// Voice leading, on the sounding pitches
+const COMMON_TONE_BONUS: i32 = 15;
+const TOP_VOICE_STEP_BONUS: i32 = 20;
+const TOP_VOICE_LEAP_PENALTY: i32 = 5;
+const PARALLEL_LEAP_PENALTY: i32 = 25;
+const STEP_SEMITONES: i32 = 2;
+
+/// Change between grips of the same character, with consistent voicing on
+const SAME_CHARACTER_BONUS: i32 = 60;
 
 #[derive(Debug, Clone)]
 pub struct ProgressionOptions {
 	pub limit: usize,
 	pub max_fret_distance: u8,
 	pub candidates_per_chord: usize,
+	/// Options for every chord's candidates; a fret window (`min_fret` and
+	/// `max_fret`) here holds for the whole progression
 	pub generator_options: GeneratorOptions,
+	/// Roman-numeral labels to attach to each sequence
+	pub numerals: Numerals,
+	/// Keep only this many partial sequences per chord (beam search) instead
+	/// of searching exactly; faster for long songs with many candidates, but
+	/// may miss the best sequence. Never narrower than `limit`.
+	pub beam_width: Option<usize>,
+	/// Tempo in beats per minute; scores each change by how much of the time
+	/// available for it it takes, and flags the ones that won't fit
+	pub bpm: Option<f32>,
+	/// Beats each chord is held for, in order (chords past the end get one beat)
+	pub durations: Vec<f32>,
+	/// Also score changes on their sounding pitches (see [`voice_leading_score`]),
+	/// not just on finger movement
+	pub voice_leading: bool,
+	/// Favor sequences whose grips all share one [`VoicingCharacter`](crate::generator::VoicingCharacter) (all
+	/// open, all barres or all compact), so the progression sounds coherent
+	pub consistent_voicing: bool,
+	/// Chords each alternative must finger differently from every better one,
+	/// so alternatives aren't the same sequence with one chord swapped
+	/// (1 allows that)
+	pub min_difference: usize,
+	/// Weight of the fingerings' own scores against their transitions in the
+	/// total, from 0 (smoothest changes only) to 1 (best individual voicings,
+	/// changes only kept within `max_fret_distance`)
+	pub alpha: f32,
+}
+
+/// Whether and in which key to label progressions with roman numerals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Numerals {
+	#[default]
+	Off,
+	/// In the key detected from the chords
+	DetectedKey,
+	/// In the given key
+	InKey(Key),
 }
 
 impl Default for ProgressionOptions {
@@ -35,11 +106,51 @@ impl Default for ProgressionOptions {
 			max_fret_distance: 3,
 			candidates_per_chord: 20,
 			generator_options: GeneratorOptions::default(),
+			numerals: Numerals::Off,
+			beam_width: None,
+			bpm: None,
+			durations: Vec::new(),
+			voice_leading: false,
+			consistent_voicing: false,
+			min_difference: 2,
+			alpha: 0.0,
+		}
+	}
+}
+
+impl ProgressionOptions {
+	/// Milliseconds available for the change away from chord `index`, if a tempo is set
+	///
+	/// A chord held up to a beat leaves all of it for the change; a longer one
+	/// leaves its last beat, or its second half once it lasts more than two
+	/// beats, so long holds tolerate bigger jumps.
+	pub fn change_budget(&self, index: usize) -> Option<u32> {
+		let bpm = self.bpm?;
+		let hold = self.durations.get(index).copied().unwrap_or(1.0);
+		Some(change_budget_ms(bpm, hold.min(1.0).max(hold / 2.0)))
+	}
+
+	/// What playing `fingering` adds to a sequence's total, by [`Self::alpha`]
+	fn fingering_score(&self, fingering: &ScoredFingering) -> i32 {
+		if self.alpha <= 0.0 {
+			return 0;
 		}
+		(self.alpha.min(1.0) * fingering.score as f32).round() as i32
+	}
+
+	/// What a change into `to` adds to a sequence's total, blending the
+	/// transition and the fingering it lands on by [`Self::alpha`]
+	fn step_score(&self, transition: &ChordTransition, to: &ScoredFingering) -> i32 {
+		if self.alpha <= 0.0 {
+			return transition.score;
+		}
+		let alpha = self.alpha.min(1.0);
+		((1.0 - alpha) * transition.score as f32).round() as i32 + self.fingering_score(to)
 	}
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChordTransition {
 	pub from_chord: String,
 	pub to_chord: String,
@@ -49,19 +160,101 @@ pub struct ChordTransition {
 	pub finger_movements: usize,
 	pub common_anchors: usize,
 	pub position_distance: u8,
+	/// Estimated time to make the change, in milliseconds
+	pub estimated_ms: u32,
+	/// Time available for the change at the options' tempo, in milliseconds
+	pub budget_ms: Option<u32>,
+	/// Voice-leading part of `score`, if [`ProgressionOptions::voice_leading`] is set
+	pub voice_leading: Option<i32>,
+}
+
+impl ChordTransition {
+	/// Whether the change can be made within `budget_ms` milliseconds
+	pub fn is_feasible(&self, budget_ms: u32) -> bool {
+		self.estimated_ms <= budget_ms
+	}
+
+	/// Whether the change won't fit the time available at the options' tempo
+	pub fn is_risky(&self) -> bool {
+		self.budget_ms
+			.is_some_and(|budget| !self.is_feasible(budget))
+	}
+}
+
+/// Milliseconds available for a chord change lasting `beats` beats at `bpm`
+pub fn change_budget_ms(bpm: f32, beats: f32) -> u32 {
+	if bpm <= 0.0 {
+		return u32::MAX;
+	}
+	(60_000.0 / bpm * beats) as u32
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProgressionSequence {
 	pub chords: Vec<String>,
 	pub fingerings: Vec<ScoredFingering>,
 	pub transitions: Vec<ChordTransition>,
 	pub total_score: i32,
 	pub avg_transition_score: f32,
+	/// Roman numerals of the chords, if requested in [`ProgressionOptions::numerals`]
+	pub numerals: Option<NumeralAnalysis>,
+	/// Bars and repeats of the input, when given in bar notation
+	///
+	/// `chords` follow it in playing order as [`Chart::to_song`] lists them:
+	/// repeats written out, and a chord held over a bar line counted once.
+	pub chart: Option<Chart>,
+}
+
+impl ProgressionSequence {
+	/// Difficulty of the sequence (0 = trivial, 100 = unplayable)
+	///
+	/// The average playability deficit of its fingerings; None if it is empty.
+	pub fn difficulty<I: Instrument>(&self, instrument: &I) -> Option<u8> {
+		if self.fingerings.is_empty() {
+			return None;
+		}
+		let deficit: u32 = self
+			.fingerings
+			.iter()
+			.map(|f| 100u32.saturating_sub(f.fingering.playability_score_for(instrument) as u32))
+			.sum();
+		Some((deficit / self.fingerings.len() as u32).min(100) as u8)
+	}
+
+	/// Number of chords fingered differently in `other`
+	pub fn differing_fingerings(&self, other: &ProgressionSequence) -> usize {
+		self.fingerings
+			.iter()
+			.zip(&other.fingerings)
+			.filter(|(a, b)| a.fingering != b.fingering)
+			.count() + self.fingerings.len().abs_diff(other.fingerings.len())
+	}
+
+	/// Transitions that can't be made within one beat at `bpm`
+	///
+	/// Changes usually happen in the gap before the next chord's downbeat, so a
+	/// single beat is used as the time budget.
+	pub fn impractical_transitions(&self, bpm: f32) -> Vec<&ChordTransition> {
+		let budget = change_budget_ms(bpm, 1.0);
+		self.transitions
+			.iter()
+			.filter(|t| !t.is_feasible(budget))
+			.collect()
+	}
+
+	/// Transitions that won't fit the time [`ProgressionOptions::bpm`] and
+	/// [`ProgressionOptions::durations`] leave for them
+	pub fn risky_transitions(&self) -> Vec<&ChordTransition> {
+		self.transitions.iter().filter(|t| t.is_risky()).collect()
+	}
 }
 
 /// Generate optimized fingering progressions for a sequence of chords
 ///
+/// Returns nothing if a chord name can't be parsed; check the names with
+/// [`parse_chord_names`] (or use [`generate_progression_with`]) to find out which.
+///
 /// # Examples
 ///
 /// ```
@@ -80,148 +273,901 @@ pub fn generate_progression<I: Instrument>(
 	instrument: &I,
 	options: &ProgressionOptions,
 ) -> Vec<ProgressionSequence> {
-	let chords: Vec<Chord> = chord_names
+	// A no-op observer never cancels
+	generate_progression_with(chord_names, instrument, options, &NoopObserver).unwrap_or_default()
+}
+
+/// Generate progressions for roman numerals in a key
+///
+/// Resolves the numerals to chords (see [`resolve_numerals`]) and optimizes
+/// them like [`generate_progression`]. Unless the options ask for other
+/// numerals, the sequences are labelled with the numerals in `key`.
+/// Returns an error if a numeral can't be parsed.
+///
+/// # Example
+///
+/// ```
+/// use chordcraft_core::Key;
+/// use chordcraft_core::instrument::Guitar;
+/// use chordcraft_core::progression::{ProgressionOptions, generate_progression_from_numerals};
+///
+/// let guitar = Guitar::default();
+/// let key = Key::parse("G").unwrap();
+/// let options = ProgressionOptions::default();
+///
+/// let progressions =
+///     generate_progression_from_numerals(&["I", "vi", "IV", "V"], key, &guitar, &options).unwrap();
+/// assert_eq!(progressions[0].chords, ["G", "Em", "C", "D"]);
+/// ```
+pub fn generate_progression_from_numerals<I: Instrument>(
+	numerals: &[&str],
+	key: Key,
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Result<Vec<ProgressionSequence>> {
+	let names: Vec<String> = resolve_numerals(numerals, &key)?
 		.iter()
-		.filter_map(|name| Chord::parse(name).ok())
+		.map(|chord| chord.to_string())
 		.collect();
+	let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+	let options = match options.numerals {
+		Numerals::Off => &ProgressionOptions {
+			numerals: Numerals::InKey(key),
+			..options.clone()
+		},
+		_ => options,
+	};
+	Ok(generate_progression(&names, instrument, options))
+}
+
+/// Generate progressions for a chart in bar notation, keeping its bars and repeats
+///
+/// Parses `| C . . . | Am | F G |` style input (see [`Chart::parse`]) and
+/// optimizes the chords in playing order like [`generate_progression`]. Each
+/// sequence carries the chart, so it can be rendered bar by bar, and unless
+/// [`ProgressionOptions::durations`] are given the chart's rhythm sets them.
+/// Returns an error if the chart can't be parsed.
+///
+/// # Example
+///
+/// ```
+/// use chordcraft_core::instrument::Guitar;
+/// use chordcraft_core::progression::{ProgressionOptions, generate_progression_from_chart};
+///
+/// let guitar = Guitar::default();
+/// let options = ProgressionOptions::default();
+///
+/// let progressions = generate_progression_from_chart("|: C | Am :| F G |", 4, &guitar, &options).unwrap();
+/// assert_eq!(progressions[0].chords, ["C", "Am", "C", "Am", "F", "G"]);
+/// assert_eq!(progressions[0].chart.as_ref().unwrap().measure_count(), 5);
+/// ```
+pub fn generate_progression_from_chart<I: Instrument>(
+	text: &str,
+	beats_per_bar: u8,
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Result<Vec<ProgressionSequence>> {
+	let chart = Chart::parse(text, beats_per_bar)?;
+	let song = chart.to_song();
+	let names = song.chord_names();
+	let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+	// The chart's rhythm gives each change its time, unless the options already do
+	let options = if options.durations.is_empty() {
+		&ProgressionOptions {
+			durations: song.chords().map(|c| c.beats).collect(),
+			..options.clone()
+		}
+	} else {
+		options
+	};
+	Ok(generate_progression(&names, instrument, options)
+		.into_iter()
+		.map(|sequence| ProgressionSequence {
+			chart: Some(chart.clone()),
+			..sequence
+		})
+		.collect())
+}
+
+/// Parse the chords of a progression, reporting every name that fails
+///
+/// Returns [`ChordCraftError::InvalidChordNames`] with each bad name and its
+/// position, so a typo like "Amaj77" can be pointed out rather than dropped.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::ChordCraftError;
+/// use chordcraft_core::progression::parse_chord_names;
+///
+/// assert_eq!(parse_chord_names(&["C", "Am"]).unwrap().len(), 2);
+/// match parse_chord_names(&["C", "Amaj77", "F", "Hm"]) {
+///     Err(ChordCraftError::InvalidChordNames(bad)) => {
+///         assert_eq!(bad, [(1, "Amaj77".to_string()), (3, "Hm".to_string())]);
+///     }
+///     other => panic!("unexpected {other:?}"),
+/// }
+/// ```
+pub fn parse_chord_names<S: AsRef<str>>(chord_names: &[S]) -> Result<Vec<Chord>> {
+	let mut chords = Vec::with_capacity(chord_names.len());
+	let mut invalid = Vec::new();
+	for (i, name) in chord_names.iter().enumerate() {
+		match Chord::parse(name.as_ref()) {
+			Ok(chord) => chords.push(chord),
+			Err(_) => invalid.push((i, name.as_ref().to_string())),
+		}
+	}
+	if invalid.is_empty() {
+		Ok(chords)
+	} else {
+		Err(ChordCraftError::InvalidChordNames(invalid))
+	}
+}
+
+/// Generate progressions, reporting progress to and polling `observer` for cancellation
+///
+/// Progress is reported per chord while generating candidates, then per
+/// transition while optimizing. Returns [`ChordCraftError::InvalidChordNames`]
+/// if any chord can't be parsed, and [`ChordCraftError::Cancelled`] as soon
+/// as the observer asks to stop.
+pub fn generate_progression_with<I: Instrument>(
+	chord_names: &[&str],
+	instrument: &I,
+	options: &ProgressionOptions,
+	observer: &dyn SearchObserver,
+) -> Result<Vec<ProgressionSequence>> {
+	parse_chord_names(chord_names)?;
+	match search_progression(chord_names, instrument, options, observer, None) {
+		(sequences, true) => Ok(sequences),
+		(_, false) => Err(ChordCraftError::Cancelled),
+	}
+}
+
+/// Generate progressions within a time or node budget
+///
+/// Half the time budget (and all of the node budget) is shared evenly between
+/// the candidate searches of the distinct chords (a repeated chord reuses its
+/// candidates), each keeping what it found when its share runs out. If the
+/// whole budget runs out while optimizing, the remaining chords are chosen
+/// greedily, so a full sequence is still returned with `complete` set to
+/// false. Like [`generate_progression`], returns nothing if a chord name
+/// can't be parsed.
+pub fn generate_progression_within<I: Instrument>(
+	chord_names: &[&str],
+	instrument: &I,
+	options: &ProgressionOptions,
+	budget: &SearchBudget,
+) -> Partial<Vec<ProgressionSequence>> {
+	let searches = parse_chord_names(chord_names).map_or(chord_names.len(), |chords| {
+		(0..chords.len())
+			.filter(|&i| !chords[..i].contains(&chords[i]))
+			.count()
+	});
+	let chord_budget = SearchBudget {
+		time_budget_ms: budget
+			.time_budget_ms
+			.map(|ms| ms / 2 / searches.max(1) as u64),
+		max_nodes: budget.max_nodes.map(|n| n / searches.max(1)),
+	};
+	let (results, complete) = search_progression(
+		chord_names,
+		instrument,
+		options,
+		&BudgetObserver::new(*budget),
+		Some(chord_budget),
+	);
+	Partial { results, complete }
+}
+
+/// Run the search until done or `observer` asks to stop; the flag is false if stopped
+///
+/// With a `chord_budget`, each chord's candidates are searched under their own
+/// budget and a stopped search returns the best it found; otherwise a stopped
+/// search returns nothing right away.
+fn search_progression<I: Instrument>(
+	chord_names: &[&str],
+	instrument: &I,
+	options: &ProgressionOptions,
+	observer: &dyn SearchObserver,
+	chord_budget: Option<SearchBudget>,
+) -> (Vec<ProgressionSequence>, bool) {
+	let keep_partial = chord_budget.is_some();
+	// Dropping a bad chord would optimize a different song, so give nothing
+	let Ok(chords) = parse_chord_names(chord_names) else {
+		return (vec![], true);
+	};
 
 	if chords.is_empty() {
-		return vec![];
+		return (vec![], true);
 	}
 
+	let mut complete = true;
 	let mut candidates: Vec<Vec<ScoredFingering>> = Vec::new();
-	for chord in &chords {
+	for (i, chord) in chords.iter().enumerate() {
+		observer.on_progress(Progress {
+			phase: SearchPhase::Candidates,
+			done: i,
+			total: chords.len(),
+		});
+		// Songs come back to the same chords; their candidates don't change
+		if let Some(seen) = chords[..i].iter().position(|c| c == chord) {
+			candidates.push(candidates[seen].clone());
+			continue;
+		}
 		let mut opts = options.generator_options.clone();
 		opts.limit = options.candidates_per_chord;
-		let fingerings = generate_fingerings(chord, instrument, &opts);
+		let (fingerings, finished) = match chord_budget {
+			Some(budget) => {
+				search_fingerings(chord, instrument, &opts, &BudgetObserver::new(budget), true)
+			}
+			None => search_fingerings(chord, instrument, &opts, &CancelOnly(observer), false),
+		};
+		if !finished && !keep_partial {
+			return (vec![], false);
+		}
+		complete &= finished;
 		candidates.push(fingerings);
 	}
+	observer.on_progress(Progress {
+		phase: SearchPhase::Candidates,
+		done: chords.len(),
+		total: chords.len(),
+	});
 
 	if candidates.iter().any(|c| c.is_empty()) {
-		return vec![];
+		return (vec![], complete);
 	}
 
-	// Beam search: keep top-K partial sequences at each step
-	let beam_width = (options.limit * 3).max(10); // wider beam for better results
+	let (result, finished) = optimize_progression(
+		chord_names,
+		&candidates,
+		instrument,
+		options,
+		observer,
+		keep_partial,
+	);
+	if !finished && !keep_partial {
+		return (vec![], false);
+	}
 
-	let sequences =
-		beam_search_progression(chord_names, &candidates, beam_width, instrument, options);
+	(pick_distinct(result, options), complete && finished)
+}
 
-	let mut result: Vec<ProgressionSequence> = sequences;
-	result.sort_by(|a, b| b.total_score.cmp(&a.total_score));
-	result.truncate(options.limit);
-	result
+/// A voicing of the next chord, ranked by how easily it follows the current grip
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NextFingering {
+	pub fingering: ScoredFingering,
+	pub transition_score: i32,
+	pub finger_movements: usize,
+	pub common_anchors: usize,
+	pub position_distance: u8,
+	/// Estimated time to make the change, in milliseconds
+	pub estimated_ms: u32,
 }
 
-/// A partial sequence being built during beam search
+/// Suggest voicings of `next_chord` that are easy to reach from the exact `current` grip
+///
+/// A lighter alternative to [`generate_progression`] for interactive use: only the
+/// next chord is generated, and candidates are ranked by transition quality
+/// (ties broken by the fingering's own score). Candidates further than
+/// `max_fret_distance` from the current position are dropped.
+pub fn suggest_next_fingering<I: Instrument>(
+	current: &Fingering,
+	next_chord: &Chord,
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Vec<NextFingering> {
+	let mut opts = options.generator_options.clone();
+	opts.limit = options.candidates_per_chord;
+
+	let current_pos = current.min_fret().unwrap_or(0);
+
+	let mut suggestions: Vec<NextFingering> = generate_fingerings(next_chord, instrument, &opts)
+		.into_iter()
+		.filter_map(|candidate| {
+			let metrics = transition_metrics(
+				current,
+				current_pos,
+				&candidate.fingering,
+				candidate.position,
+				instrument,
+				opts.playing_context,
+			);
+
+			if metrics.distance > options.max_fret_distance {
+				return None;
+			}
+
+			let voice_leading = if options.voice_leading {
+				voice_leading_score(current, &candidate.fingering, instrument)
+			} else {
+				0
+			};
+			Some(NextFingering {
+				fingering: candidate,
+				transition_score: metrics.score + voice_leading,
+				finger_movements: metrics.movements,
+				common_anchors: metrics.anchors,
+				position_distance: metrics.distance,
+				estimated_ms: metrics.estimated_ms,
+			})
+		})
+		.collect();
+
+	suggestions.sort_by(|a, b| {
+		b.transition_score
+			.cmp(&a.transition_score)
+			.then(b.fingering.score.cmp(&a.fingering.score))
+	});
+	suggestions.truncate(options.limit);
+	suggestions
+}
+
+/// Incrementally optimize a progression as chords are appended
+///
+/// Keeps the best partial sequences for every candidate between calls, so
+/// adding a chord only scores transitions into the new chord instead of
+/// re-running the whole search. Results match [`generate_progression`] for the same chords.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::progression::{ProgressionBuilder, ProgressionOptions};
+/// use chordcraft_core::instrument::Guitar;
+///
+/// let guitar = Guitar::default();
+/// let mut builder = ProgressionBuilder::new(&guitar, ProgressionOptions::default());
+///
+/// builder.push("C").unwrap();
+/// let best = builder.push("G").unwrap();
+/// assert_eq!(best[0].fingerings.len(), 2);
+/// ```
+pub struct ProgressionBuilder<'a, I: Instrument> {
+	instrument: &'a I,
+	options: ProgressionOptions,
+	chord_names: Vec<String>,
+	/// Search layer after each chord, so the last chord can be removed cheaply
+	layers: Vec<Layer>,
+	/// Candidates of chords seen before (holds nothing unless given a capacity)
+	cache: FingeringCache,
+}
+
+impl<'a, I: Instrument> ProgressionBuilder<'a, I> {
+	pub fn new(instrument: &'a I, options: ProgressionOptions) -> Self {
+		ProgressionBuilder {
+			instrument,
+			options,
+			chord_names: Vec::new(),
+			layers: Vec::new(),
+			cache: FingeringCache::default(),
+		}
+	}
+
+	/// Reuse candidates for chords that come back, as choruses and edits do
+	pub fn with_cache(self, cache: FingeringCache) -> Self {
+		ProgressionBuilder { cache, ..self }
+	}
+
+	pub fn cache(&self) -> &FingeringCache {
+		&self.cache
+	}
+
+	/// Append a chord and return the updated best sequences
+	///
+	/// Returns an error if the chord name can't be parsed; the builder is left
+	/// unchanged in that case. If no fingering sequence fits the options the
+	/// chord is still added, but no sequences are returned from then on.
+	pub fn push(&mut self, chord_name: &str) -> Result<Vec<ProgressionSequence>> {
+		let chord = Chord::parse(chord_name)?;
+
+		let mut opts = self.options.generator_options.clone();
+		opts.limit = self.options.candidates_per_chord;
+		let candidates = self.cache.generate(&chord, self.instrument, &opts);
+
+		self.chord_names.push(chord_name.to_string());
+		let layer = match self.layers.last() {
+			Some(layer) => next_layer(
+				layer,
+				&Change::into_chord(&self.chord_names, self.layers.len(), &self.options),
+				&candidates,
+				self.instrument,
+				&self.options,
+			),
+			None => initial_layer(&candidates, &self.options),
+		};
+		self.layers.push(layer);
+		Ok(self.best())
+	}
+
+	/// Remove the last chord, restoring the previous results
+	pub fn pop(&mut self) -> Option<String> {
+		self.layers.pop();
+		self.chord_names.pop()
+	}
+
+	/// Remove all chords
+	pub fn clear(&mut self) {
+		self.layers.clear();
+		self.chord_names.clear();
+	}
+
+	/// Current best sequences (up to `limit`), best first
+	pub fn best(&self) -> Vec<ProgressionSequence> {
+		let Some(layer) = self.layers.last() else {
+			return vec![];
+		};
+
+		let paths = layer.iter().flatten().cloned().collect();
+		let result = beam_to_sequences(paths, &self.chord_names, &self.options);
+		pick_distinct(result, &self.options)
+	}
+
+	pub fn chords(&self) -> &[String] {
+		&self.chord_names
+	}
+
+	pub fn len(&self) -> usize {
+		self.chord_names.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.chord_names.is_empty()
+	}
+}
+
+/// One chord change in the search: the chords' names and the time available for it
+struct Change<'a> {
+	from: &'a str,
+	to: &'a str,
+	budget_ms: Option<u32>,
+}
+
+impl<'a> Change<'a> {
+	/// The change into chord `index` of `chord_names`
+	fn into_chord<S: AsRef<str>>(
+		chord_names: &'a [S],
+		index: usize,
+		options: &ProgressionOptions,
+	) -> Self {
+		Change {
+			from: chord_names[index - 1].as_ref(),
+			to: chord_names[index].as_ref(),
+			budget_ms: options.change_budget(index - 1),
+		}
+	}
+}
+
+/// A partial sequence being built during the search
+#[derive(Clone)]
 struct BeamCandidate {
 	fingerings: Vec<ScoredFingering>,
 	transitions: Vec<ChordTransition>,
 	total_score: i32,
 }
 
-fn beam_search_progression<I: Instrument>(
+/// The best partial sequences ending on each candidate of the latest chord,
+/// best first, at most `limit` per candidate
+///
+/// Transitions only depend on neighbouring chords, so the `limit` best whole
+/// sequences are always built from these (k-best Viterbi): the search is exact
+/// over the candidate lattice, not greedy.
+type Layer = Vec<Vec<BeamCandidate>>;
+
+/// Find the best sequences through the candidate lattice
+///
+/// If `observer` cancels, returns nothing, or with `keep_partial` finishes
+/// greedily from the best partial sequence so far.
+fn optimize_progression<I: Instrument>(
 	chord_names: &[&str],
 	candidates: &[Vec<ScoredFingering>],
-	beam_width: usize,
 	instrument: &I,
 	options: &ProgressionOptions,
-) -> Vec<ProgressionSequence> {
-	// Initialize beam with all first-chord candidates
-	let mut beam: Vec<BeamCandidate> = candidates[0]
-		.iter()
-		.map(|sf| BeamCandidate {
-			fingerings: vec![sf.clone()],
-			transitions: vec![],
-			total_score: 0,
-		})
-		.collect();
+	observer: &dyn SearchObserver,
+	keep_partial: bool,
+) -> (Vec<ProgressionSequence>, bool) {
+	let mut layer = initial_layer(&candidates[0], options);
+	let steps = candidates.len() - 1;
 
-	// Expand beam for each subsequent chord
 	for i in 1..candidates.len() {
-		let mut next_beam: Vec<BeamCandidate> = Vec::new();
-		let from_chord_name = chord_names[i - 1].to_string();
-		let to_chord_name = chord_names[i].to_string();
-
-		for candidate in &beam {
-			let from = candidate.fingerings.last().unwrap();
-
-			for to in &candidates[i] {
-				let transition = score_transition(
-					from_chord_name.clone(),
-					to_chord_name.clone(),
-					from,
-					to,
+		if observer.is_cancelled() {
+			if !keep_partial {
+				return (vec![], false);
+			}
+			// Out of budget: finish greedily from the best partial sequence
+			let best = layer
+				.iter()
+				.filter_map(|paths| paths.first())
+				.max_by_key(|path| path.total_score)
+				.cloned();
+			let mut beam: Vec<BeamCandidate> = best.into_iter().collect();
+			for (j, next) in candidates.iter().enumerate().skip(i) {
+				beam = extend_beam(
+					&beam,
+					&Change::into_chord(chord_names, j, options),
+					next,
+					1,
 					instrument,
-					options.generator_options.playing_context,
+					options,
 				);
+			}
+			return (beam_to_sequences(beam, chord_names, options), false);
+		}
+		observer.on_progress(Progress {
+			phase: SearchPhase::Optimizing,
+			done: i - 1,
+			total: steps,
+		});
+		layer = next_layer(
+			&layer,
+			&Change::into_chord(chord_names, i, options),
+			&candidates[i],
+			instrument,
+			options,
+		);
+	}
+	observer.on_progress(Progress {
+		phase: SearchPhase::Optimizing,
+		done: steps,
+		total: steps,
+	});
 
-				if transition.position_distance > options.max_fret_distance {
-					continue;
-				}
+	let paths = layer.into_iter().flatten().collect();
+	(beam_to_sequences(paths, chord_names, options), true)
+}
 
-				let new_total = candidate.total_score + transition.score;
-				let mut new_fingerings = candidate.fingerings.clone();
-				new_fingerings.push(to.clone());
-				let mut new_transitions = candidate.transitions.clone();
-				new_transitions.push(transition);
-
-				next_beam.push(BeamCandidate {
-					fingerings: new_fingerings,
-					transitions: new_transitions,
-					total_score: new_total,
-				});
+fn initial_layer(candidates: &[ScoredFingering], options: &ProgressionOptions) -> Layer {
+	candidates
+		.iter()
+		.map(|sf| {
+			vec![BeamCandidate {
+				fingerings: vec![sf.clone()],
+				transitions: vec![],
+				total_score: options.fingering_score(sf),
+			}]
+		})
+		.collect()
+}
+
+/// Extend a layer by one chord, exactly or with a beam as the options say
+///
+/// A beam is a layer with a single entry holding every sequence kept.
+fn next_layer<I: Instrument>(
+	layer: &Layer,
+	change: &Change,
+	candidates: &[ScoredFingering],
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Layer {
+	let Some(width) = options.beam_width else {
+		return extend_layer(layer, change, candidates, instrument, options);
+	};
+	let beam: Vec<BeamCandidate> = layer.iter().flatten().cloned().collect();
+	vec![extend_beam(
+		&beam,
+		change,
+		candidates,
+		width.max(options.limit),
+		instrument,
+		options,
+	)]
+}
+
+/// Extend a layer by one chord, keeping the `limit` best sequences into each candidate
+fn extend_layer<I: Instrument>(
+	layer: &Layer,
+	change: &Change,
+	candidates: &[ScoredFingering],
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Layer {
+	// Every sequence ending on the same candidate makes the same transition
+	let transitions: Vec<Vec<Option<ChordTransition>>> = layer
+		.iter()
+		.map(|paths| {
+			let Some(from) = paths.first().and_then(|p| p.fingerings.last()) else {
+				return vec![None; candidates.len()];
+			};
+			candidates
+				.iter()
+				.map(|to| {
+					let transition = score_transition(change, from, to, instrument, options);
+					(transition.position_distance <= options.max_fret_distance)
+						.then_some(transition)
+				})
+				.collect()
+		})
+		.collect();
+
+	candidates
+		.iter()
+		.enumerate()
+		.map(|(t, to)| {
+			let mut ways: Vec<(i32, &BeamCandidate, &ChordTransition)> = layer
+				.iter()
+				.zip(&transitions)
+				.filter_map(|(paths, row)| Some((paths, row[t].as_ref()?)))
+				.flat_map(|(paths, transition)| {
+					let step = options.step_score(transition, to);
+					paths
+						.iter()
+						.map(move |p| (p.total_score + step, p, transition))
+				})
+				.collect();
+			ways.sort_by_key(|(total, _, _)| std::cmp::Reverse(*total));
+			ways.truncate(options.limit);
+			ways.into_iter()
+				.map(|(total_score, path, transition)| {
+					let mut fingerings = path.fingerings.clone();
+					fingerings.push(to.clone());
+					let mut transitions = path.transitions.clone();
+					transitions.push(transition.clone());
+					BeamCandidate {
+						fingerings,
+						transitions,
+						total_score,
+					}
+				})
+				.collect()
+		})
+		.collect()
+}
+
+/// Extend every partial sequence in the beam by one chord and prune to `beam_width`
+fn extend_beam<I: Instrument>(
+	beam: &[BeamCandidate],
+	change: &Change,
+	candidates: &[ScoredFingering],
+	beam_width: usize,
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Vec<BeamCandidate> {
+	let mut next_beam: Vec<BeamCandidate> = Vec::new();
+
+	for candidate in beam {
+		let from = candidate.fingerings.last().unwrap();
+
+		for to in candidates {
+			let transition = score_transition(change, from, to, instrument, options);
+
+			if transition.position_distance > options.max_fret_distance {
+				continue;
 			}
-		}
 
-		// Prune to beam width: keep top-K by total score
-		next_beam.sort_by(|a, b| b.total_score.cmp(&a.total_score));
-		next_beam.truncate(beam_width);
-		beam = next_beam;
+			let new_total = candidate.total_score + options.step_score(&transition, to);
+			let mut new_fingerings = candidate.fingerings.clone();
+			new_fingerings.push(to.clone());
+			let mut new_transitions = candidate.transitions.clone();
+			new_transitions.push(transition);
 
-		if beam.is_empty() {
-			return vec![];
+			next_beam.push(BeamCandidate {
+				fingerings: new_fingerings,
+				transitions: new_transitions,
+				total_score: new_total,
+			});
 		}
 	}
 
-	// Convert beam candidates to final sequences
+	// Prune to beam width: keep top-K by total score
+	next_beam.sort_by_key(|c| std::cmp::Reverse(c.total_score));
+	next_beam.truncate(beam_width);
+	next_beam
+}
+
+/// Convert beam candidates to final sequences
+fn beam_to_sequences<S: AsRef<str>>(
+	beam: Vec<BeamCandidate>,
+	chord_names: &[S],
+	options: &ProgressionOptions,
+) -> Vec<ProgressionSequence> {
+	let numerals = numeral_analysis(chord_names, options.numerals);
 	beam.into_iter()
 		.map(|candidate| {
 			let total_score = candidate.total_score;
 			let avg_transition_score = if candidate.transitions.is_empty() {
 				0.0
 			} else {
-				total_score as f32 / candidate.transitions.len() as f32
+				let sum: i32 = candidate.transitions.iter().map(|t| t.score).sum();
+				sum as f32 / candidate.transitions.len() as f32
 			};
 			ProgressionSequence {
-				chords: chord_names.iter().map(|s| s.to_string()).collect(),
+				chords: chord_names.iter().map(|s| s.as_ref().to_string()).collect(),
 				fingerings: candidate.fingerings,
 				transitions: candidate.transitions,
 				total_score,
 				avg_transition_score,
+				numerals: numerals.clone(),
+				chart: None,
 			}
 		})
 		.collect()
 }
 
+/// The best sequences, each differing from every better one kept in at least
+/// [`ProgressionOptions::min_difference`] fingerings, up to `limit`
+fn pick_distinct(
+	mut sequences: Vec<ProgressionSequence>,
+	options: &ProgressionOptions,
+) -> Vec<ProgressionSequence> {
+	sequences.sort_by_key(|s| std::cmp::Reverse(s.total_score));
+	let mut picked: Vec<ProgressionSequence> = Vec::new();
+	for sequence in sequences {
+		if picked.len() >= options.limit {
+			break;
+		}
+		// A short progression can't differ in more chords than it has
+		let required = options.min_difference.min(sequence.fingerings.len()).max(1);
+		if picked
+			.iter()
+			.all(|better| sequence.differing_fingerings(better) >= required)
+		{
+			picked.push(sequence);
+		}
+	}
+	picked
+}
+
+/// Numerals of the chords, or None if not requested or a chord can't be parsed
+fn numeral_analysis<S: AsRef<str>>(
+	chord_names: &[S],
+	numerals: Numerals,
+) -> Option<NumeralAnalysis> {
+	let key = match numerals {
+		Numerals::Off => return None,
+		Numerals::DetectedKey => None,
+		Numerals::InKey(key) => Some(key),
+	};
+	let chords: Vec<Chord> = chord_names
+		.iter()
+		.map(|name| Chord::parse(name.as_ref()))
+		.collect::<Result<_>>()
+		.ok()?;
+	analyze_numerals(&chords, key)
+}
+
+/// Score a change between two candidates, weighing its time against the budget
 fn score_transition<I: Instrument>(
-	from_chord: String,
-	to_chord: String,
+	change: &Change,
 	from_scored: &ScoredFingering,
 	to_scored: &ScoredFingering,
 	instrument: &I,
-	playing_context: PlayingContext,
+	options: &ProgressionOptions,
+) -> ChordTransition {
+	let metrics = transition_metrics(
+		&from_scored.fingering,
+		from_scored.position,
+		&to_scored.fingering,
+		to_scored.position,
+		instrument,
+		options.generator_options.playing_context,
+	);
+	let voice_leading = options
+		.voice_leading
+		.then(|| voice_leading_score(&from_scored.fingering, &to_scored.fingering, instrument));
+
+	let tempo_penalty = change.budget_ms.map_or(0, |budget| {
+		let share = metrics.estimated_ms as f32 / budget.max(1) as f32;
+		let risky = if metrics.estimated_ms > budget {
+			RISKY_CHANGE_PENALTY
+		} else {
+			0
+		};
+		(share * TEMPO_WEIGHT) as i32 + risky
+	});
+
+	// Every change staying within one character means the whole sequence does
+	let consistency =
+		if options.consistent_voicing && from_scored.character() == to_scored.character() {
+			SAME_CHARACTER_BONUS
+		} else {
+			0
+		};
+
+	ChordTransition {
+		from_chord: change.from.to_string(),
+		to_chord: change.to.to_string(),
+		from_fingering: from_scored.clone(),
+		to_fingering: to_scored.clone(),
+		score: metrics.score - tempo_penalty + voice_leading.unwrap_or(0) + consistency,
+		finger_movements: metrics.movements,
+		common_anchors: metrics.anchors,
+		position_distance: metrics.distance,
+		estimated_ms: metrics.estimated_ms,
+		budget_ms: change.budget_ms,
+		voice_leading,
+	}
+}
+
+/// Score a change between two grips outside a search, with no tempo budget
+pub(crate) fn transition_between<I: Instrument>(
+	from_chord: &str,
+	from: &ScoredFingering,
+	to_chord: &str,
+	to: &ScoredFingering,
+	instrument: &I,
+	options: &ProgressionOptions,
 ) -> ChordTransition {
-	let from = &from_scored.fingering;
-	let to = &to_scored.fingering;
-	let from_pos = from_scored.position;
-	let to_pos = to_scored.position;
+	let change = Change {
+		from: from_chord,
+		to: to_chord,
+		budget_ms: None,
+	};
+	score_transition(&change, from, to, instrument, options)
+}
+
+/// How smoothly the sounding pitches move from one grip to the next
+///
+/// Rewards pitches held over (common tones) and a top voice moving by step,
+/// and penalizes top-voice leaps and the outer voices leaping together in
+/// the same direction.
+pub fn voice_leading_score<I: Instrument>(from: &Fingering, to: &Fingering, instrument: &I) -> i32 {
+	let pitches = |fingering: &Fingering| -> Vec<i32> {
+		let mut midi: Vec<i32> = fingering
+			.notes(instrument)
+			.iter()
+			.map(|n| n.to_midi() as i32)
+			.collect();
+		midi.sort_unstable();
+		midi.dedup();
+		midi
+	};
+	let (from, to) = (pitches(from), pitches(to));
+	let (Some(&from_bass), Some(&from_top), Some(&to_bass), Some(&to_top)) =
+		(from.first(), from.last(), to.first(), to.last())
+	else {
+		return 0;
+	};
+
+	let common = from.iter().filter(|p| to.contains(p)).count() as i32;
+	let mut score = common * COMMON_TONE_BONUS;
+
+	let top_motion = (to_top - from_top).abs();
+	if top_motion <= STEP_SEMITONES {
+		score += TOP_VOICE_STEP_BONUS;
+	} else {
+		score -= (top_motion - STEP_SEMITONES) * TOP_VOICE_LEAP_PENALTY;
+	}
+
+	let bass_motion = to_bass - from_bass;
+	let top_motion = to_top - from_top;
+	if bass_motion.signum() == top_motion.signum()
+		&& bass_motion.abs() > STEP_SEMITONES
+		&& top_motion.abs() > STEP_SEMITONES
+	{
+		score -= PARALLEL_LEAP_PENALTY;
+	}
+
+	score
+}
+
+/// Raw transition measurements between two grips, independent of chord labels
+pub(crate) struct TransitionMetrics {
+	pub(crate) score: i32,
+	pub(crate) movements: usize,
+	pub(crate) anchors: usize,
+	pub(crate) distance: u8,
+	pub(crate) estimated_ms: u32,
+}
 
+pub(crate) fn transition_metrics<I: Instrument>(
+	from: &Fingering,
+	from_pos: u8,
+	to: &Fingering,
+	to_pos: u8,
+	instrument: &I,
+	playing_context: PlayingContext,
+) -> TransitionMetrics {
 	let mut score = BASE_SCORE;
 
 	let (movement_weight, distance_penalty) = match playing_context {
-		PlayingContext::Solo => (MOVEMENT_WEIGHT, DISTANCE_PENALTY),
+		PlayingContext::Solo | PlayingContext::Fingerstyle | PlayingContext::Recording => {
+			(MOVEMENT_WEIGHT, DISTANCE_PENALTY)
+		}
 		PlayingContext::Band => (BAND_MOVEMENT_WEIGHT, BAND_DISTANCE_PENALTY),
+		// Strumming keeps time with the whole hand, so changes need to be quick
+		PlayingContext::Strumming => (STRUMMING_MOVEMENT_WEIGHT, STRUMMING_DISTANCE_PENALTY),
 	};
 
 	let (movements, anchors) = calculate_finger_changes(from, to);
@@ -234,52 +1180,35 @@ fn score_transition<I: Instrument>(
 	let distance = (to_pos as i32 - from_pos as i32).unsigned_abs() as u8;
 	score -= (distance as i32) * distance_penalty;
 
-	ChordTransition {
-		from_chord,
-		to_chord,
-		from_fingering: from_scored.clone(),
-		to_fingering: to_scored.clone(),
+	TransitionMetrics {
 		score,
-		finger_movements: movements,
-		common_anchors: anchors,
-		position_distance: distance,
+		movements,
+		anchors,
+		distance,
+		estimated_ms: estimate_change_ms(from, to, movements, distance),
 	}
 }
 
-fn calculate_finger_changes(from: &Fingering, to: &Fingering) -> (usize, usize) {
-	let from_strings = from.strings();
-	let to_strings = to.strings();
-
-	let string_count = from_strings.len().min(to_strings.len());
-
-	let mut movements = 0;
-	let mut anchors = 0;
-
-	for i in 0..string_count {
-		let from_state = &from_strings[i];
-		let to_state = &to_strings[i];
-
-		match (from_state, to_state) {
-			(
-				crate::fingering::StringState::Fretted(f1),
-				crate::fingering::StringState::Fretted(f2),
-			) => {
-				if f1 == f2 {
-					anchors += 1;
-				} else {
-					movements += 1;
-				}
-			}
-			(crate::fingering::StringState::Fretted(_), crate::fingering::StringState::Muted) => {
-				movements += 1;
-			}
-			(crate::fingering::StringState::Muted, crate::fingering::StringState::Fretted(_)) => {
-				movements += 1;
-			}
-			_ => {}
-		}
+/// Estimate how long a change takes from finger movements, barres and position shifts
+fn estimate_change_ms(from: &Fingering, to: &Fingering, movements: usize, distance: u8) -> u32 {
+	if from == to {
+		return 0;
 	}
 
+	let mut ms = CHANGE_BASE_MS + movements as u32 * FINGER_MOVEMENT_MS;
+
+	// Forming a new barre (or moving one) takes longer than placing fingertips
+	if to.requires_barre() && (!from.requires_barre() || from.min_fret() != to.min_fret()) {
+		ms += BARRE_FORMATION_MS;
+	}
+
+	ms + distance as u32 * POSITION_SHIFT_MS
+}
+
+fn calculate_finger_changes(from: &Fingering, to: &Fingering) -> (usize, usize) {
+	let changes = from.diff(to);
+	let movements = changes.iter().filter(|c| c.is_movement()).count();
+	let anchors = changes.iter().filter(|c| c.is_anchor()).count();
 	(movements, anchors)
 }
 
@@ -292,8 +1221,8 @@ fn calculate_shape_similarity<I: Instrument>(
 
 	// Check if both fingerings match the same standard shape (barre slide)
 	// This is the easiest transition: same hand shape, just slide up/down the neck
-	let from_shape = find_shape_for_instrument(from, instrument);
-	let to_shape = find_shape_for_instrument(to, instrument);
+	let from_shape = shapes::find_matching_shape(from, instrument);
+	let to_shape = shapes::find_matching_shape(to, instrument);
 
 	if let (Some((from_name, _)), Some((to_name, _))) = (from_shape, to_shape)
 		&& from_name == to_name
@@ -318,23 +1247,10 @@ fn calculate_shape_similarity<I: Instrument>(
 	bonus
 }
 
-/// Find which standard shape a fingering matches for the given instrument
-fn find_shape_for_instrument<I: Instrument>(
-	fingering: &Fingering,
-	instrument: &I,
-) -> Option<(&'static str, u8)> {
-	match instrument.string_count() {
-		6 => shapes::guitar::find_matching_shape(fingering),
-		4 => shapes::ukulele::find_matching_shape(fingering)
-			.or_else(|| shapes::mandolin::find_matching_shape(fingering)),
-		5 => shapes::banjo::find_matching_shape(fingering),
-		_ => None,
-	}
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::generator::{Avoid, VoicingCharacter};
 	use crate::instrument::Guitar;
 
 	#[test]
@@ -351,6 +1267,79 @@ mod tests {
 		assert_eq!(progressions[0].transitions.len(), 3);
 	}
 
+	#[test]
+	fn test_progression_numerals() {
+		let guitar = Guitar::default();
+		let chords = ["C", "Am", "F", "G7"];
+
+		let plain = generate_progression(&chords, &guitar, &ProgressionOptions::default());
+		assert!(plain[0].numerals.is_none());
+
+		let options = ProgressionOptions {
+			numerals: Numerals::DetectedKey,
+			..Default::default()
+		};
+		let analysis = generate_progression(&chords, &guitar, &options)[0]
+			.numerals
+			.clone()
+			.unwrap();
+		assert_eq!(analysis.key.to_string(), "C major");
+		assert_eq!(analysis.labels(), ["I", "vi", "IV", "V7"]);
+
+		let options = ProgressionOptions {
+			numerals: Numerals::InKey(Key::parse("F").unwrap()),
+			..Default::default()
+		};
+		let analysis = generate_progression(&chords, &guitar, &options)[0]
+			.numerals
+			.clone()
+			.unwrap();
+		assert_eq!(analysis.labels(), ["V", "iii", "I", "II7"]);
+
+		let key = Key::parse("Bb").unwrap();
+		let from_numerals = generate_progression_from_numerals(
+			&["I", "IV", "V7/V", "V"],
+			key,
+			&guitar,
+			&ProgressionOptions::default(),
+		)
+		.unwrap();
+		assert_eq!(from_numerals[0].chords, ["Bb", "Eb", "C7", "F"]);
+		assert_eq!(
+			from_numerals[0].numerals.as_ref().unwrap().labels(),
+			["I", "IV", "II7", "V"]
+		);
+		assert!(
+			generate_progression_from_numerals(&["I", "X"], key, &guitar, &Default::default())
+				.is_err()
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_progression_serde_roundtrip() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions {
+			limit: 1,
+			numerals: Numerals::DetectedKey,
+			..Default::default()
+		};
+		let sequence = &generate_progression(&["C", "Am", "F", "G7"], &guitar, &options)[0];
+
+		let json = serde_json::to_string(sequence).unwrap();
+		assert!(json.contains(&format!("\"{}\"", sequence.fingerings[0].fingering)));
+		assert!(json.contains("\"V7\""));
+
+		let parsed: ProgressionSequence = serde_json::from_str(&json).unwrap();
+		assert_eq!(parsed.chords, sequence.chords);
+		assert_eq!(parsed.numerals, sequence.numerals);
+		assert_eq!(
+			parsed.transitions[0].to_fingering.fingering,
+			sequence.transitions[0].to_fingering.fingering
+		);
+		assert_eq!(parsed.fingerings[0].fingers, sequence.fingerings[0].fingers);
+	}
+
 	#[test]
 	fn test_progression_respects_max_distance() {
 		let guitar = Guitar::default();
@@ -381,6 +1370,518 @@ mod tests {
 		assert!(anchors > movements);
 	}
 
+	#[test]
+	fn test_suggest_next_fingering() {
+		let guitar = Guitar::default();
+		let current = Fingering::parse("x32010").unwrap(); // C
+		let next = Chord::parse("Am").unwrap();
+
+		let suggestions =
+			suggest_next_fingering(&current, &next, &guitar, &ProgressionOptions::default());
+
+		assert!(!suggestions.is_empty());
+		assert!(suggestions.len() <= ProgressionOptions::default().limit);
+
+		// C -> Am open keeps two fingers anchored, so it should lead
+		println!("Best next: {}", suggestions[0].fingering.fingering);
+		assert_eq!(suggestions[0].fingering.fingering.to_string(), "x02210");
+
+		for pair in suggestions.windows(2) {
+			assert!(pair[0].transition_score >= pair[1].transition_score);
+		}
+	}
+
+	#[test]
+	fn test_suggest_next_fingering_follows_position() {
+		let guitar = Guitar::default();
+		let current = Fingering::parse("x35553").unwrap(); // C barre at 3rd fret
+		let next = Chord::parse("F").unwrap();
+
+		let suggestions =
+			suggest_next_fingering(&current, &next, &guitar, &ProgressionOptions::default());
+
+		assert!(!suggestions.is_empty());
+		for s in &suggestions {
+			assert!(s.position_distance <= 3);
+		}
+	}
+
+	#[test]
+	fn test_builder_matches_generate_progression() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions::default();
+		let chords = ["C", "Am", "F", "G"];
+
+		let mut builder = ProgressionBuilder::new(&guitar, options.clone());
+		for i in 0..chords.len() {
+			let incremental = builder.push(chords[i]).unwrap();
+			let full = generate_progression(&chords[..=i], &guitar, &options);
+
+			assert_eq!(incremental.len(), full.len());
+			for (a, b) in incremental.iter().zip(&full) {
+				assert_eq!(a.total_score, b.total_score);
+				assert_eq!(a.chords, b.chords);
+			}
+		}
+		assert_eq!(builder.len(), 4);
+	}
+
+	#[test]
+	fn test_consistent_voicing() {
+		let guitar = Guitar::default();
+		let consistent = ProgressionOptions {
+			consistent_voicing: true,
+			..Default::default()
+		};
+		let same_character = |s: &ProgressionSequence| {
+			s.fingerings
+				.windows(2)
+				.filter(|w| w[0].character() == w[1].character())
+				.count()
+		};
+		for chords in [["C", "F", "G", "Am"], ["G", "Bm", "C", "D"]] {
+			let mixed = &generate_progression(&chords, &guitar, &ProgressionOptions::default())[0];
+			let coherent = &generate_progression(&chords, &guitar, &consistent)[0];
+			assert!(same_character(coherent) >= same_character(mixed));
+		}
+
+		let coherent = &generate_progression(&["C", "F", "G", "Am"], &guitar, &consistent)[0];
+		assert_eq!(same_character(coherent), 3);
+		assert_eq!(
+			VoicingCharacter::of(&Fingering::parse("x32010").unwrap()),
+			VoicingCharacter::Open
+		);
+		assert_eq!(
+			VoicingCharacter::of(&Fingering::parse("133211").unwrap()),
+			VoicingCharacter::Barre
+		);
+		assert_eq!(
+			VoicingCharacter::of(&Fingering::parse("x355xx").unwrap()),
+			VoicingCharacter::Compact
+		);
+	}
+
+	#[test]
+	fn test_fret_window_holds_for_every_chord() {
+		let guitar = Guitar::default();
+		let low = ProgressionOptions {
+			generator_options: GeneratorOptions {
+				max_fret: 4,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let chords = ["C", "Am", "F", "G", "Bb"];
+		let sequences = generate_progression(&chords, &guitar, &low);
+		assert!(!sequences.is_empty());
+		for sequence in &sequences {
+			assert_eq!(sequence.fingerings.len(), chords.len());
+			for f in &sequence.fingerings {
+				assert!(f.fingering.max_fret().unwrap_or(0) <= 4, "{}", f.fingering);
+			}
+		}
+
+		let high = ProgressionOptions {
+			generator_options: GeneratorOptions {
+				min_fret: 5,
+				max_fret: 9,
+				movable_only: true,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let sequences = generate_progression(&chords, &guitar, &high);
+		assert!(!sequences.is_empty());
+		assert!(sequences.iter().all(|s| s.fingerings.len() == chords.len()));
+		for f in sequences.iter().flat_map(|s| &s.fingerings) {
+			let pressed: Vec<u8> = f
+				.fingering
+				.strings()
+				.iter()
+				.filter_map(|s| s.fret())
+				.collect();
+			assert!(
+				pressed.iter().all(|fret| (5..=9).contains(fret)),
+				"{}",
+				f.fingering
+			);
+		}
+	}
+
+	#[test]
+	fn test_repeated_chords_reuse_candidates() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions::default();
+		let chords = ["C", "Am", "F", "G", "C", "Am", "F", "G"];
+
+		// The builder generates every chord afresh: reusing candidates changes nothing
+		let mut builder = ProgressionBuilder::new(&guitar, options.clone());
+		for chord in chords {
+			builder.push(chord).unwrap();
+		}
+		let fresh = builder.best();
+		let reused = generate_progression(&chords, &guitar, &options);
+		assert_eq!(reused.len(), fresh.len());
+		for (a, b) in reused.iter().zip(&fresh) {
+			assert_eq!(a.total_score, b.total_score);
+		}
+
+		// Equal chords share candidates but keep their own spelling
+		let spelled = generate_progression(&["Abm", "E", "G#m"], &guitar, &options);
+		assert_eq!(spelled[0].chords, ["Abm", "E", "G#m"]);
+	}
+
+	#[test]
+	fn test_progression_is_globally_optimal() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions {
+			limit: 4,
+			candidates_per_chord: 5,
+			min_difference: 1,
+			..Default::default()
+		};
+		let chords = ["C", "Em", "F", "G", "Am"];
+
+		// Score every path through the lattice
+		let mut opts = options.generator_options.clone();
+		opts.limit = options.candidates_per_chord;
+		let candidates: Vec<_> = chords
+			.iter()
+			.map(|c| generate_fingerings(&Chord::parse(c).unwrap(), &guitar, &opts))
+			.collect();
+		let mut totals: Vec<(i32, Vec<usize>)> =
+			(0..candidates[0].len()).map(|f| (0, vec![f])).collect();
+		for step in 1..chords.len() {
+			let mut next = Vec::new();
+			for (total, path) in &totals {
+				let from = &candidates[step - 1][*path.last().unwrap()];
+				for (t, to) in candidates[step].iter().enumerate() {
+					let transition = score_transition(
+						&Change::into_chord(&chords, step, &options),
+						from,
+						to,
+						&guitar,
+						&options,
+					);
+					if transition.position_distance <= options.max_fret_distance {
+						let mut path = path.clone();
+						path.push(t);
+						next.push((total + transition.score, path));
+					}
+				}
+			}
+			totals = next;
+		}
+		let mut best: Vec<i32> = totals.iter().map(|(total, _)| *total).collect();
+		best.sort_by_key(|t| std::cmp::Reverse(*t));
+		best.truncate(options.limit);
+
+		let found: Vec<i32> = generate_progression(&chords, &guitar, &options)
+			.iter()
+			.map(|s| s.total_score)
+			.collect();
+		assert_eq!(found, best);
+	}
+
+	#[test]
+	fn test_distinct_alternatives() {
+		let guitar = Guitar::default();
+		let chords = ["C", "Am", "F", "G", "C"];
+		let options = ProgressionOptions {
+			limit: 4,
+			min_difference: 3,
+			..Default::default()
+		};
+
+		let alternatives = generate_progression(&chords, &guitar, &options);
+		assert!(alternatives.len() > 1);
+		for (i, a) in alternatives.iter().enumerate() {
+			for b in &alternatives[i + 1..] {
+				assert!(a.differing_fingerings(b) >= 3);
+			}
+		}
+		assert!(
+			alternatives
+				.windows(2)
+				.all(|w| w[0].total_score >= w[1].total_score)
+		);
+
+		// Allowing near-duplicates keeps the best sequence and can only score higher
+		let alike = ProgressionOptions {
+			min_difference: 1,
+			..options.clone()
+		};
+		let any = generate_progression(&chords, &guitar, &alike);
+		assert_eq!(any[0].total_score, alternatives[0].total_score);
+		assert!(any[1].total_score >= alternatives[1].total_score);
+
+		// A single chord can't differ in more than one place
+		assert_eq!(generate_progression(&["C"], &guitar, &options).len(), 4);
+	}
+
+	#[test]
+	fn test_alpha_trades_smoothness_for_voicings() {
+		let guitar = Guitar::default();
+		let chords = ["C", "Em", "F", "G"];
+		let smooth = generate_progression(&chords, &guitar, &ProgressionOptions::default());
+		let rich = generate_progression(
+			&chords,
+			&guitar,
+			&ProgressionOptions {
+				alpha: 1.0,
+				..Default::default()
+			},
+		);
+
+		let voicings =
+			|s: &ProgressionSequence| -> u32 { s.fingerings.iter().map(|f| f.score as u32).sum() };
+		let changes =
+			|s: &ProgressionSequence| -> i32 { s.transitions.iter().map(|t| t.score).sum() };
+		assert!(voicings(&rich[0]) >= voicings(&smooth[0]));
+		assert!(changes(&smooth[0]) >= changes(&rich[0]));
+		// With all the weight on the voicings, the total is their sum
+		assert_eq!(rich[0].total_score, voicings(&rich[0]) as i32);
+		assert_eq!(smooth[0].total_score, changes(&smooth[0]));
+	}
+
+	#[test]
+	fn test_beam_search() {
+		let guitar = Guitar::default();
+		let chords = ["C", "Em", "F", "G", "Am", "Dm", "G7", "C"];
+		// Every alternative counts, however alike: a narrow beam holds few distinct ones
+		let options = ProgressionOptions {
+			min_difference: 1,
+			..Default::default()
+		};
+		let exact = generate_progression(&chords, &guitar, &options);
+
+		let beam = ProgressionOptions {
+			beam_width: Some(5),
+			..options.clone()
+		};
+		let approximate = generate_progression(&chords, &guitar, &beam);
+		assert_eq!(approximate.len(), exact.len());
+		assert_eq!(approximate[0].fingerings.len(), chords.len());
+		// A beam can only miss better sequences, never find one the exact search missed
+		assert!(approximate[0].total_score <= exact[0].total_score);
+
+		// The builder searches the same way
+		let mut builder = ProgressionBuilder::new(&guitar, beam.clone());
+		for chord in chords {
+			builder.push(chord).unwrap();
+		}
+		let built: Vec<i32> = builder.best().iter().map(|s| s.total_score).collect();
+		let full: Vec<i32> = approximate.iter().map(|s| s.total_score).collect();
+		assert_eq!(built, full);
+	}
+
+	#[test]
+	fn test_builder_pop_and_invalid_chord() {
+		let guitar = Guitar::default();
+		let mut builder = ProgressionBuilder::new(&guitar, ProgressionOptions::default());
+		assert!(builder.best().is_empty());
+
+		builder.push("G").unwrap();
+		let before = builder.best();
+		builder.push("D").unwrap();
+
+		assert!(builder.push("H#m").is_err());
+		assert_eq!(builder.chords(), ["G", "D"]);
+
+		assert_eq!(builder.pop().as_deref(), Some("D"));
+		let after = builder.best();
+		assert_eq!(after[0].total_score, before[0].total_score);
+		assert_eq!(after[0].fingerings.len(), 1);
+
+		builder.clear();
+		assert!(builder.is_empty());
+
+		let mut cached = ProgressionBuilder::new(&guitar, ProgressionOptions::default())
+			.with_cache(FingeringCache::new(16));
+		for chord in ["G", "D", "G", "D"] {
+			cached.push(chord).unwrap();
+		}
+		cached.pop();
+		cached.push("D").unwrap();
+		assert_eq!(cached.cache().misses(), 2);
+		assert_eq!(cached.cache().hits(), 3);
+	}
+
+	#[test]
+	fn test_estimated_change_time() {
+		let guitar = Guitar::default();
+		let metrics = |from: &str, to: &str| {
+			let from = Fingering::parse(from).unwrap();
+			let to = Fingering::parse(to).unwrap();
+			transition_metrics(
+				&from,
+				from.min_fret().unwrap_or(0),
+				&to,
+				to.min_fret().unwrap_or(0),
+				&guitar,
+				PlayingContext::Solo,
+			)
+		};
+
+		// C -> Am moves one finger; C -> F needs a barre
+		let c_am = metrics("x32010", "x02210").estimated_ms;
+		let c_f = metrics("x32010", "133211").estimated_ms;
+		let c_high_barre = metrics("x32010", "8(10)(10)988").estimated_ms;
+		assert!(c_am < c_f);
+		assert!(c_f < c_high_barre);
+		assert_eq!(metrics("x32010", "x32010").estimated_ms, 0);
+	}
+
+	#[test]
+	fn test_impractical_transitions_at_tempo() {
+		let guitar = Guitar::default();
+		let progressions =
+			generate_progression(&["C", "F", "C"], &guitar, &ProgressionOptions::default());
+		let best = &progressions[0];
+
+		assert!(best.impractical_transitions(60.0).is_empty());
+		assert_eq!(best.impractical_transitions(1000.0).len(), 2);
+		assert_eq!(change_budget_ms(120.0, 1.0), 500);
+	}
+
+	#[test]
+	fn test_unparseable_chords_are_reported() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions::default();
+		let chords = ["C", "Amaj77", "F", "G"];
+
+		// A typo doesn't quietly shorten the progression
+		assert!(generate_progression(&chords, &guitar, &options).is_empty());
+		let err = generate_progression_with(&chords, &guitar, &options, &NoopObserver).unwrap_err();
+		assert!(matches!(
+			&err,
+			ChordCraftError::InvalidChordNames(bad) if bad == &[(1, "Amaj77".to_string())]
+		));
+		assert_eq!(err.to_string(), "Invalid chord names: 'Amaj77' (chord 2)");
+	}
+
+	#[test]
+	fn test_avoided_fingerings_stay_out() {
+		let guitar = Guitar::default();
+		let barre = Fingering::parse("133211").unwrap();
+		let options = ProgressionOptions {
+			limit: 5,
+			generator_options: GeneratorOptions {
+				avoid: vec![Avoid::Tab(barre.clone())],
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let progressions = generate_progression(&["C", "F", "G"], &guitar, &options);
+		assert!(!progressions.is_empty());
+		for progression in &progressions {
+			assert!(progression.fingerings.iter().all(|f| f.fingering != barre));
+		}
+	}
+
+	#[test]
+	fn test_voice_leading() {
+		let guitar = Guitar::default();
+		let score = |from: &str, to: &str| {
+			voice_leading_score(
+				&Fingering::parse(from).unwrap(),
+				&Fingering::parse(to).unwrap(),
+				&guitar,
+			)
+		};
+
+		// Open C -> Am holds E3, C4 and E4; the barre Am leaps up to A4 on top
+		assert!(score("x32010", "x02210") > score("x32010", "577555"));
+		assert_eq!(
+			score("x32010", "x02210"),
+			3 * COMMON_TONE_BONUS + TOP_VOICE_STEP_BONUS
+		);
+		// Bass and top both leap up a fourth or more
+		assert!(score("x32010", "x8(10)(10)(10)8") < 0);
+
+		let chords = ["C", "Am", "F", "G"];
+		let plain = generate_progression(&chords, &guitar, &ProgressionOptions::default());
+		assert!(
+			plain[0]
+				.transitions
+				.iter()
+				.all(|t| t.voice_leading.is_none())
+		);
+
+		let options = ProgressionOptions {
+			voice_leading: true,
+			..Default::default()
+		};
+		let smooth = generate_progression(&chords, &guitar, &options);
+		let voice_leading = |s: &ProgressionSequence| {
+			s.transitions
+				.iter()
+				.filter_map(|t| t.voice_leading)
+				.sum::<i32>()
+		};
+		assert_eq!(smooth[0].transitions.len(), 3);
+		// Trading finger movement for smoother voices never makes the voices worse
+		let plain_voice_leading: i32 = plain[0]
+			.transitions
+			.iter()
+			.map(|t| {
+				voice_leading_score(
+					&t.from_fingering.fingering,
+					&t.to_fingering.fingering,
+					&guitar,
+				)
+			})
+			.sum();
+		assert!(voice_leading(&smooth[0]) >= plain_voice_leading);
+	}
+
+	#[test]
+	fn test_tempo_aware_scoring() {
+		let guitar = Guitar::default();
+		let chords = ["C", "F", "G", "C"];
+		let relaxed = generate_progression(&chords, &guitar, &ProgressionOptions::default());
+		assert!(relaxed[0].transitions.iter().all(|t| t.budget_ms.is_none()));
+		assert!(relaxed[0].risky_transitions().is_empty());
+
+		// A fast tempo penalizes slow changes, so the best sequence changes faster
+		let fast = ProgressionOptions {
+			bpm: Some(200.0),
+			..Default::default()
+		};
+		let hurried = generate_progression(&chords, &guitar, &fast);
+		let slowest = |s: &ProgressionSequence| s.transitions.iter().map(|t| t.estimated_ms).max();
+		assert!(slowest(&hurried[0]) <= slowest(&relaxed[0]));
+		assert!(
+			hurried[0]
+				.transitions
+				.iter()
+				.all(|t| t.budget_ms == Some(300))
+		);
+		assert!(hurried[0].total_score < relaxed[0].total_score);
+
+		// Long holds leave more time for the change
+		let held = ProgressionOptions {
+			durations: vec![8.0, 0.5, 4.0, 4.0],
+			..fast.clone()
+		};
+		assert_eq!(held.change_budget(0), Some(1200));
+		assert_eq!(held.change_budget(1), Some(150));
+		assert_eq!(held.change_budget(2), Some(600));
+		assert_eq!(held.change_budget(9), Some(300));
+
+		let at_speed = ProgressionOptions {
+			bpm: Some(1000.0),
+			..Default::default()
+		};
+		let rushed = generate_progression(&chords, &guitar, &at_speed);
+		assert_eq!(
+			rushed[0].risky_transitions().len(),
+			rushed[0].impractical_transitions(1000.0).len()
+		);
+		assert!(!rushed[0].risky_transitions().is_empty());
+	}
+
 	#[test]
 	fn test_empty_chord_list() {
 		let guitar = Guitar::default();
@@ -405,4 +1906,72 @@ mod tests {
 		assert_eq!(progressions[0].chords.len(), 1);
 		assert_eq!(progressions[0].transitions.len(), 0);
 	}
+
+	#[test]
+	fn test_progression_cancellation() {
+		use crate::progress::CancellationToken;
+
+		let guitar = Guitar::default();
+		let chords = vec!["C", "Am", "F", "G"];
+		let options = ProgressionOptions::default();
+
+		// Cancel once candidates are generated, before optimizing
+		let token = CancellationToken::new();
+		let observer = CancelAt {
+			token: token.clone(),
+			phase: SearchPhase::Optimizing,
+		};
+		let result = generate_progression_with(&chords, &guitar, &options, &observer);
+		assert!(matches!(result, Err(ChordCraftError::Cancelled)));
+
+		let fresh = CancellationToken::new();
+		let result = generate_progression_with(&chords, &guitar, &options, &fresh).unwrap();
+		assert!(!result.is_empty());
+	}
+
+	#[test]
+	fn test_progression_budget_falls_back_to_greedy() {
+		let guitar = Guitar::default();
+		let chords = vec!["C", "Am", "F", "G"];
+		let options = ProgressionOptions::default();
+
+		let full =
+			generate_progression_within(&chords, &guitar, &options, &SearchBudget::default());
+		assert!(full.complete);
+		assert!(!full.results.is_empty());
+
+		// Budget runs out as soon as optimization starts
+		let observer = CancelAt {
+			token: crate::progress::CancellationToken::new(),
+			phase: SearchPhase::Optimizing,
+		};
+		let (sequences, complete) = search_progression(
+			&chords,
+			&guitar,
+			&options,
+			&observer,
+			Some(SearchBudget::default()),
+		);
+		assert!(!complete);
+		assert_eq!(sequences.len(), 1);
+		assert_eq!(sequences[0].fingerings.len(), 4);
+		assert_eq!(sequences[0].transitions.len(), 3);
+	}
+
+	struct CancelAt {
+		token: crate::progress::CancellationToken,
+		phase: SearchPhase,
+	}
+
+	impl SearchObserver for CancelAt {
+		fn on_progress(&self, progress: Progress) {
+			if progress.phase == self.phase {
+				self.token.cancel();
+			}
+		}
+
+		fn is_cancelled(&self) -> bool {
+			self.token.is_cancelled()
+		}
+	}
 }
@@ -0,0 +1,365 @@
+//! Whole-song analysis
+//!
+//! [`song_report`] answers "how hard is this song for me?" in one call: it
+//! optimizes fingerings for every section, rates the song's overall
+//! difficulty, picks out the hardest chord changes, detects the key and any
+//! modulations, and suggests capo positions or alternate tunings that make the
+//! shapes easier.
+
+use std::collections::HashMap;
+
+use crate::chord::Chord;
+use crate::chordpro::import_chordpro;
+use crate::error::Result;
+use crate::generator::{GeneratorOptions, generate_fingerings};
+use crate::instrument::{CapoedInstrument, ConfigurableInstrument, Instrument};
+use crate::key::{KeyEstimate, Modulation, detect_modulations, detect_song_key};
+use crate::progression::{
+	ChordTransition, ProgressionOptions, ProgressionSequence, generate_progression,
+};
+use crate::song::Song;
+use crate::tuning::common_guitar_tunings;
+
+#[derive(Debug, Clone)]
+pub struct SongReportOptions {
+	/// Options for optimizing each section (only the best progression is kept)
+	pub progression_options: ProgressionOptions,
+	/// Number of hardest transitions to list
+	pub hardest_transitions: usize,
+	/// Highest capo position to suggest
+	pub max_capo: u8,
+	/// Tunings to try as alternatives (only those with the instrument's string count)
+	pub alternate_tunings: Vec<ConfigurableInstrument>,
+	/// Maximum number of capo and of tuning suggestions
+	pub suggestion_limit: usize,
+}
+
+impl Default for SongReportOptions {
+	fn default() -> Self {
+		SongReportOptions {
+			progression_options: ProgressionOptions::default(),
+			hardest_transitions: 3,
+			max_capo: 7,
+			alternate_tunings: common_guitar_tunings(),
+			suggestion_limit: 3,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionReport {
+	pub label: Option<String>,
+	pub chords: Vec<String>,
+	/// Best fingering sequence, if every chord is playable
+	pub progression: Option<ProgressionSequence>,
+	/// Difficulty of the section (0 = trivial, 100 = unplayable)
+	pub difficulty: Option<u8>,
+}
+
+/// A chord change, with the section it happens in
+#[derive(Debug, Clone)]
+pub struct HardTransition {
+	pub section: usize,
+	pub transition: ChordTransition,
+}
+
+/// A capo position whose shapes are easier than playing without one
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapoSuggestion {
+	pub capo: u8,
+	/// Shape to finger for each distinct chord (in order of first appearance), relative to the capo
+	pub shapes: Vec<String>,
+	/// Average difficulty of the easiest fingering of each shape
+	pub difficulty: u8,
+}
+
+/// An alternate tuning whose fingerings are easier than the current one
+#[derive(Debug, Clone)]
+pub struct TuningSuggestion {
+	pub instrument: ConfigurableInstrument,
+	/// Average difficulty of the easiest fingering of each chord
+	pub difficulty: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct SongReport {
+	pub title: Option<String>,
+	pub key: Option<KeyEstimate>,
+	pub modulations: Vec<Modulation>,
+	pub sections: Vec<SectionReport>,
+	/// Difficulty of the whole song (0 = trivial, 100 = unplayable), averaged over every chord played
+	pub difficulty: Option<u8>,
+	/// Average difficulty of the easiest fingering of each distinct chord,
+	/// the baseline capo and tuning suggestions are compared against
+	pub shape_difficulty: Option<u8>,
+	/// Lowest-scoring changes, hardest first
+	pub hardest_transitions: Vec<HardTransition>,
+	pub capo_suggestions: Vec<CapoSuggestion>,
+	pub tuning_suggestions: Vec<TuningSuggestion>,
+}
+
+/// Analyze a song for an instrument
+///
+/// Repeated sections with the same chords are optimized once.
+pub fn song_report<I: Instrument + Clone>(
+	song: &Song,
+	instrument: &I,
+	options: &SongReportOptions,
+) -> SongReport {
+	let progression_options = ProgressionOptions {
+		limit: 1,
+		..options.progression_options.clone()
+	};
+
+	let mut optimized: HashMap<Vec<String>, Option<ProgressionSequence>> = HashMap::new();
+	let sections: Vec<SectionReport> = song
+		.sections
+		.iter()
+		.map(|section| {
+			let chords: Vec<String> = section.chords.iter().map(|c| c.chord.to_string()).collect();
+			let progression = optimized
+				.entry(chords.clone())
+				.or_insert_with(|| {
+					let names: Vec<&str> = chords.iter().map(String::as_str).collect();
+					generate_progression(&names, instrument, &progression_options)
+						.into_iter()
+						.next()
+				})
+				.clone();
+			let difficulty = progression.as_ref().and_then(|p| p.difficulty(instrument));
+			SectionReport {
+				label: section.label.clone(),
+				chords,
+				progression,
+				difficulty,
+			}
+		})
+		.collect();
+
+	let distinct = distinct_chords(song);
+	let generator_options = &options.progression_options.generator_options;
+	let shape_difficulty = shape_difficulty(&distinct, instrument, generator_options);
+
+	SongReport {
+		title: song.title.clone(),
+		key: detect_song_key(song),
+		modulations: detect_modulations(song),
+		difficulty: overall_difficulty(&sections),
+		hardest_transitions: hardest_transitions(&sections, options.hardest_transitions),
+		capo_suggestions: shape_difficulty
+			.map(|baseline| capo_suggestions(&distinct, instrument, baseline, options))
+			.unwrap_or_default(),
+		tuning_suggestions: shape_difficulty
+			.map(|baseline| tuning_suggestions(&distinct, instrument, baseline, options))
+			.unwrap_or_default(),
+		sections,
+		shape_difficulty,
+	}
+}
+
+/// Analyze a ChordPro document for an instrument
+pub fn chordpro_report<I: Instrument + Clone>(
+	text: &str,
+	instrument: &I,
+	options: &SongReportOptions,
+) -> Result<SongReport> {
+	Ok(song_report(&import_chordpro(text)?, instrument, options))
+}
+
+/// Chords of the song in order of first appearance
+fn distinct_chords(song: &Song) -> Vec<Chord> {
+	let mut chords: Vec<Chord> = Vec::new();
+	for song_chord in song.chords() {
+		if !chords.contains(&song_chord.chord) {
+			chords.push(song_chord.chord.clone());
+		}
+	}
+	chords
+}
+
+/// Average of the section difficulties, weighted by how many chords each plays
+fn overall_difficulty(sections: &[SectionReport]) -> Option<u8> {
+	let (sum, count) = sections
+		.iter()
+		.filter_map(|s| Some((s.difficulty? as u32, s.chords.len() as u32)))
+		.fold((0, 0), |(sum, count), (d, n)| (sum + d * n, count + n));
+	(count > 0).then(|| (sum / count) as u8)
+}
+
+fn hardest_transitions(sections: &[SectionReport], limit: usize) -> Vec<HardTransition> {
+	let mut transitions: Vec<HardTransition> = sections
+		.iter()
+		.enumerate()
+		.filter_map(|(i, s)| Some((i, s.progression.as_ref()?)))
+		.flat_map(|(i, p)| {
+			p.transitions.iter().map(move |t| HardTransition {
+				section: i,
+				transition: t.clone(),
+			})
+		})
+		.collect();
+	transitions.sort_by_key(|t| t.transition.score);
+
+	// Repeated sections would list the same change several times
+	let mut seen = Vec::new();
+	transitions.retain(|t| {
+		let key = (
+			t.transition.from_fingering.fingering.clone(),
+			t.transition.to_fingering.fingering.clone(),
+		);
+		if seen.contains(&key) {
+			return false;
+		}
+		seen.push(key);
+		true
+	});
+	transitions.truncate(limit);
+	transitions
+}
+
+/// Average difficulty of the easiest fingering of each chord, or None if one is unplayable
+fn shape_difficulty<I: Instrument>(
+	chords: &[Chord],
+	instrument: &I,
+	options: &GeneratorOptions,
+) -> Option<u8> {
+	if chords.is_empty() {
+		return None;
+	}
+	let options = GeneratorOptions {
+		limit: 1,
+		..options.clone()
+	};
+
+	let mut deficit = 0u32;
+	for chord in chords {
+		let best = generate_fingerings(chord, instrument, &options);
+		let playability = best.first()?.fingering.playability_score_for(instrument);
+		deficit += 100u32.saturating_sub(playability as u32);
+	}
+	Some((deficit / chords.len() as u32) as u8)
+}
+
+/// Capo positions with easier shapes than `baseline`, easiest first
+///
+/// The capoed instrument's tuning is already transposed, so the sounding
+/// chords are generated directly.
+fn capo_suggestions<I: Instrument + Clone>(
+	chords: &[Chord],
+	instrument: &I,
+	baseline: u8,
+	options: &SongReportOptions,
+) -> Vec<CapoSuggestion> {
+	let generator_options = &options.progression_options.generator_options;
+	let mut suggestions: Vec<CapoSuggestion> =
+		(1..=options.max_capo.min(instrument.max_capo_fret()))
+			.filter_map(|capo| {
+				let capoed = CapoedInstrument::new(instrument.clone(), capo).ok()?;
+				let difficulty = shape_difficulty(chords, &capoed, generator_options)?;
+				(difficulty < baseline).then(|| CapoSuggestion {
+					capo,
+					shapes: chords
+						.iter()
+						.map(|c| c.transpose(-(capo as i32)).to_string())
+						.collect(),
+					difficulty,
+				})
+			})
+			.collect();
+
+	suggestions.sort_by_key(|s| (s.difficulty, s.capo));
+	suggestions.truncate(options.suggestion_limit);
+	suggestions
+}
+
+/// Alternate tunings with easier fingerings than `baseline`, easiest first
+fn tuning_suggestions<I: Instrument>(
+	chords: &[Chord],
+	instrument: &I,
+	baseline: u8,
+	options: &SongReportOptions,
+) -> Vec<TuningSuggestion> {
+	let generator_options = &options.progression_options.generator_options;
+	let mut suggestions: Vec<TuningSuggestion> = options
+		.alternate_tunings
+		.iter()
+		.filter(|t| {
+			t.tuning().len() == instrument.tuning().len() && t.tuning() != instrument.tuning()
+		})
+		.filter_map(|t| {
+			let difficulty = shape_difficulty(chords, t, generator_options)?;
+			(difficulty < baseline).then(|| TuningSuggestion {
+				instrument: t.clone(),
+				difficulty,
+			})
+		})
+		.collect();
+
+	suggestions.sort_by_key(|s| s.difficulty);
+	suggestions.truncate(options.suggestion_limit);
+	suggestions
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+	use crate::key::{Key, Mode};
+	use crate::note::PitchClass;
+
+	const SONG: &str = "\
+{title: Flat Song}
+{start_of_verse}
+[Eb]One [Bb]two [Cm]three [Ab]four
+{end_of_verse}
+{start_of_chorus}
+[Ab]Five [Bb]six [Eb]seven
+{end_of_chorus}
+{chorus}
+";
+
+	#[test]
+	fn test_chordpro_report() {
+		let guitar = Guitar::default();
+		let report = chordpro_report(SONG, &guitar, &SongReportOptions::default()).unwrap();
+
+		assert_eq!(report.title.as_deref(), Some("Flat Song"));
+		assert_eq!(
+			report.key.map(|k| k.key),
+			Some(Key::new(PitchClass::DSharp, Mode::Major))
+		);
+		assert!(report.modulations.is_empty());
+
+		assert_eq!(report.sections.len(), 3);
+		assert!(report.sections.iter().all(|s| s.progression.is_some()));
+		assert!(report.difficulty.is_some());
+
+		let hardest = &report.hardest_transitions;
+		assert!(!hardest.is_empty() && hardest.len() <= 3);
+		assert!(
+			hardest
+				.windows(2)
+				.all(|w| w[0].transition.score <= w[1].transition.score)
+		);
+
+		// Eb-family barre chords get easier with a capo (e.g., capo 3 for C shapes)
+		let baseline = report.shape_difficulty.unwrap();
+		assert!(!report.capo_suggestions.is_empty());
+		assert!(
+			report
+				.capo_suggestions
+				.iter()
+				.all(|s| s.difficulty < baseline)
+		);
+		assert_eq!(report.capo_suggestions[0].shapes.len(), 4);
+	}
+
+	#[test]
+	fn test_empty_song_report() {
+		let guitar = Guitar::default();
+		let report = song_report(&Song::new(None), &guitar, &SongReportOptions::default());
+		assert!(report.key.is_none());
+		assert!(report.difficulty.is_none());
+		assert!(report.capo_suggestions.is_empty());
+	}
+}
@@ -0,0 +1,278 @@
+//! Scales and modes
+//!
+//! A [`Scale`] is a tonic plus a [`ScaleKind`], the pattern of semitone steps
+//! above it. Degrees are 1-based as musicians count them, and wrap around the
+//! octave, so degree 8 of a major scale is its tonic again.
+
+use std::fmt;
+
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::error::{ChordCraftError, Result};
+use crate::key::{Key, Mode};
+use crate::note::PitchClass;
+use crate::spelling::{Accidentals, NoteName};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScaleKind {
+	/// Ionian
+	Major,
+	/// Aeolian
+	NaturalMinor,
+	HarmonicMinor,
+	/// Ascending form (jazz melodic minor)
+	MelodicMinor,
+	MajorPentatonic,
+	MinorPentatonic,
+	Dorian,
+	Phrygian,
+	Lydian,
+	Mixolydian,
+	Locrian,
+}
+
+impl ScaleKind {
+	/// Semitones of each degree above the tonic
+	pub fn steps(&self) -> &'static [u8] {
+		match self {
+			ScaleKind::Major => &[0, 2, 4, 5, 7, 9, 11],
+			ScaleKind::NaturalMinor => &[0, 2, 3, 5, 7, 8, 10],
+			ScaleKind::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+			ScaleKind::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+			ScaleKind::MajorPentatonic => &[0, 2, 4, 7, 9],
+			ScaleKind::MinorPentatonic => &[0, 3, 5, 7, 10],
+			ScaleKind::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+			ScaleKind::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+			ScaleKind::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+			ScaleKind::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+			ScaleKind::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			ScaleKind::Major => "major",
+			ScaleKind::NaturalMinor => "minor",
+			ScaleKind::HarmonicMinor => "harmonic minor",
+			ScaleKind::MelodicMinor => "melodic minor",
+			ScaleKind::MajorPentatonic => "major pentatonic",
+			ScaleKind::MinorPentatonic => "minor pentatonic",
+			ScaleKind::Dorian => "dorian",
+			ScaleKind::Phrygian => "phrygian",
+			ScaleKind::Lydian => "lydian",
+			ScaleKind::Mixolydian => "mixolydian",
+			ScaleKind::Locrian => "locrian",
+		}
+	}
+
+	/// Parse a scale name ("dorian", "harmonic minor", "ionian", "aeolian")
+	pub fn parse(s: &str) -> Result<Self> {
+		let normalized = s.trim().to_lowercase().replace(['-', '_'], " ");
+		match normalized.as_str() {
+			"ionian" => return Ok(ScaleKind::Major),
+			"aeolian" | "natural minor" => return Ok(ScaleKind::NaturalMinor),
+			_ => {}
+		}
+		ScaleKind::iter()
+			.find(|kind| kind.name() == normalized)
+			.ok_or_else(|| ChordCraftError::InvalidNote(format!("unknown scale '{s}'")))
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scale {
+	pub tonic: PitchClass,
+	pub kind: ScaleKind,
+}
+
+impl Scale {
+	pub fn new(tonic: PitchClass, kind: ScaleKind) -> Self {
+		Scale { tonic, kind }
+	}
+
+	/// Parse "A dorian", "Eb major" or "F# harmonic minor"
+	pub fn parse(s: &str) -> Result<Self> {
+		let s = s.trim();
+		let (tonic, kind) = s.split_once(char::is_whitespace).ok_or_else(|| {
+			ChordCraftError::InvalidNote(format!("expected tonic and scale: '{s}'"))
+		})?;
+		Ok(Scale::new(
+			PitchClass::parse(tonic)?,
+			ScaleKind::parse(kind)?,
+		))
+	}
+
+	/// Notes of the scale, from the tonic up
+	pub fn notes(&self) -> Vec<PitchClass> {
+		self.kind
+			.steps()
+			.iter()
+			.map(|&s| self.tonic.add_semitones(s as i32))
+			.collect()
+	}
+
+	/// Note on the given 1-based degree, wrapping past the octave; None for degree 0
+	pub fn degree(&self, n: usize) -> Option<PitchClass> {
+		let steps = self.kind.steps();
+		let step = steps[n.checked_sub(1)? % steps.len()];
+		Some(self.tonic.add_semitones(step as i32))
+	}
+
+	/// 1-based degree of a note, if it is in the scale
+	pub fn degree_of(&self, pitch: PitchClass) -> Option<usize> {
+		let semitones = self.tonic.semitone_distance_to(&pitch);
+		self.kind
+			.steps()
+			.iter()
+			.position(|&s| s == semitones)
+			.map(|i| i + 1)
+	}
+
+	pub fn contains(&self, pitch: PitchClass) -> bool {
+		self.degree_of(pitch).is_some()
+	}
+
+	/// Accidentals of the major or minor key with the same tonic and third
+	pub fn accidentals(&self) -> Accidentals {
+		let mode = if self.kind.steps().contains(&4) {
+			Mode::Major
+		} else {
+			Mode::Minor
+		};
+		Accidentals::for_key(&Key::new(self.tonic, mode))
+	}
+
+	/// Note names, spelled with the scale's accidentals
+	pub fn note_names(&self) -> Vec<&'static str> {
+		let accidentals = self.accidentals();
+		self.notes()
+			.into_iter()
+			.map(|n| accidentals.spell(n))
+			.collect()
+	}
+
+	/// Theory-correct note names
+	///
+	/// Seven-note scales use each letter once, so F# major has an E# and
+	/// C# harmonic minor a B#; other scales use the scale's accidentals.
+	pub fn spelled_notes(&self) -> Vec<NoteName> {
+		let accidentals = self.accidentals();
+		let tonic = NoteName::spell(self.tonic, accidentals);
+		self.notes()
+			.into_iter()
+			.enumerate()
+			.map(|(i, note)| {
+				let on_letter = (self.kind.steps().len() == 7)
+					.then(|| NoteName::on_letter(note, tonic.letter.shift(i as i32)))
+					.flatten();
+				on_letter.unwrap_or_else(|| NoteName::spell(note, accidentals))
+			})
+			.collect()
+	}
+}
+
+impl From<Key> for Scale {
+	/// The major or natural minor scale of a key
+	fn from(key: Key) -> Self {
+		let kind = match key.mode {
+			Mode::Major => ScaleKind::Major,
+			Mode::Minor => ScaleKind::NaturalMinor,
+		};
+		Scale::new(key.tonic, kind)
+	}
+}
+
+impl fmt::Display for Scale {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} {}",
+			self.accidentals().spell(self.tonic),
+			self.kind.name()
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use PitchClass::*;
+
+	#[test]
+	fn test_scale_notes_and_degrees() {
+		let c = Scale::new(C, ScaleKind::Major);
+		assert_eq!(c.notes(), vec![C, D, E, F, G, A, B]);
+		assert_eq!(c.degree(5), Some(G));
+		assert_eq!(c.degree(8), Some(C));
+		assert_eq!(c.degree(0), None);
+		assert_eq!(c.degree_of(F), Some(4));
+		assert!(!c.contains(FSharp));
+
+		let a_harmonic = Scale::new(A, ScaleKind::HarmonicMinor);
+		assert_eq!(a_harmonic.degree(7), Some(GSharp));
+
+		let e_minor_pentatonic = Scale::new(E, ScaleKind::MinorPentatonic);
+		assert_eq!(e_minor_pentatonic.notes(), vec![E, G, A, B, D]);
+	}
+
+	#[test]
+	fn test_modes_share_the_parent_scale() {
+		let c_major = Scale::new(C, ScaleKind::Major).notes();
+		for (tonic, kind) in [
+			(D, ScaleKind::Dorian),
+			(E, ScaleKind::Phrygian),
+			(F, ScaleKind::Lydian),
+			(G, ScaleKind::Mixolydian),
+			(A, ScaleKind::NaturalMinor),
+			(B, ScaleKind::Locrian),
+		] {
+			let mode = Scale::new(tonic, kind);
+			assert!(c_major.iter().all(|&n| mode.contains(n)), "{mode}");
+		}
+	}
+
+	#[test]
+	fn test_parse_scales() {
+		assert_eq!(
+			Scale::parse("A dorian").unwrap(),
+			Scale::new(A, ScaleKind::Dorian)
+		);
+		assert_eq!(
+			Scale::parse("Eb harmonic-minor").unwrap(),
+			Scale::new(DSharp, ScaleKind::HarmonicMinor)
+		);
+		assert_eq!(
+			ScaleKind::parse("Aeolian").unwrap(),
+			ScaleKind::NaturalMinor
+		);
+		assert!(Scale::parse("C bebop").is_err());
+		assert_eq!(
+			Scale::parse("F major").unwrap().note_names(),
+			vec!["F", "G", "A", "Bb", "C", "D", "E"]
+		);
+		assert_eq!(Scale::from(Key::new(A, Mode::Minor)).to_string(), "A minor");
+	}
+
+	#[test]
+	fn test_spelled_notes_use_each_letter_once() {
+		let names = |s: &str| -> Vec<String> {
+			Scale::parse(s)
+				.unwrap()
+				.spelled_notes()
+				.iter()
+				.map(|n| n.to_string())
+				.collect()
+		};
+		assert_eq!(
+			names("F# major"),
+			vec!["F#", "G#", "A#", "B", "C#", "D#", "E#"]
+		);
+		assert_eq!(
+			names("C# harmonic minor"),
+			vec!["C#", "D#", "E", "F#", "G#", "A", "B#"]
+		);
+		assert_eq!(names("A minor pentatonic"), vec!["A", "C", "D", "E", "G"]);
+	}
+}
@@ -0,0 +1,267 @@
+//! Setlists
+//!
+//! A [`Setlist`] is an ordered list of songs, each with the instrument, tuning
+//! and capo it is played with. Aggregate helpers answer gig-prep questions
+//! such as how hard the set is overall and where retunes or capo moves happen
+//! between songs.
+
+use crate::instrument::{ConfigurableInstrument, Instrument};
+use crate::note::Note;
+use crate::progression::{ProgressionOptions, generate_progression};
+use crate::song::Song;
+
+/// A song together with how it is played
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetlistEntry {
+	pub song: Song,
+	/// Instrument (including its tuning) used for this song
+	pub instrument: ConfigurableInstrument,
+	/// Capo fret (0 = no capo)
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub capo: u8,
+}
+
+impl SetlistEntry {
+	pub fn new(song: Song, instrument: ConfigurableInstrument, capo: u8) -> Self {
+		SetlistEntry {
+			song,
+			instrument,
+			capo,
+		}
+	}
+
+	/// Difficulty of the song's easiest progression (0 = trivial, 100 = unplayable)
+	///
+	/// Computed as the average playability deficit of the fingerings in the best
+	/// progression. Returns None if the song has no chords, the capo doesn't fit
+	/// the instrument, or no progression satisfies the options.
+	pub fn difficulty(&self, options: &ProgressionOptions) -> Option<u8> {
+		let names = self.song.chord_names();
+		let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+		let options = ProgressionOptions {
+			limit: 1,
+			..options.clone()
+		};
+
+		if self.capo > 0 {
+			let capoed = self.instrument.with_capo(self.capo).ok()?;
+			progression_difficulty(&names, &capoed, &options)
+		} else {
+			progression_difficulty(&names, &self.instrument, &options)
+		}
+	}
+}
+
+fn progression_difficulty<I: Instrument>(
+	names: &[&str],
+	instrument: &I,
+	options: &ProgressionOptions,
+) -> Option<u8> {
+	generate_progression(names, instrument, options)
+		.into_iter()
+		.next()?
+		.difficulty(instrument)
+}
+
+/// What has to change on stage between two consecutive songs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changeover {
+	/// Index of the song being finished
+	pub from: usize,
+	/// Index of the next song
+	pub to: usize,
+	/// A different instrument is needed (different string count)
+	pub instrument_change: bool,
+	/// Strings to retune on the same instrument: (string index, from, to)
+	pub retuned_strings: Vec<(usize, Note, Note)>,
+	/// Capo move as (from, to), 0 meaning no capo
+	pub capo_move: Option<(u8, u8)>,
+}
+
+impl Changeover {
+	pub fn is_retune(&self) -> bool {
+		!self.retuned_strings.is_empty()
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Setlist {
+	pub name: Option<String>,
+	pub entries: Vec<SetlistEntry>,
+}
+
+impl Setlist {
+	pub fn new(name: Option<String>) -> Self {
+		Setlist {
+			name,
+			entries: Vec::new(),
+		}
+	}
+
+	pub fn push(&mut self, entry: SetlistEntry) {
+		self.entries.push(entry);
+	}
+
+	/// Changes needed between consecutive songs (only where something changes)
+	pub fn changeovers(&self) -> Vec<Changeover> {
+		self.entries
+			.windows(2)
+			.enumerate()
+			.filter_map(|(i, pair)| {
+				let (a, b) = (&pair[0], &pair[1]);
+				let a_tuning = a.instrument.tuning();
+				let b_tuning = b.instrument.tuning();
+
+				let instrument_change = a_tuning.len() != b_tuning.len();
+				let retuned_strings = if instrument_change {
+					vec![]
+				} else {
+					a_tuning
+						.iter()
+						.zip(b_tuning)
+						.enumerate()
+						.filter(|(_, (from, to))| from != to)
+						.map(|(s, (from, to))| (s, *from, *to))
+						.collect()
+				};
+				let capo_move = (a.capo != b.capo).then_some((a.capo, b.capo));
+
+				let changeover = Changeover {
+					from: i,
+					to: i + 1,
+					instrument_change,
+					retuned_strings,
+					capo_move,
+				};
+				(changeover.instrument_change || changeover.is_retune() || capo_move.is_some())
+					.then_some(changeover)
+			})
+			.collect()
+	}
+
+	/// Number of retunes required between songs
+	pub fn retune_count(&self) -> usize {
+		self.changeovers().iter().filter(|c| c.is_retune()).count()
+	}
+
+	/// Number of capo moves (including putting on or taking off the capo)
+	pub fn capo_move_count(&self) -> usize {
+		self.changeovers()
+			.iter()
+			.filter(|c| c.capo_move.is_some())
+			.count()
+	}
+
+	/// Sum of song difficulties; songs without a playable progression are skipped
+	pub fn total_difficulty(&self, options: &ProgressionOptions) -> u32 {
+		self.entries
+			.iter()
+			.filter_map(|e| e.difficulty(options))
+			.map(u32::from)
+			.sum()
+	}
+
+	/// Total length of the set in beats
+	pub fn total_beats(&self) -> f32 {
+		self.entries.iter().map(|e| e.song.total_beats()).sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chord::Chord;
+	use crate::song::SongSection;
+
+	fn song(title: &str, chords: &[&str]) -> Song {
+		let mut section = SongSection::new(None);
+		for name in chords {
+			section.push(Chord::parse(name).unwrap(), 4.0);
+		}
+		let mut song = Song::new(Some(title.to_string()));
+		song.sections.push(section);
+		song
+	}
+
+	fn standard() -> ConfigurableInstrument {
+		crate::tuning::common_guitar_tunings().remove(0)
+	}
+
+	fn sample_setlist() -> Setlist {
+		let mut setlist = Setlist::new(Some("Friday".to_string()));
+		setlist.push(SetlistEntry::new(
+			song("Opener", &["G", "C", "D"]),
+			standard(),
+			0,
+		));
+		setlist.push(SetlistEntry::new(
+			song("Capo Tune", &["C", "Am", "F", "G"]),
+			standard(),
+			3,
+		));
+		setlist.push(SetlistEntry::new(
+			song("Drop D Riff", &["D", "G", "A"]),
+			ConfigurableInstrument::guitar_drop_d(),
+			3,
+		));
+		setlist.push(SetlistEntry::new(
+			song("Bass Feature", &["E", "A"]),
+			ConfigurableInstrument::bass(),
+			0,
+		));
+		setlist
+	}
+
+	#[test]
+	fn test_changeovers() {
+		let setlist = sample_setlist();
+		let changeovers = setlist.changeovers();
+
+		assert_eq!(changeovers.len(), 3);
+		assert_eq!(changeovers[0].capo_move, Some((0, 3)));
+		assert!(!changeovers[0].is_retune());
+
+		// Standard -> Drop D: only the low E string changes
+		assert_eq!(changeovers[1].retuned_strings.len(), 1);
+		assert_eq!(changeovers[1].retuned_strings[0].0, 0);
+		assert_eq!(changeovers[1].capo_move, None);
+
+		assert!(changeovers[2].instrument_change);
+		assert_eq!(setlist.retune_count(), 1);
+		assert_eq!(setlist.capo_move_count(), 2);
+	}
+
+	#[test]
+	fn test_total_difficulty() {
+		let setlist = sample_setlist();
+		let options = ProgressionOptions::default();
+
+		let per_song: Vec<Option<u8>> = setlist
+			.entries
+			.iter()
+			.map(|e| e.difficulty(&options))
+			.collect();
+		assert!(per_song.iter().all(|d| d.is_some_and(|d| d <= 100)));
+
+		let total = setlist.total_difficulty(&options);
+		assert_eq!(
+			total,
+			per_song.iter().map(|d| d.unwrap() as u32).sum::<u32>()
+		);
+		assert_eq!(setlist.total_beats(), 48.0);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_setlist_serde_roundtrip() {
+		let setlist = sample_setlist();
+		let json = serde_json::to_string(&setlist).unwrap();
+		assert!(json.contains("\"Am\""));
+
+		let parsed: Setlist = serde_json::from_str(&json).unwrap();
+		assert_eq!(parsed, setlist);
+	}
+}
@@ -6,7 +6,12 @@
 //!
 //! For example, the Am shape (x02210) barred at fret 2 becomes Bm (x24432).
 
+use std::fmt;
+
+use crate::chord::Chord;
 use crate::fingering::{Fingering, StringState};
+use crate::instrument::Instrument;
+use crate::note::PitchClass;
 
 /// A standard chord shape that can be recognized and matched against fingerings.
 #[derive(Debug, Clone)]
@@ -53,6 +58,8 @@ impl StandardShape {
 				(StringState::Muted, None) => continue,
 				// Pattern expects muted but fingering is fretted - no match
 				(StringState::Fretted(_), None) => return None,
+				// Harmonics are never part of a standard shape
+				(StringState::Harmonic(_), _) => return None,
 				// Pattern expects fretted but fingering is muted - no match
 				(StringState::Muted, Some(_)) => return None,
 				// Both fretted - check relative position
@@ -69,9 +76,101 @@ impl StandardShape {
 	}
 }
 
+/// Find which standard shape a fingering matches on an instrument, if any.
+/// Returns the shape name and base fret if found.
+///
+/// Shapes are only looked for when the instrument is tuned like the shape
+/// library (in any key), so drop D or DADGAD get no standard guitar names.
+pub fn find_matching_shape<I: Instrument>(
+	fingering: &Fingering,
+	instrument: &I,
+) -> Option<(&'static str, u8)> {
+	library_for(instrument).and_then(|(_, find)| find(fingering))
+}
+
+type FindShape = fn(&Fingering) -> Option<(&'static str, u8)>;
+
+/// The shape library an instrument is tuned for, with the open strings it was written for
+fn library_for<I: Instrument>(instrument: &I) -> Option<(&'static [PitchClass], FindShape)> {
+	let libraries: [(&'static [PitchClass], FindShape); 4] = [
+		(&guitar::TUNING, guitar::find_matching_shape),
+		(&ukulele::TUNING, ukulele::find_matching_shape),
+		(&mandolin::TUNING, mandolin::find_matching_shape),
+		(&banjo::TUNING, banjo::find_matching_shape),
+	];
+	libraries
+		.into_iter()
+		.find(|(tuning, _)| tuned_like(instrument, tuning))
+}
+
+/// Whether the open strings are the same intervals apart as `reference`,
+/// octaves aside; a short string (the banjo's fifth) is left out, since a
+/// capo leaves it alone
+fn tuned_like<I: Instrument>(instrument: &I, reference: &[PitchClass]) -> bool {
+	let tuning = instrument.tuning();
+	if tuning.len() != reference.len() {
+		return false;
+	}
+	let step = |from: PitchClass, to: PitchClass| {
+		(to.to_semitone() as i32 - from.to_semitone() as i32).rem_euclid(12)
+	};
+	let full_length: Vec<usize> = (0..tuning.len())
+		.filter(|&string| instrument.string_nut_fret(string) == 0)
+		.collect();
+	full_length.windows(2).all(|pair| {
+		let (low, high) = (pair[0], pair[1]);
+		step(tuning[low].pitch, tuning[high].pitch) == step(reference[low], reference[high])
+	})
+}
+
+/// A standard shape recognized in a fingering
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeMatch {
+	/// Name of the shape (e.g., "E" for 355433)
+	pub name: String,
+	/// Fret the shape is barred at (0 = played open)
+	pub fret: u8,
+}
+
+impl ShapeMatch {
+	pub fn find<I: Instrument>(fingering: &Fingering, instrument: &I) -> Option<Self> {
+		find_matching_shape(fingering, instrument).map(|(name, fret)| ShapeMatch {
+			name: name.to_string(),
+			fret,
+		})
+	}
+
+	/// Chord the shape makes on the instrument, e.g. G for the E shape barred
+	/// at fret 3; None for shapes not named after a chord (the "5-E" power shape)
+	pub fn chord<I: Instrument>(&self, instrument: &I) -> Option<Chord> {
+		let (reference, _) = library_for(instrument)?;
+		let string = (0..reference.len()).find(|&s| instrument.string_nut_fret(s) == 0)?;
+		let retuned = reference[string].semitone_distance_to(&instrument.tuning()[string].pitch);
+		// Variants like "C-alt" and "F-partial" still make the chord before the dash
+		let name = self.name.split('-').next()?;
+		let chord = Chord::parse(name).ok()?;
+		Some(chord.transpose(self.fret as i32 + retuned as i32))
+	}
+}
+
+impl fmt::Display for ShapeMatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.fret == 0 {
+			write!(f, "{} shape", self.name)
+		} else {
+			write!(f, "{} shape barred at fret {}", self.name, self.fret)
+		}
+	}
+}
+
 /// Standard guitar chord shapes (6 strings, EADGBE tuning)
 pub mod guitar {
 	use super::StandardShape;
+	use crate::note::PitchClass::{self, *};
+
+	/// Open strings the shapes are written for, lowest first
+	pub const TUNING: [PitchClass; 6] = [E, A, D, G, B, E];
 
 	/// Am shape: x02210 - very common, used for Bm, Cm, C#m, etc.
 	pub const AM_SHAPE: StandardShape = StandardShape {
@@ -329,6 +428,10 @@ pub mod guitar {
 /// Standard ukulele chord shapes (4 strings, GCEA tuning)
 pub mod ukulele {
 	use super::StandardShape;
+	use crate::note::PitchClass::{self, *};
+
+	/// Open strings the shapes are written for, lowest first
+	pub const TUNING: [PitchClass; 4] = [G, C, E, A];
 
 	/// A shape: 2100 - common shape, barred for Bb, B, C, etc.
 	pub const A_SHAPE: StandardShape = StandardShape {
@@ -424,6 +527,10 @@ pub mod ukulele {
 /// The 5ths tuning creates symmetric, movable shapes different from guitar.
 pub mod mandolin {
 	use super::StandardShape;
+	use crate::note::PitchClass::{self, *};
+
+	/// Open strings the shapes are written for, lowest first
+	pub const TUNING: [PitchClass; 4] = [G, D, A, E];
 
 	/// G shape: 0023 - open G major, very common
 	pub const G_SHAPE: StandardShape = StandardShape {
@@ -520,6 +627,10 @@ pub mod mandolin {
 /// String order: g (drone), D, G, B, D
 pub mod banjo {
 	use super::StandardShape;
+	use crate::note::PitchClass::{self, *};
+
+	/// Open strings the shapes are written for, lowest first
+	pub const TUNING: [PitchClass; 5] = [G, D, G, B, D];
 
 	/// G shape: 00000 - all open strings (the beauty of open G tuning!)
 	pub const G_SHAPE: StandardShape = StandardShape {
@@ -631,6 +742,7 @@ pub mod banjo {
 mod tests {
 	use super::*;
 	use crate::fingering::Fingering;
+	use crate::instrument::{ConfigurableInstrument, Guitar, Ukulele};
 
 	#[test]
 	fn test_am_shape_open() {
@@ -725,6 +837,61 @@ mod tests {
 		assert_eq!(result, Some(("E", 1)), "Should find E shape at fret 1");
 	}
 
+	#[test]
+	fn test_shape_match_for_instrument() {
+		let g = Fingering::parse("355433").unwrap();
+		let shape = ShapeMatch::find(&g, &Guitar::default()).unwrap();
+		assert_eq!(shape.to_string(), "E shape barred at fret 3");
+
+		let c = Fingering::parse("x32010").unwrap();
+		assert_eq!(
+			ShapeMatch::find(&c, &Guitar::default())
+				.unwrap()
+				.to_string(),
+			"C shape"
+		);
+
+		// Ukulele shapes on a ukulele, and nothing on a 7-string
+		let bb = Fingering::parse("3211").unwrap();
+		assert_eq!(
+			find_matching_shape(&bb, &Ukulele::default()),
+			Some(("A", 1))
+		);
+		let seven = ConfigurableInstrument::guitar_7_string();
+		assert!(ShapeMatch::find(&Fingering::parse("x355433").unwrap(), &seven).is_none());
+	}
+
+	#[test]
+	fn test_shapes_need_the_library_tuning() {
+		// Standard guitar names mean nothing in drop D, DADGAD or open G
+		let g = Fingering::parse("355433").unwrap();
+		let c = Fingering::parse("x32010").unwrap();
+		for name in ["drop-d", "dadgad", "open-g"] {
+			let retuned = ConfigurableInstrument::by_name(name).unwrap();
+			assert_eq!(find_matching_shape(&g, &retuned), None, "{name}");
+			assert_eq!(find_matching_shape(&c, &retuned), None, "{name}");
+		}
+
+		// The same intervals in another key keep the shape names
+		let baritone = ConfigurableInstrument::baritone_guitar();
+		assert_eq!(find_matching_shape(&g, &baritone), Some(("E", 3)));
+		let capoed = Guitar::default().with_capo(2).unwrap();
+		assert_eq!(find_matching_shape(&c, &capoed), Some(("C", 0)));
+		let low_g = ConfigurableInstrument::ukulele_low_g();
+		let bb = Fingering::parse("3211").unwrap();
+		assert_eq!(find_matching_shape(&bb, &low_g), Some(("A", 1)));
+
+		// Mandolin shapes on a mandolin, and the banjo's drone sits out under a capo
+		let mandolin = ConfigurableInstrument::mandolin();
+		assert_eq!(
+			find_matching_shape(&bb, &mandolin),
+			mandolin::find_matching_shape(&bb)
+		);
+		let banjo = ConfigurableInstrument::banjo().with_capo(2).unwrap();
+		let em = Fingering::parse("x0002").unwrap();
+		assert_eq!(find_matching_shape(&em, &banjo), Some(("Em", 0)));
+	}
+
 	// Ukulele tests
 	#[test]
 	fn test_ukulele_c_shape() {
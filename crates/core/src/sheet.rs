@@ -0,0 +1,199 @@
+//! Plain-text chord sheets
+//!
+//! [`render_sheet`] lays a progression out for printing or pasting into
+//! practice notes: chord names over bars of tab, highest string on top, with
+//! a hint for each chord change below its line:
+//!
+//! ```text
+//!    C     Am    F     G
+//! e|-0---|-0---|-1---|-3---|
+//! B|-1---|-1---|-1---|-0---|
+//! G|-0---|-2---|-2---|-0---|
+//! D|-2---|-2---|-3---|-0---|
+//! A|-3---|-0---|-x---|-2---|
+//! E|-x---|-x---|-x---|-3---|
+//!   C → Am: 1 finger moves, 2 stay (~180 ms)
+//! ```
+
+use crate::fingering::StringState;
+use crate::instrument::Instrument;
+use crate::progression::{ChordTransition, ProgressionSequence};
+
+/// Chords per line of the sheet
+pub const DEFAULT_CHORDS_PER_LINE: usize = 4;
+
+/// Render a progression as a plain-text chord sheet, `chords_per_line` bars per line
+///
+/// Each chord gets a bar of its own. With roman numerals on the progression,
+/// the key heads the sheet and each chord's numeral sits under its name.
+/// Changes that won't fit the tempo are flagged in their hint.
+pub fn render_sheet<I: Instrument>(
+	progression: &ProgressionSequence,
+	instrument: &I,
+	chords_per_line: usize,
+) -> String {
+	let labels = string_labels(instrument);
+	let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(1);
+	let numerals = progression.numerals.as_ref().map(|n| n.labels());
+
+	let tabs: Vec<Vec<String>> = progression
+		.fingerings
+		.iter()
+		.map(|f| f.fingering.strings().iter().map(tab_symbol).collect())
+		.collect();
+	let cell = progression
+		.chords
+		.iter()
+		.chain(numerals.iter().flatten())
+		.chain(tabs.iter().flatten())
+		.map(|s| s.chars().count())
+		.max()
+		.unwrap_or(1)
+		.max(3);
+	let bar = cell + 3;
+
+	let mut lines = Vec::new();
+	if let Some(analysis) = &progression.numerals {
+		lines.push(format!("Key: {}", analysis.key));
+		lines.push(String::new());
+	}
+
+	let indices: Vec<usize> = (0..progression.chords.len()).collect();
+	for (row, chunk) in indices.chunks(chords_per_line.max(1)).enumerate() {
+		if row > 0 {
+			lines.push(String::new());
+		}
+		let indent = " ".repeat(label_width + 2);
+		let names: String = chunk
+			.iter()
+			.map(|&i| format!("{:bar$}", progression.chords[i]))
+			.collect();
+		lines.push(format!("{indent}{names}").trim_end().to_string());
+		if let Some(numerals) = &numerals {
+			let row: String = chunk
+				.iter()
+				.map(|&i| format!("{:bar$}", numerals[i]))
+				.collect();
+			lines.push(format!("{indent}{row}").trim_end().to_string());
+		}
+
+		for (s, label) in labels.iter().enumerate().rev() {
+			let mut staff = format!("{label:>label_width$}|");
+			for &i in chunk {
+				let symbol = tabs[i].get(s).map(String::as_str).unwrap_or("x");
+				staff.push_str(&format!("-{symbol:-<cell$}-|"));
+			}
+			lines.push(staff);
+		}
+
+		for &i in chunk {
+			if let Some(transition) = progression.transitions.get(i) {
+				lines.push(format!("  {}", transition_hint(transition)));
+			}
+		}
+	}
+
+	lines.join("\n")
+}
+
+/// Tuning note of each string, lowest first; the top string is lowercase
+/// when it shares its name with a lower one (e B G D A E)
+fn string_labels<I: Instrument>(instrument: &I) -> Vec<String> {
+	let mut labels: Vec<String> = instrument
+		.tuning()
+		.iter()
+		.map(|n| n.pitch.to_string())
+		.collect();
+	if let Some((top, rest)) = labels.split_last_mut()
+		&& rest.contains(top)
+	{
+		*top = top.to_lowercase();
+	}
+	labels
+}
+
+/// A string's symbol on a tab staff: "x", "3", "10" or "<12>"
+fn tab_symbol(state: &StringState) -> String {
+	match state {
+		StringState::Muted => "x".to_string(),
+		StringState::Fretted(fret) => fret.to_string(),
+		StringState::Harmonic(fret) => format!("<{fret}>"),
+	}
+}
+
+/// One-line description of a chord change
+fn transition_hint(transition: &ChordTransition) -> String {
+	let moves = match transition.finger_movements {
+		0 => "no fingers move".to_string(),
+		1 => "1 finger moves".to_string(),
+		n => format!("{n} fingers move"),
+	};
+	let mut hint = format!(
+		"{} → {}: {moves}",
+		transition.from_chord, transition.to_chord
+	);
+	if transition.common_anchors > 0 {
+		hint.push_str(&format!(", {} stay", transition.common_anchors));
+	}
+	if transition.position_distance > 0 {
+		hint.push_str(&format!(
+			", shift {} fret{}",
+			transition.position_distance,
+			if transition.position_distance == 1 {
+				""
+			} else {
+				"s"
+			}
+		));
+	}
+	hint.push_str(&format!(" (~{} ms)", transition.estimated_ms));
+	if let Some(budget) = transition.budget_ms
+		&& transition.is_risky()
+	{
+		hint.push_str(&format!(" - hard to make in {budget} ms"));
+	}
+	hint
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+	use crate::progression::{Numerals, ProgressionOptions, generate_progression};
+
+	#[test]
+	fn test_render_sheet() {
+		let guitar = Guitar::default();
+		let options = ProgressionOptions {
+			limit: 1,
+			numerals: Numerals::DetectedKey,
+			..Default::default()
+		};
+		let progression = &generate_progression(&["C", "Am", "F", "G", "C"], &guitar, &options)[0];
+		let sheet = render_sheet(progression, &guitar, 4);
+		let lines: Vec<&str> = sheet.lines().collect();
+
+		assert_eq!(lines[0], "Key: C major");
+		assert!(lines[2].trim_start().starts_with("C"));
+		assert!(lines[2].contains("Am") && lines[2].contains("G"));
+		assert!(lines[3].trim_start().starts_with("I "));
+
+		// Six strings, high e on top, one bar per chord
+		let staff: Vec<&str> = lines.iter().copied().filter(|l| l.contains("|-")).collect();
+		assert_eq!(staff.len(), 12);
+		assert!(staff[0].starts_with("e|"));
+		assert!(staff[5].starts_with("E|"));
+		assert_eq!(staff[0].matches('|').count(), 5);
+		assert_eq!(staff[6].matches('|').count(), 2);
+
+		// A hint per change, the last one under the final chord's line
+		let hints: Vec<&str> = lines
+			.iter()
+			.copied()
+			.filter(|l| l.contains(" → "))
+			.collect();
+		assert_eq!(hints.len(), 4);
+		assert!(hints[0].trim_start().starts_with("C → Am:"));
+		assert!(lines.last().unwrap().starts_with("E|"));
+	}
+}
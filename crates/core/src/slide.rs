@@ -0,0 +1,278 @@
+//! Slide-guitar voicings
+//!
+//! A slide stops every string it touches at the same fret, so the general
+//! search (which assumes independent fingers) doesn't apply. This module builds
+//! candidates directly: one bar position, each string either under the slide,
+//! ringing open, or damped. Open strings must sit on the bass side of the
+//! slide, since the slide is worn over the treble strings.
+//!
+//! Open tunings shine here: a straight bar across all strings is a full chord,
+//! and such voicings score highest.
+
+use crate::chord::{Chord, VoicingType};
+use crate::fingering::{Difficulty, Fingering, StringState};
+use crate::generator::ScoredFingering;
+use crate::instrument::Instrument;
+
+const SLIDE_FULL_VOICING_BONUS: i32 = 30;
+const SLIDE_CORE_VOICING_BONUS: i32 = 10;
+const SLIDE_ROOT_IN_BASS_BONUS: i32 = 20;
+const SLIDE_STRING_BONUS: i32 = 5;
+/// Every played string is under the bar: the classic open-tuning grip
+const SLIDE_FULL_BAR_BONUS: i32 = 25;
+/// Damping a string between two sounding ones takes extra right-hand work
+const SLIDE_INTERIOR_MUTE_PENALTY: i32 = 15;
+const SLIDE_POSITION_PENALTY: i32 = 2;
+
+#[derive(Debug, Clone)]
+pub struct SlideOptions {
+	pub limit: usize,
+	/// Highest fret for the slide
+	pub max_fret: u8,
+	/// Let strings on the bass side of the slide ring open
+	pub include_open: bool,
+	/// Require the root as the lowest sounding note
+	pub root_in_bass: bool,
+	/// Preferred slide fret; voicings further away score lower
+	pub preferred_position: Option<u8>,
+}
+
+impl Default for SlideOptions {
+	fn default() -> Self {
+		SlideOptions {
+			limit: 10,
+			max_fret: 15,
+			include_open: true,
+			root_in_bass: false,
+			preferred_position: None,
+		}
+	}
+}
+
+/// A voicing played with a slide
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlideVoicing {
+	pub fingering: Fingering,
+	/// Fret the slide sits over
+	pub bar_fret: u8,
+	/// Every played string is under the slide (no open strings)
+	pub full_bar: bool,
+	pub score: u16,
+	pub voicing_type: VoicingType,
+	pub has_root_in_bass: bool,
+}
+
+impl SlideVoicing {
+	/// View as a regular scored fingering, e.g. for `format_fingering_diagram`
+	pub fn to_scored(&self) -> ScoredFingering {
+		ScoredFingering {
+			fingering: self.fingering.clone(),
+			score: self.score,
+			voicing_type: self.voicing_type,
+			has_root_in_bass: self.has_root_in_bass,
+			position: self.bar_fret,
+			// The slide stops the strings, not the fingers
+			fingers: None,
+			difficulty: Difficulty::default(),
+			omitted: Vec::new(),
+		}
+	}
+}
+
+/// Generate voicings of a chord playable with a slide
+///
+/// Only voicings containing all of the chord's core notes are returned, best
+/// first. Each fingering appears once, at its slide fret.
+pub fn generate_slide_voicings<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	options: &SlideOptions,
+) -> Vec<SlideVoicing> {
+	let tuning = instrument.tuning();
+	let all_notes = chord.notes();
+	let max_fret = options.max_fret.min(instrument.fret_range().1);
+
+	let mut voicings = Vec::new();
+	for bar_fret in 1..=max_fret {
+		let string_options: Vec<Vec<StringState>> = tuning
+			.iter()
+			.map(|open| {
+				let mut states = vec![StringState::Muted];
+				if options.include_open && all_notes.contains(&open.pitch) {
+					states.push(StringState::Fretted(0));
+				}
+				if all_notes.contains(&open.pitch.add_semitones(bar_fret as i32)) {
+					states.push(StringState::Fretted(bar_fret));
+				}
+				states
+			})
+			.collect();
+
+		let mut candidates = Vec::new();
+		collect_candidates(&string_options, bar_fret, &mut vec![], &mut candidates);
+
+		voicings.extend(candidates.into_iter().filter_map(|states| {
+			score_slide_voicing(states, bar_fret, chord, instrument, options)
+		}));
+	}
+
+	voicings.sort_by_key(|v| std::cmp::Reverse(v.score));
+	voicings.truncate(options.limit);
+	voicings
+}
+
+/// Enumerate string states with at least one string under the slide and no
+/// open string on the treble side of it
+fn collect_candidates(
+	string_options: &[Vec<StringState>],
+	bar_fret: u8,
+	current: &mut Vec<StringState>,
+	results: &mut Vec<Vec<StringState>>,
+) {
+	if current.len() == string_options.len() {
+		if current.contains(&StringState::Fretted(bar_fret)) {
+			results.push(current.clone());
+		}
+		return;
+	}
+
+	let under_slide = current.contains(&StringState::Fretted(bar_fret));
+	for &state in &string_options[current.len()] {
+		if under_slide && state == StringState::Fretted(0) {
+			continue;
+		}
+		current.push(state);
+		collect_candidates(string_options, bar_fret, current, results);
+		current.pop();
+	}
+}
+
+fn score_slide_voicing<I: Instrument>(
+	states: Vec<StringState>,
+	bar_fret: u8,
+	chord: &Chord,
+	instrument: &I,
+	options: &SlideOptions,
+) -> Option<SlideVoicing> {
+	let played: Vec<usize> = states
+		.iter()
+		.enumerate()
+		.filter(|(_, s)| s.is_played())
+		.map(|(i, _)| i)
+		.collect();
+	if played.len() < instrument.min_played_strings() {
+		return None;
+	}
+
+	let fingering = Fingering::new(states);
+	let pitches = fingering.unique_pitch_classes(instrument);
+	let has_all_notes = chord.notes().iter().all(|n| pitches.contains(n));
+	if !chord.core_notes().iter().all(|n| pitches.contains(n)) {
+		return None;
+	}
+
+	let has_root_in_bass = fingering.bass_note(instrument).map(|n| n.pitch) == Some(chord.root);
+	if options.root_in_bass && !has_root_in_bass {
+		return None;
+	}
+
+	let full_bar = fingering
+		.strings()
+		.iter()
+		.all(|s| !s.is_played() || *s == StringState::Fretted(bar_fret));
+	let interior_mutes = match (played.first(), played.last()) {
+		(Some(&lo), Some(&hi)) => (hi - lo + 1 - played.len()) as i32,
+		_ => 0,
+	};
+
+	let mut score = played.len() as i32 * SLIDE_STRING_BONUS;
+	score += if has_all_notes {
+		SLIDE_FULL_VOICING_BONUS
+	} else {
+		SLIDE_CORE_VOICING_BONUS
+	};
+	if has_root_in_bass {
+		score += SLIDE_ROOT_IN_BASS_BONUS;
+	}
+	if full_bar {
+		score += SLIDE_FULL_BAR_BONUS;
+	}
+	score -= interior_mutes * SLIDE_INTERIOR_MUTE_PENALTY;
+	if let Some(preferred) = options.preferred_position {
+		score -= (bar_fret as i32 - preferred as i32).abs() * SLIDE_POSITION_PENALTY;
+	}
+
+	Some(SlideVoicing {
+		fingering,
+		bar_fret,
+		full_bar,
+		score: score.max(0) as u16,
+		voicing_type: if has_all_notes {
+			VoicingType::Full
+		} else {
+			VoicingType::Core
+		},
+		has_root_in_bass,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::{ConfigurableInstrument, Guitar};
+
+	#[test]
+	fn test_open_g_straight_bar() {
+		let open_g = ConfigurableInstrument::guitar_open_g();
+		let chord = Chord::parse("C").unwrap();
+
+		let voicings = generate_slide_voicings(&chord, &open_g, &SlideOptions::default());
+		let best = &voicings[0];
+		// Bar at 5 turns open G into C; the low D string (a G there) is damped
+		// so the root stays in the bass
+		assert_eq!(best.fingering.to_string(), "x55555");
+		assert_eq!(best.bar_fret, 5);
+		assert!(best.full_bar);
+		assert!(best.has_root_in_bass);
+	}
+
+	#[test]
+	fn test_slide_voicings_are_straight() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("A").unwrap();
+
+		let voicings = generate_slide_voicings(&chord, &guitar, &SlideOptions::default());
+		assert!(!voicings.is_empty());
+
+		for v in &voicings {
+			let mut under_slide = false;
+			for state in v.fingering.strings() {
+				match state {
+					StringState::Fretted(0) => assert!(!under_slide, "{}", v.fingering),
+					StringState::Fretted(f) => {
+						assert_eq!(*f, v.bar_fret);
+						under_slide = true;
+					}
+					_ => {}
+				}
+			}
+
+			let pitches = v.fingering.unique_pitch_classes(&guitar);
+			assert!(chord.core_notes().iter().all(|n| pitches.contains(n)));
+		}
+	}
+
+	#[test]
+	fn test_slide_without_open_strings() {
+		let guitar = Guitar::default();
+		let chord = Chord::parse("E").unwrap();
+		let options = SlideOptions {
+			include_open: false,
+			..Default::default()
+		};
+
+		let voicings = generate_slide_voicings(&chord, &guitar, &options);
+		assert!(voicings.iter().all(|v| v.full_bar));
+	}
+}
@@ -0,0 +1,113 @@
+//! Song model
+//!
+//! A lightweight representation of a song's harmony: sections of timed chords.
+//! Importers (MIDI, MusicXML, ChordPro) produce a [`Song`], and its chord list
+//! can be fed straight into the progression optimizer.
+
+use crate::chord::Chord;
+
+/// A chord held for a number of beats
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SongChord {
+	pub chord: Chord,
+	pub beats: f32,
+}
+
+impl SongChord {
+	pub fn new(chord: Chord, beats: f32) -> Self {
+		SongChord { chord, beats }
+	}
+}
+
+/// A labelled part of a song (verse, chorus, ...)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SongSection {
+	pub label: Option<String>,
+	pub chords: Vec<SongChord>,
+}
+
+impl SongSection {
+	pub fn new(label: Option<String>) -> Self {
+		SongSection {
+			label,
+			chords: Vec::new(),
+		}
+	}
+
+	/// Append a chord, extending the previous one if it is the same chord
+	pub fn push(&mut self, chord: Chord, beats: f32) {
+		if let Some(last) = self.chords.last_mut()
+			&& last.chord == chord
+		{
+			last.beats += beats;
+			return;
+		}
+		self.chords.push(SongChord::new(chord, beats));
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Song {
+	pub title: Option<String>,
+	/// Beats per bar (from the time signature), used when rendering charts
+	pub beats_per_bar: u8,
+	pub sections: Vec<SongSection>,
+}
+
+impl Song {
+	pub fn new(title: Option<String>) -> Self {
+		Song {
+			title,
+			beats_per_bar: 4,
+			sections: Vec::new(),
+		}
+	}
+
+	/// All chords in playing order
+	pub fn chords(&self) -> impl Iterator<Item = &SongChord> {
+		self.sections.iter().flat_map(|s| s.chords.iter())
+	}
+
+	/// Chord names in playing order, ready for `generate_progression`
+	pub fn chord_names(&self) -> Vec<String> {
+		self.chords().map(|c| c.chord.to_string()).collect()
+	}
+
+	/// Total length in beats
+	pub fn total_beats(&self) -> f32 {
+		self.chords().map(|c| c.beats).sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_section_push_merges_repeats() {
+		let mut section = SongSection::new(Some("Verse".to_string()));
+		section.push(Chord::parse("C").unwrap(), 4.0);
+		section.push(Chord::parse("C").unwrap(), 4.0);
+		section.push(Chord::parse("G").unwrap(), 2.0);
+
+		assert_eq!(section.chords.len(), 2);
+		assert_eq!(section.chords[0].beats, 8.0);
+	}
+
+	#[test]
+	fn test_song_chord_names() {
+		let mut song = Song::new(Some("Test".to_string()));
+		let mut verse = SongSection::new(Some("Verse".to_string()));
+		verse.push(Chord::parse("Am").unwrap(), 4.0);
+		verse.push(Chord::parse("F").unwrap(), 4.0);
+		let mut chorus = SongSection::new(Some("Chorus".to_string()));
+		chorus.push(Chord::parse("C").unwrap(), 4.0);
+		song.sections = vec![verse, chorus];
+
+		assert_eq!(song.chord_names(), vec!["Am", "F", "C"]);
+		assert_eq!(song.total_beats(), 12.0);
+	}
+}
@@ -0,0 +1,234 @@
+//! Enharmonic spelling
+//!
+//! Pitch classes are stored without a spelling (G# and Ab are the same
+//! [`PitchClass`]), so output needs an accidental context to print black-key
+//! notes the way a musician expects: flats in F, Bb or Eb major, sharps in G,
+//! D or A. A parsed chord remembers how its name was written, so Abm7 comes
+//! back out as Abm7 rather than G#m7.
+//!
+//! Where the letter matters too, a [`NoteName`] keeps it alongside the
+//! accidental, so spellings like Cb, E# or Fx (F double sharp) survive.
+
+use std::fmt;
+
+use crate::error::{ChordCraftError, Result};
+use crate::key::Key;
+use crate::note::PitchClass;
+
+/// Accidental used to spell black-key notes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Accidentals {
+	#[default]
+	Sharps,
+	Flats,
+}
+
+impl Accidentals {
+	/// Accidentals of a key's signature
+	pub fn for_key(key: &Key) -> Self {
+		if key.prefers_flats() {
+			Accidentals::Flats
+		} else {
+			Accidentals::Sharps
+		}
+	}
+
+	/// Accidental a written note name uses, if any ("Ab" → flats, "C" → None)
+	pub fn of_name(name: &str) -> Option<Self> {
+		match name.trim().chars().nth(1) {
+			Some('b' | '♭') => Some(Accidentals::Flats),
+			Some('#' | '♯') => Some(Accidentals::Sharps),
+			_ => None,
+		}
+	}
+
+	/// Name of a pitch class with these accidentals
+	pub fn spell(self, pitch: PitchClass) -> &'static str {
+		match self {
+			Accidentals::Sharps => pitch.sharp_name(),
+			Accidentals::Flats => pitch.flat_name(),
+		}
+	}
+}
+
+/// Natural note letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Letter {
+	C,
+	D,
+	E,
+	F,
+	G,
+	A,
+	B,
+}
+
+impl Letter {
+	const ALL: [Letter; 7] = [
+		Letter::C,
+		Letter::D,
+		Letter::E,
+		Letter::F,
+		Letter::G,
+		Letter::A,
+		Letter::B,
+	];
+
+	pub fn parse(c: char) -> Option<Self> {
+		let index = "CDEFGAB".find(c.to_ascii_uppercase())?;
+		Some(Letter::ALL[index])
+	}
+
+	/// Pitch class of the letter without an accidental
+	pub fn natural(self) -> PitchClass {
+		const NATURALS: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+		PitchClass::from_semitone(NATURALS[self as usize])
+	}
+
+	/// Letter the given number of steps up (negative: down), wrapping from B to C
+	pub fn shift(self, steps: i32) -> Self {
+		Letter::ALL[(self as i32 + steps).rem_euclid(7) as usize]
+	}
+}
+
+/// A written note name: a letter plus an accidental
+///
+/// Unlike [`PitchClass`], B# and C are different names (for the same pitch).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoteName {
+	pub letter: Letter,
+	/// Semitones above the natural letter, from -2 (double flat) to 2 (double sharp)
+	pub accidental: i8,
+}
+
+impl NoteName {
+	pub fn new(letter: Letter, accidental: i8) -> Self {
+		NoteName { letter, accidental }
+	}
+
+	/// Parse a note name: "C", "Cb", "E#", "Bbb", "F##", "Fx", "G♭"
+	pub fn parse(s: &str) -> Result<Self> {
+		let s = s.trim();
+		let invalid = || ChordCraftError::InvalidNote(s.to_string());
+		let mut chars = s.chars();
+		let letter = chars.next().and_then(Letter::parse).ok_or_else(invalid)?;
+		let mut accidental = 0i8;
+		for c in chars {
+			accidental += match c {
+				'b' | '♭' => -1,
+				'#' | '♯' => 1,
+				'x' | '𝄪' => 2,
+				'𝄫' => -2,
+				_ => return Err(invalid()),
+			};
+		}
+		if accidental.abs() > 2 {
+			return Err(invalid());
+		}
+		Ok(NoteName::new(letter, accidental))
+	}
+
+	/// Name of a pitch class on the given letter, if it is at most a double accidental away
+	pub fn on_letter(pitch: PitchClass, letter: Letter) -> Option<Self> {
+		let up = letter.natural().semitone_distance_to(&pitch) as i8;
+		let accidental = if up > 6 { up - 12 } else { up };
+		(accidental.abs() <= 2).then_some(NoteName::new(letter, accidental))
+	}
+
+	/// Name of a pitch class with the given accidentals (never a double or E#-style spelling)
+	pub fn spell(pitch: PitchClass, accidentals: Accidentals) -> Self {
+		NoteName::parse(accidentals.spell(pitch)).expect("spelled pitch classes parse")
+	}
+
+	pub fn pitch_class(&self) -> PitchClass {
+		self.letter.natural().add_semitones(self.accidental as i32)
+	}
+}
+
+impl From<NoteName> for PitchClass {
+	fn from(name: NoteName) -> Self {
+		name.pitch_class()
+	}
+}
+
+impl From<PitchClass> for NoteName {
+	/// Spelled with sharps, like a pitch class prints
+	fn from(pitch: PitchClass) -> Self {
+		NoteName::spell(pitch, Accidentals::Sharps)
+	}
+}
+
+impl fmt::Display for NoteName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let accidental = match self.accidental {
+			-2 => "bb",
+			-1 => "b",
+			1 => "#",
+			2 => "x",
+			_ => "",
+		};
+		write!(f, "{:?}{accidental}", self.letter)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::key::Mode;
+
+	#[test]
+	fn test_note_names_keep_their_letter() {
+		let c_flat = NoteName::parse("Cb").unwrap();
+		assert_eq!(c_flat.letter, Letter::C);
+		assert_eq!(c_flat.pitch_class(), PitchClass::B);
+		assert_ne!(c_flat, NoteName::from(PitchClass::B));
+		assert_eq!(c_flat.to_string(), "Cb");
+
+		assert_eq!(NoteName::parse("F##").unwrap().to_string(), "Fx");
+		assert_eq!(
+			PitchClass::from(NoteName::parse("Fx").unwrap()),
+			PitchClass::G
+		);
+		assert_eq!(NoteName::parse("Bbb").unwrap().pitch_class(), PitchClass::A);
+		assert_eq!(NoteName::parse("E♯").unwrap().pitch_class(), PitchClass::F);
+		assert!(NoteName::parse("Cbbb").is_err());
+		assert!(NoteName::parse("H").is_err());
+
+		assert_eq!(
+			NoteName::on_letter(PitchClass::F, Letter::E)
+				.unwrap()
+				.to_string(),
+			"E#"
+		);
+		assert_eq!(
+			NoteName::on_letter(PitchClass::A, Letter::B)
+				.unwrap()
+				.to_string(),
+			"Bbb"
+		);
+		assert_eq!(NoteName::on_letter(PitchClass::G, Letter::C), None);
+		assert_eq!(Letter::B.shift(1), Letter::C);
+		assert_eq!(Letter::C.shift(-1), Letter::B);
+	}
+
+	#[test]
+	fn test_accidentals_from_names_and_keys() {
+		assert_eq!(Accidentals::of_name("Ab"), Some(Accidentals::Flats));
+		assert_eq!(Accidentals::of_name("B♭"), Some(Accidentals::Flats));
+		assert_eq!(Accidentals::of_name("F#"), Some(Accidentals::Sharps));
+		assert_eq!(Accidentals::of_name("B"), None);
+
+		let eb = Key::new(PitchClass::DSharp, Mode::Major);
+		let e = Key::new(PitchClass::E, Mode::Major);
+		let cm = Key::new(PitchClass::C, Mode::Minor);
+		assert_eq!(Accidentals::for_key(&eb), Accidentals::Flats);
+		assert_eq!(Accidentals::for_key(&e), Accidentals::Sharps);
+		assert_eq!(Accidentals::for_key(&cm), Accidentals::Flats);
+
+		assert_eq!(Accidentals::Flats.spell(PitchClass::GSharp), "Ab");
+		assert_eq!(Accidentals::Sharps.spell(PitchClass::GSharp), "G#");
+	}
+}
@@ -0,0 +1,464 @@
+//! Key and transposition suggestions
+//!
+//! Helpers for answering "what key should I play this in?": transposing a
+//! progression so a melody sits in a singer's comfortable range, finding the
+//! key it is easiest to play in, and choosing a capo position so the
+//! transposed chords stay easy to finger.
+
+use crate::chord::Chord;
+use crate::generator::{GeneratorOptions, generate_fingerings};
+use crate::instrument::{CapoedInstrument, Instrument};
+use crate::key::{Key, detect_key};
+use crate::note::Note;
+use crate::progression::{ProgressionOptions, ProgressionSequence, generate_progression};
+
+/// A singer's comfortable range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VocalRange {
+	pub low: Note,
+	pub high: Note,
+}
+
+impl VocalRange {
+	pub fn new(low: Note, high: Note) -> Self {
+		VocalRange { low, high }
+	}
+
+	/// Semitones by which a melody spanning `low..=high` falls outside this range
+	pub fn overshoot(&self, low: Note, high: Note) -> u8 {
+		let below = (self.low.to_midi() as i32 - low.to_midi() as i32).max(0);
+		let above = (high.to_midi() as i32 - self.high.to_midi() as i32).max(0);
+		(below + above) as u8
+	}
+
+	fn center(&self) -> i32 {
+		(self.low.to_midi() as i32 + self.high.to_midi() as i32) / 2
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct KeySuggestionOptions {
+	/// Highest capo position to consider
+	pub max_capo: u8,
+	/// Largest transposition considered in either direction
+	pub max_shift: u8,
+	/// Maximum number of suggestions to return
+	pub limit: usize,
+	/// Options for optimizing the transposed progression
+	pub progression_options: ProgressionOptions,
+}
+
+impl Default for KeySuggestionOptions {
+	fn default() -> Self {
+		KeySuggestionOptions {
+			max_capo: 5,
+			max_shift: 12,
+			limit: 3,
+			progression_options: ProgressionOptions {
+				limit: 1,
+				..Default::default()
+			},
+		}
+	}
+}
+
+/// A transposition that fits the singer, with a way to play it
+#[derive(Debug, Clone)]
+pub struct KeySuggestion {
+	/// Semitones the song is moved (positive = up)
+	pub semitones: i32,
+	/// Sounding chords after transposition
+	pub chords: Vec<String>,
+	/// Capo fret (0 = no capo)
+	pub capo: u8,
+	/// Chord shapes to finger relative to the capo
+	pub shapes: Vec<String>,
+	/// Melody range after transposition
+	pub melody_low: Note,
+	pub melody_high: Note,
+	/// Semitones the melody still falls outside the vocal range (0 = fits)
+	pub overshoot: u8,
+	/// Sum of the best fingering score of each shape (higher = easier shapes)
+	pub shape_score: u32,
+	/// Best fingering sequence on the capoed instrument (frets relative to the capo)
+	pub progression: ProgressionSequence,
+}
+
+/// Suggest keys that put a melody inside a singer's comfortable range
+///
+/// Every transposition up to `max_shift` semitones is rated by how far the
+/// melody (`melody_low..=melody_high`) falls outside `vocal`; the best-fitting
+/// transpositions are then tried with each capo position up to `max_capo`, and
+/// the capo whose shapes have the best fingerings is kept. Suggestions are
+/// ordered by vocal fit, then by shape score.
+pub fn suggest_keys_for_range<I: Instrument + Clone>(
+	chord_names: &[&str],
+	melody_low: Note,
+	melody_high: Note,
+	vocal: &VocalRange,
+	instrument: &I,
+	options: &KeySuggestionOptions,
+) -> Vec<KeySuggestion> {
+	let chords: Vec<Chord> = chord_names
+		.iter()
+		.filter_map(|name| Chord::parse(name).ok())
+		.collect();
+	if chords.is_empty() {
+		return vec![];
+	}
+
+	let max_shift = options.max_shift as i32;
+	let melody_center = (melody_low.to_midi() as i32 + melody_high.to_midi() as i32) / 2;
+
+	// Rank transpositions by fit, then by how centered the melody sits
+	let mut shifts: Vec<(i32, u8)> = (-max_shift..=max_shift)
+		.map(|shift| {
+			let overshoot = vocal.overshoot(
+				melody_low.add_semitones(shift),
+				melody_high.add_semitones(shift),
+			);
+			(shift, overshoot)
+		})
+		.collect();
+	shifts.sort_by_key(|&(shift, overshoot)| {
+		(overshoot, (melody_center + shift - vocal.center()).abs())
+	});
+	let best_overshoot = shifts[0].1;
+	shifts.retain(|&(_, overshoot)| overshoot == best_overshoot);
+	shifts.truncate(options.limit.max(1) * 2);
+
+	let mut suggestions: Vec<KeySuggestion> = shifts
+		.into_iter()
+		.filter_map(|(shift, overshoot)| {
+			let sounding: Vec<String> = chords
+				.iter()
+				.map(|c| c.transpose(shift).to_string())
+				.collect();
+
+			(0..=options.max_capo.min(instrument.max_capo_fret()))
+				.filter_map(|capo| {
+					let shapes: Vec<String> = chords
+						.iter()
+						.map(|c| c.transpose(shift - capo as i32).to_string())
+						.collect();
+					let (shape_score, progression) =
+						evaluate_capo(&sounding, instrument, capo, options)?;
+
+					Some(KeySuggestion {
+						semitones: shift,
+						chords: sounding.clone(),
+						capo,
+						shapes,
+						melody_low: melody_low.add_semitones(shift),
+						melody_high: melody_high.add_semitones(shift),
+						overshoot,
+						shape_score,
+						progression,
+					})
+				})
+				.max_by_key(|s| (s.shape_score, std::cmp::Reverse(s.capo)))
+		})
+		.collect();
+
+	suggestions.sort_by_key(|s| (s.overshoot, std::cmp::Reverse(s.shape_score)));
+	suggestions.truncate(options.limit);
+	suggestions
+}
+
+/// A transposition rated by how easy it is to play
+#[derive(Debug, Clone)]
+pub struct EasyKey {
+	/// Semitones the song is moved (positive = up)
+	pub semitones: i32,
+	/// Key of the transposed chords, if one can be detected
+	pub key: Option<Key>,
+	/// Sounding chords after transposition
+	pub chords: Vec<String>,
+	/// Capo fret (0 = no capo)
+	pub capo: u8,
+	/// Chord shapes to finger relative to the capo
+	pub shapes: Vec<String>,
+	/// Sum of the best fingering score of each shape (higher = easier shapes)
+	pub shape_score: u32,
+	/// Best fingering sequence of the shapes (frets relative to the capo)
+	pub progression: ProgressionSequence,
+}
+
+impl EasyKey {
+	/// Overall ease: the shape score plus the sequence's transition score
+	pub fn score(&self) -> i64 {
+		self.shape_score as i64 + self.progression.total_score as i64
+	}
+}
+
+/// Find the keys a progression is easiest to play in
+///
+/// Tries the progression in each of the 12 keys (those within `max_shift`
+/// semitones, moving by at most a fifth either way) with every capo position
+/// up to `max_capo`, keeping the easiest capo for each key. Keys are rated
+/// on both the fingerings of their shapes and the changes between them. A
+/// capo only moves the shapes, so each set of shapes is rated once, on the
+/// open instrument, and only the best key playing it is kept. Keys come
+/// easiest first, at most `limit` of them.
+///
+/// # Examples
+///
+/// ```
+/// use chordcraft_core::instrument::Guitar;
+/// use chordcraft_core::transposition::{KeySuggestionOptions, easiest_keys};
+///
+/// let guitar = Guitar::default();
+/// let options = KeySuggestionOptions { max_capo: 0, ..Default::default() };
+///
+/// let keys = easiest_keys(&["Ab", "Fm", "Db", "Eb"], &guitar, &options);
+/// assert_eq!(keys.len(), 3);
+/// assert!(keys[0].score() >= keys[1].score());
+/// ```
+pub fn easiest_keys<I: Instrument>(
+	chord_names: &[&str],
+	instrument: &I,
+	options: &KeySuggestionOptions,
+) -> Vec<EasyKey> {
+	let chords: Vec<Chord> = chord_names
+		.iter()
+		.filter_map(|name| Chord::parse(name).ok())
+		.collect();
+	if chords.is_empty() {
+		return vec![];
+	}
+
+	// Shapes moved by each number of semitones (0-11), rated once
+	let shapes: Vec<Vec<String>> = (0..12)
+		.map(|offset| {
+			chords
+				.iter()
+				.map(|c| c.transpose(offset).to_string())
+				.collect()
+		})
+		.collect();
+	let ratings: Vec<Option<(u32, ProgressionSequence)>> = shapes
+		.iter()
+		.map(|shapes| evaluate_on(shapes, instrument, options))
+		.collect();
+
+	let max_shift = options.max_shift as i32;
+	let max_capo = options.max_capo.min(instrument.max_capo_fret());
+	let mut keys: Vec<EasyKey> = (-5..=6)
+		.filter(|shift: &i32| shift.abs() <= max_shift)
+		.filter_map(|shift| {
+			let (capo, offset, (shape_score, progression)) = (0..=max_capo)
+				.filter_map(|capo| {
+					let offset = (shift - capo as i32).rem_euclid(12) as usize;
+					Some((capo, offset, ratings[offset].as_ref()?))
+				})
+				.max_by_key(|(capo, _, (score, progression))| {
+					(
+						*score as i64 + progression.total_score as i64,
+						std::cmp::Reverse(*capo),
+					)
+				})?;
+
+			let transposed: Vec<Chord> = chords.iter().map(|c| c.transpose(shift)).collect();
+			Some(EasyKey {
+				semitones: shift,
+				key: detect_key(&transposed).map(|estimate| estimate.key),
+				chords: transposed.iter().map(|c| c.to_string()).collect(),
+				capo,
+				shapes: shapes[offset].clone(),
+				shape_score: *shape_score,
+				progression: progression.clone(),
+			})
+		})
+		.collect();
+
+	keys.sort_by_key(|k| (std::cmp::Reverse(k.score()), k.capo, k.semitones.abs()));
+	let mut seen: Vec<Vec<String>> = Vec::new();
+	keys.retain(|k| {
+		let new = !seen.contains(&k.shapes);
+		seen.push(k.shapes.clone());
+		new
+	});
+	keys.truncate(options.limit);
+	keys
+}
+
+/// Shape score and best progression for the sounding chords at a capo position
+///
+/// The capoed instrument's tuning is already transposed, so the sounding chords
+/// are generated directly and frets come out relative to the capo.
+fn evaluate_capo<I: Instrument + Clone>(
+	chords: &[String],
+	instrument: &I,
+	capo: u8,
+	options: &KeySuggestionOptions,
+) -> Option<(u32, ProgressionSequence)> {
+	if capo > 0 {
+		let capoed = CapoedInstrument::new(instrument.clone(), capo).ok()?;
+		evaluate_on(chords, &capoed, options)
+	} else {
+		evaluate_on(chords, instrument, options)
+	}
+}
+
+fn evaluate_on<I: Instrument>(
+	chords: &[String],
+	instrument: &I,
+	options: &KeySuggestionOptions,
+) -> Option<(u32, ProgressionSequence)> {
+	let generator_options = GeneratorOptions {
+		limit: 1,
+		..options.progression_options.generator_options.clone()
+	};
+
+	let mut shape_score = 0;
+	for name in chords {
+		let chord = Chord::parse(name).ok()?;
+		let best = generate_fingerings(&chord, instrument, &generator_options);
+		shape_score += best.first()?.score as u32;
+	}
+
+	let names: Vec<&str> = chords.iter().map(String::as_str).collect();
+	let progression = generate_progression(&names, instrument, &options.progression_options)
+		.into_iter()
+		.next()?;
+	Some((shape_score, progression))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instrument::Guitar;
+
+	fn note(s: &str) -> Note {
+		Note::parse(s).unwrap()
+	}
+
+	#[test]
+	fn test_vocal_range_overshoot() {
+		let range = VocalRange::new(note("A2"), note("E4"));
+		assert_eq!(range.overshoot(note("C3"), note("C4")), 0);
+		assert_eq!(range.overshoot(note("G2"), note("G4")), 5);
+	}
+
+	#[test]
+	fn test_suggest_keys_moves_melody_into_range() {
+		let guitar = Guitar::default();
+		// Melody C4-A4 is too high for a baritone singing A2-E4
+		let vocal = VocalRange::new(note("A2"), note("E4"));
+		let suggestions = suggest_keys_for_range(
+			&["C", "Am", "F", "G"],
+			note("C4"),
+			note("A4"),
+			&vocal,
+			&guitar,
+			&KeySuggestionOptions::default(),
+		);
+
+		assert!(!suggestions.is_empty());
+		for s in &suggestions {
+			println!(
+				"{:+} semitones: {:?} (capo {} shapes {:?})",
+				s.semitones, s.chords, s.capo, s.shapes
+			);
+			assert_eq!(s.overshoot, 0);
+			assert!(s.semitones < 0);
+			assert_eq!(s.progression.fingerings.len(), 4);
+
+			// Shapes played with the capo sound the transposed chords
+			for (shape, sounding) in s.shapes.iter().zip(&s.chords) {
+				let shape = Chord::parse(shape).unwrap().transpose(s.capo as i32);
+				assert_eq!(&shape.to_string(), sounding);
+			}
+		}
+	}
+
+	#[test]
+	fn test_suggest_keys_within_max_shift() {
+		let guitar = Guitar::default();
+		let vocal = VocalRange::new(note("C3"), note("G4"));
+		let suggestions = suggest_keys_for_range(
+			&["G", "C", "D"],
+			note("D3"),
+			note("F4"),
+			&vocal,
+			&guitar,
+			&KeySuggestionOptions {
+				max_shift: 2,
+				..Default::default()
+			},
+		);
+
+		// Every nearby key fits, so the easiest shapes decide
+		assert_eq!(suggestions.len(), 3);
+		for s in &suggestions {
+			assert_eq!(s.overshoot, 0);
+			assert!(s.semitones.abs() <= 2);
+		}
+		assert!(
+			suggestions
+				.windows(2)
+				.all(|w| w[0].shape_score >= w[1].shape_score)
+		);
+	}
+
+	#[test]
+	fn test_easiest_keys() {
+		let guitar = Guitar::default();
+		let chords = ["Ab", "Fm", "Db", "Eb"];
+
+		// Without a capo, moving to open-position keys beats the barre chords of Ab
+		let options = KeySuggestionOptions {
+			max_capo: 0,
+			limit: 12,
+			..Default::default()
+		};
+		let keys = easiest_keys(&chords, &guitar, &options);
+		assert_eq!(keys.len(), 12);
+		assert!(keys.windows(2).all(|w| w[0].score() >= w[1].score()));
+		let original = keys.iter().find(|k| k.semitones == 0).unwrap();
+		assert_ne!(keys[0].semitones, 0);
+		assert!(keys[0].score() > original.score());
+		assert_eq!(original.key, Key::parse("Ab").ok());
+		assert!(keys.iter().all(|k| k.capo == 0 && k.shapes == k.chords));
+		assert_eq!(keys[0].progression.chords, keys[0].shapes);
+
+		// A capo keeps the original key playable with easy shapes
+		let options = KeySuggestionOptions {
+			max_capo: 4,
+			max_shift: 0,
+			..Default::default()
+		};
+		let keys = easiest_keys(&chords, &guitar, &options);
+		assert_eq!(keys.len(), 1);
+		assert_eq!(keys[0].chords, chords);
+		assert!(keys[0].capo > 0);
+		assert!(keys[0].score() > original.score());
+
+		// Keys playing the same shapes with another capo are listed once
+		let options = KeySuggestionOptions {
+			limit: 12,
+			..Default::default()
+		};
+		let keys = easiest_keys(&chords, &guitar, &options);
+		for (i, key) in keys.iter().enumerate() {
+			assert!(keys[i + 1..].iter().all(|other| other.shapes != key.shapes));
+		}
+
+		assert!(easiest_keys(&[], &guitar, &options).is_empty());
+	}
+
+	#[test]
+	fn test_suggest_keys_empty() {
+		let guitar = Guitar::default();
+		let vocal = VocalRange::new(note("C3"), note("G4"));
+		let suggestions = suggest_keys_for_range(
+			&[],
+			note("C4"),
+			note("G4"),
+			&vocal,
+			&guitar,
+			&KeySuggestionOptions::default(),
+		);
+		assert!(suggestions.is_empty());
+	}
+}
@@ -0,0 +1,558 @@
+//! Tuning utilities
+//!
+//! Helpers for reasoning about instrument tunings, such as parsing tuning
+//! strings ("DADGAD", "D2 A2 D3 G3 A3 D4"), inferring an unstated alternate
+//! tuning (and capo) from tabs whose intended chords are known, or listing the
+//! easy chords a tuning offers.
+
+use crate::analyzer::analyze_fingering;
+use crate::chord::Chord;
+use crate::error::{ChordCraftError, Result};
+use crate::fingering::{Fingering, StringState};
+use crate::instrument::{CapoedInstrument, ConfigurableInstrument, Instrument};
+use crate::note::{Note, PitchClass};
+
+/// Octave of the lowest string when a tuning string doesn't give one (guitar range)
+const DEFAULT_LOWEST_OCTAVE: i8 = 2;
+
+/// Points awarded when the intended chord is the analyzer's top match.
+const TOP_MATCH_POINTS: u32 = 100;
+
+/// Points lost for every analyzer match ranked above the intended chord.
+const RANK_PENALTY: u32 = 25;
+
+/// A tuning/capo combination that explains a set of observed tabs.
+#[derive(Debug, Clone)]
+pub struct TuningCandidate {
+	/// Name of the tuning (e.g., "Guitar (Open G)")
+	pub name: String,
+	/// Open string notes, without the capo applied
+	pub tuning: Vec<Note>,
+	/// Capo fret the tabs are assumed to be relative to (0 = no capo)
+	pub capo: u8,
+	/// Number of observations where the intended chord was the top analysis result
+	pub matched: usize,
+	/// Number of observations the candidate was evaluated against
+	pub total: usize,
+	/// Aggregate consistency score (higher is better)
+	pub score: u32,
+}
+
+impl TuningCandidate {
+	/// Fraction of observations explained by this candidate (0.0 - 1.0)
+	pub fn confidence(&self) -> f32 {
+		if self.total == 0 {
+			return 0.0;
+		}
+		self.matched as f32 / self.total as f32
+	}
+}
+
+/// Options for tuning inference
+#[derive(Debug, Clone)]
+pub struct TuningInferenceOptions {
+	/// Tunings to consider (only those matching the tabs' string count are evaluated)
+	pub tunings: Vec<ConfigurableInstrument>,
+	/// Highest capo position to consider
+	pub max_capo: u8,
+	/// Maximum number of candidates to return
+	pub limit: usize,
+}
+
+impl Default for TuningInferenceOptions {
+	fn default() -> Self {
+		TuningInferenceOptions {
+			tunings: common_guitar_tunings(),
+			max_capo: 7,
+			limit: 5,
+		}
+	}
+}
+
+/// Common 6-string guitar tunings, standard tuning first.
+pub fn common_guitar_tunings() -> Vec<ConfigurableInstrument> {
+	use crate::note::PitchClass::*;
+
+	let guitar = |name: &str, tuning: [(crate::note::PitchClass, i8); 6]| {
+		ConfigurableInstrument::builder()
+			.name(name)
+			.tuning(tuning.iter().map(|&(pc, oct)| Note::new(pc, oct)).collect())
+			.fret_range(0, 24)
+			.max_stretch(4)
+			.build()
+			.expect("guitar tuning presets are valid")
+	};
+
+	vec![
+		guitar(
+			"Guitar (Standard)",
+			[(E, 2), (A, 2), (D, 3), (G, 3), (B, 3), (E, 4)],
+		),
+		ConfigurableInstrument::guitar_drop_d(),
+		ConfigurableInstrument::guitar_open_g(),
+		ConfigurableInstrument::guitar_dadgad(),
+		guitar(
+			"Guitar (Open D)",
+			[(D, 2), (A, 2), (D, 3), (FSharp, 3), (A, 3), (D, 4)],
+		),
+		guitar(
+			"Guitar (Open E)",
+			[(E, 2), (B, 2), (E, 3), (GSharp, 3), (B, 3), (E, 4)],
+		),
+		guitar(
+			"Guitar (Half Step Down)",
+			[
+				(DSharp, 2),
+				(GSharp, 2),
+				(CSharp, 3),
+				(FSharp, 3),
+				(ASharp, 3),
+				(DSharp, 4),
+			],
+		),
+		guitar(
+			"Guitar (Whole Step Down)",
+			[(D, 2), (G, 2), (C, 3), (F, 3), (A, 3), (D, 4)],
+		),
+		guitar(
+			"Guitar (Drop C)",
+			[(C, 2), (G, 2), (C, 3), (F, 3), (A, 3), (D, 4)],
+		),
+	]
+}
+
+/// Infer the most likely tuning/capo combination from (tab, intended chord) pairs.
+///
+/// Each candidate tuning is tried with every capo position up to `max_capo`
+/// (tabs are read relative to the capo, as they are usually written). A
+/// candidate earns points for each observation where the analyzer identifies
+/// the intended chord, with more points the higher that chord ranks.
+///
+/// Candidates are ranked by explained observations, then score, preferring
+/// lower capo positions and earlier tunings in the list on ties (so standard
+/// tuning wins over equivalent detuned + capo combinations).
+pub fn infer_tuning(
+	observations: &[(Fingering, Chord)],
+	options: &TuningInferenceOptions,
+) -> Vec<TuningCandidate> {
+	let Some(string_count) = observations.first().map(|(f, _)| f.string_count()) else {
+		return vec![];
+	};
+
+	let mut candidates = Vec::new();
+
+	for tuning in &options.tunings {
+		if tuning.string_count() != string_count
+			|| observations
+				.iter()
+				.any(|(f, _)| f.string_count() != string_count)
+		{
+			continue;
+		}
+
+		let max_capo = options.max_capo.min(tuning.max_capo_fret());
+		for capo in 0..=max_capo {
+			let Ok(capoed) = CapoedInstrument::new(tuning.clone(), capo) else {
+				continue;
+			};
+
+			let mut matched = 0;
+			let mut score = 0;
+			for (fingering, chord) in observations {
+				if let Some(points) = observation_points(fingering, chord, &capoed) {
+					score += points;
+					if points == TOP_MATCH_POINTS {
+						matched += 1;
+					}
+				}
+			}
+
+			if score > 0 {
+				candidates.push(TuningCandidate {
+					name: tuning.name().to_string(),
+					tuning: tuning.tuning().to_vec(),
+					capo,
+					matched,
+					total: observations.len(),
+					score,
+				});
+			}
+		}
+	}
+
+	// Stable sort keeps tuning-list order and ascending capo on ties
+	candidates.sort_by(|a, b| b.matched.cmp(&a.matched).then(b.score.cmp(&a.score)));
+	candidates.truncate(options.limit);
+	candidates
+}
+
+/// Points for one observation, or None if the intended chord isn't recognized at all.
+fn observation_points<I: Instrument>(
+	fingering: &Fingering,
+	chord: &Chord,
+	instrument: &I,
+) -> Option<u32> {
+	let rank = analyze_fingering(fingering, instrument)
+		.iter()
+		.position(|m| m.chord.root == chord.root && m.chord.quality == chord.quality)?;
+
+	Some(TOP_MATCH_POINTS.saturating_sub(RANK_PENALTY * rank as u32)).filter(|&p| p > 0)
+}
+
+/// How a chord found by [`explore_tuning`] is played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EasyGrip {
+	/// All strings open
+	Open,
+	/// One finger on one string, the rest open
+	OneFinger,
+	/// One finger barring every string at the same fret
+	Barre,
+}
+
+/// An easy chord available in a tuning
+#[derive(Debug, Clone)]
+pub struct ExploredChord {
+	pub chord: Chord,
+	pub fingering: Fingering,
+	pub grip: EasyGrip,
+	/// Fret of the finger or barre (0 for open strings)
+	pub fret: u8,
+}
+
+/// Options for [`explore_tuning`]
+#[derive(Debug, Clone)]
+pub struct ExplorerOptions {
+	/// Highest fret to place the finger or barre on
+	pub max_fret: u8,
+	/// Include one-finger barres across all strings
+	pub include_barres: bool,
+}
+
+impl Default for ExplorerOptions {
+	fn default() -> Self {
+		ExplorerOptions {
+			max_fret: 12,
+			include_barres: true,
+		}
+	}
+}
+
+/// List the chords a tuning offers with no fingers, one finger, or a one-finger barre
+///
+/// Every open strum, single-finger grip and straight barre up to `max_fret` is
+/// analyzed; grips whose best match is a complete chord are kept. Each chord
+/// is listed once with its simplest grip, sorted by simplicity (open strings
+/// first, then one finger, then barres; lower frets first).
+pub fn explore_tuning<I: Instrument>(
+	instrument: &I,
+	options: &ExplorerOptions,
+) -> Vec<ExploredChord> {
+	let string_count = instrument.string_count();
+	let max_fret = options.max_fret.min(instrument.fret_range().1);
+
+	let mut grips = vec![(
+		EasyGrip::Open,
+		0,
+		vec![StringState::Fretted(0); string_count],
+	)];
+	for fret in 1..=max_fret {
+		for string in 0..string_count {
+			let mut strings = vec![StringState::Fretted(0); string_count];
+			strings[string] = StringState::Fretted(fret);
+			grips.push((EasyGrip::OneFinger, fret, strings));
+		}
+		if options.include_barres {
+			grips.push((
+				EasyGrip::Barre,
+				fret,
+				vec![StringState::Fretted(fret); string_count],
+			));
+		}
+	}
+	grips.sort_by_key(|(grip, fret, _)| (*grip, *fret));
+
+	let mut explored: Vec<ExploredChord> = Vec::new();
+	for (grip, fret, strings) in grips {
+		let fingering = Fingering::new(strings);
+		let Some(best) = analyze_fingering(&fingering, instrument).into_iter().next() else {
+			continue;
+		};
+		// Listed by harmony: open tunings often have the fifth on the lowest
+		// string, and a strum in open G is still a G chord
+		let chord = Chord {
+			bass: None,
+			..best.chord
+		};
+		if best.completeness < 1.0 || explored.iter().any(|e| e.chord == chord) {
+			continue;
+		}
+
+		explored.push(ExploredChord {
+			chord,
+			fingering,
+			grip,
+			fret,
+		});
+	}
+
+	explored
+}
+
+/// Tuning strings, lowest string first
+pub struct Tuning;
+
+impl Tuning {
+	/// Parse a tuning into the open notes of its strings, lowest first
+	///
+	/// Notes may run together ("DADGAD", "EbAbDbGbBbEb") or be separated by
+	/// spaces, commas or dashes ("E-A-D-G-B-E"); a lowercase `b` right after a
+	/// letter is a flat. Run together after a lowercase letter and before
+	/// another note, as in "eadgbe", it could also be the B string, so that is
+	/// an error. Octaves are optional: a note without one is the
+	/// nearest above the string before it, and the lowest string defaults to
+	/// octave 2. Give octaves for tunings that drop back down, like the
+	/// reentrant ukulele's G4 C4 E4 A4.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use chordcraft_core::note::Note;
+	/// use chordcraft_core::tuning::Tuning;
+	///
+	/// let dadgad = Tuning::parse("DADGAD").unwrap();
+	/// assert_eq!(dadgad, Tuning::parse("D2 A2 D3 G3 A3 D4").unwrap());
+	/// assert_eq!(dadgad[3], Note::parse("G3").unwrap());
+	/// assert!(Tuning::parse("DAHGAD").is_err());
+	/// ```
+	pub fn parse(s: &str) -> Result<Vec<Note>> {
+		let invalid = || ChordCraftError::InvalidTuning(s.to_string());
+		let chars: Vec<char> = s.chars().collect();
+		let mut notes: Vec<Note> = Vec::new();
+		let mut i = 0;
+
+		while i < chars.len() {
+			let c = chars[i];
+			if c.is_whitespace() || c == ',' || c == '-' {
+				i += 1;
+				continue;
+			}
+			if !matches!(c.to_ascii_uppercase(), 'A'..='G') {
+				return Err(invalid());
+			}
+			let mut name = c.to_string();
+			i += 1;
+			if let Some(&accidental) = chars.get(i)
+				&& matches!(accidental, '#' | 'b' | '♯' | '♭')
+			{
+				// In "eadgbe" the b could be a flat or the B string
+				let next_is_note = chars
+					.get(i + 1)
+					.is_some_and(|n| matches!(n.to_ascii_uppercase(), 'A'..='G'));
+				if accidental == 'b' && c.is_ascii_lowercase() && next_is_note {
+					return Err(ChordCraftError::InvalidTuning(format!(
+						"{s} (is the 'b' after '{c}' a flat or a B? Write B or separate the notes)"
+					)));
+				}
+				name.push(accidental);
+				i += 1;
+			}
+			let pitch = PitchClass::parse(&name).map_err(|_| invalid())?;
+
+			// An octave is digits, with a minus sign only right before them
+			let start = i;
+			if chars.get(i) == Some(&'-') && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()) {
+				i += 1;
+			}
+			while chars.get(i).is_some_and(|d| d.is_ascii_digit()) {
+				i += 1;
+			}
+			let octave = if i > start {
+				let digits: String = chars[start..i].iter().collect();
+				digits.parse::<i8>().map_err(|_| invalid())?
+			} else {
+				match notes.last() {
+					Some(below) => next_octave_above(*below, pitch),
+					None => DEFAULT_LOWEST_OCTAVE,
+				}
+			};
+			notes.push(Note::new(pitch, octave));
+		}
+
+		if notes.is_empty() {
+			return Err(invalid());
+		}
+		Ok(notes)
+	}
+}
+
+/// Octave that puts `pitch` closest above `below` (a unison goes up an octave)
+fn next_octave_above(below: Note, pitch: PitchClass) -> i8 {
+	let same_octave = Note::new(pitch, below.octave);
+	if same_octave.to_midi() > below.to_midi() {
+		below.octave
+	} else {
+		below.octave + 1
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn obs(tab: &str, chord: &str) -> (Fingering, Chord) {
+		(Fingering::parse(tab).unwrap(), Chord::parse(chord).unwrap())
+	}
+
+	#[test]
+	fn test_parse_tuning() {
+		let notes = |s: &str| -> Vec<String> {
+			Tuning::parse(s)
+				.unwrap()
+				.iter()
+				.map(|n| n.to_string())
+				.collect()
+		};
+		let standard = ["E2", "A2", "D3", "G3", "B3", "E4"];
+		assert_eq!(notes("EADGBE"), standard);
+		assert_eq!(notes("E-A-D-G-B-E"), standard);
+		assert_eq!(notes("E2,A2,D3,G3,B3,E4"), standard);
+		assert_eq!(notes(" e a d g b e "), standard);
+		assert_eq!(notes("DADGAD"), ["D2", "A2", "D3", "G3", "A3", "D4"]);
+		assert_eq!(
+			notes("EbAbDbGbBbEb"),
+			["D#2", "G#2", "C#3", "F#3", "A#3", "D#4"]
+		);
+		// An octave anchors the strings after it; reentrant tunings need them all
+		assert_eq!(notes("E1 A D G"), ["E1", "A1", "D2", "G2"]);
+		assert_eq!(notes("G4 C4 E4 A4"), ["G4", "C4", "E4", "A4"]);
+		assert_eq!(notes("C-1 C"), ["C-1", "C0"]);
+
+		for bad in ["", " , ", "DAHGAD", "E2 A2 X", "E2x"] {
+			assert!(Tuning::parse(bad).is_err(), "{bad:?}");
+		}
+
+		// A lowercase b between lowercase notes is never silently a flat
+		assert!(Tuning::parse("eadgbe").is_err());
+		assert!(Tuning::parse("ebabdbgbbbeb").is_err());
+		assert_eq!(notes("eadgBe"), standard);
+		assert_eq!(notes("eb ab db gb bb eb"), notes("EbAbDbGbBbEb"));
+		assert_eq!(notes("eb3"), ["D#3"]);
+	}
+
+	#[test]
+	fn test_infer_standard_tuning() {
+		let observations = vec![
+			obs("x32010", "C"),
+			obs("320003", "G"),
+			obs("x02210", "Am"),
+			obs("xx0232", "D"),
+		];
+
+		let candidates = infer_tuning(&observations, &TuningInferenceOptions::default());
+		assert!(!candidates.is_empty());
+
+		let best = &candidates[0];
+		println!(
+			"Best: {} capo {} ({}/{})",
+			best.name, best.capo, best.matched, best.total
+		);
+		assert_eq!(best.name, "Guitar (Standard)");
+		assert_eq!(best.capo, 0);
+		assert_eq!(best.matched, 4);
+		assert_eq!(best.confidence(), 1.0);
+	}
+
+	#[test]
+	fn test_infer_capo() {
+		// Open C/G/Am shapes labelled a whole step higher -> capo 2
+		let observations = vec![obs("x32010", "D"), obs("320003", "A"), obs("x02210", "Bm")];
+
+		let candidates = infer_tuning(&observations, &TuningInferenceOptions::default());
+		let best = &candidates[0];
+		assert_eq!(best.name, "Guitar (Standard)");
+		assert_eq!(best.capo, 2);
+		assert_eq!(best.matched, 3);
+	}
+
+	#[test]
+	fn test_infer_open_g() {
+		// Open strum is G, one-finger barres at 5 and 7 are C and D in open G
+		let observations = vec![obs("000000", "G"), obs("555555", "C"), obs("777777", "D")];
+
+		let candidates = infer_tuning(&observations, &TuningInferenceOptions::default());
+		let best = &candidates[0];
+		assert_eq!(best.name, "Guitar (Open G)");
+		assert_eq!(best.capo, 0);
+		assert_eq!(best.matched, 3);
+	}
+
+	#[test]
+	fn test_infer_dadgad() {
+		// Open DADGAD is a Dsus4
+		let observations = vec![obs("000000", "Dsus4"), obs("x00200", "D")];
+
+		let candidates = infer_tuning(&observations, &TuningInferenceOptions::default());
+		assert_eq!(candidates[0].name, "Guitar (DADGAD)");
+		assert_eq!(candidates[0].capo, 0);
+	}
+
+	#[test]
+	fn test_infer_ranked_candidates() {
+		let observations = vec![obs("x32010", "C"), obs("x02210", "Am")];
+
+		let candidates = infer_tuning(&observations, &TuningInferenceOptions::default());
+		assert!(candidates.len() > 1);
+		for pair in candidates.windows(2) {
+			assert!(pair[0].matched >= pair[1].matched);
+		}
+	}
+
+	#[test]
+	fn test_infer_empty_and_mismatched() {
+		assert!(infer_tuning(&[], &TuningInferenceOptions::default()).is_empty());
+
+		// Ukulele tabs don't fit any 6-string tuning
+		let observations = vec![obs("0003", "C")];
+		assert!(infer_tuning(&observations, &TuningInferenceOptions::default()).is_empty());
+	}
+
+	#[test]
+	fn test_explore_open_g() {
+		let open_g = ConfigurableInstrument::guitar_open_g();
+		let chords = explore_tuning(&open_g, &ExplorerOptions::default());
+
+		assert_eq!(chords[0].chord.to_string(), "G");
+		assert_eq!(chords[0].grip, EasyGrip::Open);
+
+		let find = |name: &str| chords.iter().find(|c| c.chord.to_string() == name);
+		let c = find("C").unwrap();
+		assert_eq!(c.fingering.to_string(), "555555");
+		assert_eq!(c.grip, EasyGrip::Barre);
+		assert!(find("D").is_some());
+
+		// Sorted by simplicity, and each chord listed once
+		for pair in chords.windows(2) {
+			assert!((pair[0].grip, pair[0].fret) <= (pair[1].grip, pair[1].fret));
+		}
+		for (i, a) in chords.iter().enumerate() {
+			assert!(chords[i + 1..].iter().all(|b| b.chord != a.chord));
+		}
+	}
+
+	#[test]
+	fn test_explore_without_barres() {
+		let dadgad = ConfigurableInstrument::guitar_dadgad();
+		let options = ExplorerOptions {
+			include_barres: false,
+			..Default::default()
+		};
+		let chords = explore_tuning(&dadgad, &options);
+
+		assert!(!chords.is_empty());
+		assert!(chords.iter().all(|c| c.grip != EasyGrip::Barre));
+		for c in &chords {
+			println!("{:6} {}", c.chord.to_string(), c.fingering);
+		}
+	}
+}
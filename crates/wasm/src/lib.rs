@@ -4,13 +4,28 @@
 //! allowing chord-fingering conversion to run in web browsers.
 
 use chordcraft_core::{
-	Chord, ConfigurableInstrument, Fingering, Guitar, Instrument, PlayingContext, Ukulele,
-	analyzer::{ChordMatch, analyze_fingering},
+	Accidentals, BarreStrength, Chord, ConfigurableInstrument, Fingering, Guitar, HandSize,
+	Instrument, Key, PitchClass, PlayingContext, StringChange, StringPin, Ukulele,
+	analyzer::{
+		AnalyzerOptions, ChordMatch, ChromaOptions, PartialMatch, StringRole, analyze_chroma,
+		analyze_fingering_with_capo, analyze_midi_notes, analyze_notes, analyze_partial_tab,
+		near_misses, string_roles,
+	},
+	cache::FingeringCache,
+	chart::Chart,
 	chord::VoicingType,
-	generator::{GeneratorOptions, ScoredFingering, generate_fingerings},
-	progression::{ProgressionOptions, ProgressionSequence, generate_progression},
+	formula::{ChordFormula, register_quality},
+	generator::{Avoid, GeneratorOptions, ScoredFingering, SlashBass, StringSet},
+	harmony::resolve_numerals,
+	instrument::presets,
+	progression::{
+		Numerals, ProgressionOptions, ProgressionSequence, generate_progression, parse_chord_names,
+	},
+	song::{Song, SongSection},
+	transposition::{KeySuggestionOptions, easiest_keys},
 };
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 /// Initialize panic hook for better error messages in browser console
@@ -24,11 +39,18 @@ pub fn init() {
 // ============================================================================
 
 /// Instrument type for WASM API
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+///
+/// A preset name such as `"guitar"`, or `{ custom: {...} }` with an instrument
+/// definition as saved by the CLI's `export instrument`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum InstrumentType {
 	Guitar,
 	Ukulele,
+	UkuleleLowG,
+	ConcertUkulele,
+	TenorUkulele,
+	TenorUkuleleLowG,
 	BaritoneUkulele,
 	Bass,
 	#[serde(rename = "bass-5")]
@@ -37,9 +59,45 @@ pub enum InstrumentType {
 	Banjo,
 	#[serde(rename = "guitar-7")]
 	Guitar7,
+	#[serde(rename = "guitar-8")]
+	Guitar8,
+	BaritoneGuitar,
+	BaritoneGuitarA,
+	#[serde(rename = "guitar-12")]
+	Guitar12,
 	DropD,
 	OpenG,
 	Dadgad,
+	/// A user-defined instrument
+	Custom(ConfigurableInstrument),
+}
+
+impl InstrumentType {
+	/// Preset name, as serialized; a custom instrument's own name
+	fn name(&self) -> &str {
+		match self {
+			InstrumentType::Guitar => "guitar",
+			InstrumentType::Ukulele => "ukulele",
+			InstrumentType::UkuleleLowG => "ukulele-low-g",
+			InstrumentType::ConcertUkulele => "concert-ukulele",
+			InstrumentType::TenorUkulele => "tenor-ukulele",
+			InstrumentType::TenorUkuleleLowG => "tenor-ukulele-low-g",
+			InstrumentType::BaritoneUkulele => "baritone-ukulele",
+			InstrumentType::Bass => "bass",
+			InstrumentType::Bass5 => "bass-5",
+			InstrumentType::Mandolin => "mandolin",
+			InstrumentType::Banjo => "banjo",
+			InstrumentType::Guitar7 => "guitar-7",
+			InstrumentType::Guitar8 => "guitar-8",
+			InstrumentType::BaritoneGuitar => "baritone-guitar",
+			InstrumentType::BaritoneGuitarA => "baritone-guitar-a",
+			InstrumentType::Guitar12 => "guitar-12",
+			InstrumentType::DropD => "drop-d",
+			InstrumentType::OpenG => "open-g",
+			InstrumentType::Dadgad => "dadgad",
+			InstrumentType::Custom(instrument) => instrument.name(),
+		}
+	}
 }
 
 /// Wrapper for type erasure across different instrument types
@@ -56,19 +114,11 @@ impl InstrumentWrapper {
 		match inst_type {
 			InstrumentType::Guitar => Self::Guitar(Guitar::default()),
 			InstrumentType::Ukulele => Self::Ukulele(Ukulele::default()),
-			InstrumentType::BaritoneUkulele => {
-				Self::Configurable(ConfigurableInstrument::baritone_ukulele())
-			}
-			InstrumentType::Bass => Self::Configurable(ConfigurableInstrument::bass()),
-			InstrumentType::Bass5 => Self::Configurable(ConfigurableInstrument::bass_5_string()),
-			InstrumentType::Mandolin => Self::Configurable(ConfigurableInstrument::mandolin()),
-			InstrumentType::Banjo => Self::Configurable(ConfigurableInstrument::banjo()),
-			InstrumentType::Guitar7 => {
-				Self::Configurable(ConfigurableInstrument::guitar_7_string())
-			}
-			InstrumentType::DropD => Self::Configurable(ConfigurableInstrument::guitar_drop_d()),
-			InstrumentType::OpenG => Self::Configurable(ConfigurableInstrument::guitar_open_g()),
-			InstrumentType::Dadgad => Self::Configurable(ConfigurableInstrument::guitar_dadgad()),
+			InstrumentType::Custom(instrument) => Self::Configurable(instrument),
+			other => Self::Configurable(
+				ConfigurableInstrument::by_name(other.name())
+					.expect("every instrument type is a preset"),
+			),
 		}
 	}
 }
@@ -99,15 +149,76 @@ pub struct JsGeneratorOptions {
 	/// Whether to include fingerings with the root in the bass
 	#[serde(default = "default_true")]
 	pub root_in_bass: bool,
+	/// Lowest fret to press (open strings are still allowed)
+	#[serde(default)]
+	pub min_fret: u8,
 	/// Maximum fret to consider
 	#[serde(default = "default_max_fret")]
 	pub max_fret: u8,
-	/// Playing context ("solo" or "band")
+	/// Playing context ("solo", "band", "fingerstyle", "strumming", or "recording")
 	#[serde(default)]
 	pub playing_context: String,
 	/// Capo position (0 = no capo)
 	#[serde(default)]
 	pub capo: u8,
+	/// Hand size ("small", "medium", or "large")
+	#[serde(default)]
+	pub hand_size: String,
+	/// Frets of stretch on top of the hand size (negative: less)
+	#[serde(default)]
+	pub stretch_tolerance: i8,
+	/// How well the player barres ("weak", "normal", or "strong")
+	#[serde(default)]
+	pub barre_strength: String,
+	/// No barres, at most three fingers, reduced stretch
+	#[serde(default)]
+	pub accessible: bool,
+	/// Beginner preset: no barres, at most three fingers, first three frets, common shapes
+	#[serde(default)]
+	pub beginner: bool,
+	/// Allow natural harmonics (frets 12, 7, 5, 4) as chord tones
+	#[serde(default)]
+	pub include_harmonics: bool,
+	/// Slash-chord bass: "require" (only that bass) or "prefer" (others ranked lower)
+	#[serde(default)]
+	pub slash_bass: String,
+	/// Let the thumb fret the lowest string (e.g., 1x3211 for F)
+	#[serde(default)]
+	pub thumb_fretting: bool,
+	/// Strings every result must play a given way
+	#[serde(default)]
+	pub pins: Vec<JsStringPin>,
+	/// Melody note the highest played string must sound (e.g., "E"), even outside the chord
+	#[serde(default)]
+	pub top_note: Option<String>,
+	/// Strings to play: "top4", "middle4", "bottom3", or a list counted from the lowest ("2,3,4")
+	#[serde(default)]
+	pub string_set: Option<String>,
+	/// Chord degrees to leave out: 1 (root), 3 or 5
+	#[serde(default)]
+	pub omit: Vec<u8>,
+	/// Movable shapes only: no open strings or harmonics
+	#[serde(default)]
+	pub movable_only: bool,
+	/// Spread results over different shapes instead of variants of one
+	#[serde(default)]
+	pub diverse: bool,
+	/// Best fingering per region of the neck (open, frets 2-4, 5-7, ...), lowest first
+	#[serde(default)]
+	pub by_position: bool,
+	/// Grips ("133211") and shapes ("E shape", "E shape at 1") never to suggest
+	#[serde(default)]
+	pub avoid: Vec<String>,
+}
+
+/// A string pinned to one state (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsStringPin {
+	/// String index, lowest string first (0 = low E on guitar)
+	pub string: usize,
+	/// State in tab notation ("x", "0", "3", "(10)", "<12>")
+	pub state: String,
 }
 
 fn default_limit() -> usize {
@@ -127,9 +238,26 @@ impl Default for JsGeneratorOptions {
 			preferred_position: None,
 			voicing_type: None,
 			root_in_bass: true,
+			min_fret: 0,
 			max_fret: 12,
 			playing_context: "solo".to_string(),
 			capo: 0,
+			hand_size: "medium".to_string(),
+			stretch_tolerance: 0,
+			barre_strength: "normal".to_string(),
+			accessible: false,
+			beginner: false,
+			include_harmonics: false,
+			slash_bass: "require".to_string(),
+			thumb_fretting: false,
+			pins: Vec::new(),
+			top_note: None,
+			string_set: None,
+			omit: Vec::new(),
+			movable_only: false,
+			diverse: false,
+			by_position: false,
+			avoid: Vec::new(),
 		}
 	}
 }
@@ -150,6 +278,33 @@ pub struct JsProgressionOptions {
 	/// Generator options for each chord
 	#[serde(default)]
 	pub generator_options: JsGeneratorOptions,
+	/// Label chords with roman numerals and harmonic functions in the detected key
+	#[serde(default)]
+	pub numerals: bool,
+	/// Key for roman numerals (e.g., "G", "Em", "Bb major"); implies `numerals`
+	#[serde(default)]
+	pub key: Option<String>,
+	/// Beam width for a faster, approximate search of long progressions (exact if unset)
+	#[serde(default)]
+	pub beam_width: Option<usize>,
+	/// Song tempo; favors quick changes and flags ones too slow to make in time
+	#[serde(default)]
+	pub bpm: Option<f32>,
+	/// Beats each chord is held for, in order (one beat for chords past the end)
+	#[serde(default)]
+	pub durations: Vec<f32>,
+	/// Also favor smooth voice leading: held common tones and a stepwise top voice
+	#[serde(default)]
+	pub voice_leading: bool,
+	/// Favor one kind of grip throughout: all open, all barres, or all compact
+	#[serde(default)]
+	pub consistent_voicing: bool,
+	/// Chords each alternative must finger differently from the ones before it
+	#[serde(default = "default_min_difference")]
+	pub min_difference: usize,
+	/// Weight of each chord's own voicing against smooth changes, 0 (smoothest) to 1 (best voicings)
+	#[serde(default)]
+	pub alpha: f32,
 }
 
 fn default_progression_limit() -> usize {
@@ -161,6 +316,9 @@ fn default_max_distance() -> u8 {
 fn default_candidates() -> usize {
 	20
 }
+fn default_min_difference() -> usize {
+	2
+}
 
 impl Default for JsProgressionOptions {
 	fn default() -> Self {
@@ -169,6 +327,15 @@ impl Default for JsProgressionOptions {
 			max_fret_distance: 3,
 			candidates_per_chord: 20,
 			generator_options: JsGeneratorOptions::default(),
+			numerals: false,
+			key: None,
+			beam_width: None,
+			bpm: None,
+			durations: Vec::new(),
+			voice_leading: false,
+			consistent_voicing: false,
+			min_difference: 2,
+			alpha: 0.0,
 		}
 	}
 }
@@ -189,6 +356,14 @@ pub struct JsScoredFingering {
 	pub position: u8,
 	/// Notes in the fingering (e.g., ["C", "E", "G"])
 	pub notes: Vec<String>,
+	/// Finger per string, lowest first (e.g., "-32-1-"; T = thumb, - = none)
+	pub fingers: Option<String>,
+	/// Difficulty grade ("beginner", "intermediate", or "advanced")
+	pub difficulty: String,
+	/// Chord degrees left out on request (e.g., [5] for no5)
+	pub omitted: Vec<u8>,
+	/// Region of the neck (e.g., "Open position", "Frets 5-7")
+	pub region: String,
 }
 
 /// Chord match result (JS-friendly)
@@ -197,10 +372,136 @@ pub struct JsScoredFingering {
 pub struct JsChordMatch {
 	/// Chord name (e.g., "Cmaj7")
 	pub name: String,
-	/// Confidence percentage (0-100)
+	/// Confidence percentage (0-100): chord tones sounding, notes the chord
+	/// explains, and how usual the bass is (100 = every note accounted for, root in bass)
 	pub confidence: u8,
 	/// Explanation of why this chord matches
 	pub explanation: String,
+	/// Note and role of each played string, lowest first (only when analyzing a fingering)
+	pub roles: Vec<JsStringRole>,
+	/// Chord the shape makes as if the capo were the nut (only with a capo on)
+	pub shape_name: Option<String>,
+	/// Standard shape the tab matches (e.g., "E shape barred at fret 3")
+	pub shape: Option<String>,
+}
+
+/// What one played string sounds in a chord (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsStringRole {
+	/// String index, lowest string first
+	pub string: usize,
+	/// Note name (e.g., "E")
+	pub note: String,
+	/// "R" for the root, otherwise the degree (e.g., "b3", "5", "9")
+	pub degree: String,
+	/// False for a note outside the chord (e.g., the F# of C/F#)
+	pub in_chord: bool,
+}
+
+/// Chord a partially typed tab can still become (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsPartialMatch {
+	/// Chord name (e.g., "C")
+	pub name: String,
+	/// Chord tones not sounding yet (e.g., ["G"])
+	pub missing_notes: Vec<String>,
+	/// One way to finish the tab (e.g., "x32010")
+	pub completion: String,
+}
+
+/// One-fret correction of a tab that may have a typo (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsNearMiss {
+	/// Corrected tab (e.g., "x32010" for "x32011")
+	pub fingering: String,
+	/// String that moved, lowest string first
+	pub string: usize,
+	/// Best reading of the corrected tab
+	pub chord: JsChordMatch,
+}
+
+/// A key to play a progression in, rated by ease (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsEasyKey {
+	/// Semitones the song is moved (positive = up)
+	pub semitones: i32,
+	/// Key of the transposed chords (e.g., "A major"), if one can be detected
+	pub key: Option<String>,
+	/// Sounding chords after transposition
+	pub chords: Vec<String>,
+	/// Capo fret (0 = no capo)
+	pub capo: u8,
+	/// Chord shapes to finger relative to the capo
+	pub shapes: Vec<String>,
+	/// Overall ease: best fingering scores plus transition score (higher = easier)
+	pub score: i64,
+	/// Best fingering sequence of the shapes (frets relative to the capo)
+	pub progression: JsProgressionSequence,
+}
+
+/// How one string changes between two fingerings (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsStringChange {
+	/// "same", "moved", "added" or "removed"
+	pub change: String,
+	/// Tab symbol before (e.g., "3", "x", "(10)", "<12>")
+	pub from: String,
+	/// Tab symbol after
+	pub to: String,
+}
+
+/// Options for chroma analysis (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JsChromaOptions {
+	/// Bins weaker than this fraction of the strongest bin are ignored
+	pub threshold: f32,
+	/// Maximum number of pitch classes considered
+	pub max_notes: usize,
+	/// Bass note name (e.g., "A"), if known
+	pub bass: Option<String>,
+	/// Maximum number of matches to return
+	pub limit: usize,
+}
+
+impl Default for JsChromaOptions {
+	fn default() -> Self {
+		let defaults = ChromaOptions::default();
+		Self {
+			threshold: defaults.threshold,
+			max_notes: defaults.max_notes,
+			bass: None,
+			limit: defaults.limit,
+		}
+	}
+}
+
+/// Options for chord charts (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JsChartOptions {
+	/// Beats each chord lasts (defaults to one bar)
+	pub beats_per_chord: Option<f32>,
+	pub beats_per_bar: u8,
+	/// Bars per rendered line
+	pub measures_per_line: usize,
+	pub title: Option<String>,
+}
+
+impl Default for JsChartOptions {
+	fn default() -> Self {
+		Self {
+			beats_per_chord: None,
+			beats_per_bar: 4,
+			measures_per_line: chordcraft_core::chart::DEFAULT_MEASURES_PER_LINE,
+			title: None,
+		}
+	}
 }
 
 /// Transition between chords (JS-friendly)
@@ -215,6 +516,14 @@ pub struct JsChordTransition {
 	pub finger_movements: usize,
 	pub common_anchors: usize,
 	pub position_distance: u8,
+	/// Estimated time to make the change, in milliseconds
+	pub estimated_ms: u32,
+	/// Time available for the change at the given tempo, in milliseconds
+	pub budget_ms: Option<u32>,
+	/// Whether the change won't fit the time available at the given tempo
+	pub risky: bool,
+	/// Voice-leading part of the score, if requested
+	pub voice_leading: Option<i32>,
 }
 
 /// Complete progression sequence (JS-friendly)
@@ -226,6 +535,23 @@ pub struct JsProgressionSequence {
 	pub transitions: Vec<JsChordTransition>,
 	pub total_score: i32,
 	pub avg_transition_score: f32,
+	/// Key the numerals are relative to (e.g., "G major"), if requested
+	pub key: Option<String>,
+	/// Roman numeral per chord (e.g., ["I", "vi", "IV", "V7"]), if requested
+	pub numerals: Option<Vec<String>>,
+	/// Harmonic function per chord (e.g., ["T", "T", "S", "D"]), alongside the numerals
+	pub functions: Option<Vec<String>>,
+	/// Cadences found alongside the numerals
+	pub cadences: Option<Vec<JsCadence>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsCadence {
+	/// Index of the chord the cadence lands on
+	pub chord: usize,
+	/// "authentic", "plagal", "half" or "deceptive"
+	pub kind: String,
 }
 
 // ============================================================================
@@ -257,10 +583,29 @@ fn voicing_type_to_string(vt: &VoicingType) -> String {
 fn parse_playing_context(s: &str) -> PlayingContext {
 	match s.to_lowercase().as_str() {
 		"band" => PlayingContext::Band,
+		"fingerstyle" => PlayingContext::Fingerstyle,
+		"strumming" => PlayingContext::Strumming,
+		"recording" => PlayingContext::Recording,
 		_ => PlayingContext::Solo,
 	}
 }
 
+fn parse_hand_size(s: &str) -> HandSize {
+	match s.to_lowercase().as_str() {
+		"small" => HandSize::Small,
+		"large" => HandSize::Large,
+		_ => HandSize::Medium,
+	}
+}
+
+fn parse_barre_strength(s: &str) -> BarreStrength {
+	match s.to_lowercase().as_str() {
+		"weak" => BarreStrength::Weak,
+		"strong" => BarreStrength::Strong,
+		_ => BarreStrength::Normal,
+	}
+}
+
 /// Convert JsGeneratorOptions to GeneratorOptions
 fn js_to_generator_options(js_opts: &JsGeneratorOptions) -> GeneratorOptions {
 	GeneratorOptions {
@@ -271,21 +616,62 @@ fn js_to_generator_options(js_opts: &JsGeneratorOptions) -> GeneratorOptions {
 			.as_ref()
 			.and_then(|s| parse_voicing_type(s)),
 		root_in_bass: js_opts.root_in_bass,
+		min_fret: js_opts.min_fret,
 		max_fret: js_opts.max_fret,
 		playing_context: parse_playing_context(&js_opts.playing_context),
+		hand_size: parse_hand_size(&js_opts.hand_size),
+		stretch_tolerance: js_opts.stretch_tolerance,
+		barre_strength: parse_barre_strength(&js_opts.barre_strength),
+		accessible: js_opts.accessible,
+		beginner: js_opts.beginner,
+		include_harmonics: js_opts.include_harmonics,
+		slash_bass: match js_opts.slash_bass.to_lowercase().as_str() {
+			"prefer" => SlashBass::Prefer,
+			_ => SlashBass::Require,
+		},
+		thumb_fretting: js_opts.thumb_fretting,
+		// Pins that aren't a single string in tab notation are ignored
+		pins: js_opts
+			.pins
+			.iter()
+			.filter_map(|pin| match Fingering::parse(&pin.state).ok()?.strings() {
+				[state] => Some(StringPin::new(pin.string, *state)),
+				_ => None,
+			})
+			.collect(),
+		// An unknown string set is ignored
+		string_set: js_opts
+			.string_set
+			.as_deref()
+			.and_then(|s| StringSet::parse(s).ok()),
+		top_note: js_opts
+			.top_note
+			.as_deref()
+			.and_then(|n| PitchClass::parse(n).ok()),
+		omit: js_opts.omit.clone(),
+		movable_only: js_opts.movable_only,
+		diverse: js_opts.diverse,
+		by_position: js_opts.by_position,
+		// Entries that are neither a tab nor a shape are ignored
+		avoid: js_opts
+			.avoid
+			.iter()
+			.filter_map(|a| Avoid::parse(a).ok())
+			.collect(),
 	}
 }
 
-/// Convert ScoredFingering to JsScoredFingering
+/// Convert ScoredFingering to JsScoredFingering, spelling notes like the chord
 fn scored_fingering_to_js<I: Instrument>(
 	sf: &ScoredFingering,
 	instrument: &I,
+	accidentals: Accidentals,
 ) -> JsScoredFingering {
 	let notes = sf
 		.fingering
 		.unique_pitch_classes(instrument)
 		.into_iter()
-		.map(|pc| format!("{pc}"))
+		.map(|pc| accidentals.spell(pc).to_string())
 		.collect();
 
 	JsScoredFingering {
@@ -295,22 +681,53 @@ fn scored_fingering_to_js<I: Instrument>(
 		has_root_in_bass: sf.has_root_in_bass,
 		position: sf.position,
 		notes,
+		fingers: sf.fingers.as_ref().map(|f| f.to_string()),
+		difficulty: sf.difficulty.to_string(),
+		omitted: sf.omitted.clone(),
+		region: sf.region(instrument).to_string(),
 	}
 }
 
+/// Accidentals a chord name is written with (sharps if it has none)
+fn spelling_of(chord_name: &str) -> Accidentals {
+	Accidentals::of_name(chord_name).unwrap_or_default()
+}
+
 /// Convert ChordMatch to JsChordMatch
 fn chord_match_to_js(cm: &ChordMatch) -> JsChordMatch {
-	let confidence = (cm.completeness * 100.0) as u8;
-	let explanation = if cm.root_in_bass {
-		format!("{confidence}% complete with root in bass")
+	let complete = (cm.completeness * 100.0) as u8;
+	let explanation = if cm.rootless {
+		format!("{complete}% complete, rootless (root implied)")
+	} else if cm.root_in_bass {
+		format!("{complete}% complete with root in bass")
 	} else {
-		format!("{confidence}% complete")
+		format!("{complete}% complete")
 	};
 
 	JsChordMatch {
-		name: cm.chord.to_string(),
-		confidence,
+		name: cm.name.clone(),
+		confidence: (cm.confidence * 100.0).round() as u8,
 		explanation,
+		roles: Vec::new(),
+		shape_name: None,
+		shape: cm.shape.as_ref().map(|s| s.to_string()),
+	}
+}
+
+fn string_role_to_js(role: &StringRole, accidentals: Accidentals) -> JsStringRole {
+	JsStringRole {
+		string: role.string,
+		note: accidentals.spell(role.note.pitch).to_string(),
+		degree: role.label(),
+		in_chord: role.in_chord,
+	}
+}
+
+fn partial_match_to_js(pm: &PartialMatch) -> JsPartialMatch {
+	JsPartialMatch {
+		name: pm.name.clone(),
+		missing_notes: pm.missing_notes.iter().map(|n| n.to_string()).collect(),
+		completion: pm.completion.to_string(),
 	}
 }
 
@@ -322,7 +739,8 @@ fn progression_to_js<I: Instrument>(
 	let js_fingerings: Vec<JsScoredFingering> = seq
 		.fingerings
 		.iter()
-		.map(|sf| scored_fingering_to_js(sf, instrument))
+		.zip(&seq.chords)
+		.map(|(sf, name)| scored_fingering_to_js(sf, instrument, spelling_of(name)))
 		.collect();
 
 	let js_transitions: Vec<JsChordTransition> = seq
@@ -331,12 +749,24 @@ fn progression_to_js<I: Instrument>(
 		.map(|t| JsChordTransition {
 			from_chord: t.from_chord.clone(),
 			to_chord: t.to_chord.clone(),
-			from_fingering: scored_fingering_to_js(&t.from_fingering, instrument),
-			to_fingering: scored_fingering_to_js(&t.to_fingering, instrument),
+			from_fingering: scored_fingering_to_js(
+				&t.from_fingering,
+				instrument,
+				spelling_of(&t.from_chord),
+			),
+			to_fingering: scored_fingering_to_js(
+				&t.to_fingering,
+				instrument,
+				spelling_of(&t.to_chord),
+			),
 			score: t.score,
 			finger_movements: t.finger_movements,
 			common_anchors: t.common_anchors,
 			position_distance: t.position_distance,
+			estimated_ms: t.estimated_ms,
+			budget_ms: t.budget_ms,
+			risky: t.is_risky(),
+			voice_leading: t.voice_leading,
 		})
 		.collect();
 
@@ -346,6 +776,21 @@ fn progression_to_js<I: Instrument>(
 		transitions: js_transitions,
 		total_score: seq.total_score,
 		avg_transition_score: seq.avg_transition_score,
+		key: seq.numerals.as_ref().map(|n| n.key.to_string()),
+		numerals: seq.numerals.as_ref().map(|n| n.labels()),
+		functions: seq
+			.numerals
+			.as_ref()
+			.map(|n| n.functions().iter().map(|f| f.to_string()).collect()),
+		cadences: seq.numerals.as_ref().map(|n| {
+			n.cadences()
+				.into_iter()
+				.map(|(chord, cadence)| JsCadence {
+					chord,
+					kind: cadence.to_string(),
+				})
+				.collect()
+		}),
 	}
 }
 
@@ -359,6 +804,24 @@ pub struct JsInstrumentInfo {
 	pub string_names: Vec<String>,
 }
 
+/// A built-in instrument (JS-friendly)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsInstrumentPreset {
+	/// Name accepted wherever an instrument type is (e.g., "drop-d")
+	pub name: String,
+	/// Other names for it (e.g., ["guitar-drop-d"])
+	pub aliases: Vec<String>,
+	/// Display name (e.g., "Guitar (Drop D)")
+	pub label: String,
+	/// One-line description
+	pub description: String,
+	/// Number of strings
+	pub string_count: usize,
+	/// String names from low to high
+	pub string_names: Vec<String>,
+}
+
 // ============================================================================
 // WASM Exports
 // ============================================================================
@@ -395,6 +858,37 @@ pub fn get_instrument_info(instrument_type: JsValue) -> Result<JsValue, JsValue>
 		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
 
+/// List every built-in instrument, in menu order
+///
+/// # Returns
+/// JSON array of presets with name, aliases, label, description, stringCount and stringNames
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const presets = listInstruments();
+/// console.log(presets.map(p => p.name)); // ["guitar", "ukulele", ...]
+/// ```
+#[wasm_bindgen(js_name = listInstruments)]
+pub fn list_instruments() -> Result<JsValue, JsValue> {
+	let presets: Vec<JsInstrumentPreset> = presets()
+		.iter()
+		.map(|preset| {
+			let instrument = preset.instrument();
+			JsInstrumentPreset {
+				name: preset.name.to_string(),
+				aliases: preset.aliases.iter().map(|a| a.to_string()).collect(),
+				label: instrument.name().to_string(),
+				description: preset.description.to_string(),
+				string_count: instrument.string_count(),
+				string_names: instrument.string_names(),
+			}
+		})
+		.collect();
+
+	serde_wasm_bindgen::to_value(&presets)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
 /// Find fingerings for a chord
 ///
 /// # Arguments
@@ -445,16 +939,16 @@ pub fn find_fingerings(
 	// Generate fingerings using wrapper pattern
 	let js_fingerings: Vec<JsScoredFingering> = with_instrument!(wrapper, inst => {
 		let fingerings = if js_opts.capo > 0 {
-			let capo_instrument = inst
-				.with_capo(js_opts.capo)
-				.map_err(|e| JsValue::from_str(&format!("Invalid capo position: {e}")))?;
-			generate_fingerings(&chord, &capo_instrument, &gen_opts)
+			FINGERING_CACHE
+				.with_borrow_mut(|cache| cache.generate_with_capo(&chord, &inst, js_opts.capo, &gen_opts))
+				.map_err(|e| JsValue::from_str(&format!("Invalid capo position: {e}")))?
+				.fingerings
 		} else {
-			generate_fingerings(&chord, &inst, &gen_opts)
+			cached_fingerings(&chord, &inst, &gen_opts)
 		};
 		fingerings
 			.iter()
-			.map(|sf| scored_fingering_to_js(sf, &inst))
+			.map(|sf| scored_fingering_to_js(sf, &inst, chord.accidentals()))
 			.collect()
 	});
 
@@ -463,23 +957,60 @@ pub fn find_fingerings(
 		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
 
+thread_local! {
+	/// Results of earlier `findFingerings` calls; holds nothing until given a size
+	static FINGERING_CACHE: RefCell<FingeringCache> = RefCell::new(FingeringCache::default());
+}
+
+fn cached_fingerings<I: Instrument>(
+	chord: &Chord,
+	instrument: &I,
+	options: &GeneratorOptions,
+) -> Vec<ScoredFingering> {
+	FINGERING_CACHE.with_borrow_mut(|cache| cache.generate(chord, instrument, options))
+}
+
+/// Remember the results of up to `size` `findFingerings` calls
+///
+/// Repeated lookups, like flipping between the chords of a song, then return
+/// instantly. 0 (the default) turns caching off and empties the cache; a
+/// smaller size drops the least recently used results.
+#[wasm_bindgen(js_name = setFingeringCacheSize)]
+pub fn set_fingering_cache_size(size: usize) {
+	FINGERING_CACHE.with_borrow_mut(|cache| cache.set_capacity(size));
+}
+
+/// Forget every cached `findFingerings` result, keeping the cache size
+#[wasm_bindgen(js_name = clearFingeringCache)]
+pub fn clear_fingering_cache() {
+	FINGERING_CACHE.with_borrow_mut(FingeringCache::clear);
+}
+
 /// Identify chord from fingering (tab notation)
 ///
 /// # Arguments
 /// * `tab_notation` - Tab notation (e.g., "x32010" for guitar, "0003" for ukulele)
 /// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+/// * `capo` - Optional capo fret; frets in the tab count from the capo
 ///
 /// # Returns
-/// JSON array of chord matches with confidence scores
+/// JSON array of chord matches with confidence scores, named as they sound
 ///
 /// # Example (JavaScript)
 /// ```javascript
 /// const matches = analyzeChord("x32010", "guitar");
 /// console.log(matches[0].name); // "C"
 /// console.log(matches[0].confidence); // 100
+///
+/// const capoed = analyzeChord("320003", "guitar", 2);
+/// console.log(capoed[0].name, capoed[0].shapeName); // "A" "G"
 /// ```
 #[wasm_bindgen(js_name = analyzeChord)]
-pub fn analyze_chord(tab_notation: &str, instrument_type: JsValue) -> Result<JsValue, JsValue> {
+pub fn analyze_chord(
+	tab_notation: &str,
+	instrument_type: JsValue,
+	capo: Option<u8>,
+) -> Result<JsValue, JsValue> {
 	// Parse instrument type
 	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
 		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
@@ -490,19 +1021,464 @@ pub fn analyze_chord(tab_notation: &str, instrument_type: JsValue) -> Result<JsV
 
 	let wrapper = InstrumentWrapper::from_type(inst_type);
 
-	// Analyze fingering using wrapper pattern
-	let matches = with_instrument!(wrapper, inst => {
-		analyze_fingering(&fingering, &inst)
+	// Analyze fingering using wrapper pattern, labelling each string's role in every match
+	// (degrees don't change with a capo, so the shape's notes are labelled)
+	let capo = capo.unwrap_or(0);
+	let js_matches = with_instrument!(wrapper, inst => {
+		analyze_fingering_with_capo(&fingering, &inst, capo, &AnalyzerOptions::default()).map(
+			|matches| {
+				matches
+					.iter()
+					.map(|m| JsChordMatch {
+						roles: string_roles(&fingering, &inst, &m.shape.chord)
+							.iter()
+							.map(|role| string_role_to_js(role, m.shape.chord.accidentals()))
+							.collect(),
+						shape_name: (capo > 0).then(|| m.shape.name.clone()),
+						..chord_match_to_js(&m.sounding)
+					})
+					.collect::<Vec<_>>()
+			},
+		)
+	})
+	.map_err(|e| JsValue::from_str(&format!("Invalid tab notation: {e}")))?;
+
+	// Serialize to JS
+	serde_wasm_bindgen::to_value(&js_matches)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Check whether a fingering can be played on an instrument
+///
+/// # Arguments
+/// * `tab_notation` - Tab notation (e.g., "x32010")
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+///
+/// # Returns
+/// JSON array of human-readable issues; empty if the fingering is playable
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// validateFingering("123456", "guitar");
+/// // ["spans 5 frets, more than the 4-fret stretch", "needs 6 fingers, only 4 available"]
+/// ```
+#[wasm_bindgen(js_name = validateFingering)]
+pub fn validate_fingering(
+	tab_notation: &str,
+	instrument_type: JsValue,
+) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+
+	let fingering = Fingering::parse(tab_notation)
+		.map_err(|e| JsValue::from_str(&format!("Invalid tab notation: {e}")))?;
+
+	let wrapper = InstrumentWrapper::from_type(inst_type);
+
+	let issues: Vec<String> = with_instrument!(wrapper, inst => {
+		fingering.validate_for(&inst)
+	})
+	.iter()
+	.map(|i| i.to_string())
+	.collect();
+
+	serde_wasm_bindgen::to_value(&issues)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Suggest standard shapes one fret away, to catch typos in a tab
+///
+/// # Arguments
+/// * `tab_notation` - Tab notation (e.g., "x32011")
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+///
+/// # Returns
+/// JSON array of corrections, best first; empty if the tab already is a standard shape
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const fixes = nearMisses("x32011", "guitar");
+/// console.log(fixes[0].fingering, fixes[0].chord.name); // "x32010" "C"
+/// ```
+#[wasm_bindgen(js_name = nearMisses)]
+pub fn js_near_misses(tab_notation: &str, instrument_type: JsValue) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+
+	let fingering = Fingering::parse(tab_notation)
+		.map_err(|e| JsValue::from_str(&format!("Invalid tab notation: {e}")))?;
+
+	let wrapper = InstrumentWrapper::from_type(inst_type);
+
+	let js_misses: Vec<JsNearMiss> = with_instrument!(wrapper, inst => {
+		near_misses(&fingering, &inst, &AnalyzerOptions::default())
+	})
+	.iter()
+	.map(|miss| JsNearMiss {
+		fingering: miss.fingering.to_string(),
+		string: miss.string,
+		chord: chord_match_to_js(&miss.chord),
+	})
+	.collect();
+
+	serde_wasm_bindgen::to_value(&js_misses)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Find the keys a progression is easiest to play in, with or without a capo
+///
+/// # Arguments
+/// * `chord_names` - Array of chord names (e.g., ["Ab", "Fm", "Db", "Eb"])
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+/// * `max_capo` - Highest capo position to try (or null for 5; 0 = no capo)
+/// * `limit` - Number of keys to return (or null for 3)
+///
+/// # Returns
+/// JSON array of keys, easiest first
+#[wasm_bindgen(js_name = easiestKeys)]
+pub fn js_easiest_keys(
+	chord_names: JsValue,
+	instrument_type: JsValue,
+	max_capo: Option<u8>,
+	limit: Option<usize>,
+) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+	let chord_names: Vec<String> = serde_wasm_bindgen::from_value(chord_names)
+		.map_err(|e| JsValue::from_str(&format!("Invalid chord names: {e}")))?;
+	let chord_name_refs: Vec<&str> = chord_names.iter().map(|s| s.as_str()).collect();
+
+	let defaults = KeySuggestionOptions::default();
+	let options = KeySuggestionOptions {
+		max_capo: max_capo.unwrap_or(defaults.max_capo),
+		limit: limit.unwrap_or(defaults.limit),
+		..defaults
+	};
+
+	let wrapper = InstrumentWrapper::from_type(inst_type);
+	let js_keys: Vec<JsEasyKey> = with_instrument!(wrapper, inst => {
+		easiest_keys(&chord_name_refs, &inst, &options)
+			.iter()
+			.map(|k| JsEasyKey {
+				semitones: k.semitones,
+				key: k.key.map(|key| key.to_string()),
+				chords: k.chords.clone(),
+				capo: k.capo,
+				shapes: k.shapes.clone(),
+				score: k.score(),
+				progression: progression_to_js(&k.progression, &inst),
+			})
+			.collect()
 	});
 
-	// Convert to JS-friendly format
+	serde_wasm_bindgen::to_value(&js_keys)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Per-string changes from one fingering to another, e.g. to animate a chord change
+///
+/// # Arguments
+/// * `from_tab` - Tab notation of the current fingering (e.g., "x32010")
+/// * `to_tab` - Tab notation of the next fingering (e.g., "x02210")
+///
+/// # Returns
+/// JSON array with one change per string, lowest string first
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const changes = diffFingerings("x32010", "x02210");
+/// console.log(changes[1]); // { change: "moved", from: "3", to: "0" }
+/// ```
+#[wasm_bindgen(js_name = diffFingerings)]
+pub fn diff_fingerings(from_tab: &str, to_tab: &str) -> Result<JsValue, JsValue> {
+	let parse = |tab: &str| {
+		Fingering::parse(tab).map_err(|e| JsValue::from_str(&format!("Invalid tab notation: {e}")))
+	};
+	let (from, to) = (parse(from_tab)?, parse(to_tab)?);
+
+	let changes: Vec<JsStringChange> = from
+		.strings()
+		.iter()
+		.zip(from.diff(&to))
+		.zip(to.strings())
+		.map(|((before, change), after)| JsStringChange {
+			change: match change {
+				StringChange::Same(_) => "same",
+				StringChange::Moved { .. } => "moved",
+				StringChange::Added(_) => "added",
+				StringChange::Removed(_) => "removed",
+			}
+			.to_string(),
+			from: before.to_string(),
+			to: after.to_string(),
+		})
+		.collect();
+
+	serde_wasm_bindgen::to_value(&changes)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Suggest chords for a tab that is still being typed
+///
+/// # Arguments
+/// * `tab_notation` - Partial tab: `?` marks an unknown string, and strings
+///   past the end are unknown (e.g., "x32" or "x32???")
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+/// * `limit` - Maximum number of suggestions
+///
+/// # Returns
+/// JSON array of possible chords, most likely first, each with its missing
+/// notes and a completed tab
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const matches = analyzePartialTab("x32", "guitar", 5);
+/// console.log(matches[0].completion); // e.g. "x32010"
+/// ```
+#[wasm_bindgen(js_name = analyzePartialTab)]
+pub fn analyze_partial(
+	tab_notation: &str,
+	instrument_type: JsValue,
+	limit: usize,
+) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+
+	let wrapper = InstrumentWrapper::from_type(inst_type);
+
+	let matches = with_instrument!(wrapper, inst => {
+		analyze_partial_tab(tab_notation, &inst, limit)
+	})
+	.map_err(|e| JsValue::from_str(&format!("Invalid tab notation: {e}")))?;
+
+	let js_matches: Vec<JsPartialMatch> = matches.iter().map(partial_match_to_js).collect();
+
+	serde_wasm_bindgen::to_value(&js_matches)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Identify chords from a 12-bin chroma vector
+///
+/// # Arguments
+/// * `chroma` - 12 energies, starting at C (as produced by most audio libraries)
+/// * `options` - Chroma options (or null for defaults)
+///
+/// # Returns
+/// JSON array of chord matches with confidence scores
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const matches = analyzeChroma([1, 0, 0, 0, 0.8, 0, 0, 0.9, 0, 0, 0, 0], null);
+/// console.log(matches[0].name); // "C"
+/// ```
+#[wasm_bindgen(js_name = analyzeChroma)]
+pub fn js_analyze_chroma(chroma: Vec<f32>, options: JsValue) -> Result<JsValue, JsValue> {
+	let chroma: [f32; 12] = chroma.try_into().map_err(|v: Vec<f32>| {
+		JsValue::from_str(&format!("Expected 12 chroma bins, got {}", v.len()))
+	})?;
+
+	let js_opts: JsChromaOptions = if options.is_null() || options.is_undefined() {
+		JsChromaOptions::default()
+	} else {
+		serde_wasm_bindgen::from_value(options)
+			.map_err(|e| JsValue::from_str(&format!("Invalid options: {e}")))?
+	};
+
+	let bass = js_opts
+		.bass
+		.as_deref()
+		.map(PitchClass::parse)
+		.transpose()
+		.map_err(|e| JsValue::from_str(&format!("Invalid bass note: {e}")))?;
+
+	let matches = analyze_chroma(
+		&chroma,
+		&ChromaOptions {
+			threshold: js_opts.threshold,
+			max_notes: js_opts.max_notes,
+			bass,
+			limit: js_opts.limit,
+		},
+	);
+
 	let js_matches: Vec<JsChordMatch> = matches.iter().map(chord_match_to_js).collect();
 
-	// Serialize to JS
 	serde_wasm_bindgen::to_value(&js_matches)
 		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
 
+/// Identify chords from note names, without a fingering or instrument
+///
+/// # Arguments
+/// * `notes` - Note names, lowest first (e.g., ["E", "G", "C"]); the first is the bass
+///
+/// # Returns
+/// JSON array of chord matches with confidence scores
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const matches = analyzeNotes(["C", "E", "G", "Bb"]);
+/// console.log(matches[0].name); // "C7"
+/// ```
+#[wasm_bindgen(js_name = analyzeNotes)]
+pub fn js_analyze_notes(notes: JsValue) -> Result<JsValue, JsValue> {
+	let notes: Vec<String> = serde_wasm_bindgen::from_value(notes)
+		.map_err(|e| JsValue::from_str(&format!("Invalid notes: {e}")))?;
+	let notes = notes
+		.iter()
+		.map(|n| PitchClass::parse(n))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| JsValue::from_str(&format!("Invalid note: {e}")))?;
+
+	let js_matches: Vec<JsChordMatch> = analyze_notes(&notes)
+		.iter()
+		.map(chord_match_to_js)
+		.collect();
+
+	serde_wasm_bindgen::to_value(&js_matches)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Identify chords from MIDI note numbers, e.g. from a keyboard
+///
+/// # Arguments
+/// * `notes` - MIDI note numbers in any order (e.g., [52, 60, 67]); the lowest is the bass
+///
+/// # Returns
+/// JSON array of chord matches with confidence scores
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const matches = analyzeMidiNotes([60, 52, 67]);
+/// console.log(matches[0].name); // "C/E"
+/// ```
+#[wasm_bindgen(js_name = analyzeMidiNotes)]
+pub fn js_analyze_midi_notes(notes: Vec<u8>) -> Result<JsValue, JsValue> {
+	if let Some(n) = notes.iter().find(|&&n| n > 127) {
+		return Err(JsValue::from_str(&format!("Invalid MIDI note: {n}")));
+	}
+
+	let js_matches: Vec<JsChordMatch> = analyze_midi_notes(&notes)
+		.iter()
+		.map(chord_match_to_js)
+		.collect();
+
+	serde_wasm_bindgen::to_value(&js_matches)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Render a measure-aligned chord chart as monospace text
+///
+/// # Arguments
+/// * `chord_names` - Array of chord names (e.g., ["C", "Am", "F", "G"])
+/// * `options` - Chart options (or null for one chord per 4/4 bar)
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const text = renderChart(["C", "Am", "F", "G"], { beatsPerChord: 2 });
+/// ```
+#[wasm_bindgen(js_name = renderChart)]
+pub fn js_render_chart(chord_names: JsValue, options: JsValue) -> Result<String, JsValue> {
+	let chord_names: Vec<String> = serde_wasm_bindgen::from_value(chord_names)
+		.map_err(|e| JsValue::from_str(&format!("Invalid chord names: {e}")))?;
+
+	let js_opts: JsChartOptions = if options.is_null() || options.is_undefined() {
+		JsChartOptions::default()
+	} else {
+		serde_wasm_bindgen::from_value(options)
+			.map_err(|e| JsValue::from_str(&format!("Invalid options: {e}")))?
+	};
+
+	let beats_per_bar = js_opts.beats_per_bar.max(1);
+	let beats = js_opts.beats_per_chord.unwrap_or(beats_per_bar as f32);
+	let mut section = SongSection::new(None);
+	for name in &chord_names {
+		let chord = Chord::parse(name)
+			.map_err(|e| JsValue::from_str(&format!("Invalid chord '{name}': {e}")))?;
+		section.push(chord, beats);
+	}
+
+	let mut song = Song::new(js_opts.title);
+	song.beats_per_bar = beats_per_bar;
+	song.sections.push(section);
+
+	Ok(Chart::from_song(&song).render(js_opts.measures_per_line))
+}
+
+/// Register a custom chord symbol for the rest of the session
+///
+/// # Arguments
+/// * `symbol` - Quality symbol written after the root (e.g., "mu")
+/// * `formula` - Chord degrees (e.g., "1 2 3 5"); degrees in parentheses are optional
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// registerQuality("mu", "1 2 3 5");
+/// const fingerings = findFingerings("Dmu", "guitar", null);
+/// ```
+#[wasm_bindgen(js_name = registerQuality)]
+pub fn js_register_quality(symbol: &str, formula: &str) -> Result<(), JsValue> {
+	let formula = ChordFormula::parse(formula)
+		.map_err(|e| JsValue::from_str(&format!("Invalid formula: {e}")))?;
+	register_quality(symbol, formula)
+		.map_err(|e| JsValue::from_str(&format!("Invalid symbol: {e}")))?;
+	// A symbol registered again changes the notes of its cached chords
+	clear_fingering_cache();
+	Ok(())
+}
+
+/// Optimize a chord sequence and convert the results for JS
+fn progressions_to_js(
+	chord_names: &[&str],
+	inst_type: InstrumentType,
+	js_opts: &JsProgressionOptions,
+) -> Result<Vec<JsProgressionSequence>, JsValue> {
+	parse_chord_names(chord_names).map_err(|e| JsValue::from_str(&e.to_string()))?;
+	let numerals = match &js_opts.key {
+		Some(key) => Numerals::InKey(
+			Key::parse(key).map_err(|e| JsValue::from_str(&format!("Invalid key: {e}")))?,
+		),
+		None if js_opts.numerals => Numerals::DetectedKey,
+		None => Numerals::Off,
+	};
+
+	// Build progression options
+	let prog_opts = ProgressionOptions {
+		limit: js_opts.limit,
+		max_fret_distance: js_opts.max_fret_distance,
+		candidates_per_chord: js_opts.candidates_per_chord,
+		generator_options: js_to_generator_options(&js_opts.generator_options),
+		numerals,
+		beam_width: js_opts.beam_width,
+		bpm: js_opts.bpm,
+		durations: js_opts.durations.clone(),
+		voice_leading: js_opts.voice_leading,
+		consistent_voicing: js_opts.consistent_voicing,
+		min_difference: js_opts.min_difference,
+		alpha: js_opts.alpha,
+	};
+
+	let wrapper = InstrumentWrapper::from_type(inst_type);
+
+	// Generate progressions using wrapper pattern
+	let js_progressions: Vec<JsProgressionSequence> = with_instrument!(wrapper, inst => {
+		let progressions = if js_opts.generator_options.capo > 0 {
+			let capo_instrument = inst
+				.with_capo(js_opts.generator_options.capo)
+				.map_err(|e| JsValue::from_str(&format!("Invalid capo position: {e}")))?;
+			generate_progression(chord_names, &capo_instrument, &prog_opts)
+		} else {
+			generate_progression(chord_names, &inst, &prog_opts)
+		};
+		progressions
+			.iter()
+			.map(|seq| progression_to_js(seq, &inst))
+			.collect()
+	});
+
+	Ok(js_progressions)
+}
+
 /// Generate optimal fingering progressions for a chord sequence
 ///
 /// # Arguments
@@ -544,36 +1520,97 @@ pub fn js_generate_progression(
 			.map_err(|e| JsValue::from_str(&format!("Invalid options: {e}")))?
 	};
 
-	// Build progression options
-	let prog_opts = ProgressionOptions {
-		limit: js_opts.limit,
-		max_fret_distance: js_opts.max_fret_distance,
-		candidates_per_chord: js_opts.candidates_per_chord,
-		generator_options: js_to_generator_options(&js_opts.generator_options),
-	};
-
 	// Convert Vec<String> to Vec<&str> for API compatibility
 	let chord_name_refs: Vec<&str> = chord_names_vec.iter().map(|s| s.as_str()).collect();
+	let js_progressions = progressions_to_js(&chord_name_refs, inst_type, &js_opts)?;
 
-	let wrapper = InstrumentWrapper::from_type(inst_type);
+	// Serialize to JS
+	serde_wasm_bindgen::to_value(&js_progressions)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
 
-	// Generate progressions using wrapper pattern
-	let js_progressions: Vec<JsProgressionSequence> = with_instrument!(wrapper, inst => {
-		let progressions = if js_opts.generator_options.capo > 0 {
-			let capo_instrument = inst
-				.with_capo(js_opts.generator_options.capo)
-				.map_err(|e| JsValue::from_str(&format!("Invalid capo position: {e}")))?;
-			generate_progression(&chord_name_refs, &capo_instrument, &prog_opts)
-		} else {
-			generate_progression(&chord_name_refs, &inst, &prog_opts)
-		};
-		progressions
-			.iter()
-			.map(|seq| progression_to_js(seq, &inst))
-			.collect()
-	});
+/// Generate progressions for roman numerals in a key
+///
+/// # Arguments
+/// * `numerals` - Array of numerals (e.g., ["I", "vi", "IV", "V7/V"])
+/// * `key` - Key the numerals are in (e.g., "G", "Em", "Bb major")
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+/// * `options` - Progression options (or null for defaults)
+///
+/// # Returns
+/// JSON array of progression sequences, labelled with the numerals
+#[wasm_bindgen(js_name = generateProgressionFromNumerals)]
+pub fn js_generate_progression_from_numerals(
+	numerals: JsValue,
+	key: &str,
+	instrument_type: JsValue,
+	options: JsValue,
+) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+	let numerals: Vec<String> = serde_wasm_bindgen::from_value(numerals)
+		.map_err(|e| JsValue::from_str(&format!("Invalid numerals: {e}")))?;
+	let mut js_opts: JsProgressionOptions = if options.is_null() || options.is_undefined() {
+		JsProgressionOptions::default()
+	} else {
+		serde_wasm_bindgen::from_value(options)
+			.map_err(|e| JsValue::from_str(&format!("Invalid options: {e}")))?
+	};
+
+	let key = Key::parse(key).map_err(|e| JsValue::from_str(&format!("Invalid key: {e}")))?;
+	let numeral_refs: Vec<&str> = numerals.iter().map(|s| s.as_str()).collect();
+	let chord_names: Vec<String> = resolve_numerals(&numeral_refs, &key)
+		.map_err(|e| JsValue::from_str(&e.to_string()))?
+		.iter()
+		.map(|chord| chord.to_string())
+		.collect();
+	if js_opts.key.is_none() && !js_opts.numerals {
+		js_opts.key = Some(key.to_string());
+	}
+
+	let chord_name_refs: Vec<&str> = chord_names.iter().map(|s| s.as_str()).collect();
+	let js_progressions = progressions_to_js(&chord_name_refs, inst_type, &js_opts)?;
+
+	serde_wasm_bindgen::to_value(&js_progressions)
+		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
+}
+
+/// Generate progressions for a chart written in bar notation
+///
+/// # Arguments
+/// * `chart` - Bars such as "|: C . . . | Am | F G :| x2"
+/// * `beats_per_bar` - Beats in each bar (or null for 4)
+/// * `instrument_type` - Instrument type ("guitar" or "ukulele")
+/// * `options` - Progression options (or null for defaults)
+///
+/// # Returns
+/// JSON array of progression sequences, one fingering per chord with repeats written out
+#[wasm_bindgen(js_name = generateProgressionFromChart)]
+pub fn js_generate_progression_from_chart(
+	chart: &str,
+	beats_per_bar: Option<u8>,
+	instrument_type: JsValue,
+	options: JsValue,
+) -> Result<JsValue, JsValue> {
+	let inst_type: InstrumentType = serde_wasm_bindgen::from_value(instrument_type)
+		.map_err(|e| JsValue::from_str(&format!("Invalid instrument type: {e}")))?;
+	let mut js_opts: JsProgressionOptions = if options.is_null() || options.is_undefined() {
+		JsProgressionOptions::default()
+	} else {
+		serde_wasm_bindgen::from_value(options)
+			.map_err(|e| JsValue::from_str(&format!("Invalid options: {e}")))?
+	};
+
+	let song = Chart::parse(chart, beats_per_bar.unwrap_or(4))
+		.map_err(|e| JsValue::from_str(&e.to_string()))?
+		.to_song();
+	let chord_names = song.chord_names();
+	if js_opts.durations.is_empty() {
+		js_opts.durations = song.chords().map(|c| c.beats).collect();
+	}
+	let chord_name_refs: Vec<&str> = chord_names.iter().map(|s| s.as_str()).collect();
+	let js_progressions = progressions_to_js(&chord_name_refs, inst_type, &js_opts)?;
 
-	// Serialize to JS
 	serde_wasm_bindgen::to_value(&js_progressions)
 		.map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
 }
@@ -609,16 +1646,26 @@ mod tests {
 	fn test_analyze_chord_basic() {
 		let inst = serde_wasm_bindgen::to_value(&InstrumentType::Guitar).unwrap();
 
-		let result = analyze_chord("x32010", inst);
+		let result = analyze_chord("x32010", inst, None);
 		assert!(result.is_ok());
 	}
 
+	#[wasm_bindgen_test]
+	fn test_analyze_chord_capo() {
+		let inst = serde_wasm_bindgen::to_value(&InstrumentType::Guitar).unwrap();
+
+		let result = analyze_chord("320003", inst, Some(2)).unwrap();
+		let matches: Vec<JsChordMatch> = serde_wasm_bindgen::from_value(result).unwrap();
+		assert_eq!(matches[0].name, "A");
+		assert_eq!(matches[0].shape_name.as_deref(), Some("G"));
+	}
+
 	#[wasm_bindgen_test]
 	fn test_analyze_chord_ukulele() {
 		let inst = serde_wasm_bindgen::to_value(&InstrumentType::Ukulele).unwrap();
 
 		// 0003 is C on ukulele (G-C-E-C)
-		let result = analyze_chord("0003", inst);
+		let result = analyze_chord("0003", inst, None);
 		assert!(result.is_ok());
 	}
 
@@ -719,7 +1766,7 @@ mod tests {
 		let inst = serde_wasm_bindgen::to_value(&InstrumentType::DropD).unwrap();
 
 		// Drop D tuning: D-A-D-G-B-E, so 000232 would be D major
-		let result = analyze_chord("000232", inst);
+		let result = analyze_chord("000232", inst, None);
 		assert!(result.is_ok());
 	}
 
@@ -728,7 +1775,35 @@ mod tests {
 		let inst = serde_wasm_bindgen::to_value(&InstrumentType::Mandolin).unwrap();
 
 		// 0023 could be a chord on mandolin (GDAE tuning)
-		let result = analyze_chord("0023", inst);
+		let result = analyze_chord("0023", inst, None);
 		assert!(result.is_ok());
 	}
+
+	#[wasm_bindgen_test]
+	fn test_find_fingerings_custom_instrument() {
+		let custom = ConfigurableInstrument::builder()
+			.name("Tenor Guitar")
+			.tuning(chordcraft_core::tuning::Tuning::parse("C3 G3 D4 A4").unwrap())
+			.fret_range(0, 19)
+			.max_stretch(4)
+			.build()
+			.unwrap();
+		let inst = serde_wasm_bindgen::to_value(&InstrumentType::Custom(custom)).unwrap();
+
+		let result = find_fingerings("C", inst, JsValue::NULL);
+		assert!(result.is_ok());
+	}
+
+	#[wasm_bindgen_test]
+	fn test_list_instruments() {
+		let presets: Vec<JsInstrumentPreset> =
+			serde_wasm_bindgen::from_value(list_instruments().unwrap()).unwrap();
+		// Every preset name is an instrument type
+		for preset in &presets {
+			let inst: Result<InstrumentType, _> =
+				serde_wasm_bindgen::from_value(JsValue::from_str(&preset.name));
+			assert!(inst.is_ok(), "{}", preset.name);
+		}
+		assert_eq!(presets[0].string_count, 6);
+	}
 }
@@ -109,12 +109,20 @@ function parseInstrument(params: URLSearchParams): Instrument {
 	const validInstruments: Instrument[] = [
 		'guitar',
 		'ukulele',
+		'ukulele-low-g',
+		'concert-ukulele',
+		'tenor-ukulele',
+		'tenor-ukulele-low-g',
 		'baritone-ukulele',
 		'bass',
 		'bass-5',
 		'mandolin',
 		'banjo',
 		'guitar-7',
+		'guitar-8',
+		'baritone-guitar',
+		'baritone-guitar-a',
+		'guitar-12',
 		'drop-d',
 		'open-g',
 		'dadgad',
@@ -5,9 +5,24 @@
 
 import init, {
 	analyzeChord as wasmAnalyzeChord,
+	analyzeChroma as wasmAnalyzeChroma,
+	analyzeMidiNotes as wasmAnalyzeMidiNotes,
+	analyzeNotes as wasmAnalyzeNotes,
+	analyzePartialTab as wasmAnalyzePartialTab,
+	clearFingeringCache as wasmClearFingeringCache,
+	diffFingerings as wasmDiffFingerings,
+	easiestKeys as wasmEasiestKeys,
 	findFingerings as wasmFindFingerings,
 	generateProgression as wasmGenerateProgression,
+	generateProgressionFromChart as wasmGenerateProgressionFromChart,
+	generateProgressionFromNumerals as wasmGenerateProgressionFromNumerals,
 	getInstrumentInfo as wasmGetInstrumentInfo,
+	listInstruments as wasmListInstruments,
+	nearMisses as wasmNearMisses,
+	registerQuality as wasmRegisterQuality,
+	renderChart as wasmRenderChart,
+	setFingeringCacheSize as wasmSetFingeringCacheSize,
+	validateFingering as wasmValidateFingering,
 } from 'chordcraft-wasm';
 
 // ============================================================================
@@ -18,22 +33,72 @@ import init, {
 export type Instrument =
 	| 'guitar'
 	| 'ukulele'
+	| 'ukulele-low-g'
+	| 'concert-ukulele'
+	| 'tenor-ukulele'
+	| 'tenor-ukulele-low-g'
 	| 'baritone-ukulele'
 	| 'bass'
 	| 'bass-5'
 	| 'mandolin'
 	| 'banjo'
 	| 'guitar-7'
+	| 'guitar-8'
+	| 'baritone-guitar'
+	| 'baritone-guitar-a'
+	| 'guitar-12'
 	| 'drop-d'
 	| 'open-g'
 	| 'dadgad';
 
+/**
+ * A user-defined instrument, as saved by `chordcraft export instrument --format json`.
+ * Pass it to the WASM functions as `{ custom: definition }` in place of an instrument type.
+ */
+export interface InstrumentDefinition {
+	name?: string;
+	/** Notes from the lowest string, e.g. ["D2", "A2", "D3"], or a string like "DADGAD" */
+	tuning: string[] | string;
+	fret_range: [number, number];
+	max_stretch: number;
+	max_fingers?: number;
+	open_position_threshold?: number;
+	main_barre_threshold?: number;
+	min_played_strings?: number;
+	bass_string_index?: number;
+	string_names?: string[];
+	/** Partner note of each course (null for single strings) */
+	course_pairs?: (string | null)[];
+	/** Frets each string can be stopped at, e.g. [6, 22] for a banjo's short fifth string */
+	string_fret_ranges?: [number, number][];
+}
+
+/** A built-in instrument, as listed by listInstruments */
+export interface InstrumentPreset {
+	/** Name accepted as an Instrument (e.g., "drop-d") */
+	name: Instrument;
+	/** Other names for it (e.g., ["guitar-drop-d"]) */
+	aliases: string[];
+	/** Display name (e.g., "Guitar (Drop D)") */
+	label: string;
+	description: string;
+	stringCount: number;
+	stringNames: string[];
+}
+
 /** Instrument configuration info */
 export interface InstrumentInfo {
 	stringCount: number;
 	stringNames: string[];
 }
 
+/** A string pinned to one state; strings count from the lowest (0) */
+export interface StringPin {
+	string: number;
+	/** Tab notation: "x", "0", "3", "(10)" or "<12>" */
+	state: string;
+}
+
 export interface ScoredFingering {
 	tab: string;
 	score: number;
@@ -41,12 +106,76 @@ export interface ScoredFingering {
 	hasRootInBass: boolean;
 	position: number;
 	notes: string[];
+	/** Finger per string, lowest first (e.g., "-32-1-"; T = thumb, - = none) */
+	fingers?: string | null;
+	difficulty: 'beginner' | 'intermediate' | 'advanced';
+	/** Chord degrees left out on request (e.g., [5] for no5) */
+	omitted: number[];
+	/** Region of the neck (e.g., "Open position", "Frets 5-7") */
+	region: string;
 }
 
 export interface ChordMatch {
 	name: string;
+	/**
+	 * 0-100: chord tones sounding, notes the chord explains, and how usual the
+	 * bass is (100 = every note accounted for, root in bass)
+	 */
 	confidence: number;
 	explanation: string;
+	/** Note and role of each played string, lowest first (only when analyzing a fingering) */
+	roles: StringRole[];
+	/** Chord the shape makes as if the capo were the nut (only with a capo on) */
+	shapeName?: string | null;
+	/** Standard shape the tab matches (e.g., "E shape barred at fret 3") */
+	shape?: string | null;
+}
+
+export interface StringRole {
+	/** String index, lowest string first */
+	string: number;
+	note: string;
+	/** "R" for the root, otherwise the degree (e.g., "b3", "5", "9") */
+	degree: string;
+	/** False for a note outside the chord (e.g., the F# of C/F#) */
+	inChord: boolean;
+}
+
+export interface NearMiss {
+	/** Corrected tab (e.g., "x32010" for "x32011") */
+	fingering: string;
+	/** String that moved, lowest string first */
+	string: number;
+	/** Best reading of the corrected tab */
+	chord: ChordMatch;
+}
+
+export interface PartialMatch {
+	name: string;
+	missingNotes: string[];
+	completion: string;
+}
+
+export interface StringChange {
+	change: 'same' | 'moved' | 'added' | 'removed';
+	/** Tab symbol before (e.g., "3", "x", "(10)", "<12>") */
+	from: string;
+	/** Tab symbol after */
+	to: string;
+}
+
+export interface ChartOptions {
+	beatsPerChord?: number;
+	beatsPerBar?: number;
+	measuresPerLine?: number;
+	title?: string;
+}
+
+export interface ChromaOptions {
+	threshold?: number;
+	maxNotes?: number;
+	bass?: string;
+	limit?: number;
 }
 
 export interface ChordTransition {
@@ -58,6 +187,13 @@ export interface ChordTransition {
 	fingerMovements: number;
 	commonAnchors: number;
 	positionDistance: number;
+	estimatedMs: number;
+	/** Time available for the change at the given tempo, in milliseconds */
+	budgetMs?: number;
+	/** Whether the change won't fit the time available at the given tempo */
+	risky: boolean;
+	/** Voice-leading part of the score, if requested */
+	voiceLeading?: number;
 }
 
 export interface ProgressionSequence {
@@ -66,6 +202,38 @@ export interface ProgressionSequence {
 	transitions: ChordTransition[];
 	totalScore: number;
 	avgTransitionScore: number;
+	/** Key the numerals are relative to (e.g., "G major"), if requested */
+	key?: string | null;
+	/** Roman numeral per chord (e.g., ["I", "vi", "IV", "V7"]), if requested */
+	numerals?: string[] | null;
+	/** Harmonic function per chord (T, S, D, V/ii or chr), alongside the numerals */
+	functions?: string[] | null;
+	/** Cadences found alongside the numerals */
+	cadences?: Cadence[] | null;
+}
+
+export interface Cadence {
+	/** Index of the chord the cadence lands on */
+	chord: number;
+	/** Authentic (V-I), plagal (IV-I), half (ends on V) or deceptive (V-vi) */
+	kind: 'authentic' | 'plagal' | 'half' | 'deceptive';
+}
+
+export interface EasyKey {
+	/** Semitones the song is moved (positive = up) */
+	semitones: number;
+	/** Key of the transposed chords (e.g., "A major"), if one can be detected */
+	key?: string | null;
+	/** Sounding chords after transposition */
+	chords: string[];
+	/** Capo fret (0 = no capo) */
+	capo: number;
+	/** Chord shapes to finger relative to the capo */
+	shapes: string[];
+	/** Overall ease: best fingering scores plus transition score (higher = easier) */
+	score: number;
+	/** Best fingering sequence of the shapes (frets relative to the capo) */
+	progression: ProgressionSequence;
 }
 
 export interface GeneratorOptions {
@@ -73,9 +241,37 @@ export interface GeneratorOptions {
 	preferredPosition?: number;
 	voicingType?: 'core' | 'full' | 'jazzy';
 	rootInBass?: boolean;
+	/** Lowest fret to press (open strings are still allowed) */
+	minFret?: number;
 	maxFret?: number;
-	playingContext?: 'solo' | 'band';
+	playingContext?: 'solo' | 'band' | 'fingerstyle' | 'strumming' | 'recording';
 	capo?: number;
+	handSize?: 'small' | 'medium' | 'large';
+	stretchTolerance?: number;
+	barreStrength?: 'weak' | 'normal' | 'strong';
+	accessible?: boolean;
+	/** Beginner preset: no barres, at most three fingers, first three frets, common shapes */
+	beginner?: boolean;
+	includeHarmonics?: boolean;
+	slashBass?: 'require' | 'prefer';
+	/** Let the thumb fret the lowest string (e.g., 1x3211 for F) */
+	thumbFretting?: boolean;
+	/** Strings every result must play a given way, e.g. { string: 5, state: "3" } */
+	pins?: StringPin[];
+	/** Melody note the highest played string must sound (e.g., "E"), even outside the chord */
+	topNote?: string;
+	/** Strings to play: "top4", "middle4", "bottom3", or a list counted from the lowest ("2,3,4") */
+	stringSet?: string;
+	/** Chord degrees to leave out: 1 (root), 3 or 5 */
+	omit?: number[];
+	/** Movable shapes only: no open strings or harmonics */
+	movableOnly?: boolean;
+	/** Spread results over different shapes instead of variants of one */
+	diverse?: boolean;
+	/** Best fingering per region of the neck (open, frets 2-4, 5-7, ...), lowest first */
+	byPosition?: boolean;
+	/** Grips ("133211") and shapes ("E shape", "E shape at 1") never to suggest */
+	avoid?: string[];
 }
 
 export interface ProgressionOptions {
@@ -83,6 +279,24 @@ export interface ProgressionOptions {
 	maxFretDistance?: number;
 	candidatesPerChord?: number;
 	generatorOptions?: GeneratorOptions;
+	/** Label chords with roman numerals and harmonic functions in the detected key */
+	numerals?: boolean;
+	/** Key for roman numerals (e.g., "G", "Em"); implies numerals */
+	key?: string;
+	/** Beam width for a faster, approximate search of long progressions (exact if unset) */
+	beamWidth?: number;
+	/** Song tempo; favors quick changes and flags ones too slow to make in time */
+	bpm?: number;
+	/** Beats each chord is held for, in order (one beat for chords past the end) */
+	durations?: number[];
+	/** Also favor smooth voice leading: held common tones and a stepwise top voice */
+	voiceLeading?: boolean;
+	/** Favor one kind of grip throughout: all open, all barres, or all compact */
+	consistentVoicing?: boolean;
+	/** Chords each alternative must finger differently from the ones before it (default 2) */
+	minDifference?: number;
+	/** Weight of each chord's own voicing against smooth changes, 0 (smoothest) to 1 (best voicings); default 0 */
+	alpha?: number;
 }
 
 // ============================================================================
@@ -114,6 +328,34 @@ export const INSTRUMENT_METADATA: Record<
 		tuning: 'GCEA',
 		description: 'Standard soprano ukulele',
 	},
+	'ukulele-low-g': {
+		label: 'Ukulele (Low G)',
+		category: 'alternate-tuning',
+		stringCount: 4,
+		tuning: 'GCEA',
+		description: 'Linear tuning, the G string an octave down',
+	},
+	'concert-ukulele': {
+		label: 'Concert Ukulele',
+		category: 'standard',
+		stringCount: 4,
+		tuning: 'GCEA',
+		description: 'Longer neck than the soprano',
+	},
+	'tenor-ukulele': {
+		label: 'Tenor Ukulele',
+		category: 'standard',
+		stringCount: 4,
+		tuning: 'GCEA',
+		description: 'Longest neck of the GCEA ukuleles',
+	},
+	'tenor-ukulele-low-g': {
+		label: 'Tenor Ukulele (Low G)',
+		category: 'alternate-tuning',
+		stringCount: 4,
+		tuning: 'GCEA',
+		description: 'Tenor ukulele with the G string an octave down',
+	},
 	'baritone-ukulele': {
 		label: 'Baritone Ukulele',
 		category: 'standard',
@@ -149,6 +391,13 @@ export const INSTRUMENT_METADATA: Record<
 		tuning: 'gDGBD',
 		description: 'Standard 5-string banjo with high G drone',
 	},
+	'guitar-12': {
+		label: '12-String Guitar',
+		category: 'standard',
+		stringCount: 6,
+		tuning: 'EADGBE',
+		description: 'Six doubled courses, the lower four with octave strings',
+	},
 	'guitar-7': {
 		label: '7-String Guitar',
 		category: 'alternate-tuning',
@@ -156,6 +405,27 @@ export const INSTRUMENT_METADATA: Record<
 		tuning: 'BEADGBE',
 		description: 'Extended range guitar with low B',
 	},
+	'guitar-8': {
+		label: '8-String Guitar',
+		category: 'alternate-tuning',
+		stringCount: 8,
+		tuning: 'F#BEADGBE',
+		description: 'Extended range guitar with low F# and B',
+	},
+	'baritone-guitar': {
+		label: 'Baritone Guitar (B)',
+		category: 'alternate-tuning',
+		stringCount: 6,
+		tuning: 'BEADF#B',
+		description: 'Longer scale guitar in B standard, a fourth below',
+	},
+	'baritone-guitar-a': {
+		label: 'Baritone Guitar (A)',
+		category: 'alternate-tuning',
+		stringCount: 6,
+		tuning: 'ADGCEA',
+		description: 'Longer scale guitar in A standard, a fifth below',
+	},
 	'drop-d': {
 		label: 'Drop D Guitar',
 		category: 'alternate-tuning',
@@ -184,13 +454,26 @@ export const INSTRUMENT_CATEGORIES = {
 	standard: [
 		'guitar',
 		'ukulele',
+		'concert-ukulele',
+		'tenor-ukulele',
 		'baritone-ukulele',
 		'bass',
 		'bass-5',
 		'mandolin',
 		'banjo',
+		'guitar-12',
+	] as const,
+	'alternate-tuning': [
+		'drop-d',
+		'open-g',
+		'dadgad',
+		'guitar-7',
+		'guitar-8',
+		'baritone-guitar',
+		'baritone-guitar-a',
+		'ukulele-low-g',
+		'tenor-ukulele-low-g',
 	] as const,
-	'alternate-tuning': ['drop-d', 'open-g', 'dadgad', 'guitar-7'] as const,
 } as const;
 
 /** Helper: Get display label for instrument */
@@ -269,6 +552,20 @@ export async function getInstrumentInfo(instrument: Instrument): Promise<Instrum
 	}
 }
 
+/**
+ * List every built-in instrument, in menu order
+ */
+export async function listInstruments(): Promise<InstrumentPreset[]> {
+	await initializeWasm();
+
+	try {
+		return wasmListInstruments() as InstrumentPreset[];
+	} catch (error) {
+		console.error('Error listing instruments:', error);
+		throw new Error(`Failed to list instruments: ${error}`);
+	}
+}
+
 /**
  * Find fingerings for a chord
  */
@@ -290,15 +587,18 @@ export async function findFingerings(
 
 /**
  * Analyze a fingering and identify possible chords
+ *
+ * With a capo, frets count from the capo and matches are named as they sound.
  */
 export async function analyzeChord(
 	tabNotation: string,
-	instrument: Instrument = 'guitar'
+	instrument: Instrument = 'guitar',
+	capo?: number
 ): Promise<ChordMatch[]> {
 	await initializeWasm();
 
 	try {
-		const result = wasmAnalyzeChord(tabNotation, instrument);
+		const result = wasmAnalyzeChord(tabNotation, instrument, capo);
 		return result as ChordMatch[];
 	} catch (error) {
 		console.error('Error analyzing chord:', error);
@@ -306,6 +606,144 @@ export async function analyzeChord(
 	}
 }
 
+/**
+ * List what keeps a fingering from being playable (empty if it is)
+ */
+export async function validateFingering(
+	tabNotation: string,
+	instrument: Instrument = 'guitar'
+): Promise<string[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmValidateFingering(tabNotation, instrument);
+		return result as string[];
+	} catch (error) {
+		console.error('Error validating fingering:', error);
+		throw new Error(`Failed to validate "${tabNotation}": ${error}`);
+	}
+}
+
+/**
+ * Standard shapes one fret away from a tab, to catch typos (empty if it is a shape)
+ */
+export async function nearMisses(
+	tabNotation: string,
+	instrument: Instrument = 'guitar'
+): Promise<NearMiss[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmNearMisses(tabNotation, instrument);
+		return result as NearMiss[];
+	} catch (error) {
+		console.error('Error finding near misses:', error);
+		throw new Error(`Failed to check "${tabNotation}" for typos: ${error}`);
+	}
+}
+
+/**
+ * Find the keys a progression is easiest to play in, with or without a capo
+ */
+export async function easiestKeys(
+	chordNames: string[],
+	instrument: Instrument = 'guitar',
+	maxCapo?: number,
+	limit?: number
+): Promise<EasyKey[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmEasiestKeys(chordNames, instrument, maxCapo ?? null, limit ?? null);
+		return result as EasyKey[];
+	} catch (error) {
+		console.error('Error finding easiest keys:', error);
+		throw new Error(`Failed to find easiest keys: ${error}`);
+	}
+}
+
+/**
+ * Per-string changes between two fingerings, lowest string first
+ */
+export async function diffFingerings(fromTab: string, toTab: string): Promise<StringChange[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmDiffFingerings(fromTab, toTab);
+		return result as StringChange[];
+	} catch (error) {
+		console.error('Error diffing fingerings:', error);
+		throw new Error(`Failed to diff "${fromTab}" and "${toTab}": ${error}`);
+	}
+}
+
+/**
+ * Suggest chords for a tab still being typed ("x32" or "x32???")
+ */
+export async function analyzePartialTab(
+	tabNotation: string,
+	instrument: Instrument = 'guitar',
+	limit = 5
+): Promise<PartialMatch[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmAnalyzePartialTab(tabNotation, instrument, limit);
+		return result as PartialMatch[];
+	} catch (error) {
+		console.error('Error analyzing partial tab:', error);
+		throw new Error(`Failed to analyze "${tabNotation}": ${error}`);
+	}
+}
+
+/**
+ * Identify chords from a 12-bin chroma vector (bin 0 = C)
+ */
+export async function analyzeChroma(
+	chroma: number[],
+	options?: ChromaOptions
+): Promise<ChordMatch[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmAnalyzeChroma(new Float32Array(chroma), options || null);
+		return result as ChordMatch[];
+	} catch (error) {
+		console.error('Error analyzing chroma:', error);
+		throw new Error(`Failed to analyze chroma: ${error}`);
+	}
+}
+
+/**
+ * Identify chords from note names, lowest first
+ */
+export async function analyzeNotes(notes: string[]): Promise<ChordMatch[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmAnalyzeNotes(notes);
+		return result as ChordMatch[];
+	} catch (error) {
+		console.error('Error analyzing notes:', error);
+		throw new Error(`Failed to analyze notes: ${error}`);
+	}
+}
+
+/**
+ * Identify chords from MIDI note numbers in any order; the lowest is the bass
+ */
+export async function analyzeMidiNotes(notes: number[]): Promise<ChordMatch[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmAnalyzeMidiNotes(new Uint8Array(notes));
+		return result as ChordMatch[];
+	} catch (error) {
+		console.error('Error analyzing MIDI notes:', error);
+		throw new Error(`Failed to analyze MIDI notes: ${error}`);
+	}
+}
+
 /**
  * Generate optimal fingering progression for a chord sequence
  */
@@ -324,3 +762,93 @@ export async function generateProgression(
 		throw new Error(`Failed to generate progression: ${error}`);
 	}
 }
+
+/**
+ * Generate optimal fingering progression for roman numerals in a key
+ */
+export async function generateProgressionFromNumerals(
+	numerals: string[],
+	key: string,
+	instrument: Instrument = 'guitar',
+	options?: ProgressionOptions
+): Promise<ProgressionSequence[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmGenerateProgressionFromNumerals(numerals, key, instrument, options || null);
+		return result as ProgressionSequence[];
+	} catch (error) {
+		console.error('Error generating progression from numerals:', error);
+		throw new Error(`Failed to generate progression: ${error}`);
+	}
+}
+
+/**
+ * Generate optimal fingering progression for a chart in bar notation,
+ * e.g. "|: C . . . | Am | F G :| x2" (repeats are written out)
+ */
+export async function generateProgressionFromChart(
+	chart: string,
+	instrument: Instrument = 'guitar',
+	options?: ProgressionOptions,
+	beatsPerBar?: number
+): Promise<ProgressionSequence[]> {
+	await initializeWasm();
+
+	try {
+		const result = wasmGenerateProgressionFromChart(
+			chart,
+			beatsPerBar ?? null,
+			instrument,
+			options || null
+		);
+		return result as ProgressionSequence[];
+	} catch (error) {
+		console.error('Error generating progression from chart:', error);
+		throw new Error(`Failed to generate progression: ${error}`);
+	}
+}
+
+/**
+ * Render a measure-aligned chord chart as monospace text
+ */
+export async function renderChart(chordNames: string[], options?: ChartOptions): Promise<string> {
+	await initializeWasm();
+
+	try {
+		return wasmRenderChart(chordNames, options || null);
+	} catch (error) {
+		console.error('Error rendering chart:', error);
+		throw new Error(`Failed to render chart: ${error}`);
+	}
+}
+
+/**
+ * Cache the results of up to `size` findFingerings calls (0 turns caching off)
+ */
+export async function setFingeringCacheSize(size: number): Promise<void> {
+	await initializeWasm();
+	wasmSetFingeringCacheSize(size);
+}
+
+/**
+ * Forget every cached findFingerings result
+ */
+export async function clearFingeringCache(): Promise<void> {
+	await initializeWasm();
+	wasmClearFingeringCache();
+}
+
+/**
+ * Register a custom chord symbol (e.g., "mu" with formula "1 2 3 5")
+ */
+export async function registerQuality(symbol: string, formula: string): Promise<void> {
+	await initializeWasm();
+
+	try {
+		wasmRegisterQuality(symbol, formula);
+	} catch (error) {
+		console.error('Error registering chord quality:', error);
+		throw new Error(`Failed to register chord quality: ${error}`);
+	}
+}
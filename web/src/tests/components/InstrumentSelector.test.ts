@@ -36,13 +36,13 @@ describe('InstrumentSelector', () => {
 		expect(icon).toBeTruthy();
 	});
 
-	it('should have all 11 instruments defined in metadata', () => {
+	it('should have all 19 instruments defined in metadata', () => {
 		const allInstruments = [
 			...INSTRUMENT_CATEGORIES.standard,
 			...INSTRUMENT_CATEGORIES['alternate-tuning'],
 		];
 
-		expect(allInstruments).toHaveLength(11);
+		expect(allInstruments).toHaveLength(19);
 
 		allInstruments.forEach((inst) => {
 			const meta = INSTRUMENT_METADATA[inst];
@@ -53,26 +53,34 @@ describe('InstrumentSelector', () => {
 		});
 	});
 
-	it('should have 7 standard instruments', () => {
-		expect(INSTRUMENT_CATEGORIES.standard).toHaveLength(7);
+	it('should have 10 standard instruments', () => {
+		expect(INSTRUMENT_CATEGORIES.standard).toHaveLength(10);
 		expect(INSTRUMENT_CATEGORIES.standard).toEqual([
 			'guitar',
 			'ukulele',
+			'concert-ukulele',
+			'tenor-ukulele',
 			'baritone-ukulele',
 			'bass',
 			'bass-5',
 			'mandolin',
 			'banjo',
+			'guitar-12',
 		]);
 	});
 
-	it('should have 4 alternate tunings', () => {
-		expect(INSTRUMENT_CATEGORIES['alternate-tuning']).toHaveLength(4);
+	it('should have 9 alternate tunings', () => {
+		expect(INSTRUMENT_CATEGORIES['alternate-tuning']).toHaveLength(9);
 		expect(INSTRUMENT_CATEGORIES['alternate-tuning']).toEqual([
 			'drop-d',
 			'open-g',
 			'dadgad',
 			'guitar-7',
+			'guitar-8',
+			'baritone-guitar',
+			'baritone-guitar-a',
+			'ukulele-low-g',
+			'tenor-ukulele-low-g',
 		]);
 	});
 
@@ -80,12 +88,16 @@ describe('InstrumentSelector', () => {
 		const expectedTunings: Record<string, string> = {
 			guitar: 'EADGBE',
 			ukulele: 'GCEA',
+			'ukulele-low-g': 'GCEA',
 			'baritone-ukulele': 'DGBE',
 			bass: 'EADG',
 			'bass-5': 'BEADG',
 			mandolin: 'GDAE',
 			banjo: 'gDGBD',
 			'guitar-7': 'BEADGBE',
+			'guitar-8': 'F#BEADGBE',
+			'baritone-guitar': 'BEADF#B',
+			'guitar-12': 'EADGBE',
 			'drop-d': 'DADGBE',
 			'open-g': 'DGDGBD',
 			dadgad: 'DADGAD',
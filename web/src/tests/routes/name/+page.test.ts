@@ -26,6 +26,7 @@ vi.mock('$lib/wasm', () => ({
 			name: 'C',
 			confidence: 100,
 			explanation: 'Complete C major chord',
+			roles: [],
 		},
 	]),
 	getInstrumentInfo: vi.fn().mockResolvedValue({
@@ -85,16 +85,21 @@ describe('URL State - Find Params', () => {
 			expect(result.instrument).toBe('guitar');
 		});
 
-		// Test all 11 supported instruments
+		// Test every supported instrument
 		it.each([
 			['guitar', 'guitar'],
 			['ukulele', 'ukulele'],
+			['ukulele-low-g', 'ukulele-low-g'],
+			['tenor-ukulele', 'tenor-ukulele'],
 			['baritone-ukulele', 'baritone-ukulele'],
 			['bass', 'bass'],
 			['bass-5', 'bass-5'],
 			['mandolin', 'mandolin'],
 			['banjo', 'banjo'],
 			['guitar-7', 'guitar-7'],
+			['guitar-8', 'guitar-8'],
+			['baritone-guitar-a', 'baritone-guitar-a'],
+			['guitar-12', 'guitar-12'],
 			['drop-d', 'drop-d'],
 			['open-g', 'open-g'],
 			['dadgad', 'dadgad'],